@@ -0,0 +1,125 @@
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A stored answer and when it was received
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedResponse {
+    created_at: u64,
+    text: String,
+}
+
+/// On-disk cache of Claude answers for single-shot questions
+pub struct ResponseCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+impl ResponseCache {
+    pub fn new(dir: PathBuf, ttl: Duration) -> Self {
+        Self { dir, ttl }
+    }
+
+    /// Build the cache key for a question asked of a model with a system prompt
+    pub fn key(model: &str, system_prompt: &str, question: &str) -> String {
+        format!(
+            "{:016x}",
+            fnv1a(&[
+                model,
+                &format!("{:016x}", fnv1a(&[system_prompt])),
+                question
+            ])
+        )
+    }
+
+    /// Look up a cached answer, ignoring entries older than the TTL
+    pub fn get(&self, key: &str) -> Option<String> {
+        let content = fs::read_to_string(self.entry_path(key)).ok()?;
+        let cached: CachedResponse = serde_json::from_str(&content).ok()?;
+
+        let age = now_secs().saturating_sub(cached.created_at);
+        if age > self.ttl.as_secs() {
+            return None;
+        }
+
+        Some(cached.text)
+    }
+
+    /// Store an answer under the given key
+    pub fn put(&self, key: &str, text: &str) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+
+        let cached = CachedResponse {
+            created_at: now_secs(),
+            text: text.to_string(),
+        };
+        fs::write(self.entry_path(key), serde_json::to_string_pretty(&cached)?)?;
+        Ok(())
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// FNV-1a over the parts, so keys stay stable across builds
+fn fnv1a(parts: &[&str]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for part in parts {
+        // Separate the parts so ("ab", "c") and ("a", "bc") differ
+        for byte in part.bytes().chain(std::iter::once(0)) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_round_trip_and_key_stability() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = ResponseCache::new(temp_dir.path().to_path_buf(), Duration::from_secs(60));
+
+        let key = ResponseCache::key("model", "system", "question");
+        assert_eq!(key, ResponseCache::key("model", "system", "question"));
+        assert_ne!(key, ResponseCache::key("other-model", "system", "question"));
+        assert_ne!(key, ResponseCache::key("model", "other system", "question"));
+
+        assert!(cache.get(&key).is_none());
+        cache.put(&key, "answer").unwrap();
+        assert_eq!(cache.get(&key).as_deref(), Some("answer"));
+    }
+
+    #[test]
+    fn test_expired_entries_are_ignored() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = ResponseCache::new(temp_dir.path().to_path_buf(), Duration::from_secs(60));
+
+        let key = ResponseCache::key("model", "system", "question");
+        let stale = CachedResponse {
+            created_at: now_secs() - 120,
+            text: "old answer".to_string(),
+        };
+        fs::write(
+            cache.entry_path(&key),
+            serde_json::to_string(&stale).unwrap(),
+        )
+        .unwrap();
+
+        assert!(cache.get(&key).is_none());
+    }
+}
@@ -1,7 +1,9 @@
+use crate::ai::cache::ResponseCache;
+use crate::cli::theme::Themed;
 use crate::commands::{Command, Workflow, WorkflowStep};
-use crate::error::{ClixError, Result};
+use crate::error::{ClixError, Result, register_secret};
 use crate::settings::Settings;
-use colored::Colorize;
+use crate::settings::secrets::{ANTHROPIC_API_KEY_SECRET, KeychainProvider, resolve_secret};
 use dotenv::dotenv;
 use reqwest::blocking::Client;
 use reqwest::header::{CONTENT_TYPE, HeaderMap, HeaderValue};
@@ -56,7 +58,7 @@ impl RateLimiter {
                 if wait_time > Duration::from_secs(0) {
                     println!(
                         "{} Rate limit reached. Waiting {} seconds...",
-                        "Clix:".yellow().bold(),
+                        "Clix:".warning(),
                         wait_time.as_secs()
                     );
                     thread::sleep(wait_time);
@@ -73,17 +75,17 @@ impl RateLimiter {
 
             let current_tokens: u32 = token_usage.iter().map(|(_, tokens)| tokens).sum();
 
-            if current_tokens + estimated_tokens > self.tokens_per_minute {
-                if let Some((oldest_time, _)) = token_usage.first() {
-                    let wait_time = *oldest_time + Duration::from_secs(60) - now;
-                    if wait_time > Duration::from_secs(0) {
-                        println!(
-                            "{} Token rate limit reached. Waiting {} seconds...",
-                            "Clix:".yellow().bold(),
-                            wait_time.as_secs()
-                        );
-                        thread::sleep(wait_time);
-                    }
+            if current_tokens + estimated_tokens > self.tokens_per_minute
+                && let Some((oldest_time, _)) = token_usage.first()
+            {
+                let wait_time = *oldest_time + Duration::from_secs(60) - now;
+                if wait_time > Duration::from_secs(0) {
+                    println!(
+                        "{} Token rate limit reached. Waiting {} seconds...",
+                        "Clix:".warning(),
+                        wait_time.as_secs()
+                    );
+                    thread::sleep(wait_time);
                 }
             }
 
@@ -185,6 +187,39 @@ struct ContentBlock {
     text: String,
 }
 
+/// Sends a messages request and returns the raw response body
+trait MessageSender {
+    fn send(&self, api_key: &str, request: &ClaudeRequest) -> Result<String>;
+}
+
+struct HttpSender {
+    client: Client,
+}
+
+impl MessageSender for HttpSender {
+    fn send(&self, api_key: &str, request: &ClaudeRequest) -> Result<String> {
+        // Create headers
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        headers.insert("x-api-key", HeaderValue::from_str(api_key)?);
+        headers.insert("anthropic-version", HeaderValue::from_static("2023-06-01"));
+
+        let response = self
+            .client
+            .post(CLAUDE_API_URL)
+            .headers(headers)
+            .json(request)
+            .send()
+            .map_err(|e| {
+                ClixError::CommandExecutionFailed(format!("Failed to call Claude API: {}", e))
+            })?;
+
+        response.text().map_err(|e| {
+            ClixError::CommandExecutionFailed(format!("Failed to get raw response body: {}", e))
+        })
+    }
+}
+
 // Models list response - Claude API uses "data" field, not "models"
 #[derive(Debug, Deserialize)]
 struct ModelsResponse {
@@ -234,12 +269,63 @@ pub enum ClaudeAction {
     NoAction,
 }
 
+/// Which kinds of suggested action `clix ask` is allowed to carry out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ActionFilter {
+    #[default]
+    All,
+    /// Only create commands and workflows, never run them
+    CreateOnly,
+    /// Only run stored commands and workflows, never create them
+    RunOnly,
+}
+
+impl ActionFilter {
+    pub fn from_flags(create_only: bool, run_only: bool) -> Self {
+        match (create_only, run_only) {
+            (true, _) => ActionFilter::CreateOnly,
+            (_, true) => ActionFilter::RunOnly,
+            _ => ActionFilter::All,
+        }
+    }
+
+    /// Pass an action through, or replace it with NoAction and return a note
+    /// saying what was left out
+    pub fn apply(self, action: ClaudeAction) -> (ClaudeAction, Option<String>) {
+        let (skipped, flag) = match (&action, self) {
+            (ClaudeAction::RunCommand(name), ActionFilter::CreateOnly) => {
+                (format!("run command '{}'", name), "--create-only")
+            }
+            (ClaudeAction::RunWorkflow(name), ActionFilter::CreateOnly) => {
+                (format!("run workflow '{}'", name), "--create-only")
+            }
+            (ClaudeAction::CreateCommand { name, .. }, ActionFilter::RunOnly) => {
+                (format!("create command '{}'", name), "--run-only")
+            }
+            (ClaudeAction::CreateWorkflow { name, .. }, ActionFilter::RunOnly) => {
+                (format!("create workflow '{}'", name), "--run-only")
+            }
+            _ => return (action, None),
+        };
+        (
+            ClaudeAction::NoAction,
+            Some(format!(
+                "Ignored the suggestion to {} because of {}",
+                skipped, flag
+            )),
+        )
+    }
+}
+
 pub struct ClaudeAssistant {
     client: Client,
+    sender: Box<dyn MessageSender>,
     api_key: String,
     settings: Settings,
     rate_limiter: RateLimiter,
     retry_config: RetryConfig,
+    model_override: Option<String>,
+    cache: Option<ResponseCache>,
 }
 
 impl ClaudeAssistant {
@@ -247,24 +333,64 @@ impl ClaudeAssistant {
         // Load .env file if it exists
         dotenv().ok();
 
-        // Get API key from environment
-        let api_key = env::var("ANTHROPIC_API_KEY").map_err(|_| {
+        // Get API key from environment, falling back to the keychain
+        let api_key = resolve_secret(
+            env::var("ANTHROPIC_API_KEY").ok(),
+            &KeychainProvider,
+            ANTHROPIC_API_KEY_SECRET,
+        )?
+        .ok_or_else(|| {
             ClixError::InvalidCommandFormat(
-                "ANTHROPIC_API_KEY environment variable not set. Please set it or create a .env file.".to_string(),
+                "ANTHROPIC_API_KEY environment variable not set. Please set it, create a .env file, or run 'clix settings set-secret anthropic-api-key'.".to_string(),
             )
         })?;
+        register_secret(&api_key);
 
         let client = Client::new();
 
         Ok(ClaudeAssistant {
+            sender: Box::new(HttpSender {
+                client: client.clone(),
+            }),
             client,
             api_key,
             settings,
             rate_limiter: RateLimiter::with_defaults(),
             retry_config: RetryConfig::default(),
+            model_override: None,
+            cache: None,
         })
     }
 
+    /// Answer repeated single-shot questions from a response cache
+    pub fn with_cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Use a different model for this assistant without touching the stored settings
+    pub fn with_model_override(mut self, model: String) -> Self {
+        self.model_override = Some(model);
+        self
+    }
+
+    /// The model requests are sent to
+    pub fn model(&self) -> &str {
+        self.model_override
+            .as_deref()
+            .unwrap_or(&self.settings.ai_model)
+    }
+
+    fn build_request(&self, messages: Vec<Message>, system: String) -> ClaudeRequest {
+        ClaudeRequest {
+            model: self.model().to_string(),
+            max_tokens: self.settings.ai_settings.max_tokens,
+            temperature: self.settings.ai_settings.temperature,
+            messages,
+            system,
+        }
+    }
+
     pub fn ask(
         &self,
         question: &str,
@@ -289,28 +415,28 @@ impl ClaudeAssistant {
         let mut last_error: Option<RetryableError> = None;
 
         for attempt in 0..=retry_config.max_retries {
-            if attempt > 0 {
-                if let Some(ref error) = last_error {
-                    if !error.should_retry(retry_config) {
-                        break;
-                    }
+            if attempt > 0
+                && let Some(ref error) = last_error
+            {
+                if !error.should_retry(retry_config) {
+                    break;
+                }
 
-                    let delay = if retry_config.exponential_backoff {
-                        retry_config.base_delay_ms * (2_u64.pow(attempt - 1))
-                    } else {
-                        retry_config.base_delay_ms
-                    };
+                let delay = if retry_config.exponential_backoff {
+                    retry_config.base_delay_ms * (2_u64.pow(attempt - 1))
+                } else {
+                    retry_config.base_delay_ms
+                };
 
-                    println!(
-                        "{} Retrying in {} seconds... (attempt {}/{})",
-                        "Clix:".yellow().bold(),
-                        delay / 1000,
-                        attempt,
-                        retry_config.max_retries
-                    );
+                println!(
+                    "{} Retrying in {} seconds... (attempt {}/{})",
+                    "Clix:".warning(),
+                    delay / 1000,
+                    attempt,
+                    retry_config.max_retries
+                );
 
-                    thread::sleep(Duration::from_millis(delay));
-                }
+                thread::sleep(Duration::from_millis(delay));
             }
 
             match self.ask_internal(question, &command_history, &workflow_history) {
@@ -333,7 +459,20 @@ impl ClaudeAssistant {
         command_history: &[&Command],
         workflow_history: &[&Workflow],
     ) -> Result<(String, ClaudeAction)> {
-        println!("{} Asking Claude...", "Clix:".blue().bold());
+        // Create system prompt
+        let system_prompt = Self::create_system_prompt(command_history, workflow_history);
+
+        // Answer from the cache when the same question was asked recently
+        let cache_key = ResponseCache::key(self.model(), &system_prompt, question);
+        if let Some(cache) = &self.cache
+            && let Some(text) = cache.get(&cache_key)
+        {
+            println!("{} Using cached answer", "Clix:".header());
+            let action = self.parse_action(&text)?;
+            return Ok((text, action));
+        }
+
+        println!("{} Asking Claude...", "Clix:".header());
 
         // Estimate tokens (rough estimation)
         let estimated_tokens = (question.len() / 4) as u32 + 1000; // Rough token estimation
@@ -341,9 +480,6 @@ impl ClaudeAssistant {
         // Apply rate limiting
         self.rate_limiter.check_and_wait(estimated_tokens)?;
 
-        // Create system prompt
-        let system_prompt = self.create_system_prompt(command_history, workflow_history);
-
         // Create user message
         let user_message = Message {
             role: "user".to_string(),
@@ -354,35 +490,10 @@ impl ClaudeAssistant {
         };
 
         // Create request
-        let request = ClaudeRequest {
-            model: self.settings.ai_model.clone(),
-            max_tokens: self.settings.ai_settings.max_tokens,
-            temperature: self.settings.ai_settings.temperature,
-            messages: vec![user_message],
-            system: system_prompt,
-        };
-
-        // Create headers
-        let mut headers = HeaderMap::new();
-        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
-        headers.insert("x-api-key", HeaderValue::from_str(&self.api_key)?);
-        headers.insert("anthropic-version", HeaderValue::from_static("2023-06-01"));
-
-        // Make request
-        let response = self
-            .client
-            .post(CLAUDE_API_URL)
-            .headers(headers)
-            .json(&request)
-            .send()
-            .map_err(|e| {
-                ClixError::CommandExecutionFailed(format!("Failed to call Claude API: {}", e))
-            })?;
+        let request = self.build_request(vec![user_message], system_prompt);
 
-        // Get the raw response body first
-        let raw_response = response.text().map_err(|e| {
-            ClixError::CommandExecutionFailed(format!("Failed to get raw response body: {}", e))
-        })?;
+        // Make request and get the raw response body
+        let raw_response = self.sender.send(&self.api_key, &request)?;
 
         // Print the raw response for debugging
         println!("Raw API response: {}", raw_response);
@@ -418,9 +529,28 @@ impl ClaudeAssistant {
 
         let action = self.parse_action(&text)?;
 
+        if let Some(cache) = &self.cache {
+            cache.put(&cache_key, &text)?;
+        }
+
         Ok((text, action))
     }
 
+    /// Render the prompt `ask` would send, without calling the API
+    pub fn preview_prompt(
+        question: &str,
+        command_history: &[&Command],
+        workflow_history: &[&Workflow],
+    ) -> String {
+        let system_prompt = Self::create_system_prompt(command_history, workflow_history);
+        let estimated_tokens = (system_prompt.len() + question.len()) / 4;
+
+        format!(
+            "=== System prompt ===\n{}\n=== User message ===\n{}\n\nEstimated input tokens: ~{}\n",
+            system_prompt, question, estimated_tokens
+        )
+    }
+
     fn categorize_error(&self, error: &ClixError) -> RetryableError {
         match error {
             ClixError::ApiError(msg) => {
@@ -458,17 +588,16 @@ impl ClaudeAssistant {
     fn extract_status_code(&self, message: &str) -> Option<u16> {
         // Try to extract HTTP status code from error message
         for word in message.split_whitespace() {
-            if let Ok(code) = word.parse::<u16>() {
-                if (400..600).contains(&code) {
-                    return Some(code);
-                }
+            if let Ok(code) = word.parse::<u16>()
+                && (400..600).contains(&code)
+            {
+                return Some(code);
             }
         }
         None
     }
 
     fn create_system_prompt(
-        &self,
         command_history: &[&Command],
         workflow_history: &[&Workflow],
     ) -> String {
@@ -644,54 +773,11 @@ Follow these guidelines:
                 let description = desc_match.get(1).unwrap().as_str().trim().to_string();
 
                 // Parse steps using line-by-line approach
-                let mut steps = Vec::new();
-
-                // Find the Steps: section and parse each step
-                if let Some(steps_section) = text.split("Steps:").nth(1) {
-                    for line in steps_section.lines() {
-                        let line = line.trim();
-                        if line.starts_with("- ")
-                            && line.contains("name=")
-                            && line.contains("command=")
-                        {
-                            // Extract step info with string operations instead of regex
-                            if let (Some(name_part), Some(rest)) =
-                                (line.split("name=").nth(1), line.split("command=").nth(1))
-                            {
-                                let step_name =
-                                    name_part.split('"').nth(1).unwrap_or("").to_string();
-                                let command = rest.split('"').nth(1).unwrap_or("").to_string();
-
-                                // Extract description
-                                let step_desc =
-                                    if let Some(desc_part) = rest.split("description=").nth(1) {
-                                        desc_part.split('"').nth(1).unwrap_or("").to_string()
-                                    } else {
-                                        "Step generated by Claude".to_string()
-                                    };
-
-                                // Extract continue_on_error
-                                let continue_on_error = rest.contains("continue_on_error=true");
-
-                                // Extract step type
-                                let is_auth_step = rest.contains("step_type=\"Auth\"");
-
-                                let step = if is_auth_step {
-                                    WorkflowStep::new_auth(step_name, command, step_desc)
-                                } else {
-                                    WorkflowStep::new_command(
-                                        step_name,
-                                        command,
-                                        step_desc,
-                                        continue_on_error,
-                                    )
-                                };
-
-                                steps.push(step);
-                            }
-                        }
-                    }
-                }
+                let steps = text
+                    .split("Steps:")
+                    .nth(1)
+                    .map(parse_step_lines)
+                    .unwrap_or_default();
 
                 if !steps.is_empty() {
                     return Ok(ClaudeAction::CreateWorkflow {
@@ -765,24 +851,23 @@ Follow these guidelines:
                 if let Some(obj) = json_value.as_object() {
                     // Look for common field names that might contain model data
                     for (key, value) in obj {
-                        if key.contains("model") || key == "data" {
-                            if let Some(array) = value.as_array() {
-                                let mut model_names = Vec::new();
-                                for item in array {
-                                    if let Some(name) = item.get("id").and_then(|v| v.as_str()) {
-                                        model_names.push(name.to_string());
-                                    } else if let Some(name) =
-                                        item.get("name").and_then(|v| v.as_str())
-                                    {
-                                        model_names.push(name.to_string());
-                                    } else if let Some(name) = item.as_str() {
-                                        model_names.push(name.to_string());
-                                    }
-                                }
-                                if !model_names.is_empty() {
-                                    return Ok(model_names);
+                        if (key.contains("model") || key == "data")
+                            && let Some(array) = value.as_array()
+                        {
+                            let mut model_names = Vec::new();
+                            for item in array {
+                                if let Some(name) = item.get("id").and_then(|v| v.as_str()) {
+                                    model_names.push(name.to_string());
+                                } else if let Some(name) = item.get("name").and_then(|v| v.as_str())
+                                {
+                                    model_names.push(name.to_string());
+                                } else if let Some(name) = item.as_str() {
+                                    model_names.push(name.to_string());
                                 }
                             }
+                            if !model_names.is_empty() {
+                                return Ok(model_names);
+                            }
                         }
                     }
                 }
@@ -805,30 +890,22 @@ Follow these guidelines:
     pub fn confirm_action(&self, action: &ClaudeAction) -> Result<bool> {
         match action {
             ClaudeAction::RunCommand(name) => {
-                print!(
-                    "{} Run command '{}'? [y/N]: ",
-                    "Confirm:".green().bold(),
-                    name
-                );
+                print!("{} Run command '{}'? [y/N]: ", "Confirm:".success(), name);
             }
             ClaudeAction::RunWorkflow(name) => {
-                print!(
-                    "{} Run workflow '{}'? [y/N]: ",
-                    "Confirm:".green().bold(),
-                    name
-                );
+                print!("{} Run workflow '{}'? [y/N]: ", "Confirm:".success(), name);
             }
             ClaudeAction::CreateCommand { name, .. } => {
                 print!(
                     "{} Create command '{}'? [y/N]: ",
-                    "Confirm:".green().bold(),
+                    "Confirm:".success(),
                     name
                 );
             }
             ClaudeAction::CreateWorkflow { name, .. } => {
                 print!(
                     "{} Create workflow '{}'? [y/N]: ",
-                    "Confirm:".green().bold(),
+                    "Confirm:".success(),
                     name
                 );
             }
@@ -855,7 +932,7 @@ Follow these guidelines:
         command_history: Vec<&Command>,
         workflow_history: Vec<&Workflow>,
     ) -> Result<(String, ClaudeAction)> {
-        println!("{} Asking Claude...", "Clix:".blue().bold());
+        println!("{} Asking Claude...", "Clix:".header());
 
         // Estimate tokens (rough estimation)
         let estimated_tokens = (question.len() / 4) as u32 + 2000; // More tokens for context
@@ -898,35 +975,10 @@ Follow these guidelines:
         });
 
         // Create request
-        let request = ClaudeRequest {
-            model: self.settings.ai_model.clone(),
-            max_tokens: self.settings.ai_settings.max_tokens,
-            temperature: self.settings.ai_settings.temperature,
-            messages,
-            system: system_prompt,
-        };
-
-        // Create headers
-        let mut headers = HeaderMap::new();
-        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
-        headers.insert("x-api-key", HeaderValue::from_str(&self.api_key)?);
-        headers.insert("anthropic-version", HeaderValue::from_static("2023-06-01"));
+        let request = self.build_request(messages, system_prompt);
 
-        // Make request
-        let response = self
-            .client
-            .post(CLAUDE_API_URL)
-            .headers(headers)
-            .json(&request)
-            .send()
-            .map_err(|e| {
-                ClixError::CommandExecutionFailed(format!("Failed to call Claude API: {}", e))
-            })?;
-
-        // Get the raw response body first
-        let raw_response = response.text().map_err(|e| {
-            ClixError::CommandExecutionFailed(format!("Failed to get raw response body: {}", e))
-        })?;
+        // Make request and get the raw response body
+        let raw_response = self.sender.send(&self.api_key, &request)?;
 
         // Check if this is an error response
         if raw_response.contains("\"type\":\"error\"") {
@@ -997,6 +1049,9 @@ CURRENT CONVERSATION STATE: "#.to_string();
                     prompt.push_str(&format!("  Description: {}\n", desc));
                 }
                 prompt.push_str(&format!("  Steps defined so far: {}\n", state.steps.len()));
+                for refinement in &state.pending_refinements {
+                    prompt.push_str(&format!("  Still needed: {}\n", refinement));
+                }
             }
             crate::ai::conversation::ConversationState::RefiningWorkflow(name) => {
                 prompt.push_str(&format!("Currently refining workflow: {}\n", name));
@@ -1031,7 +1086,18 @@ Name: command_name
 Description: description
 Command: shell_command
 
-5. For when conversation should end:
+5. For building a workflow over several turns, send each part once it is settled.
+Parts are added to a draft kept for this conversation, and a step named like an
+earlier one replaces it:
+[WORKFLOW DRAFT]
+Name: workflow_name (once known)
+Description: description (once known)
+Steps:
+- Step 1: name="Step 1", command="command1", description="step description", continue_on_error=false, step_type="Command"
+When the workflow is finished, end that reply with [WORKFLOW COMPLETE] and the
+user will be offered to save it.
+
+6. For when conversation should end:
 [COMPLETE]
 Final summary or goodbye message...
 
@@ -1101,3 +1167,159 @@ Final summary or goodbye message...
         self.parse_action(text)
     }
 }
+
+/// Parse the `- Step N: name="...", command="...", ...` lines of a workflow's
+/// `Steps:` section, skipping lines that aren't steps
+pub(crate) fn parse_step_lines(section: &str) -> Vec<WorkflowStep> {
+    let mut steps = Vec::new();
+    for line in section.lines() {
+        let line = line.trim();
+        if !(line.starts_with("- ") && line.contains("name=") && line.contains("command=")) {
+            continue;
+        }
+
+        // Extract step info with string operations instead of regex
+        if let (Some(name_part), Some(rest)) =
+            (line.split("name=").nth(1), line.split("command=").nth(1))
+        {
+            let step_name = name_part.split('"').nth(1).unwrap_or("").to_string();
+            let command = rest.split('"').nth(1).unwrap_or("").to_string();
+
+            // Extract description
+            let step_desc = if let Some(desc_part) = rest.split("description=").nth(1) {
+                desc_part.split('"').nth(1).unwrap_or("").to_string()
+            } else {
+                "Step generated by Claude".to_string()
+            };
+
+            // Extract continue_on_error
+            let continue_on_error = rest.contains("continue_on_error=true");
+
+            // Extract step type
+            let is_auth_step = rest.contains("step_type=\"Auth\"");
+
+            let step = if is_auth_step {
+                WorkflowStep::new_auth(step_name, command, step_desc)
+            } else {
+                WorkflowStep::new_command(step_name, command, step_desc, continue_on_error)
+            };
+
+            steps.push(step);
+        }
+    }
+    steps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use tempfile::TempDir;
+
+    /// Sender that answers every request with a canned reply and counts calls
+    struct CountingSender {
+        calls: Rc<Cell<usize>>,
+    }
+
+    impl MessageSender for CountingSender {
+        fn send(&self, _api_key: &str, _request: &ClaudeRequest) -> Result<String> {
+            self.calls.set(self.calls.get() + 1);
+            Ok(r#"{"content":[{"type":"text","text":"Use ls -la"}]}"#.to_string())
+        }
+    }
+
+    fn assistant_with_sender(
+        settings: Settings,
+        sender: Box<dyn MessageSender>,
+    ) -> ClaudeAssistant {
+        ClaudeAssistant {
+            client: Client::new(),
+            sender,
+            api_key: "test-key".to_string(),
+            settings,
+            rate_limiter: RateLimiter::with_defaults(),
+            retry_config: RetryConfig::default(),
+            model_override: None,
+            cache: None,
+        }
+    }
+
+    fn assistant(settings: Settings) -> ClaudeAssistant {
+        let calls = Rc::new(Cell::new(0));
+        assistant_with_sender(settings, Box::new(CountingSender { calls }))
+    }
+
+    #[test]
+    fn test_model_override_only_applies_to_request() {
+        let settings = Settings::default();
+        let stored_model = settings.ai_model.clone();
+
+        let assistant = assistant(settings).with_model_override("claude-override".to_string());
+        let request = assistant.build_request(vec![], String::new());
+
+        assert_eq!(request.model, "claude-override");
+        assert_eq!(assistant.settings.ai_model, stored_model);
+    }
+
+    #[test]
+    fn test_request_uses_settings_model_by_default() {
+        let settings = Settings::default();
+        let stored_model = settings.ai_model.clone();
+
+        let request = assistant(settings).build_request(vec![], String::new());
+        assert_eq!(request.model, stored_model);
+    }
+
+    #[test]
+    fn test_cached_question_skips_the_api() {
+        let temp_dir = TempDir::new().unwrap();
+        let calls = Rc::new(Cell::new(0));
+        let assistant = assistant_with_sender(
+            Settings::default(),
+            Box::new(CountingSender {
+                calls: calls.clone(),
+            }),
+        )
+        .with_cache(ResponseCache::new(
+            temp_dir.path().to_path_buf(),
+            Duration::from_secs(60),
+        ));
+
+        let (first, _) = assistant
+            .ask("How do I list files?", vec![], vec![])
+            .unwrap();
+        let (second, _) = assistant
+            .ask("How do I list files?", vec![], vec![])
+            .unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(calls.get(), 1);
+
+        // A different question still goes to the API
+        assistant
+            .ask("How do I copy files?", vec![], vec![])
+            .unwrap();
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn test_uncached_assistant_always_calls_the_api() {
+        let calls = Rc::new(Cell::new(0));
+        let assistant = assistant_with_sender(
+            Settings::default(),
+            Box::new(CountingSender {
+                calls: calls.clone(),
+            }),
+        );
+
+        assistant
+            .ask("How do I list files?", vec![], vec![])
+            .unwrap();
+        assistant
+            .ask("How do I list files?", vec![], vec![])
+            .unwrap();
+        assert_eq!(calls.get(), 2);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::ai::claude::{ClaudeAction, parse_step_lines};
 use crate::commands::{Command, Workflow, WorkflowStep};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -54,7 +55,7 @@ pub enum ConversationState {
     Completed,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct WorkflowCreationState {
     pub name: Option<String>,
     pub description: Option<String>,
@@ -62,6 +63,49 @@ pub struct WorkflowCreationState {
     pub pending_refinements: Vec<String>,
 }
 
+/// Marks a reply that adds to the workflow being built over several turns
+pub const WORKFLOW_DRAFT_MARKER: &str = "[WORKFLOW DRAFT]";
+/// Marks the reply that finishes the workflow being built
+pub const WORKFLOW_COMPLETE_MARKER: &str = "[WORKFLOW COMPLETE]";
+
+impl WorkflowCreationState {
+    /// Take the name, description and steps from a `[WORKFLOW DRAFT]` block.
+    /// A step with the name of one already in the draft replaces it.
+    pub fn apply_draft(&mut self, draft: &str) {
+        let (header, steps) = draft.split_once("Steps:").unwrap_or((draft, ""));
+        for line in header.lines() {
+            let line = line.trim();
+            if let Some(name) = line.strip_prefix("Name:") {
+                self.name = Some(name.trim().to_string());
+            } else if let Some(description) = line.strip_prefix("Description:") {
+                self.description = Some(description.trim().to_string());
+            }
+        }
+
+        for step in parse_step_lines(steps) {
+            match self.steps.iter_mut().find(|s| s.name == step.name) {
+                Some(existing) => *existing = step,
+                None => self.steps.push(step),
+            }
+        }
+    }
+
+    /// What the draft still needs before it can be saved
+    pub fn missing(&self) -> Vec<&'static str> {
+        let mut missing = Vec::new();
+        if self.name.is_none() {
+            missing.push("a name");
+        }
+        if self.description.is_none() {
+            missing.push("a description");
+        }
+        if self.steps.is_empty() {
+            missing.push("steps");
+        }
+        missing
+    }
+}
+
 impl Default for ConversationSession {
     fn default() -> Self {
         Self::new()
@@ -135,6 +179,48 @@ impl ConversationSession {
             .as_secs();
     }
 
+    /// Fold an assistant reply into the workflow being built across turns.
+    /// A `[WORKFLOW DRAFT]` block starts or extends the draft; once a reply
+    /// says `[WORKFLOW COMPLETE]` and the draft has a name, description and
+    /// steps, it is returned as a workflow to offer for saving.
+    pub fn track_workflow_creation(&mut self, response: &str) -> Option<ClaudeAction> {
+        if let Some((_, draft)) = response.split_once(WORKFLOW_DRAFT_MARKER) {
+            let draft = draft
+                .split(WORKFLOW_COMPLETE_MARKER)
+                .next()
+                .unwrap_or(draft);
+            let mut state = match &self.state {
+                ConversationState::CreatingWorkflow(state) => state.clone(),
+                _ => WorkflowCreationState::default(),
+            };
+            state.apply_draft(draft);
+            self.set_state(ConversationState::CreatingWorkflow(state));
+        }
+
+        if !response.contains(WORKFLOW_COMPLETE_MARKER) {
+            return None;
+        }
+        let ConversationState::CreatingWorkflow(state) = &mut self.state else {
+            return None;
+        };
+        state.pending_refinements = state
+            .missing()
+            .into_iter()
+            .map(|missing| format!("The workflow needs {}", missing))
+            .collect();
+        if !state.pending_refinements.is_empty() {
+            return None;
+        }
+
+        let state = state.clone();
+        self.set_state(ConversationState::Active);
+        Some(ClaudeAction::CreateWorkflow {
+            name: state.name?,
+            description: state.description?,
+            steps: state.steps,
+        })
+    }
+
     pub fn is_expired(&self, max_age_hours: u64) -> bool {
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
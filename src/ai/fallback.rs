@@ -0,0 +1,29 @@
+use crate::ai::claude::ClaudeAction;
+use crate::error::Result;
+
+/// The question asked about a name `clix run` couldn't find, e.g. `list-pods`
+/// becomes "How do I list pods? ..."
+pub fn question_for(name: &str) -> String {
+    let words = name.replace(['-', '_'], " ");
+    format!(
+        "How do I {}? If none of my commands does this, suggest a command named '{}' to create.",
+        words.trim(),
+        name
+    )
+}
+
+/// Ask `ask` about a command name that isn't stored, when the fallback is
+/// enabled. Returns None without asking when it isn't, since asking costs tokens.
+pub fn ask_about_unknown<F>(
+    name: &str,
+    enabled: bool,
+    ask: F,
+) -> Result<Option<(String, ClaudeAction)>>
+where
+    F: FnOnce(&str) -> Result<(String, ClaudeAction)>,
+{
+    if !enabled {
+        return Ok(None);
+    }
+    ask(&question_for(name)).map(Some)
+}
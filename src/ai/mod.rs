@@ -1,5 +1,7 @@
+pub mod cache;
 pub mod claude;
 pub mod conversation;
+pub mod fallback;
 pub mod mock;
 
 pub use claude::ClaudeAssistant;
@@ -1,8 +1,41 @@
+use crate::cli::theme::Theme;
+use crate::commands::{OverrunPolicy, Severity};
+use crate::settings::{DataLocation, SettingsSection};
+use crate::share::{ExportFormat, ReportFormat};
 use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 pub struct CliArgs {
+    /// Directory for clix data instead of ~/.clix (also read from CLIX_CONFIG_DIR)
+    #[arg(long, global = true)]
+    pub config_dir: Option<PathBuf>,
+
+    /// Disable coloured output
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
+    /// Color theme for output (default: the `theme` setting)
+    #[arg(long, global = true, value_enum)]
+    pub theme: Option<Theme>,
+
+    /// Skip syncing with git repositories at startup (also read from CLIX_NO_SYNC)
+    #[arg(long, global = true)]
+    pub no_sync: bool,
+
+    /// Show output through a pager (CLIX_PAGER, PAGER or less -R) when writing to a terminal
+    #[arg(long, global = true)]
+    pub pager: bool,
+
+    /// Never use a pager, even with --pager
+    #[arg(long, global = true)]
+    pub no_pager: bool,
+
+    /// Reuse values entered at variable prompts for later workflows, including runs in the next 15 minutes
+    #[arg(long, global = true)]
+    pub remember_vars: bool,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -18,6 +51,9 @@ pub enum Commands {
     /// List all stored commands and workflows
     List(ListArgs),
 
+    /// List the tags in use and how many commands and workflows carry each
+    Tags(TagsArgs),
+
     /// Remove a stored command
     Remove(RemoveArgs),
 
@@ -36,9 +72,53 @@ pub enum Commands {
     /// Add a branch step to a workflow
     AddBranch(AddBranchArgs),
 
+    /// Copy a step from one workflow into another
+    CopyStep(CopyStepArgs),
+
     /// Convert a shell function to a workflow
     ConvertFunction(ConvertFunctionArgs),
 
+    /// Convert Makefile targets to commands and workflows
+    ConvertMakefile(ConvertMakefileArgs),
+
+    /// Convert the scripts in a package.json to commands
+    ConvertNpm(ConvertNpmArgs),
+
+    /// Validate a stored workflow
+    Validate(ValidateArgs),
+
+    /// Check a steps file for structural mistakes without storing it
+    CheckSteps(CheckStepsArgs),
+
+    /// Report workflow issues and fix the safe ones with --fix
+    Lint(LintArgs),
+
+    /// Export a workflow as a standalone bash script
+    ExportScript(ExportScriptArgs),
+
+    /// Suggest which adjacent workflow steps could run in parallel
+    SuggestParallel(SuggestParallelArgs),
+
+    /// List a workflow's steps
+    Steps(StepsArgs),
+
+    /// Re-run the workflows whose last run failed
+    RetryFailed(RetryFailedArgs),
+
+    /// Summarise local run history for sharing, without any telemetry
+    Report(ReportArgs),
+
+    /// Manage the local run history
+    #[command(subcommand)]
+    History(HistoryCommands),
+
+    /// Estimate how long a command or workflow takes from its past runs
+    TimeEstimate(TimeEstimateArgs),
+
+    /// Run a command or workflow repeatedly on an interval
+    #[command(subcommand)]
+    Schedule(ScheduleCommands),
+
     /// Export commands and workflows to a file
     Export(ExportArgs),
 
@@ -52,12 +132,30 @@ pub enum Commands {
     #[command(subcommand)]
     Settings(SettingsCommands),
 
+    /// Show where clix keeps its data
+    Open(OpenArgs),
+
+    /// Check the clix data directory for problems and optionally repair them
+    Doctor(DoctorArgs),
+
     /// Generate shell completions
     Completions(CompletionsArgs),
 
     /// Git repository management commands
     #[command(subcommand)]
     Git(GitCommands),
+
+    /// Run, remove or export everything with a tag
+    #[command(subcommand)]
+    Bulk(BulkCommands),
+
+    /// Manage reusable step snippets that workflows include
+    #[command(subcommand)]
+    Snippet(SnippetCommands),
+
+    /// Attach free-form notes to a command or workflow
+    #[command(subcommand)]
+    Note(NoteCommands),
 }
 
 #[derive(Args, Debug)]
@@ -73,6 +171,10 @@ pub struct AddArgs {
     #[arg(short, long, conflicts_with = "steps_file")]
     pub command: Option<String>,
 
+    /// Read the command to execute from stdin until EOF, e.g. a multi-line script
+    #[arg(long, conflicts_with_all = ["command", "steps_file"])]
+    pub command_stdin: bool,
+
     /// Path to a JSON file containing workflow steps (for workflows)
     #[arg(short = 'f', long, conflicts_with = "command")]
     pub steps_file: Option<String>,
@@ -80,6 +182,32 @@ pub struct AddArgs {
     /// Optional tags for categorization
     #[arg(short, long)]
     pub tags: Option<Vec<String>>,
+
+    /// Refuse to start a run while another run of this command is in progress
+    #[arg(long)]
+    pub lock: bool,
+    /// Always run the workflow with --strict-vars
+    #[arg(long, requires = "steps_file")]
+    pub strict_vars: bool,
+    /// Always run the workflow with --reject-unsafe-vars
+    #[arg(long, requires = "steps_file")]
+    pub reject_unsafe_vars: bool,
+
+    /// Who maintains the command
+    #[arg(long)]
+    pub author: Option<String>,
+
+    /// Version of the definition, e.g. 1.2.0
+    #[arg(long = "version", value_name = "VERSION", conflicts_with = "bump")]
+    pub version: Option<String>,
+
+    /// A note on what changed, added to the changelog (can be repeated)
+    #[arg(long, value_name = "NOTE")]
+    pub changelog: Vec<String>,
+
+    /// When replacing a stored command, keep its author and changelog and increment its version
+    #[arg(long)]
+    pub bump: bool,
 }
 
 #[derive(Args, Debug)]
@@ -91,9 +219,152 @@ pub struct RunArgs {
     #[arg(short, long)]
     pub profile: Option<String>,
 
-    /// Variable values in the format key=value (for workflows)
+    /// Variable values in the format key=value (fills {{ placeholders }} in simple commands)
     #[arg(short, long)]
     pub var: Option<Vec<String>>,
+
+    /// Variable values as one JSON object, e.g. '{"ENV":"prod"}', or - to read it from stdin; --var wins where both set a name
+    #[arg(long, value_name = "JSON")]
+    pub input_json: Option<String>,
+
+    /// Environment variables for the command's process, as KEY=VALUE; values are not substituted into the command (for simple commands)
+    #[arg(long, value_name = "KEY=VALUE")]
+    pub env: Vec<String>,
+
+    /// Never prompt; confirm steps use their default answer (for workflows)
+    #[arg(long)]
+    pub non_interactive: bool,
+
+    /// Pause before each step to run, skip, edit variables or abort (for workflows)
+    #[arg(long)]
+    pub step_through: bool,
+
+    /// Print the steps that would run with the given variables, without running anything (for workflows)
+    #[arg(long, conflicts_with_all = ["step_through", "dump_context", "record", "save_outputs"])]
+    pub explain_plan: bool,
+
+    /// Refuse to run when a step references an undefined variable (for workflows)
+    #[arg(long)]
+    pub strict_vars: bool,
+
+    /// Refuse to run when an unquoted variable value contains shell metacharacters (for workflows)
+    #[arg(long)]
+    pub reject_unsafe_vars: bool,
+
+    /// Write the final workflow variables to a JSON file (for workflows)
+    #[arg(long)]
+    pub dump_context: Option<String>,
+
+    /// Seed workflow variables from a JSON file written by --dump-context
+    #[arg(long)]
+    pub load_context: Option<String>,
+
+    /// Do not mask secret-looking values in the dumped context
+    #[arg(long, requires = "dump_context")]
+    pub include_secrets: bool,
+
+    /// Report the environment variables the command exports or changes (for simple commands)
+    #[arg(long, conflicts_with = "raw")]
+    pub diff_env: bool,
+
+    /// Record the run as an asciinema v2 cast file (for workflows)
+    #[arg(long, value_name = "FILE")]
+    pub record: Option<String>,
+
+    /// Write each step's stdout and stderr to files in this directory (for workflows)
+    #[arg(long, value_name = "DIR")]
+    pub save_outputs: Option<String>,
+
+    /// Tolerate up to N failed steps, ignoring each step's continue-on-error, and stop at the next (for workflows)
+    #[arg(long, value_name = "N")]
+    pub max_step_failures: Option<usize>,
+
+    /// Run this saved command or shell command whenever a step fails, with the step's name in CLIX_FAILED_STEP (for workflows)
+    #[arg(long, value_name = "COMMAND")]
+    pub on_step_fail: Option<String>,
+
+    /// Print only the command's stdout, and its stderr on stderr, and exit with its exit code (for simple commands)
+    #[arg(long)]
+    pub raw: bool,
+
+    /// Skip the security warnings for this run only; approval prompts still apply and the override is recorded in the run history
+    #[arg(long)]
+    pub allow_dangerous: bool,
+
+    /// Refuse to run, with exit code 3, when the security checks flag any step (for workflows)
+    #[arg(long, conflicts_with = "allow_dangerous")]
+    pub abort_on_security: bool,
+
+    /// Skip the steps the security checks flag and run the rest, instead of asking to approve the whole workflow (for workflows)
+    #[arg(long, conflicts_with_all = ["allow_dangerous", "abort_on_security"])]
+    pub skip_unsafe: bool,
+
+    /// Record the OS, shell, clix version, profile and variables in the run history, with secret-looking values masked
+    #[arg(long)]
+    pub capture_env: bool,
+
+    /// Before running a recognized deletion (rm, rmdir, find -delete, git clean), list what it would remove; a best-effort heuristic
+    #[arg(long, conflicts_with_all = ["raw", "print_command", "explain_plan"])]
+    pub preview_destructive: bool,
+
+    /// Print the command string that would be passed to the shell, without running it (for simple commands)
+    #[arg(long, conflicts_with_all = ["raw", "diff_env", "explain_plan"])]
+    pub print_command: bool,
+
+    /// How to report step results (for workflows)
+    #[arg(
+        long,
+        value_enum,
+        default_value = "text",
+        conflicts_with = "explain_plan"
+    )]
+    pub format: RunFormat,
+
+    /// Write each step's duration and exit code to this CSV file (for workflows)
+    #[arg(long, value_name = "FILE")]
+    pub capture_timing_csv: Option<String>,
+
+    /// Save loop progress and the workflow variables every N iterations, so an interrupted run can be resumed with --continue (for workflows)
+    #[arg(long, value_name = "N")]
+    pub checkpoint_every: Option<usize>,
+
+    /// Skip auth steps, recording them as skipped, when you have already logged in (for workflows)
+    #[arg(long)]
+    pub skip_auth: bool,
+
+    /// Kill a step and fail it after this many seconds, unless the step sets its own timeout_secs (for workflows)
+    #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub step_timeout: Option<u64>,
+
+    /// Resume from the checkpoint an interrupted --checkpoint-every run left, skipping the steps before its loop (for workflows)
+    #[arg(long = "continue", conflicts_with_all = ["explain_plan", "retry"])]
+    pub resume: bool,
+
+    /// File to write the --format junit report to
+    #[arg(long, value_name = "FILE")]
+    pub output: Option<String>,
+
+    /// Run the whole workflow again from the start, up to N more times, while it ends with a failed step (for workflows)
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    pub retry: u32,
+
+    /// Seconds to wait before each --retry
+    #[arg(long, value_name = "SECONDS", default_value_t = 0, requires = "retry")]
+    pub retry_delay: u64,
+
+    /// Print a table of step, status and duration at the end instead of each step's output (for workflows)
+    #[arg(long, conflicts_with_all = ["format", "explain_plan"])]
+    pub summary_only: bool,
+}
+
+#[derive(ValueEnum, Clone, Debug, PartialEq)]
+pub enum RunFormat {
+    /// Progress and a results block on stdout
+    Text,
+    /// One JSON object per step on stdout as it finishes; progress goes to stderr
+    Ndjson,
+    /// Progress and results as for text, plus a JUnit XML report written to --output
+    Junit,
 }
 
 #[derive(Args, Debug)]
@@ -109,6 +380,26 @@ pub struct ListArgs {
     /// List only workflows (no commands)
     #[arg(long)]
     pub workflows_only: bool,
+
+    /// List only the commands and workflows that call this one with `clix run` or `clix flow run`
+    #[arg(long, value_name = "NAME")]
+    pub referenced_by: Option<String>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "detailed")]
+    pub format: ListFormat,
+
+    /// Also show each item's notes (detailed format only)
+    #[arg(short, long)]
+    pub verbose: bool,
+}
+
+#[derive(ValueEnum, Clone, Debug, PartialEq)]
+pub enum ListFormat {
+    /// A block of details per item
+    Detailed,
+    /// One aligned row per item
+    Table,
 }
 
 #[derive(Args, Debug)]
@@ -118,10 +409,122 @@ pub struct RemoveArgs {
 }
 
 #[derive(Args, Debug)]
-pub struct ExportArgs {
-    /// Output file path
+pub struct ValidateArgs {
+    /// Name of the workflow to validate
+    pub name: String,
+
+    /// Lowest issue severity that fails validation
+    #[arg(long, value_enum, default_value = "error")]
+    pub fail_on: Severity,
+
+    /// Print the report as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct CheckStepsArgs {
+    /// JSON file holding the steps, as passed to `add --steps-file`
+    pub file: String,
+
+    /// Print the report as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct LintArgs {
+    /// Name of the workflow to lint
+    pub name: String,
+
+    /// Apply safe automatic fixes and save the workflow
+    #[arg(long)]
+    pub fix: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct ExportScriptArgs {
+    /// Name of the workflow to export
+    pub name: String,
+
+    /// Output script path
     #[arg(short, long)]
     pub output: String,
+}
+
+#[derive(Args, Debug)]
+pub struct SuggestParallelArgs {
+    /// Name of the workflow to analyze
+    pub name: String,
+}
+
+#[derive(Args, Debug)]
+pub struct StepsArgs {
+    /// Name of the workflow
+    pub name: String,
+
+    /// Also list the steps inside conditional, branch and loop blocks, with their depth and parent
+    #[arg(long)]
+    pub flatten: bool,
+
+    /// Print the steps as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct TagsArgs {
+    /// Print the tags and counts as JSON
+    #[arg(long)]
+    pub json: bool,
+
+    /// Print only the tag names, one per line (used by shell completions)
+    #[arg(long, hide = true, conflicts_with = "json")]
+    pub names: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct RetryFailedArgs {
+    /// Only retry workflows with this tag
+    #[arg(short, long)]
+    pub tag: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct ReportArgs {
+    /// File to write the report to (default: stdout)
+    #[arg(short, long)]
+    pub output: Option<String>,
+
+    /// Only count runs from this long ago until now, e.g. 30d or 12h
+    #[arg(long)]
+    pub since: Option<String>,
+
+    /// Report format
+    #[arg(long, value_enum, default_value = "json")]
+    pub format: ReportFormat,
+}
+
+#[derive(Args, Debug)]
+pub struct OpenArgs {
+    /// What to locate (default: the clix data directory)
+    #[arg(long, value_enum)]
+    pub what: Option<DataLocation>,
+
+    /// Also open the location in the file manager
+    #[arg(long)]
+    pub reveal: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct ExportArgs {
+    /// Output file path
+    #[arg(short, long, required_unless_present = "to_clipboard")]
+    pub output: Option<String>,
+
+    /// Copy the export to the system clipboard instead of writing a file
+    #[arg(long, conflicts_with_all = ["output", "only_changed"])]
+    pub to_clipboard: bool,
 
     /// Export only commands with specific tag
     #[arg(short, long)]
@@ -134,17 +537,50 @@ pub struct ExportArgs {
     /// Export workflows only (no commands)
     #[arg(long)]
     pub workflows_only: bool,
+
+    /// Export only items changed since the last --only-changed export
+    #[arg(long)]
+    pub only_changed: bool,
+    /// Export only this command or workflow, with the workflows it calls (repeatable)
+    #[arg(long, value_name = "NAME", conflicts_with_all = ["tag", "commands_only", "workflows_only", "only_changed"])]
+    pub only: Vec<String>,
+
+    /// With --only, also include the commands the exported items call with `clix run`
+    #[arg(long, requires = "only")]
+    pub with_dependencies: bool,
+
+    /// Export format; csv writes a name, type, description, tags, use_count,
+    /// last_used and created_at row per item
+    #[arg(
+        long,
+        value_enum,
+        default_value = "json",
+        conflicts_with = "only_changed"
+    )]
+    pub format: ExportFormat,
 }
 
 #[derive(Args, Debug)]
 pub struct ImportArgs {
     /// Input file path
-    #[arg(short, long)]
-    pub input: String,
+    #[arg(short, long, required_unless_present = "from_clipboard")]
+    pub input: Option<String>,
+
+    /// Read the export JSON from the system clipboard instead of a file
+    #[arg(long, conflicts_with = "input")]
+    pub from_clipboard: bool,
 
     /// Overwrite existing commands with the same name
     #[arg(short, long)]
     pub overwrite: bool,
+
+    /// Ask whether to skip, overwrite, merge or rename each item whose name is taken
+    #[arg(long)]
+    pub interactive: bool,
+
+    /// Don't prompt; resolve conflicts as --overwrite says (skip without it)
+    #[arg(short, long)]
+    pub yes: bool,
 }
 
 #[derive(Args, Debug)]
@@ -159,6 +595,26 @@ pub struct AskArgs {
     /// Continue an existing conversation session by ID
     #[arg(short, long)]
     pub session: Option<String>,
+
+    /// Use this model for this call instead of the configured one
+    #[arg(long)]
+    pub model: Option<String>,
+
+    /// Always ask Claude instead of reusing a cached answer
+    #[arg(long)]
+    pub no_cache: bool,
+
+    /// Print the prompt that would be sent to Claude and exit
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Only act on suggestions to create commands or workflows, never to run them
+    #[arg(long, conflicts_with = "run_only")]
+    pub create_only: bool,
+
+    /// Only act on suggestions to run stored commands or workflows, never to create them
+    #[arg(long)]
+    pub run_only: bool,
 }
 
 #[derive(Subcommand, Debug)]
@@ -177,6 +633,21 @@ pub enum SettingsCommands {
 
     /// Set the AI max tokens
     SetAiMaxTokens(SetAiMaxTokensArgs),
+
+    /// Set a default value used by every workflow (e.g. REGION=eu)
+    SetVarDefault(SetVarDefaultArgs),
+
+    /// Store a secret in the OS keychain; the only one clix reads is anthropic-api-key
+    SetSecret(SetSecretArgs),
+
+    /// Print a setting by dotted path (e.g. ai_settings.temperature)
+    Get(SettingsGetArgs),
+
+    /// Change a setting by dotted path (e.g. ai_settings.temperature 0.5)
+    Set(SettingsSetArgs),
+
+    /// Restore settings to their defaults
+    Reset(SettingsResetArgs),
 }
 
 #[derive(Args, Debug)]
@@ -197,6 +668,44 @@ pub struct SetAiMaxTokensArgs {
     pub max_tokens: usize,
 }
 
+#[derive(Args, Debug)]
+pub struct SetSecretArgs {
+    /// Name of the secret (anthropic-api-key)
+    pub name: String,
+}
+
+#[derive(Args, Debug)]
+pub struct SettingsGetArgs {
+    /// Dotted path of the setting
+    pub key: String,
+}
+
+#[derive(Args, Debug)]
+pub struct SettingsSetArgs {
+    /// Dotted path of the setting
+    pub key: String,
+
+    /// New value, parsed to match the setting's type
+    pub value: String,
+}
+
+#[derive(Args, Debug)]
+pub struct SettingsResetArgs {
+    /// Which settings to reset
+    #[arg(long, value_enum, default_value = "all")]
+    pub section: SettingsSection,
+
+    /// Skip the confirmation prompt
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct SetVarDefaultArgs {
+    /// The default in the format key=value
+    pub assignment: String,
+}
+
 #[derive(Args, Debug)]
 pub struct AddWorkflowVarArgs {
     /// Name of the command/workflow to add the variable to
@@ -211,12 +720,36 @@ pub struct AddWorkflowVarArgs {
     pub description: String,
 
     /// Default value for the variable
-    #[arg(short = 'd', long)]
+    #[arg(long)]
     pub default: Option<String>,
 
     /// Whether the variable is required
     #[arg(short, long)]
     pub required: bool,
+
+    /// Comma-separated values the variable may take, checked by `clix validate`
+    #[arg(long, value_delimiter = ',')]
+    pub allowed_values: Vec<String>,
+
+    /// Single-quote the value when it is substituted into commands
+    #[arg(long)]
+    pub quote: bool,
+
+    /// Compute the value from other variables at run time, e.g. '$REGISTRY/$APP:$TAG'
+    #[arg(long, value_name = "EXPRESSION", conflicts_with_all = ["default", "required"])]
+    pub computed: Option<String>,
+
+    /// Kind of value the variable holds, checked when a run starts; enum takes its values from --allowed-values
+    #[arg(long = "type", value_enum, default_value = "string")]
+    pub var_type: VarTypeArg,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VarTypeArg {
+    String,
+    Int,
+    Bool,
+    Enum,
 }
 
 #[derive(Args, Debug)]
@@ -268,6 +801,10 @@ pub struct AddConditionArgs {
     #[arg(long)]
     pub then_file: String,
 
+    /// Elif branch as a condition and its steps file; repeat for more branches
+    #[arg(long, num_args = 2, value_names = ["CONDITION", "STEPS_FILE"])]
+    pub elif: Vec<String>,
+
     /// Steps file for the 'else' block (optional)
     #[arg(long)]
     pub else_file: Option<String>,
@@ -307,6 +844,24 @@ pub struct AddBranchArgs {
     pub default_file: Option<String>,
 }
 
+#[derive(Args, Debug)]
+pub struct CopyStepArgs {
+    /// Name of the workflow to copy the step into
+    pub command_name: String,
+
+    /// Workflow to copy the step from
+    #[arg(long)]
+    pub from: String,
+
+    /// Name of the step to copy; steps nested in conditionals, branches and loops are found too
+    #[arg(long)]
+    pub step: String,
+
+    /// 1-based position to insert the step at (default: the end)
+    #[arg(long)]
+    pub position: Option<usize>,
+}
+
 #[derive(Args, Debug)]
 pub struct ConvertFunctionArgs {
     /// Name for the new command/workflow
@@ -327,16 +882,63 @@ pub struct ConvertFunctionArgs {
     /// Optional tags for categorization
     #[arg(short, long)]
     pub tags: Option<Vec<String>>,
+
+    /// Print the steps the function converts to without saving the workflow
+    #[arg(long)]
+    pub preview: bool,
+
+    /// Replace calls to other functions in the same file with their steps
+    #[arg(long)]
+    pub inline_calls: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct ConvertMakefileArgs {
+    /// Path to the Makefile
+    pub path: String,
+
+    /// Convert only this target (default: every .PHONY target, or every target if none is phony)
+    #[arg(long)]
+    pub target: Option<String>,
+
+    /// Optional tags for categorization
+    #[arg(short, long)]
+    pub tags: Option<Vec<String>>,
+}
+
+#[derive(Args, Debug)]
+pub struct ConvertNpmArgs {
+    /// Path to the package.json
+    pub path: String,
+
+    /// Tags to add besides 'npm'
+    #[arg(short, long)]
+    pub tags: Option<Vec<String>>,
+}
+
+#[derive(Args, Debug)]
+pub struct DoctorArgs {
+    /// Repair the problems that can be fixed, asking before each fix
+    #[arg(long)]
+    pub fix: bool,
+
+    /// Apply fixes without asking
+    #[arg(short, long, requires = "fix")]
+    pub yes: bool,
 }
 
 #[derive(Args, Debug)]
 pub struct CompletionsArgs {
-    /// The shell to generate completions for
+    /// The shell to generate completions for; detected from $SHELL when omitted
     #[arg(value_enum)]
-    pub shell: Shell,
+    pub shell: Option<Shell>,
+
+    /// Print the supported shells and exit
+    #[arg(long, conflicts_with = "shell")]
+    pub list_shells: bool,
 }
 
-#[derive(ValueEnum, Clone, Debug)]
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Shell {
     Bash,
     Zsh,
@@ -345,6 +947,88 @@ pub enum Shell {
     Elvish,
 }
 
+impl Shell {
+    /// The shell a program path such as `/bin/zsh` or `pwsh.exe` runs, if supported
+    pub fn from_path(path: &str) -> Option<Self> {
+        let program = path.rsplit(['/', '\\']).next().unwrap_or(path);
+        let program = program.strip_suffix(".exe").unwrap_or(program);
+        match program.to_ascii_lowercase().as_str() {
+            "bash" => Some(Shell::Bash),
+            "zsh" => Some(Shell::Zsh),
+            "fish" => Some(Shell::Fish),
+            "pwsh" | "powershell" => Some(Shell::PowerShell),
+            "elvish" => Some(Shell::Elvish),
+            _ => None,
+        }
+    }
+
+    /// Detect the user's shell from `$SHELL`, falling back to PowerShell when
+    /// `PSModulePath` is set. `lookup` reads an environment variable.
+    pub fn detect(lookup: impl Fn(&str) -> Option<String>) -> Option<Self> {
+        if let Some(shell) = lookup("SHELL").as_deref().and_then(Self::from_path) {
+            return Some(shell);
+        }
+        lookup("PSModulePath").map(|_| Shell::PowerShell)
+    }
+
+    /// Make a generated completion script offer the stored tags, read from
+    /// `clix tags --names`, for `--tag` and `--tags` values. PowerShell and
+    /// Elvish scripts are returned unchanged.
+    pub fn with_tag_completion(self, script: String) -> String {
+        match self {
+            Shell::Bash => {
+                let script = script.replace("complete -F _clix ", "complete -F _clix_tags ");
+                format!(
+                    "{}
+{}",
+                    script,
+                    r#"_clix_tags() {
+    local prev="${COMP_WORDS[COMP_CWORD-1]}"
+    if [[ "${prev}" == "--tag" || "${prev}" == "--tags" ]]; then
+        COMPREPLY=( $(compgen -W "$(clix tags --names 2>/dev/null)" -- "${COMP_WORDS[COMP_CWORD]}") )
+        return 0
+    fi
+    _clix "$@"
+}
+"#
+                )
+            }
+            Shell::Zsh => {
+                let helper = r#"_clix_tags() {
+    local -a tags
+    tags=(${(f)"$(clix tags --names 2>/dev/null)"})
+    compadd -a tags
+}
+"#;
+                let script = script
+                    .replace(":TAG:_default", ":TAG:_clix_tags")
+                    .replace(":TAGS:_default", ":TAGS:_clix_tags");
+                // The helper has to exist before the script's last lines call _clix
+                match script.split_once('\n') {
+                    Some((compdef, rest)) => format!("{}\n\n{}{}", compdef, helper, rest),
+                    None => script,
+                }
+            }
+            Shell::Fish => format!(
+                "{}{}",
+                script,
+                "complete -c clix -l tag -f -a '(clix tags --names 2>/dev/null)'\n\
+                 complete -c clix -l tags -f -a '(clix tags --names 2>/dev/null)'\n"
+            ),
+            Shell::PowerShell | Shell::Elvish => script,
+        }
+    }
+
+    /// Names of the supported shells as accepted on the command line
+    pub fn names() -> Vec<String> {
+        Self::value_variants()
+            .iter()
+            .filter_map(|shell| shell.to_possible_value())
+            .map(|value| value.get_name().to_string())
+            .collect()
+    }
+}
+
 #[derive(Subcommand, Debug)]
 pub enum GitCommands {
     /// Add a git repository for sharing commands
@@ -361,6 +1045,16 @@ pub enum GitCommands {
 
     /// Sync (pull) and show status of all repositories
     Status,
+
+    /// Pull and load commands from all repositories, or just one
+    Sync(GitSyncArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct GitSyncArgs {
+    /// Only sync the repository with this name
+    #[arg(long)]
+    pub repo: Option<String>,
 }
 
 #[derive(Args, Debug)]
@@ -378,3 +1072,136 @@ pub struct RemoveRepoArgs {
     /// Name of the repository to remove
     pub name: String,
 }
+
+#[derive(Subcommand, Debug)]
+pub enum BulkCommands {
+    /// Run every command and workflow with the tag
+    Run(BulkArgs),
+
+    /// Remove every command and workflow with the tag
+    Remove(BulkArgs),
+
+    /// Export every command and workflow with the tag to a file
+    Export(BulkExportArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct BulkArgs {
+    /// Tag selecting the items to operate on
+    #[arg(short, long)]
+    pub tag: String,
+
+    /// Skip the confirmation prompt
+    #[arg(short, long)]
+    pub yes: bool,
+
+    /// Only list the items that would be affected
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct BulkExportArgs {
+    #[command(flatten)]
+    pub target: BulkArgs,
+
+    /// Output file path
+    #[arg(short, long)]
+    pub output: String,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ScheduleCommands {
+    /// Run a command or workflow every interval until the duration elapses
+    Run(ScheduleRunArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct TimeEstimateArgs {
+    /// Name of the command or workflow
+    pub name: String,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum HistoryCommands {
+    /// Delete every recorded run
+    Clear(HistoryClearArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct HistoryClearArgs {
+    /// Skip the confirmation prompt
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SnippetCommands {
+    /// Store a snippet, replacing any with the same name
+    Add(SnippetAddArgs),
+
+    /// List stored snippets
+    List,
+
+    /// Remove a snippet
+    Remove(SnippetRemoveArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct SnippetAddArgs {
+    /// Name workflows use to include the snippet
+    pub name: String,
+
+    /// JSON file containing the snippet's steps
+    #[arg(short, long)]
+    pub steps_file: String,
+}
+
+#[derive(Args, Debug)]
+pub struct SnippetRemoveArgs {
+    /// Name of the snippet to remove
+    pub name: String,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum NoteCommands {
+    /// Write a command's notes, in $VISUAL or $EDITOR unless --text is given
+    Set(NoteSetArgs),
+
+    /// Remove a command's notes
+    Clear(NoteClearArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct NoteSetArgs {
+    /// Name of the command or workflow
+    pub name: String,
+
+    /// Use this text instead of opening an editor
+    #[arg(long)]
+    pub text: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct NoteClearArgs {
+    /// Name of the command or workflow
+    pub name: String,
+}
+
+#[derive(Args, Debug)]
+pub struct ScheduleRunArgs {
+    /// Name of the command or workflow to run
+    pub name: String,
+
+    /// Time between runs, e.g. 30s, 5m or 1h
+    #[arg(long)]
+    pub every: String,
+
+    /// How long to keep running, e.g. 1h or 90m
+    #[arg(long = "for", value_name = "DURATION")]
+    pub duration: String,
+
+    /// What to do when a run takes longer than the interval
+    #[arg(long, value_enum, default_value = "skip")]
+    pub overrun: OverrunPolicy,
+}
@@ -1,3 +1,9 @@
 pub mod app;
+pub mod pager;
+pub mod table;
+pub mod theme;
 
 pub use app::{CliArgs, Commands};
+pub use pager::Pager;
+pub use table::Table;
+pub use theme::{Role, Theme, Themed};
@@ -0,0 +1,71 @@
+use std::env;
+use std::io;
+use std::process::{Command as ProcessCommand, Stdio};
+
+/// Pager used when neither `CLIX_PAGER` nor `PAGER` is set
+pub const DEFAULT_PAGER: &str = "less -R";
+
+/// Environment variable naming the pager for clix only; it takes precedence over `PAGER`
+pub const PAGER_ENV: &str = "CLIX_PAGER";
+
+/// A pager program and its arguments
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pager {
+    pub program: String,
+    pub args: Vec<String>,
+}
+
+impl Pager {
+    /// Pick the pager from `CLIX_PAGER`, then `PAGER`, then `less -R`, reading the
+    /// environment through `lookup`. An empty value or `cat` turns paging off.
+    pub fn select<F>(lookup: F) -> Option<Pager>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let command = lookup(PAGER_ENV)
+            .or_else(|| lookup("PAGER"))
+            .unwrap_or_else(|| DEFAULT_PAGER.to_string());
+
+        let mut words = command.split_whitespace().map(str::to_string);
+        let program = words.next()?;
+        if program == "cat" {
+            return None;
+        }
+
+        Some(Pager {
+            program,
+            args: words.collect(),
+        })
+    }
+
+    /// The pager configured in the environment, if paging isn't turned off
+    pub fn from_env() -> Option<Pager> {
+        Self::select(|name| env::var(name).ok())
+    }
+
+    /// Page only when asked to, not told not to, and writing to a terminal
+    pub fn should_page(requested: bool, disabled: bool, stdout_is_terminal: bool) -> bool {
+        requested && !disabled && stdout_is_terminal
+    }
+
+    /// Run this clix invocation again with `--no-pager`, its stdout piped into the
+    /// pager, and return its exit code once the pager has closed
+    pub fn page_current_process(&self) -> io::Result<i32> {
+        let mut pager = ProcessCommand::new(&self.program)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .spawn()?;
+        let pager_input = pager.stdin.take().expect("stdin is piped");
+
+        // The copy writes to a pipe, so colour has to be forced on for the pager to show it
+        let status = ProcessCommand::new(env::current_exe()?)
+            .arg("--no-pager")
+            .args(env::args_os().skip(1))
+            .env("CLICOLOR_FORCE", "1")
+            .stdout(Stdio::from(pager_input))
+            .status();
+
+        pager.wait()?;
+        Ok(status?.code().unwrap_or(1))
+    }
+}
@@ -0,0 +1,73 @@
+use colored::Colorize;
+
+/// Plain text table with left-aligned columns sized to their widest cell
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(headers: &[&str]) -> Self {
+        Self {
+            headers: headers.iter().map(|header| header.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn add_row(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+
+    /// Render the header and rows, one line each
+    pub fn render(&self) -> String {
+        let widths: Vec<usize> = (0..self.headers.len())
+            .map(|column| {
+                self.rows
+                    .iter()
+                    .filter_map(|row| row.get(column))
+                    .chain(std::iter::once(&self.headers[column]))
+                    .map(|cell| cell.chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        // Pad before colouring so escape codes don't throw off the alignment
+        let header = Self::render_row(&self.headers, &widths);
+        let mut lines = vec![header.bold().to_string()];
+        lines.extend(self.rows.iter().map(|row| Self::render_row(row, &widths)));
+        lines.push(String::new());
+        lines.join("\n")
+    }
+
+    fn render_row(cells: &[String], widths: &[usize]) -> String {
+        let padded: Vec<String> = widths
+            .iter()
+            .enumerate()
+            .map(|(column, width)| {
+                let cell = cells.get(column).map(String::as_str).unwrap_or("");
+                format!("{:<width$}", cell, width = width)
+            })
+            .collect();
+        padded.join("  ").trim_end().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_columns_are_aligned() {
+        colored::control::set_override(false);
+
+        let mut table = Table::new(&["Name", "Uses"]);
+        table.add_row(vec!["deploy-staging".to_string(), "3".to_string()]);
+        table.add_row(vec!["ls".to_string(), "12".to_string()]);
+
+        assert_eq!(
+            table.render(),
+            "Name            Uses\ndeploy-staging  3\nls              12\n"
+        );
+    }
+}
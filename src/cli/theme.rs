@@ -0,0 +1,106 @@
+use clap::ValueEnum;
+use colored::{ColoredString, Colorize};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Named palettes mapping the roles of output text to colors
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Theme {
+    /// Blue headers, green success, red errors and yellow warnings
+    #[default]
+    Default,
+    /// The bright variants, bold throughout
+    HighContrast,
+    /// No color; headers and status labels are bold
+    Monochrome,
+}
+
+/// What a piece of output text is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    /// Section titles and progress labels
+    Header,
+    Success,
+    Error,
+    Warning,
+    /// Field names in summaries
+    Label,
+    /// Values worth noticing that aren't warnings
+    Note,
+}
+
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+impl Theme {
+    /// Use this theme for all output from now on
+    pub fn apply(self) {
+        CURRENT.store(self as u8, Ordering::Relaxed);
+    }
+
+    /// The theme set by `apply`, or the default one
+    pub fn current() -> Theme {
+        match CURRENT.load(Ordering::Relaxed) {
+            1 => Theme::HighContrast,
+            2 => Theme::Monochrome,
+            _ => Theme::Default,
+        }
+    }
+
+    /// Style text for a role in this theme
+    pub fn paint(self, text: &str, role: Role) -> ColoredString {
+        match (self, role) {
+            (Theme::Default, Role::Header) => text.blue().bold(),
+            (Theme::Default, Role::Success) => text.green().bold(),
+            (Theme::Default, Role::Error) => text.red().bold(),
+            (Theme::Default, Role::Warning) => text.yellow().bold(),
+            (Theme::Default, Role::Label) => text.green(),
+            (Theme::Default, Role::Note) => text.yellow(),
+
+            (Theme::HighContrast, Role::Header) => text.bright_cyan().bold(),
+            (Theme::HighContrast, Role::Success) => text.bright_green().bold(),
+            (Theme::HighContrast, Role::Error) => text.bright_red().bold(),
+            (Theme::HighContrast, Role::Warning | Role::Note) => text.bright_yellow().bold(),
+            (Theme::HighContrast, Role::Label) => text.bright_white().bold(),
+
+            (Theme::Monochrome, Role::Label | Role::Note) => text.normal(),
+            (Theme::Monochrome, _) => text.bold(),
+        }
+    }
+}
+
+/// Style text by role using the current theme
+pub trait Themed {
+    fn header(&self) -> ColoredString;
+    fn success(&self) -> ColoredString;
+    fn error(&self) -> ColoredString;
+    fn warning(&self) -> ColoredString;
+    fn label(&self) -> ColoredString;
+    fn note(&self) -> ColoredString;
+}
+
+impl Themed for str {
+    fn header(&self) -> ColoredString {
+        Theme::current().paint(self, Role::Header)
+    }
+
+    fn success(&self) -> ColoredString {
+        Theme::current().paint(self, Role::Success)
+    }
+
+    fn error(&self) -> ColoredString {
+        Theme::current().paint(self, Role::Error)
+    }
+
+    fn warning(&self) -> ColoredString {
+        Theme::current().paint(self, Role::Warning)
+    }
+
+    fn label(&self) -> ColoredString {
+        Theme::current().paint(self, Role::Label)
+    }
+
+    fn note(&self) -> ColoredString {
+        Theme::current().paint(self, Role::Note)
+    }
+}
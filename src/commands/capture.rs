@@ -0,0 +1,300 @@
+use crate::error::{ClixError, Result};
+use serde_json::Value;
+use std::io::{self, Read};
+use std::process::{Child, Command as ProcessCommand, Output, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Output kept per stream when no limit is configured
+pub const DEFAULT_MAX_OUTPUT_BYTES: usize = 10 * 1024 * 1024;
+
+/// Run a command like `Command::output`, but keep at most `max_bytes` of stdout and
+/// of stderr. Anything past the limit is read and thrown away so the command can
+/// still finish and report its exit status; a marker notes where the cut was made.
+pub fn output_limited(command: &mut ProcessCommand, max_bytes: usize) -> io::Result<Output> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    let stdout = child.stdout.take().expect("stdout is piped");
+    let stderr = child.stderr.take().expect("stderr is piped");
+
+    // Drain both pipes at once so a full stderr can't stall a chatty stdout
+    let stderr_reader = thread::spawn(move || read_limited(stderr, max_bytes));
+    let stdout = read_limited(stdout, max_bytes)?;
+    let stderr = stderr_reader
+        .join()
+        .map_err(|_| io::Error::other("stderr reader panicked"))??;
+
+    Ok(Output {
+        status: child.wait()?,
+        stdout,
+        stderr,
+    })
+}
+
+/// `output_limited` with a time limit. A command still running after
+/// `timeout` is killed, along with anything it started, and the output it
+/// wrote up to then is returned with `true` for timed out.
+pub fn output_limited_with_timeout(
+    command: &mut ProcessCommand,
+    max_bytes: usize,
+    timeout: Duration,
+) -> io::Result<(Output, bool)> {
+    // Its own process group, so the kill reaches the commands a shell started
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut *command, 0);
+
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    // Outside clix's process group the step no longer sees the terminal's
+    // Ctrl-C, so pass on the signals that stop clix
+    #[cfg(unix)]
+    let _forwarding = interrupts::forward_to(&child);
+
+    let stdout = child.stdout.take().expect("stdout is piped");
+    let stderr = child.stderr.take().expect("stderr is piped");
+    let stdout_reader = thread::spawn(move || read_limited(stdout, max_bytes));
+    let stderr_reader = thread::spawn(move || read_limited(stderr, max_bytes));
+
+    let started = Instant::now();
+    let mut timed_out = false;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if started.elapsed() >= timeout {
+            timed_out = true;
+            kill_process_tree(&mut child);
+            break child.wait()?;
+        }
+        thread::sleep(Duration::from_millis(10));
+    };
+
+    let join = |reader: thread::JoinHandle<io::Result<Vec<u8>>>| {
+        reader
+            .join()
+            .map_err(|_| io::Error::other("output reader panicked"))?
+    };
+    Ok((
+        Output {
+            status,
+            stdout: join(stdout_reader)?,
+            stderr: join(stderr_reader)?,
+        },
+        timed_out,
+    ))
+}
+
+fn kill_process_tree(child: &mut Child) {
+    #[cfg(unix)]
+    {
+        let _ = ProcessCommand::new("kill")
+            .args(["-KILL", "--", &format!("-{}", child.id())])
+            .stderr(Stdio::null())
+            .status();
+    }
+    let _ = child.kill();
+}
+
+#[cfg(unix)]
+mod interrupts {
+    use std::process::Child;
+    use std::sync::Once;
+    use std::sync::atomic::{AtomicI32, Ordering};
+
+    /// Process group of the step being run with a time limit, or 0
+    static STEP_GROUP: AtomicI32 = AtomicI32::new(0);
+    static INSTALL: Once = Once::new();
+
+    /// Stops forwarding signals to the step's group when dropped
+    pub struct Forwarding;
+
+    impl Drop for Forwarding {
+        fn drop(&mut self) {
+            STEP_GROUP.store(0, Ordering::SeqCst);
+        }
+    }
+
+    /// Send SIGINT and SIGTERM on to the child's process group until the
+    /// returned guard is dropped. clix itself still stops on them as before.
+    pub fn forward_to(child: &Child) -> Forwarding {
+        INSTALL.call_once(|| {
+            let handler = forward as extern "C" fn(libc::c_int) as libc::sighandler_t;
+            for signal in [libc::SIGINT, libc::SIGTERM] {
+                // SAFETY: the handler only calls async-signal-safe functions
+                unsafe {
+                    // A signal clix was started ignoring stays ignored
+                    if libc::signal(signal, handler) == libc::SIG_IGN {
+                        libc::signal(signal, libc::SIG_IGN);
+                    }
+                }
+            }
+        });
+        STEP_GROUP.store(child.id() as i32, Ordering::SeqCst);
+        Forwarding
+    }
+
+    extern "C" fn forward(signal: libc::c_int) {
+        let group = STEP_GROUP.load(Ordering::SeqCst);
+        // SAFETY: kill, signal and raise are async-signal-safe
+        unsafe {
+            if group > 0 {
+                libc::kill(-group, signal);
+            }
+            // Then take the default action, as if no handler were installed
+            libc::signal(signal, libc::SIG_DFL);
+            libc::raise(signal);
+        }
+    }
+}
+
+/// Text appended to output that hit the limit
+pub fn truncation_marker(max_bytes: usize) -> String {
+    format!("\n[output truncated at {} bytes]\n", max_bytes)
+}
+
+fn read_limited<R: Read>(mut reader: R, max_bytes: usize) -> io::Result<Vec<u8>> {
+    let mut kept = Vec::new();
+    (&mut reader)
+        .take(max_bytes as u64)
+        .read_to_end(&mut kept)?;
+
+    let discarded = io::copy(&mut reader, &mut io::sink())?;
+    if discarded > 0 {
+        kept.extend_from_slice(truncation_marker(max_bytes).as_bytes());
+    }
+    Ok(kept)
+}
+
+/// Parse command output as JSON and return the value at `path`: strings as
+/// they are, anything else as compact JSON. Paths are a small JSONPath subset:
+/// an optional leading `$`, then `.key`, `["key"]` and `[index]` segments.
+pub fn json_path_value(stdout: &[u8], path: &str) -> Result<String> {
+    let document: Value = serde_json::from_slice(stdout)
+        .map_err(|e| ClixError::ValidationError(format!("Output is not valid JSON: {}", e)))?;
+
+    let mut value = &document;
+    for segment in parse_json_path(path)? {
+        let next = match &segment {
+            PathSegment::Key(key) => value.get(key.as_str()),
+            PathSegment::Index(index) => value.get(*index),
+        };
+        value = next.ok_or_else(|| {
+            ClixError::ValidationError(format!("JSON output has nothing at '{}'", path))
+        })?;
+    }
+
+    Ok(match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    })
+}
+
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+fn parse_json_path(path: &str) -> Result<Vec<PathSegment>> {
+    let invalid = || ClixError::ValidationError(format!("Invalid JSON path '{}'", path));
+    let mut rest = path.trim();
+    rest = rest.strip_prefix('$').unwrap_or(rest);
+
+    let mut segments = Vec::new();
+    let mut first = true;
+    while !rest.is_empty() {
+        if let Some(inner) = rest.strip_prefix('[') {
+            let end = inner.find(']').ok_or_else(invalid)?;
+            let token = inner[..end].trim();
+            let quoted = token
+                .strip_prefix('"')
+                .and_then(|t| t.strip_suffix('"'))
+                .or_else(|| token.strip_prefix('\'').and_then(|t| t.strip_suffix('\'')));
+            segments.push(match quoted {
+                Some(key) => PathSegment::Key(key.to_string()),
+                None => PathSegment::Index(token.parse().map_err(|_| invalid())?),
+            });
+            rest = &inner[end + 1..];
+        } else {
+            // A bare key may start the path; later keys need a leading dot
+            let key_start = match rest.strip_prefix('.') {
+                Some(after_dot) => after_dot,
+                None if first => rest,
+                None => return Err(invalid()),
+            };
+            let end = key_start.find(['.', '[']).unwrap_or(key_start.len());
+            if end == 0 {
+                // `.` on its own selects the whole document, as in jq
+                if key_start.is_empty() && segments.is_empty() {
+                    break;
+                }
+                return Err(invalid());
+            }
+            segments.push(PathSegment::Key(key_start[..end].to_string()));
+            rest = &key_start[end..];
+        }
+        first = false;
+    }
+    Ok(segments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_under_the_limit_is_untouched() {
+        let output =
+            output_limited(ProcessCommand::new("sh").args(["-c", "printf hello"]), 5).unwrap();
+        assert_eq!(output.stdout, b"hello");
+        assert!(output.status.success());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_timeout_kills_the_command_and_keeps_its_output() {
+        let started = Instant::now();
+        let (output, timed_out) = output_limited_with_timeout(
+            ProcessCommand::new("sh").args(["-c", "echo started; sleep 30"]),
+            1024,
+            Duration::from_millis(200),
+        )
+        .unwrap();
+        assert!(timed_out);
+        assert_eq!(output.stdout, b"started\n");
+        assert!(started.elapsed() < Duration::from_secs(10));
+
+        let (output, timed_out) = output_limited_with_timeout(
+            ProcessCommand::new("sh").args(["-c", "printf done"]),
+            1024,
+            Duration::from_secs(10),
+        )
+        .unwrap();
+        assert!(!timed_out);
+        assert_eq!(output.stdout, b"done");
+    }
+
+    #[test]
+    fn test_json_path_value() {
+        let output = br#"{"id": "abc", "items": [{"name": "web", "port": 80}], "a b": true}"#;
+        assert_eq!(json_path_value(output, ".id").unwrap(), "abc");
+        assert_eq!(json_path_value(output, "$.items[0].name").unwrap(), "web");
+        assert_eq!(json_path_value(output, "items[0].port").unwrap(), "80");
+        assert_eq!(json_path_value(output, "[\"a b\"]").unwrap(), "true");
+        assert_eq!(
+            json_path_value(output, ".items[0]").unwrap(),
+            r#"{"name":"web","port":80}"#
+        );
+
+        assert!(json_path_value(output, ".missing").is_err());
+        assert!(json_path_value(output, ".items[3]").is_err());
+        assert!(json_path_value(output, ".items[x]").is_err());
+        assert!(json_path_value(b"not json", ".id").is_err());
+    }
+}
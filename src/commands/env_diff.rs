@@ -0,0 +1,136 @@
+use crate::commands::capture::output_limited;
+use crate::error::{ClixError, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use std::process::{Command as ProcessCommand, Output};
+
+/// Environment variables a command added, changed or removed
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct EnvDiff {
+    /// Name and new value, sorted by name
+    pub added: Vec<(String, String)>,
+    /// Name, old value and new value, sorted by name
+    pub changed: Vec<(String, String, String)>,
+    /// Names, sorted
+    pub removed: Vec<String>,
+}
+
+impl EnvDiff {
+    /// Compare two environment snapshots
+    pub fn between(before: &HashMap<String, String>, after: &HashMap<String, String>) -> Self {
+        let before: BTreeMap<_, _> = before.iter().collect();
+        let after: BTreeMap<_, _> = after.iter().collect();
+        let mut diff = EnvDiff::default();
+
+        for (name, value) in &after {
+            match before.get(name) {
+                None => diff.added.push((name.to_string(), value.to_string())),
+                Some(old) if old != value => {
+                    diff.changed
+                        .push((name.to_string(), old.to_string(), value.to_string()))
+                }
+                Some(_) => {}
+            }
+        }
+        diff.removed = before
+            .keys()
+            .filter(|name| !after.contains_key(*name))
+            .map(|name| name.to_string())
+            .collect();
+
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Run a shell command and report how it changed the shell's exported environment.
+/// Both snapshots come from the same shell, so variables the shell sets itself
+/// don't show up as changes.
+pub fn run_with_env_diff(
+    command: &str,
+    max_output_bytes: usize,
+    env: &HashMap<String, String>,
+) -> Result<(Output, EnvDiff)> {
+    if cfg!(target_os = "windows") {
+        return Err(ClixError::InvalidInput(
+            "Environment diffing needs a POSIX shell and is not supported on Windows".to_string(),
+        ));
+    }
+
+    let snapshot_dir = std::env::temp_dir().join(format!("clix-env-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&snapshot_dir)?;
+    let before_path = snapshot_dir.join("before");
+    let after_path = snapshot_dir.join("after");
+
+    // The snapshot paths are positional parameters so they stay out of the environment
+    let script = format!(
+        "env -0 > \"$1\"\n{}\n__clix_status=$?\nenv -0 > \"$2\"\nexit $__clix_status",
+        command
+    );
+    let output = output_limited(
+        ProcessCommand::new("sh")
+            .arg("-c")
+            .arg(&script)
+            .arg("sh")
+            .arg(&before_path)
+            .arg(&after_path)
+            .envs(env),
+        max_output_bytes,
+    );
+
+    let snapshots = read_snapshot(&before_path).and_then(|before| {
+        if !after_path.exists() {
+            return Err(ClixError::CommandExecutionFailed(
+                "The command exited before its environment could be captured".to_string(),
+            ));
+        }
+        Ok(EnvDiff::between(&before, &read_snapshot(&after_path)?))
+    });
+    let _ = fs::remove_dir_all(&snapshot_dir);
+
+    let output = output.map_err(|e| {
+        ClixError::CommandExecutionFailed(format!("Failed to execute command: {}", e))
+    })?;
+    Ok((output, snapshots?))
+}
+
+/// Parse the NUL-separated output of `env -0`
+fn read_snapshot(path: &Path) -> Result<HashMap<String, String>> {
+    let content = fs::read(path)?;
+    Ok(String::from_utf8_lossy(&content)
+        .split('\0')
+        .filter_map(|entry| entry.split_once('='))
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_between_sorts_changes() {
+        let before = HashMap::from([
+            ("KEEP".to_string(), "1".to_string()),
+            ("EDIT".to_string(), "old".to_string()),
+            ("DROP".to_string(), "x".to_string()),
+        ]);
+        let after = HashMap::from([
+            ("KEEP".to_string(), "1".to_string()),
+            ("EDIT".to_string(), "new".to_string()),
+            ("NEW".to_string(), "y".to_string()),
+        ]);
+
+        let diff = EnvDiff::between(&before, &after);
+        assert_eq!(diff.added, vec![("NEW".to_string(), "y".to_string())]);
+        assert_eq!(
+            diff.changed,
+            vec![("EDIT".to_string(), "old".to_string(), "new".to_string())]
+        );
+        assert_eq!(diff.removed, vec!["DROP".to_string()]);
+    }
+}
@@ -1,40 +1,391 @@
+use crate::cli::theme::Themed;
+use crate::commands::capture::{
+    DEFAULT_MAX_OUTPUT_BYTES, json_path_value, output_limited, output_limited_with_timeout,
+};
+use crate::commands::env_diff::{EnvDiff, run_with_env_diff};
 use crate::commands::expression::ExpressionEvaluator;
 use crate::commands::models::{Command, ConditionalAction, StepType, Workflow, WorkflowStep};
-use crate::commands::variables::{VariableProcessor, WorkflowContext};
+use crate::commands::plan::ExecutionPlan;
+use crate::commands::progress::{ProgressRedirect, progress, progress_inline};
+use crate::commands::recording::CastRecorder;
+use crate::commands::result::CommandResult;
+use crate::commands::variables::{
+    BackgroundProcess, VariableCache, VariableProcessor, WorkflowContext,
+};
 use crate::error::{ClixError, Result};
-use crate::security::{CommandSanitizer, SecurityConfig, SecurityValidator};
-use colored::Colorize;
-use std::collections::HashMap;
+use crate::security::{CommandSanitizer, DestructivePreview, SecurityConfig, SecurityValidator};
+use crate::share::report::csv_field;
+use crate::storage::{CheckpointStorage, LockStorage, LoopCheckpoint, file_stem};
+use serde::Serialize;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fs;
 use std::io::{self, BufRead, Write};
 #[cfg(unix)]
 use std::os::unix::process::ExitStatusExt;
 #[cfg(windows)]
 use std::os::windows::process::ExitStatusExt;
-use std::process::{Command as ProcessCommand, Output};
+use std::path::{Path, PathBuf};
+use std::process::{Command as ProcessCommand, Output, Stdio};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// What the step observer is told when a top-level workflow step finishes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StepReport {
+    pub step: String,
+    /// None when the step did not run to an exit status (skipped by a failed
+    /// dependency, killed by a signal or failed to start)
+    pub exit_code: Option<i32>,
+    pub duration_ms: u64,
+    pub stdout_len: usize,
+    /// The step was deliberately not run, so it neither passed nor failed
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub skipped: bool,
+}
+
+/// Callback told about each finished step
+pub type StepObserver = Box<dyn Fn(&StepReport)>;
+
+impl StepReport {
+    fn new(step: &str, result: &Result<Output>, started: Instant) -> Self {
+        Self::from(&CommandResult::from_result(step, result, started.elapsed()))
+    }
+
+    /// Render step timings as CSV with a `step,duration_ms,exit_code` header;
+    /// the exit code is empty for steps that didn't run to an exit status
+    pub fn timing_csv(reports: &[StepReport]) -> String {
+        let mut csv = String::from("step,duration_ms,exit_code\n");
+        for report in reports {
+            csv.push_str(&format!(
+                "{},{},{}\n",
+                csv_field(&report.step),
+                report.duration_ms,
+                report
+                    .exit_code
+                    .map(|code| code.to_string())
+                    .unwrap_or_default()
+            ));
+        }
+        csv
+    }
+}
+
+impl From<&CommandResult> for StepReport {
+    fn from(result: &CommandResult) -> Self {
+        StepReport {
+            step: result.name.clone(),
+            exit_code: result.exit_code,
+            duration_ms: result.duration_ms,
+            stdout_len: result.stdout.len(),
+            skipped: result.skipped,
+        }
+    }
+}
+
+/// Per-run switches that change how a workflow is executed
+pub struct ExecutionOptions {
+    /// Prompt for security and per-step approvals
+    pub require_approval: bool,
+    /// Never wait for user input; prompts fall back to their defaults
+    pub non_interactive: bool,
+    /// Pause before each step to run, skip or abort it (ignored when non-interactive)
+    pub step_through: bool,
+    /// Write the final workflow variables to this file
+    pub dump_context: Option<PathBuf>,
+    /// Keep secret-looking values unmasked in the dumped context
+    pub include_secrets: bool,
+    /// Write an asciinema cast of the run to this file
+    pub record_cast: Option<PathBuf>,
+    /// Global variable defaults, overridden by profiles and provided variables
+    pub var_defaults: HashMap<String, String>,
+    /// Source of user answers (stdin when unset)
+    pub input: Option<RefCell<Box<dyn BufRead>>>,
+    /// Directory relative step command files are resolved against (the working directory when unset)
+    pub command_base_dir: Option<PathBuf>,
+    /// Fail steps that reference a variable that isn't defined
+    pub strict_variables: bool,
+    /// Refuse to run when an unquoted variable value contains shell metacharacters
+    pub reject_unsafe_vars: bool,
+    /// Most stdout, and separately stderr, kept from each step
+    pub max_output_bytes: usize,
+    /// Called as each top-level step finishes, in run order
+    pub on_step_finished: Option<StepObserver>,
+    /// Print run progress and prompts to stderr, leaving stdout to the observer
+    pub progress_to_stderr: bool,
+    /// Snippets that `include` steps expand to, by name
+    pub snippets: HashMap<String, Vec<WorkflowStep>>,
+    /// Write each executed step's stdout and stderr to files in this directory
+    pub save_outputs: Option<PathBuf>,
+    /// Keep going past failed top-level steps, whatever their
+    /// `continue_on_error`, until more than this many have failed
+    pub max_step_failures: Option<usize>,
+    /// Reuse values entered at variable prompts, shared between runs
+    pub variable_cache: Option<Rc<VariableCache>>,
+    /// Run commands flagged by the security checks without warning about them
+    pub allow_dangerous: bool,
+    /// Extra environment variables for a simple command's process
+    pub env: HashMap<String, String>,
+    /// Shell command run whenever a workflow step fails, with the step's
+    /// name in `CLIX_FAILED_STEP`
+    pub on_step_fail: Option<String>,
+    /// Data directory single-instance workflows take their lock under
+    /// (the default config directory when unset)
+    pub config_dir: Option<PathBuf>,
+    /// Before running a recognized deletion command, run a listing of what
+    /// it would remove (see `DestructivePreview`)
+    pub preview_destructive: bool,
+    /// Refuse to start a workflow the security checks flag, instead of
+    /// warning and running it
+    pub abort_on_security: bool,
+    /// Check each top-level step as it comes up and skip the ones the
+    /// security checks flag, instead of asking once for the whole workflow
+    pub skip_unsafe: bool,
+    /// Save a loop's progress and the workflow variables every this many
+    /// iterations, so an interrupted run can be resumed
+    pub checkpoint_every: Option<usize>,
+    /// Resume from this checkpoint: steps before its loop are skipped and
+    /// the loop starts after the checkpointed iteration
+    pub resume_from: Option<LoopCheckpoint>,
+    /// Seconds a command step may run when it sets no `timeout_secs` of its own
+    pub step_timeout_secs: Option<u64>,
+    /// Treat auth steps as already done, e.g. when re-running after a
+    /// successful login; they are recorded as skipped without running
+    pub skip_auth: bool,
+}
+
+impl Default for ExecutionOptions {
+    fn default() -> Self {
+        Self {
+            require_approval: true,
+            non_interactive: false,
+            step_through: false,
+            dump_context: None,
+            include_secrets: false,
+            record_cast: None,
+            var_defaults: HashMap::new(),
+            input: None,
+            command_base_dir: None,
+            strict_variables: false,
+            reject_unsafe_vars: false,
+            max_output_bytes: DEFAULT_MAX_OUTPUT_BYTES,
+            on_step_finished: None,
+            progress_to_stderr: false,
+            snippets: HashMap::new(),
+            save_outputs: None,
+            max_step_failures: None,
+            variable_cache: None,
+            allow_dangerous: false,
+            env: HashMap::new(),
+            on_step_fail: None,
+            config_dir: None,
+            preview_destructive: false,
+            abort_on_security: false,
+            skip_unsafe: false,
+            checkpoint_every: None,
+            resume_from: None,
+            step_timeout_secs: None,
+            skip_auth: false,
+        }
+    }
+}
+
+impl ExecutionOptions {
+    /// Read user answers from the given reader instead of stdin
+    pub fn with_input<R: BufRead + 'static>(mut self, input: R) -> Self {
+        self.input = Some(RefCell::new(Box::new(input)));
+        self
+    }
+
+    /// Read a single trimmed line of user input
+    fn read_line(&self) -> Result<String> {
+        io::stdout().flush().map_err(|e| {
+            ClixError::CommandExecutionFailed(format!("Failed to flush stdout: {}", e))
+        })?;
+
+        let mut input = String::new();
+        let read = match &self.input {
+            Some(reader) => reader.borrow_mut().read_line(&mut input),
+            None => io::stdin().lock().read_line(&mut input),
+        };
+        read.map_err(|e| {
+            ClixError::CommandExecutionFailed(format!("Failed to read user input: {}", e))
+        })?;
+
+        Ok(input.trim().to_string())
+    }
+
+    /// The security settings for this run
+    fn security_config(&self) -> SecurityConfig {
+        SecurityConfig {
+            allow_dangerous_commands: self.allow_dangerous,
+            ..SecurityConfig::default()
+        }
+    }
+
+    /// Tell the observer, if any, how a step finished
+    fn report_step(&self, step: &str, result: &Result<Output>, started: Instant) {
+        if let Some(observer) = &self.on_step_finished {
+            observer(&StepReport::new(step, result, started));
+        }
+    }
+}
+
+/// What to do with the next step in step-through mode
+enum StepControl {
+    Run,
+    Skip,
+    Abort,
+}
 
 pub struct CommandExecutor;
 
 impl CommandExecutor {
     pub fn execute_command(command: &Command) -> Result<Output> {
+        Self::execute_command_with_limit(command, DEFAULT_MAX_OUTPUT_BYTES)
+    }
+
+    /// Execute a simple command, keeping at most `max_output_bytes` of each output stream
+    pub fn execute_command_with_limit(
+        command: &Command,
+        max_output_bytes: usize,
+    ) -> Result<Output> {
+        let options = ExecutionOptions {
+            max_output_bytes,
+            ..ExecutionOptions::default()
+        };
+        Self::execute_command_with_options(command, &options)
+    }
+
+    /// Execute a simple command with the output limit, security override and
+    /// environment from `options`
+    pub fn execute_command_with_options(
+        command: &Command,
+        options: &ExecutionOptions,
+    ) -> Result<Output> {
+        let command_str = command.command.as_ref().ok_or_else(|| {
+            ClixError::InvalidCommandFormat(
+                "Command has no executable command string (it may be a workflow)".to_string(),
+            )
+        })?;
+
+        progress!("{} {}", "Executing:".header(), command.name);
+        progress!("{} {}", "Description:".header(), command.description);
+        progress!("{} {}", "Command:".header(), command_str);
+
+        if options.preview_destructive {
+            Self::preview_destructive(command_str, options);
+        }
+
+        // Security validation
+        Self::validate_command_security(command_str, &options.security_config())?;
+
+        Self::run_shell(command_str, options.max_output_bytes, &options.env)
+    }
+
+    /// The command string a simple command passes to the shell, with its
+    /// `{{ placeholders }}` filled from `vars`. Placeholders without a value are
+    /// left as they are.
+    pub fn resolve_command(command: &Command, vars: &HashMap<String, String>) -> Result<String> {
+        let command_str = command.command.as_ref().ok_or_else(|| {
+            ClixError::InvalidCommandFormat(
+                "Command has no executable command string (it may be a workflow)".to_string(),
+            )
+        })?;
+
+        let mut context = WorkflowContext::new();
+        context.merge_variables(vars.clone());
+        Ok(VariableProcessor::process_variables(command_str, &context))
+    }
+
+    /// Execute a simple command and capture its outcome as a `CommandResult`
+    pub fn execute_command_result(
+        command: &Command,
+        options: &ExecutionOptions,
+    ) -> Result<CommandResult> {
+        let started = Instant::now();
+        let output = Self::execute_command_with_options(command, options)?;
+        Ok(CommandResult::from_output(
+            &command.name,
+            &output,
+            started.elapsed(),
+        ))
+    }
+
+    /// Execute a simple command and report the environment variables it changed
+    pub fn execute_command_with_env_diff(
+        command: &Command,
+        options: &ExecutionOptions,
+    ) -> Result<(Output, EnvDiff)> {
         let command_str = command.command.as_ref().ok_or_else(|| {
             ClixError::InvalidCommandFormat(
                 "Command has no executable command string (it may be a workflow)".to_string(),
             )
         })?;
 
-        println!("{} {}", "Executing:".blue().bold(), command.name);
-        println!("{} {}", "Description:".blue().bold(), command.description);
-        println!("{} {}", "Command:".blue().bold(), command_str);
+        progress!("{} {}", "Executing:".header(), command.name);
+        progress!("{} {}", "Description:".header(), command.description);
+        progress!("{} {}", "Command:".header(), command_str);
+
+        if options.preview_destructive {
+            Self::preview_destructive(command_str, options);
+        }
 
         // Security validation
-        Self::validate_command_security(command_str)?;
+        Self::validate_command_security(command_str, &options.security_config())?;
+
+        run_with_env_diff(command_str, options.max_output_bytes, &options.env)
+    }
+
+    /// Execute a simple command without printing anything to stdout
+    pub fn execute_command_raw(command: &Command, options: &ExecutionOptions) -> Result<Output> {
+        let command_str = command.command.as_ref().ok_or_else(|| {
+            ClixError::InvalidCommandFormat(
+                "Command has no executable command string (it may be a workflow)".to_string(),
+            )
+        })?;
 
+        let security = options.security_config();
+        let validator = SecurityValidator::new(security.clone());
+        let sanitized_command = CommandSanitizer::sanitize_command(command_str)?;
+        let security_check = validator.validate_command(&sanitized_command)?;
+
+        // There is no room for an approval prompt when stdout is the payload
+        if security_check.requires_approval {
+            return Err(ClixError::SecurityError(
+                "Command requires security approval; run it without --raw".to_string(),
+            ));
+        }
+
+        if !security.allow_dangerous_commands {
+            for issue in &security_check.issues {
+                eprintln!("{} {}", "Security Warning:".error(), issue);
+            }
+        }
+
+        Self::run_shell(command_str, options.max_output_bytes, &options.env)
+    }
+
+    /// Run a command string through the platform shell, adding `env` to its environment
+    fn run_shell(
+        command_str: &str,
+        max_output_bytes: usize,
+        env: &HashMap<String, String>,
+    ) -> Result<Output> {
         let output = if cfg!(target_os = "windows") {
-            ProcessCommand::new("cmd")
-                .args(["/C", command_str])
-                .output()
+            output_limited(
+                ProcessCommand::new("cmd")
+                    .args(["/C", command_str])
+                    .envs(env),
+                max_output_bytes,
+            )
         } else {
-            ProcessCommand::new("sh").args(["-c", command_str]).output()
+            output_limited(
+                ProcessCommand::new("sh")
+                    .args(["-c", command_str])
+                    .envs(env),
+                max_output_bytes,
+            )
         };
 
         match output {
@@ -46,10 +397,30 @@ impl CommandExecutor {
         }
     }
 
+    /// Show what a recognized deletion command would remove by running a
+    /// read-only listing in its place; other commands are left alone
+    fn preview_destructive(command: &str, options: &ExecutionOptions) {
+        let Some(listing) = DestructivePreview::listing_command(command) else {
+            return;
+        };
+
+        progress!("{} {}", "Destructive preview:".warning(), listing);
+        match Self::run_shell(&listing, options.max_output_bytes, &options.env) {
+            Ok(output) => {
+                for stream in [&output.stdout, &output.stderr] {
+                    let text = String::from_utf8_lossy(stream);
+                    if !text.trim().is_empty() {
+                        progress!("{}", text.trim_end());
+                    }
+                }
+            }
+            Err(e) => progress!("{} {}", "Preview failed:".error(), e),
+        }
+    }
+
     /// Validate command security before execution
-    fn validate_command_security(command: &str) -> Result<()> {
-        let config = SecurityConfig::default();
-        let validator = SecurityValidator::new(config);
+    fn validate_command_security(command: &str, config: &SecurityConfig) -> Result<()> {
+        let validator = SecurityValidator::new(config.clone());
 
         // Sanitize the command first
         let sanitized_command = CommandSanitizer::sanitize_command(command)?;
@@ -57,37 +428,35 @@ impl CommandExecutor {
         // Validate for security issues
         let security_check = validator.validate_command(&sanitized_command)?;
 
-        if !security_check.is_safe {
-            println!("{}", "Security Warning:".red().bold());
+        // The user has acknowledged the risk for this run
+        if !security_check.is_safe && !config.allow_dangerous_commands {
+            progress!("{}", "Security Warning:".error());
             for issue in &security_check.issues {
-                println!("  ⚠️  {}", issue.yellow());
+                progress!("  ⚠️  {}", issue.note());
             }
 
             // Get recommendations
             let recommendations = validator.get_security_recommendations(&sanitized_command);
             if !recommendations.is_empty() {
-                println!("\n{}", "Security Recommendations:".blue().bold());
+                progress!("\n{}", "Security Recommendations:".header());
                 for rec in recommendations {
-                    println!("  💡 {}", rec);
+                    progress!("  💡 {}", rec);
                 }
             }
 
             // For now, we'll warn but still allow execution
             // In production, you might want to block dangerous commands
-            println!(
+            progress!(
                 "\n{}",
                 "⚠️  Command has security concerns but will be executed. Use with caution!"
-                    .yellow()
-                    .bold()
+                    .warning()
             );
         }
 
         if security_check.requires_approval {
-            println!(
+            progress!(
                 "{}",
-                "This command requires additional approval due to security concerns."
-                    .yellow()
-                    .bold()
+                "This command requires additional approval due to security concerns.".warning()
             );
             Self::request_security_approval(&sanitized_command)?;
         }
@@ -97,16 +466,16 @@ impl CommandExecutor {
 
     /// Request security approval from user
     fn request_security_approval(command: &str) -> Result<()> {
-        println!("{}", "🔒 Security Approval Required".red().bold());
-        println!("{} {}", "Command:".blue().bold(), command);
-        println!(
+        progress!("{}", "🔒 Security Approval Required".error());
+        progress!("{} {}", "Command:".header(), command);
+        progress!(
             "{}",
-            "This command has been flagged for security review.".yellow()
+            "This command has been flagged for security review.".note()
         );
 
-        print!(
+        progress_inline!(
             "{} [y/N]: ",
-            "Do you want to proceed with execution?".yellow().bold()
+            "Do you want to proceed with execution?".warning()
         );
         io::stdout().flush().map_err(|e| {
             ClixError::CommandExecutionFailed(format!("Failed to flush stdout: {}", e))
@@ -125,9 +494,9 @@ impl CommandExecutor {
 
         let input = input.trim().to_lowercase();
         if input == "y" || input == "yes" {
-            println!(
+            progress!(
                 "{}",
-                "✅ Security approval granted, proceeding with execution.".green()
+                "✅ Security approval granted, proceeding with execution.".label()
             );
             Ok(())
         } else {
@@ -142,7 +511,12 @@ impl CommandExecutor {
         profile_name: Option<&str>,
         provided_vars: Option<HashMap<String, String>>,
     ) -> Result<Vec<(String, Result<Output>)>> {
-        Self::execute_workflow_with_approval(workflow, profile_name, provided_vars, true)
+        Self::execute_workflow_with_options(
+            workflow,
+            profile_name,
+            provided_vars,
+            &ExecutionOptions::default(),
+        )
     }
 
     /// Execute workflow with optional approval bypass for testing
@@ -152,75 +526,426 @@ impl CommandExecutor {
         provided_vars: Option<HashMap<String, String>>,
         require_approval: bool,
     ) -> Result<Vec<(String, Result<Output>)>> {
-        println!("{} {}", "Executing workflow:".blue().bold(), workflow.name);
-        println!("{} {}", "Description:".blue().bold(), workflow.description);
+        let options = ExecutionOptions {
+            require_approval,
+            ..ExecutionOptions::default()
+        };
+        Self::execute_workflow_with_options(workflow, profile_name, provided_vars, &options)
+    }
+
+    /// Execute workflow with the given execution options
+    pub fn execute_workflow_with_options(
+        workflow: &Workflow,
+        profile_name: Option<&str>,
+        provided_vars: Option<HashMap<String, String>>,
+        options: &ExecutionOptions,
+    ) -> Result<Vec<(String, Result<Output>)>> {
+        let _redirect = ProgressRedirect::new(options.progress_to_stderr);
+        let workflow = &workflow
+            .with_includes_expanded(&options.snippets)?
+            .with_step_defaults_applied()
+            .with_dependency_order()?
+            .with_command_files_loaded(options.command_base_dir.as_deref());
+        WorkflowStep::check_timeouts(&workflow.steps)?;
+
+        // Held until the run returns, whether it completes, aborts or fails
+        let _lock = if workflow.single_instance {
+            let locks = match &options.config_dir {
+                Some(dir) => LockStorage::new_with_dir(dir.clone())?,
+                None => LockStorage::new()?,
+            };
+            Some(locks.acquire(&workflow.name)?)
+        } else {
+            None
+        };
+        progress!("{} {}", "Executing workflow:".header(), workflow.name);
+        progress!("{} {}", "Description:".header(), workflow.description);
+
+        if options.abort_on_security {
+            Self::enforce_security_gate(workflow, &options.security_config())?;
+        }
+
+        if options.preview_destructive {
+            for step in &workflow.steps {
+                // Placeholders are only filled in when the step runs
+                if step.step_type == StepType::Command && !step.command.contains("{{") {
+                    Self::preview_destructive(&step.command, options);
+                }
+            }
+        }
 
-        // Security validation for the entire workflow
-        if require_approval {
-            Self::validate_workflow_security(workflow)?;
+        // Security validation for the entire workflow; with skip_unsafe each
+        // step is checked as it comes up instead
+        if options.require_approval && !options.skip_unsafe {
+            Self::validate_workflow_security(workflow, &options.security_config())?;
         }
 
-        let mut context = Self::setup_workflow_context(workflow, profile_name, provided_vars)?;
+        let mut context =
+            Self::setup_workflow_context(workflow, profile_name, provided_vars, options)?;
+        context.strict_variables = options.strict_variables || workflow.strict_vars;
+        let reject_unsafe = options.reject_unsafe_vars || workflow.reject_unsafe_vars;
+        for warning in VariableProcessor::guard_variables(workflow, &mut context, reject_unsafe)? {
+            progress!("{} {}", "Security Warning:".error(), warning.note());
+        }
+        context.workflow_name = workflow.name.clone();
+        if let Some(checkpoint) = &options.resume_from {
+            progress!(
+                "{} loop '{}' after iteration {}",
+                "Resuming from checkpoint:".header(),
+                checkpoint.loop_step,
+                checkpoint.iteration
+            );
+            context.merge_variables(checkpoint.variables.clone());
+            context.resume_loop = Some(checkpoint.clone());
+        }
         let mut results = Vec::new();
         let mut last_output: Option<Output> = None;
+        let mut current_section: Option<&str> = None;
+        let mut completed = true;
+        let mut failed_steps: HashSet<&str> = HashSet::new();
+        let mut failure_count = 0;
+        let mut recorder = options
+            .record_cast
+            .as_ref()
+            .map(|_| CastRecorder::new(&workflow.name));
+        if let Some(dir) = &options.save_outputs {
+            fs::create_dir_all(dir)?;
+        }
 
         for (index, step) in workflow.steps.iter().enumerate() {
+            // Steps before the checkpointed loop already ran
+            if options
+                .resume_from
+                .as_ref()
+                .is_some_and(|checkpoint| index < checkpoint.step_index)
+            {
+                continue;
+            }
+            context.step_index = index;
+
+            // Announce a section once, when the first of its steps starts
+            let section = step.section.as_deref();
+            if let Some(name) = section
+                && section != current_section
+            {
+                Self::print_section_banner(name);
+            }
+            current_section = section;
+
             Self::print_step_header(step, index);
+            let started = Instant::now();
+
+            // A step never runs after a dependency failed, so neither do its dependents
+            if let Some(dependency) = step
+                .depends_on
+                .iter()
+                .find(|dependency| failed_steps.contains(dependency.as_str()))
+            {
+                let reason = format!("Skipped because dependency '{}' failed", dependency);
+                progress!("{}", reason.note());
+                failed_steps.insert(&step.name);
+                let result = Err(ClixError::CommandExecutionFailed(reason));
+                options.report_step(&step.name, &result, started);
+                results.push((step.name.clone(), result));
+                continue;
+            }
 
-            // Process variables in the step
-            let processed_step = VariableProcessor::process_step(step, &context);
+            if options.step_through && !options.non_interactive {
+                match Self::prompt_step_control(&mut context, options)? {
+                    StepControl::Run => {}
+                    StepControl::Skip => {
+                        let result = Self::skip_step("by request in step-through mode");
+                        options.report_step(&step.name, &result, started);
+                        results.push((step.name.clone(), result));
+                        continue;
+                    }
+                    StepControl::Abort => {
+                        progress!(
+                            "{} aborted in step-through mode",
+                            "Workflow stopped:".warning()
+                        );
+                        completed = false;
+                        break;
+                    }
+                }
+            }
+
+            // Process variables in the step; an undefined variable under
+            // strict variables fails just this step
+            let result = match VariableProcessor::try_process_step(step, &context) {
+                Ok(processed_step) => {
+                    if options.skip_unsafe
+                        && let Some(issues) =
+                            Self::step_security_issues(&processed_step, &options.security_config())?
+                    {
+                        let result = Self::skip_step("flagged by the security checks");
+                        for issue in &issues {
+                            progress!("  ⚠️  {}", issue.note());
+                        }
+                        options.report_step(&step.name, &result, started);
+                        results.push((step.name.clone(), result));
+                        continue;
+                    }
+
+                    // Check if step requires approval
+                    if options.require_approval && processed_step.require_approval {
+                        Self::request_approval(&processed_step)?;
+                    }
+
+                    if let Some(recorder) = &mut recorder {
+                        recorder.output(&format!("Step {} - {}\n", index + 1, step.name));
+                        if !processed_step.command.is_empty() {
+                            recorder.output(&format!("$ {}\n", processed_step.command));
+                        }
+                    }
 
-            // Check if step requires approval
-            if require_approval && processed_step.require_approval {
-                Self::request_approval(&processed_step)?;
+                    // Execute the step
+                    Self::execute_single_step(
+                        &processed_step,
+                        &mut context,
+                        &mut results,
+                        last_output.as_ref(),
+                        options,
+                    )
+                }
+                Err(e) => Err(e),
+            };
+
+            if let Some(recorder) = &mut recorder {
+                match &result {
+                    Ok(output) => {
+                        recorder.output(&String::from_utf8_lossy(&output.stdout));
+                        recorder.output(&String::from_utf8_lossy(&output.stderr));
+                    }
+                    Err(e) => recorder.output(&format!("Error: {}\n", e)),
+                }
             }
 
-            // Execute the step
-            let result = Self::execute_single_step(
-                &processed_step,
-                &mut context,
-                &mut results,
-                last_output.as_ref(),
-            );
+            if let Some(dir) = &options.save_outputs {
+                Self::save_step_output(dir, index, &step.name, &result)?;
+            }
+
+            // A declined confirmation ends the workflow without counting as a failure
+            if let Err(ClixError::WorkflowAborted(reason)) = &result {
+                progress!("{} {}", "Workflow stopped:".warning(), reason);
+                completed = false;
+                break;
+            }
 
             // Update the last_output if this step produced an output
             if let Ok(ref output) = result {
                 last_output = Some(output.clone());
             }
 
-            // Check if we should continue after this step
-            if !Self::should_continue_after_step(&result, &processed_step) {
-                println!(
-                    "{} Command failed, stopping workflow",
-                    "Error:".red().bold()
-                );
-                break;
+            let failed = !Self::was_skipped(&result)
+                && !result.as_ref().is_ok_and(|output| output.status.success());
+            if failed {
+                failed_steps.insert(&step.name);
+                failure_count += 1;
+                if let Some(handler) = &options.on_step_fail {
+                    Self::run_failure_handler(handler, &step.name, &result, options);
+                }
             }
 
-            // Store the result
+            // A failure budget replaces the per-step continue_on_error decision
+            let should_continue = match options.max_step_failures {
+                Some(max) => failure_count <= max,
+                None => Self::should_continue_after_step(&result, step),
+            };
+
+            // Store the result, including the failure that stops the workflow
+            options.report_step(&step.name, &result, started);
             results.push((step.name.clone(), result));
+
+            if !should_continue {
+                match options.max_step_failures {
+                    Some(max) => progress!(
+                        "{} {} steps failed, more than the {} allowed; stopping workflow",
+                        "Error:".error(),
+                        failure_count,
+                        max
+                    ),
+                    None => progress!("{} Command failed, stopping workflow", "Error:".error()),
+                }
+                completed = false;
+                break;
+            }
+        }
+
+        // Background processes never outlive the workflow that started them
+        Self::stop_background_processes(&context);
+
+        // A run that got through every step has no use for its checkpoint
+        if completed && (options.checkpoint_every.is_some() || options.resume_from.is_some()) {
+            Self::checkpoints(options)?.clear(&workflow.name)?;
+        }
+
+        if let (Some(recorder), Some(path)) = (&recorder, &options.record_cast) {
+            recorder.save(path)?;
+            progress!("{} {}", "Recording written to:".header(), path.display());
+        }
+
+        if let Some(path) = &options.dump_context {
+            context.dump_to_file(path, options.include_secrets)?;
+            progress!(
+                "{} {}",
+                "Workflow context written to:".header(),
+                path.display()
+            );
+        }
+
+        if let Some(dir) = &options.save_outputs {
+            progress!("{} {}", "Step outputs written to:".header(), dir.display());
         }
 
         Ok(results)
     }
 
+    /// Run the `--on-step-fail` handler for a failed step. The handler's own
+    /// failure is only reported; it never changes how the workflow goes on.
+    fn run_failure_handler(
+        handler: &str,
+        step_name: &str,
+        result: &Result<Output>,
+        options: &ExecutionOptions,
+    ) {
+        let exit_code = match result {
+            Ok(output) => output.status.code(),
+            Err(ClixError::StepFailed { exit_code, .. }) => *exit_code,
+            Err(_) => None,
+        };
+
+        let mut command = if cfg!(target_os = "windows") {
+            let mut command = ProcessCommand::new("cmd");
+            command.args(["/C", handler]);
+            command
+        } else {
+            let mut command = ProcessCommand::new("sh");
+            command.args(["-c", handler]);
+            command
+        };
+        command.env("CLIX_FAILED_STEP", step_name);
+        if let Some(code) = exit_code {
+            command.env("CLIX_FAILED_EXIT_CODE", code.to_string());
+        }
+
+        progress!("{} {}", "Running failure handler for:".header(), step_name);
+        match output_limited(&mut command, options.max_output_bytes) {
+            Ok(output) => {
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                if !stdout.trim().is_empty() {
+                    progress!("{}", stdout.trim_end());
+                }
+                if !stderr.trim().is_empty() {
+                    progress!("{}", stderr.trim_end());
+                }
+                if !output.status.success() {
+                    progress!(
+                        "{} Failure handler exited with {}",
+                        "Warning:".warning(),
+                        output.status
+                    );
+                }
+            }
+            Err(e) => progress!(
+                "{} Could not run failure handler: {}",
+                "Warning:".warning(),
+                e
+            ),
+        }
+    }
+
+    /// Write a step's output to `<index>-<name>.stdout` and `.stderr`, and its
+    /// exit code to `.exit_code` when there is one, with the index counted
+    /// from 1 and the name made filename-safe by `file_stem`. A step that
+    /// failed without running gets the error as its stderr.
+    fn save_step_output(
+        dir: &Path,
+        index: usize,
+        name: &str,
+        result: &Result<Output>,
+    ) -> Result<()> {
+        let stem = format!("{}-{}", index + 1, file_stem(name));
+
+        let (stdout, stderr, exit_code) = match result {
+            Ok(output) => (
+                output.stdout.clone(),
+                output.stderr.clone(),
+                output.status.code(),
+            ),
+            Err(ClixError::StepFailed {
+                exit_code,
+                stdout,
+                stderr,
+                ..
+            }) => (
+                stdout.clone().into_bytes(),
+                stderr.clone().into_bytes(),
+                *exit_code,
+            ),
+            Err(e) => (Vec::new(), format!("{}\n", e).into_bytes(), None),
+        };
+
+        fs::write(dir.join(format!("{}.stdout", stem)), stdout)?;
+        fs::write(dir.join(format!("{}.stderr", stem)), stderr)?;
+        if let Some(code) = exit_code {
+            fs::write(
+                dir.join(format!("{}.exit_code", stem)),
+                format!("{}\n", code),
+            )?;
+        }
+        Ok(())
+    }
+
     /// Setup workflow context with variables, profiles, and user input
     fn setup_workflow_context(
         workflow: &Workflow,
         profile_name: Option<&str>,
         provided_vars: Option<HashMap<String, String>>,
+        options: &ExecutionOptions,
     ) -> Result<WorkflowContext> {
+        let mut context = Self::seed_workflow_context(
+            workflow,
+            profile_name,
+            provided_vars,
+            &options.var_defaults,
+        );
+
+        // Ask for any missing required variables
+        VariableProcessor::prompt_for_variables_with(
+            workflow,
+            &mut context,
+            options.variable_cache.as_deref(),
+            VariableProcessor::read_stdin_line,
+        )?;
+        VariableProcessor::apply_variable_types(workflow, &mut context)?;
+        VariableProcessor::resolve_computed_variables(workflow, &mut context)?;
+
+        Ok(context)
+    }
+
+    /// Build the starting variables from defaults, a profile and provided values
+    fn seed_workflow_context(
+        workflow: &Workflow,
+        profile_name: Option<&str>,
+        provided_vars: Option<HashMap<String, String>>,
+        var_defaults: &HashMap<String, String>,
+    ) -> WorkflowContext {
         let mut context = WorkflowContext::new();
 
+        // Seed global defaults at the lowest precedence
+        context.merge_variables(var_defaults.clone());
+
         // Apply profile variables if a profile was specified
         if let Some(profile_name) = profile_name {
             if let Some(profile) = workflow.get_profile(profile_name) {
-                println!("{} {}", "Using profile:".blue().bold(), profile.name);
+                progress!("{} {}", "Using profile:".header(), profile.name);
                 context.merge_variables(profile.variables.clone());
             } else {
-                println!(
+                progress!(
                     "{} Profile '{}' not found",
-                    "Warning:".yellow().bold(),
+                    "Warning:".warning(),
                     profile_name
                 );
             }
@@ -231,24 +956,107 @@ impl CommandExecutor {
             context.merge_variables(vars);
         }
 
-        // Ask for any missing required variables
-        VariableProcessor::prompt_for_variables(workflow, &mut context)?;
+        context
+    }
 
-        Ok(context)
+    /// Work out which steps a workflow would run with the given variables, without
+    /// running it. Missing variables fall back to their declared defaults instead of
+    /// being prompted for.
+    pub fn plan_workflow(
+        workflow: &Workflow,
+        profile_name: Option<&str>,
+        provided_vars: Option<HashMap<String, String>>,
+        options: &ExecutionOptions,
+    ) -> Result<ExecutionPlan> {
+        let workflow = &workflow.with_includes_expanded(&options.snippets)?;
+        let mut context = Self::seed_workflow_context(
+            workflow,
+            profile_name,
+            provided_vars,
+            &options.var_defaults,
+        );
+        for variable in &workflow.variables {
+            if let Some(default) = &variable.default_value
+                && !context.has_variable(&variable.name)
+            {
+                context.add_variable(variable.name.clone(), default.clone());
+            }
+        }
+        VariableProcessor::apply_variable_types(workflow, &mut context)?;
+        VariableProcessor::resolve_computed_variables(workflow, &mut context)?;
+
+        ExecutionPlan::build(workflow, &context)
+    }
+
+    /// Ask what to do with the next step, allowing variables to be edited first
+    fn prompt_step_control(
+        context: &mut WorkflowContext,
+        options: &ExecutionOptions,
+    ) -> Result<StepControl> {
+        loop {
+            progress_inline!(
+                "{} [r]un / [s]kip / [e]dit-vars / [a]bort (default: run): ",
+                "Step-through:".header()
+            );
+            match options.read_line()?.to_lowercase().as_str() {
+                "" | "r" | "run" => return Ok(StepControl::Run),
+                "s" | "skip" => return Ok(StepControl::Skip),
+                "a" | "abort" => return Ok(StepControl::Abort),
+                "e" | "edit-vars" => Self::edit_variables(context, options)?,
+                other => progress!("{} Unknown choice '{}'", "Warning:".warning(), other),
+            }
+        }
+    }
+
+    /// Show the workflow variables and read NAME=value overrides until an empty line
+    fn edit_variables(context: &mut WorkflowContext, options: &ExecutionOptions) -> Result<()> {
+        let mut names: Vec<&String> = context.variables.keys().collect();
+        names.sort();
+        for name in names {
+            let value = if WorkflowContext::is_secret_name(name) {
+                "********"
+            } else {
+                context.variables[name].as_str()
+            };
+            progress!("  {} = {}", name, value);
+        }
+
+        progress!("Enter NAME=value to set a variable, or an empty line to finish:");
+        loop {
+            let line = options.read_line()?;
+            if line.is_empty() {
+                return Ok(());
+            }
+            match line.split_once('=') {
+                Some((name, value)) if !name.trim().is_empty() => {
+                    context.add_variable(name.trim().to_string(), value.to_string());
+                }
+                _ => progress!(
+                    "{} Expected NAME=value, got '{}'",
+                    "Warning:".warning(),
+                    line
+                ),
+            }
+        }
+    }
+
+    /// Print a banner introducing a section of steps
+    fn print_section_banner(section: &str) {
+        progress!("\n{}", format!("=== {} ===", section).header());
     }
 
     /// Print step header information
     fn print_step_header(step: &WorkflowStep, index: usize) {
-        println!(
+        progress!(
             "\n{} {} - {}",
-            "Step".blue().bold(),
-            (index + 1).to_string().blue().bold(),
+            "Step".header(),
+            (index + 1).to_string().header(),
             step.name
         );
-        println!("{} {}", "Description:".blue().bold(), step.description);
+        progress!("{} {}", "Description:".header(), step.description);
 
         if !step.command.is_empty() {
-            println!("{} {}", "Command:".blue().bold(), step.command);
+            progress!("{} {}", "Command:".header(), step.command);
         }
     }
 
@@ -258,69 +1066,139 @@ impl CommandExecutor {
         context: &mut WorkflowContext,
         results: &mut Vec<(String, Result<Output>)>,
         last_output: Option<&Output>,
+        options: &ExecutionOptions,
     ) -> Result<Output> {
+        // Command files are read when the workflow starts; one left unread
+        // couldn't be loaded then
+        if let Some(command_file) = &step.command_file {
+            return Err(ClixError::CommandExecutionFailed(format!(
+                "Failed to read command file '{}' for step '{}'",
+                command_file, step.name
+            )));
+        }
+
         match step.step_type {
-            StepType::Command => Self::execute_command_step(step),
-            StepType::Auth => Self::execute_auth_step(step),
+            StepType::Command if step.background => Self::start_background_step(step, context),
+            StepType::Command => {
+                let output = Self::execute_command_step(step, options)?;
+                if let Some(var_name) = &step.capture_var
+                    && output.status.success()
+                {
+                    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+                    context.add_variable(var_name.clone(), value);
+                }
+                if let Some((var_name, path)) = &step.capture_json
+                    && output.status.success()
+                {
+                    match json_path_value(&output.stdout, path) {
+                        Ok(value) => context.add_variable(var_name.clone(), value),
+                        Err(e) => {
+                            let reason = match e {
+                                ClixError::ValidationError(reason) => reason,
+                                other => other.to_string(),
+                            };
+                            let message = format!(
+                                "Step '{}' could not capture '{}': {}",
+                                step.name, var_name, reason
+                            );
+                            if !step.continues_on_error() {
+                                return Err(ClixError::CommandExecutionFailed(message));
+                            }
+                            progress!("{} {}", "Warning:".warning(), message);
+                        }
+                    }
+                }
+
+                // Steps allowed to fail keep their output so `$?` checks can see it
+                if output.status.success() || step.continues_on_error() {
+                    Ok(output)
+                } else {
+                    Err(ClixError::StepFailed {
+                        step: step.name.clone(),
+                        command: step.command.clone(),
+                        exit_code: output.status.code(),
+                        stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+                        stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+                    })
+                }
+            }
+            StepType::Auth if options.skip_auth => {
+                Self::skip_step("authentication already done (--skip-auth)")
+            }
+            StepType::Auth => Self::execute_auth_step(step, options),
             StepType::Conditional => {
-                Self::execute_conditional_step(step, &context.variables, last_output)
+                Self::execute_conditional_step(step, context, last_output, options)
+            }
+            StepType::Branch => Self::execute_branch_step(step, context, results, options),
+            StepType::Loop => Self::execute_loop_step(step, context, results, options),
+            StepType::Confirm => Self::execute_confirm_step(step, options),
+            StepType::WaitFor => Self::execute_wait_for_step(step, options),
+            StepType::StopBackground => {
+                Self::stop_background_processes(context);
+                Ok(Output {
+                    status: std::process::ExitStatus::from_raw(0),
+                    stdout: Vec::new(),
+                    stderr: Vec::new(),
+                })
             }
-            StepType::Branch => Self::execute_branch_step(step, context, results),
-            StepType::Loop => Self::execute_loop_step(step, context, results),
         }
     }
 
     /// Determine if workflow should continue after a step
     fn should_continue_after_step(result: &Result<Output>, step: &WorkflowStep) -> bool {
         match result {
-            Ok(_) => true,
-            Err(_) => step.continue_on_error,
+            Ok(_) | Err(ClixError::StepSkipped(_)) => true,
+            Err(_) => step.continues_on_error(),
         }
     }
 
+    /// Record a step as skipped rather than run, announcing why
+    fn skip_step(reason: &str) -> Result<Output> {
+        let skipped = ClixError::StepSkipped(reason.to_string());
+        progress!("{}", skipped.to_string().note());
+        Err(skipped)
+    }
+
+    /// Whether a step result is a skip; skips are neither passes nor failures
+    pub fn was_skipped(result: &Result<Output>) -> bool {
+        matches!(result, Err(ClixError::StepSkipped(_)))
+    }
+
     /// Validate workflow security before execution
-    fn validate_workflow_security(workflow: &Workflow) -> Result<()> {
-        let config = SecurityConfig::default();
-        let validator = SecurityValidator::new(config);
+    fn validate_workflow_security(workflow: &Workflow, config: &SecurityConfig) -> Result<()> {
+        let validator = SecurityValidator::new(config.clone());
 
         let security_report = validator.validate_workflow(workflow)?;
 
-        if !security_report.is_safe {
-            println!("{}", "🔒 Workflow Security Warning".red().bold());
-            println!(
-                "{}: {}",
-                "Workflow".blue().bold(),
-                security_report.workflow_name
-            );
+        if !security_report.is_safe && !config.allow_dangerous_commands {
+            progress!("{}", "🔒 Workflow Security Warning".error());
+            progress!("{}: {}", "Workflow".header(), security_report.workflow_name);
 
             for issue in &security_report.issues {
-                println!("  ⚠️  {}", issue.yellow());
+                progress!("  ⚠️  {}", issue.note());
             }
 
-            println!("\n{}", "Step-by-step security report:".blue().bold());
+            progress!("\n{}", "Step-by-step security report:".header());
             for step_report in &security_report.step_reports {
                 if !step_report.is_safe {
-                    println!("  📋 {}: {}", "Step".yellow().bold(), step_report.step_name);
+                    progress!("  📋 {}: {}", "Step".warning(), step_report.step_name);
                     for issue in &step_report.issues {
-                        println!("    ⚠️  {}", issue.yellow());
+                        progress!("    ⚠️  {}", issue.note());
                     }
                 }
             }
 
-            println!(
+            progress!(
                 "\n{}",
                 "⚠️  Workflow has security concerns but will be executed. Use with caution!"
-                    .yellow()
-                    .bold()
+                    .warning()
             );
         }
 
         if security_report.requires_approval {
-            println!(
+            progress!(
                 "{}",
-                "This workflow requires additional security approval."
-                    .yellow()
-                    .bold()
+                "This workflow requires additional security approval.".warning()
             );
             Self::request_workflow_security_approval(workflow)?;
         }
@@ -328,22 +1206,60 @@ impl CommandExecutor {
         Ok(())
     }
 
+    /// The security issues of a step, nested steps included, or None when it
+    /// is clean. A step's own `require_approval` doesn't count as an issue.
+    fn step_security_issues(
+        step: &WorkflowStep,
+        config: &SecurityConfig,
+    ) -> Result<Option<Vec<String>>> {
+        let step = WorkflowStep {
+            require_approval: false,
+            ..step.clone()
+        };
+        let report = SecurityValidator::new(config.clone()).validate_workflow_step(&step)?;
+        if report.is_safe && !report.requires_approval {
+            return Ok(None);
+        }
+
+        let mut issues = report.issues;
+        if issues.is_empty() {
+            issues.push("Matches a pattern that requires security approval".to_string());
+        }
+        Ok(Some(issues))
+    }
+
+    /// Fail before any step runs when the workflow has security issues
+    fn enforce_security_gate(workflow: &Workflow, config: &SecurityConfig) -> Result<()> {
+        let report = SecurityValidator::new(config.clone()).validate_workflow(workflow)?;
+        if report.is_safe {
+            return Ok(());
+        }
+
+        progress!("{}", "🔒 Workflow Security Gate".error());
+        for issue in &report.issues {
+            progress!("  ⚠️  {}", issue.note());
+        }
+        Err(ClixError::SecurityGate(format!(
+            "'{}' has {} security issue(s)",
+            workflow.name,
+            report.issues.len()
+        )))
+    }
+
     /// Request workflow-level security approval
     fn request_workflow_security_approval(workflow: &Workflow) -> Result<()> {
-        println!("{}", "🔒 Workflow Security Approval Required".red().bold());
-        println!("{} {}", "Workflow:".blue().bold(), workflow.name);
-        println!("{} {}", "Description:".blue().bold(), workflow.description);
-        println!("{} {}", "Steps:".blue().bold(), workflow.steps.len());
-        println!(
+        progress!("{}", "🔒 Workflow Security Approval Required".error());
+        progress!("{} {}", "Workflow:".header(), workflow.name);
+        progress!("{} {}", "Description:".header(), workflow.description);
+        progress!("{} {}", "Steps:".header(), workflow.steps.len());
+        progress!(
             "{}",
-            "This workflow contains steps that require security review.".yellow()
+            "This workflow contains steps that require security review.".note()
         );
 
-        print!(
+        progress_inline!(
             "{} [y/N]: ",
-            "Do you want to proceed with workflow execution?"
-                .yellow()
-                .bold()
+            "Do you want to proceed with workflow execution?".warning()
         );
         io::stdout().flush().map_err(|e| {
             ClixError::CommandExecutionFailed(format!("Failed to flush stdout: {}", e))
@@ -362,9 +1278,9 @@ impl CommandExecutor {
 
         let input = input.trim().to_lowercase();
         if input == "y" || input == "yes" {
-            println!(
+            progress!(
                 "{}",
-                "✅ Workflow security approval granted, proceeding with execution.".green()
+                "✅ Workflow security approval granted, proceeding with execution.".label()
             );
             Ok(())
         } else {
@@ -374,11 +1290,90 @@ impl CommandExecutor {
         }
     }
 
-    /// Execute a conditional step (if/then/else)
+    /// Execute the steps of one branch of a conditional, returning the last step's output
+    fn execute_conditional_block(
+        label: &str,
+        steps: &[WorkflowStep],
+        parent: &WorkflowContext,
+        options: &ExecutionOptions,
+    ) -> Result<Output> {
+        let mut context = parent.block_context();
+
+        // We'll execute the steps and use the last step's output as our result
+        let mut last_step_output = None;
+        let mut results = Vec::new();
+
+        for (index, step) in steps.iter().enumerate() {
+            progress!(
+                "\n{} {} - {}",
+                label.header(),
+                (index + 1).to_string().header(),
+                step.name
+            );
+
+            // Process variables in the step; an undefined variable under
+            // strict variables fails just this step
+            let result = match VariableProcessor::try_process_step(step, &context) {
+                Ok(processed_step) => {
+                    // Check if step requires approval
+                    if processed_step.require_approval {
+                        Self::request_approval(&processed_step)?;
+                    }
+
+                    // Execute the step
+                    Self::execute_single_step(
+                        &processed_step,
+                        &mut context,
+                        &mut results,
+                        last_step_output.as_ref(),
+                        options,
+                    )
+                }
+                Err(e) => Err(e),
+            };
+
+            if let Err(ClixError::WorkflowAborted(_)) = result {
+                return result;
+            }
+
+            // Update last_step_output if successful
+            if let Ok(ref output) = result {
+                last_step_output = Some(output.clone());
+            }
+
+            // Check if we need to continue
+            let should_continue = Self::should_continue_after_step(&result, step);
+
+            // Store the result
+            results.push((step.name.clone(), result));
+
+            if !should_continue {
+                progress!(
+                    "{} Command failed, stopping conditional block execution",
+                    "Error:".error()
+                );
+                break;
+            }
+        }
+
+        // Return the last output if we have one, or create a success output
+        if let Some(output) = last_step_output {
+            Ok(output)
+        } else {
+            Ok(Output {
+                status: std::process::ExitStatus::from_raw(0),
+                stdout: Vec::new(),
+                stderr: Vec::new(),
+            })
+        }
+    }
+
+    /// Execute a conditional step (if/then/elif/else)
     fn execute_conditional_step(
         step: &WorkflowStep,
-        variables: &HashMap<String, String>,
+        context: &WorkflowContext,
         last_output: Option<&Output>,
+        options: &ExecutionOptions,
     ) -> Result<Output> {
         // Conditional steps must have a conditional property
         let conditional = step.conditional.as_ref().ok_or_else(|| {
@@ -388,25 +1383,27 @@ impl CommandExecutor {
         })?;
 
         // Evaluate the condition
-        println!(
+        progress!(
             "{} {}",
-            "Evaluating condition:".blue().bold(),
+            "Evaluating condition:".header(),
             conditional.condition.expression
         );
 
-        let condition_result = ExpressionEvaluator::evaluate(
+        let variables = &context.variables;
+        let condition_result = ExpressionEvaluator::evaluate_typed(
             &conditional.condition.expression,
             variables,
+            &context.var_types,
             last_output,
         )?;
 
-        println!("{} {}", "Condition result:".blue().bold(), condition_result);
+        progress!("{} {}", "Condition result:".header(), condition_result);
 
         // Store the result in a variable if specified
         if let Some(var_name) = &conditional.condition.variable {
-            println!(
+            progress!(
                 "{} {} = {}",
-                "Setting variable:".blue().bold(),
+                "Setting variable:".header(),
                 var_name,
                 condition_result
             );
@@ -423,6 +1420,35 @@ impl CommandExecutor {
             (Some(ConditionalAction::Return(code)), _) => ConditionalAction::Return(*code),
             (None, true) => ConditionalAction::RunThen,
             (None, false) => {
+                // Try each elif in order; the first true one runs instead of the else block
+                for (index, elif) in conditional.elif_blocks.iter().enumerate() {
+                    progress!(
+                        "{} {}",
+                        "Evaluating elif condition:".header(),
+                        elif.condition.expression
+                    );
+                    let elif_result = ExpressionEvaluator::evaluate_typed(
+                        &elif.condition.expression,
+                        variables,
+                        &context.var_types,
+                        last_output,
+                    )?;
+                    progress!("{} {}", "Condition result:".header(), elif_result);
+
+                    if elif_result {
+                        progress!(
+                            "{}",
+                            format!("Executing 'elif' block {}", index + 1).header()
+                        );
+                        return Self::execute_conditional_block(
+                            "Elif Block Step",
+                            &elif.then_block.steps,
+                            context,
+                            options,
+                        );
+                    }
+                }
+
                 if conditional.else_block.is_some() {
                     ConditionalAction::RunElse
                 } else {
@@ -434,162 +1460,23 @@ impl CommandExecutor {
         // Take the appropriate action
         match action {
             ConditionalAction::RunThen => {
-                println!("{}", "Executing 'then' block".blue().bold());
-                // Execute the steps in the then block
-                let mut context = WorkflowContext::new();
-                context.variables = variables.clone();
-
-                // We'll execute the steps and use the last step's output as our result
-                let mut last_step_output = None;
-                let mut results = Vec::new();
-
-                for (index, step) in conditional.then_block.steps.iter().enumerate() {
-                    println!(
-                        "\n{} {} - {}",
-                        "Then Block Step".blue().bold(),
-                        (index + 1).to_string().blue().bold(),
-                        step.name
-                    );
-
-                    // Process variables in the step
-                    let processed_step = VariableProcessor::process_step(step, &context);
-
-                    // Check if step requires approval
-                    if processed_step.require_approval {
-                        Self::request_approval(&processed_step)?;
-                    }
-
-                    // Execute the step
-                    let result = match processed_step.step_type {
-                        StepType::Command => Self::execute_command_step(&processed_step),
-                        StepType::Auth => Self::execute_auth_step(&processed_step),
-                        StepType::Conditional => Self::execute_conditional_step(
-                            &processed_step,
-                            &context.variables,
-                            last_step_output.as_ref(),
-                        ),
-                        StepType::Branch => {
-                            Self::execute_branch_step(&processed_step, &mut context, &mut results)
-                        }
-                        StepType::Loop => {
-                            Self::execute_loop_step(&processed_step, &mut context, &mut results)
-                        }
-                    };
-
-                    // Update last_step_output if successful
-                    if let Ok(ref output) = result {
-                        last_step_output = Some(output.clone());
-                    }
-
-                    // Check if we need to continue
-                    let should_continue = match &result {
-                        Ok(_) => true,
-                        Err(_) => processed_step.continue_on_error,
-                    };
-
-                    // Store the result
-                    results.push((processed_step.name.clone(), result));
-
-                    if !should_continue {
-                        println!(
-                            "{} Command failed, stopping conditional block execution",
-                            "Error:".red().bold()
-                        );
-                        break;
-                    }
-                }
-
-                // Return the last output if we have one, or create a success output
-                if let Some(output) = last_step_output {
-                    Ok(output)
-                } else {
-                    Ok(Output {
-                        status: std::process::ExitStatus::from_raw(0),
-                        stdout: Vec::new(),
-                        stderr: Vec::new(),
-                    })
-                }
+                progress!("{}", "Executing 'then' block".header());
+                Self::execute_conditional_block(
+                    "Then Block Step",
+                    &conditional.then_block.steps,
+                    context,
+                    options,
+                )
             }
             ConditionalAction::RunElse => {
                 if let Some(else_block) = &conditional.else_block {
-                    println!("{}", "Executing 'else' block".blue().bold());
-
-                    // Execute the steps in the else block
-                    let mut context = WorkflowContext::new();
-                    context.variables = variables.clone();
-
-                    // We'll execute the steps and use the last step's output as our result
-                    let mut last_step_output = None;
-                    let mut results = Vec::new();
-
-                    for (index, step) in else_block.steps.iter().enumerate() {
-                        println!(
-                            "\n{} {} - {}",
-                            "Else Block Step".blue().bold(),
-                            (index + 1).to_string().blue().bold(),
-                            step.name
-                        );
-
-                        // Process variables in the step
-                        let processed_step = VariableProcessor::process_step(step, &context);
-
-                        // Check if step requires approval
-                        if processed_step.require_approval {
-                            Self::request_approval(&processed_step)?;
-                        }
-
-                        // Execute the step
-                        let result = match processed_step.step_type {
-                            StepType::Command => Self::execute_command_step(&processed_step),
-                            StepType::Auth => Self::execute_auth_step(&processed_step),
-                            StepType::Conditional => Self::execute_conditional_step(
-                                &processed_step,
-                                &context.variables,
-                                last_step_output.as_ref(),
-                            ),
-                            StepType::Branch => Self::execute_branch_step(
-                                &processed_step,
-                                &mut context,
-                                &mut results,
-                            ),
-                            StepType::Loop => {
-                                Self::execute_loop_step(&processed_step, &mut context, &mut results)
-                            }
-                        };
-
-                        // Update last_step_output if successful
-                        if let Ok(ref output) = result {
-                            last_step_output = Some(output.clone());
-                        }
-
-                        // Check if we need to continue
-                        let should_continue = match &result {
-                            Ok(_) => true,
-                            Err(_) => processed_step.continue_on_error,
-                        };
-
-                        // Store the result
-                        results.push((processed_step.name.clone(), result));
-
-                        if !should_continue {
-                            println!(
-                                "{} Command failed, stopping conditional block execution",
-                                "Error:".red().bold()
-                            );
-                            break;
-                        }
-                    }
-
-                    // Return the last output if we have one, or create a success output
-                    if let Some(output) = last_step_output {
-                        Ok(output)
-                    } else {
-                        Ok(Output {
-                            status: std::process::ExitStatus::from_raw(0),
-                            stdout: Vec::new(),
-                            stderr: Vec::new(),
-                        })
-                    }
+                    progress!("{}", "Executing 'else' block".header());
+                    Self::execute_conditional_block(
+                        "Else Block Step",
+                        &else_block.steps,
+                        context,
+                        options,
+                    )
                 } else {
                     // No else block, return a success output
                     Ok(Output {
@@ -600,7 +1487,7 @@ impl CommandExecutor {
                 }
             }
             ConditionalAction::Continue => {
-                println!("{}", "Skipping conditional block".blue().bold());
+                progress!("{}", "Skipping conditional block".header());
                 // Return a success output
                 Ok(Output {
                     status: std::process::ExitStatus::from_raw(0),
@@ -609,13 +1496,13 @@ impl CommandExecutor {
                 })
             }
             ConditionalAction::Break => {
-                println!("{}", "Breaking workflow execution".yellow().bold());
+                progress!("{}", "Breaking workflow execution".warning());
                 Err(ClixError::CommandExecutionFailed(
                     "Workflow execution stopped by conditional break".to_string(),
                 ))
             }
             ConditionalAction::Return(code) => {
-                println!("{} {}", "Returning with exit code:".yellow().bold(), code);
+                progress!("{} {}", "Returning with exit code:".warning(), code);
                 // Create an output with the specified exit code
                 Ok(Output {
                     #[cfg(unix)]
@@ -634,6 +1521,7 @@ impl CommandExecutor {
         step: &WorkflowStep,
         context: &mut WorkflowContext,
         results: &mut Vec<(String, Result<Output>)>,
+        options: &ExecutionOptions,
     ) -> Result<Output> {
         // Branch steps must have a branch property
         let branch = step.branch.as_ref().ok_or_else(|| {
@@ -644,27 +1532,19 @@ impl CommandExecutor {
         let var_name = &branch.variable;
         let var_value = context.variables.get(var_name).cloned().unwrap_or_default();
 
-        println!(
-            "{} {} = {}",
-            "Branching on:".blue().bold(),
-            var_name,
-            var_value
-        );
+        progress!("{} {} = {}", "Branching on:".header(), var_name, var_value);
 
         // Find the matching case
         let matching_case = branch.cases.iter().find(|case| case.value == var_value);
 
         let steps_to_execute = if let Some(case) = matching_case {
-            println!("{} {}", "Matched case:".blue().bold(), case.value);
+            progress!("{} {}", "Matched case:".header(), case.value);
             &case.steps
         } else if let Some(default_steps) = &branch.default_case {
-            println!("{}", "Using default case".blue().bold());
+            progress!("{}", "Using default case".header());
             default_steps
         } else {
-            println!(
-                "{}",
-                "No matching case found and no default case".yellow().bold()
-            );
+            progress!("{}", "No matching case found and no default case".warning());
             // Return a success output since we're not treating this as an error
             return Ok(Output {
                 status: std::process::ExitStatus::from_raw(0),
@@ -677,52 +1557,53 @@ impl CommandExecutor {
         let mut last_step_output = None;
 
         for (index, step) in steps_to_execute.iter().enumerate() {
-            println!(
+            progress!(
                 "\n{} {} - {}",
-                "Branch Step".blue().bold(),
-                (index + 1).to_string().blue().bold(),
+                "Branch Step".header(),
+                (index + 1).to_string().header(),
                 step.name
             );
 
-            // Process variables in the step
-            let processed_step = VariableProcessor::process_step(step, context);
-
-            // Check if step requires approval
-            if processed_step.require_approval {
-                Self::request_approval(&processed_step)?;
-            }
+            // Process variables in the step; an undefined variable under
+            // strict variables fails just this step
+            let result = match VariableProcessor::try_process_step(step, context) {
+                Ok(processed_step) => {
+                    // Check if step requires approval
+                    if processed_step.require_approval {
+                        Self::request_approval(&processed_step)?;
+                    }
 
-            // Execute the step
-            let result = match processed_step.step_type {
-                StepType::Command => Self::execute_command_step(&processed_step),
-                StepType::Auth => Self::execute_auth_step(&processed_step),
-                StepType::Conditional => Self::execute_conditional_step(
-                    &processed_step,
-                    &context.variables,
-                    last_step_output.as_ref(),
-                ),
-                StepType::Branch => Self::execute_branch_step(&processed_step, context, results),
-                StepType::Loop => Self::execute_loop_step(&processed_step, context, results),
+                    // Execute the step
+                    Self::execute_single_step(
+                        &processed_step,
+                        context,
+                        results,
+                        last_step_output.as_ref(),
+                        options,
+                    )
+                }
+                Err(e) => Err(e),
             };
 
+            if let Err(ClixError::WorkflowAborted(_)) = result {
+                return result;
+            }
+
             // Update last_step_output if successful
             if let Ok(ref output) = result {
                 last_step_output = Some(output.clone());
             }
 
             // Check if we need to continue
-            let should_continue = match &result {
-                Ok(_) => true,
-                Err(_) => processed_step.continue_on_error,
-            };
+            let should_continue = Self::should_continue_after_step(&result, step);
 
             // Store the result
-            results.push((processed_step.name.clone(), result));
+            results.push((step.name.clone(), result));
 
             if !should_continue {
-                println!(
+                progress!(
                     "{} Command failed, stopping branch execution",
-                    "Error:".red().bold()
+                    "Error:".error()
                 );
                 break;
             }
@@ -745,15 +1626,16 @@ impl CommandExecutor {
         step: &WorkflowStep,
         context: &mut WorkflowContext,
         results: &mut Vec<(String, Result<Output>)>,
+        options: &ExecutionOptions,
     ) -> Result<Output> {
         // Loop steps must have a loop_data property
         let loop_data = step.loop_data.as_ref().ok_or_else(|| {
             ClixError::CommandExecutionFailed("Loop step missing loop_data property".to_string())
         })?;
 
-        println!(
+        progress!(
             "{} {}",
-            "Loop condition:".blue().bold(),
+            "Loop condition:".header(),
             loop_data.condition.expression
         );
 
@@ -762,89 +1644,101 @@ impl CommandExecutor {
         let mut iterations = 0;
         let mut last_step_output = None;
 
+        let index = context.step_index;
+        if let Some(checkpoint) = context.resume_loop.take_if(|checkpoint| {
+            checkpoint.step_index == index && checkpoint.loop_step == step.name
+        }) {
+            iterations = checkpoint.iteration;
+            progress!(
+                "{} iteration {}",
+                "Resuming loop at".header(),
+                iterations + 1
+            );
+        }
+
         // Execute the loop until the condition becomes false or we hit max iterations
         while iterations < max_iterations {
             // Evaluate the loop condition
-            let condition_result = ExpressionEvaluator::evaluate(
+            let condition_result = ExpressionEvaluator::evaluate_typed(
                 &loop_data.condition.expression,
                 &context.variables,
+                &context.var_types,
                 last_step_output.as_ref(),
             )?;
 
             if !condition_result {
-                println!("{}", "Loop condition is false, exiting loop".blue().bold());
+                progress!("{}", "Loop condition is false, exiting loop".header());
                 break;
             }
 
-            println!("{} {}", "Loop iteration:".blue().bold(), iterations + 1);
+            progress!("{} {}", "Loop iteration:".header(), iterations + 1);
 
             // Execute the steps in the loop
             for (index, step) in loop_data.steps.iter().enumerate() {
-                println!(
+                progress!(
                     "\n{} {}.{} - {}",
-                    "Loop Step".blue().bold(),
+                    "Loop Step".header(),
                     iterations + 1,
                     index + 1,
                     step.name
                 );
 
-                // Process variables in the step
-                let processed_step = VariableProcessor::process_step(step, context);
-
-                // Check if step requires approval
-                if processed_step.require_approval {
-                    Self::request_approval(&processed_step)?;
-                }
+                // Process variables in the step; an undefined variable under
+                // strict variables fails just this step
+                let result = match VariableProcessor::try_process_step(step, context) {
+                    Ok(processed_step) => {
+                        // Check if step requires approval
+                        if processed_step.require_approval {
+                            Self::request_approval(&processed_step)?;
+                        }
 
-                // Execute the step
-                let result = match processed_step.step_type {
-                    StepType::Command => Self::execute_command_step(&processed_step),
-                    StepType::Auth => Self::execute_auth_step(&processed_step),
-                    StepType::Conditional => Self::execute_conditional_step(
-                        &processed_step,
-                        &context.variables,
-                        last_step_output.as_ref(),
-                    ),
-                    StepType::Branch => {
-                        Self::execute_branch_step(&processed_step, context, results)
+                        // Execute the step
+                        Self::execute_single_step(
+                            &processed_step,
+                            context,
+                            results,
+                            last_step_output.as_ref(),
+                            options,
+                        )
                     }
-                    StepType::Loop => Self::execute_loop_step(&processed_step, context, results),
+                    Err(e) => Err(e),
                 };
 
+                if let Err(ClixError::WorkflowAborted(_)) = result {
+                    return result;
+                }
+
                 // Update last_step_output if successful
                 if let Ok(ref output) = result {
                     last_step_output = Some(output.clone());
                 }
 
                 // Check if we need to continue
-                let should_continue = match &result {
-                    Ok(_) => true,
-                    Err(_) => processed_step.continue_on_error,
-                };
+                let should_continue = Self::should_continue_after_step(&result, step);
 
                 // Store the result
-                results.push((
-                    format!("Loop[{}].{}", iterations + 1, processed_step.name),
-                    result,
-                ));
+                results.push((format!("Loop[{}].{}", iterations + 1, step.name), result));
 
                 if !should_continue {
-                    println!(
+                    progress!(
                         "{} Command failed, stopping loop execution",
-                        "Error:".red().bold()
+                        "Error:".error()
                     );
                     break;
                 }
             }
 
             iterations += 1;
+
+            if let Some(every) = options.checkpoint_every
+                && iterations % every == 0
+            {
+                Self::save_loop_checkpoint(step, context, iterations, options)?;
+            }
         }
 
         if iterations >= max_iterations {
-            println!(
-                "{}",
-                "Loop reached maximum iterations, stopping".yellow().bold()
-            );
+            progress!("{}", "Loop reached maximum iterations, stopping".warning());
         }
 
         // Return the last output if we have one, or create a success output
@@ -859,60 +1753,252 @@ impl CommandExecutor {
         }
     }
 
-    fn execute_command_step(step: &WorkflowStep) -> Result<Output> {
-        let output = if cfg!(target_os = "windows") {
+    /// Record that `iterations` passes of a loop step are done, with the
+    /// current variables other than secrets
+    fn save_loop_checkpoint(
+        step: &WorkflowStep,
+        context: &WorkflowContext,
+        iterations: usize,
+        options: &ExecutionOptions,
+    ) -> Result<()> {
+        let saved_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        Self::checkpoints(options)?.save(&LoopCheckpoint {
+            workflow: context.workflow_name.clone(),
+            step_index: context.step_index,
+            loop_step: step.name.clone(),
+            iteration: iterations,
+            variables: context
+                .variables
+                .iter()
+                .filter(|(name, _)| !WorkflowContext::is_secret_name(name))
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect(),
+            saved_at,
+        })?;
+        progress!(
+            "{} after iteration {}",
+            "Checkpoint saved".note(),
+            iterations
+        );
+        Ok(())
+    }
+
+    fn checkpoints(options: &ExecutionOptions) -> Result<CheckpointStorage> {
+        match &options.config_dir {
+            Some(dir) => CheckpointStorage::new_with_dir(dir.clone()),
+            None => CheckpointStorage::new(),
+        }
+    }
+
+    /// Execute a confirm step (yes/no gate that can stop the workflow)
+    fn execute_confirm_step(step: &WorkflowStep, options: &ExecutionOptions) -> Result<Output> {
+        // Confirm steps must have a confirm property
+        let confirm = step.confirm.as_ref().ok_or_else(|| {
+            ClixError::CommandExecutionFailed("Confirm step missing confirm property".to_string())
+        })?;
+
+        let hint = if confirm.default { "[Y/n]" } else { "[y/N]" };
+
+        let confirmed = if options.non_interactive {
+            progress!(
+                "{} {} {} (non-interactive, using default)",
+                "Confirm:".warning(),
+                confirm.prompt,
+                hint
+            );
+            confirm.default
+        } else {
+            progress_inline!("{} {} {}: ", "Confirm:".warning(), confirm.prompt, hint);
+            let input = options.read_line()?.to_lowercase();
+
+            if input.is_empty() {
+                confirm.default
+            } else {
+                input == "y" || input == "yes"
+            }
+        };
+
+        if confirmed {
+            progress!("{}", "Confirmed, continuing workflow.".label());
+            Ok(Output {
+                status: std::process::ExitStatus::from_raw(0),
+                stdout: Vec::new(),
+                stderr: Vec::new(),
+            })
+        } else {
+            Err(ClixError::WorkflowAborted(format!(
+                "'{}' was not confirmed",
+                step.name
+            )))
+        }
+    }
+
+    fn execute_command_step(step: &WorkflowStep, options: &ExecutionOptions) -> Result<Output> {
+        let mut command = if cfg!(target_os = "windows") {
+            let mut command = ProcessCommand::new("cmd");
+            command.args(["/C", &step.command]);
+            command
+        } else {
+            let mut command = ProcessCommand::new("sh");
+            command.args(["-c", &step.command]);
+            command
+        };
+        let failed = |e: io::Error| ClixError::StepFailed {
+            step: step.name.clone(),
+            command: step.command.clone(),
+            exit_code: None,
+            stdout: String::new(),
+            stderr: e.to_string(),
+        };
+
+        let Some(secs) = step.timeout_secs.or(options.step_timeout_secs) else {
+            return output_limited(&mut command, options.max_output_bytes).map_err(failed);
+        };
+        let (output, timed_out) = output_limited_with_timeout(
+            &mut command,
+            options.max_output_bytes,
+            Duration::from_secs(secs),
+        )
+        .map_err(failed)?;
+
+        if !timed_out {
+            return Ok(output);
+        }
+        // The error is all that's kept of the step, so it carries the output
+        let mut message = format!("Step '{}' timed out after {}s", step.name, secs);
+        for (label, text) in [("stdout", &output.stdout), ("stderr", &output.stderr)] {
+            let text = String::from_utf8_lossy(text);
+            if !text.trim().is_empty() {
+                message.push_str(&format!(
+                    "\n{} before the timeout:\n{}",
+                    label,
+                    text.trim_end()
+                ));
+            }
+        }
+        Err(ClixError::CommandExecutionFailed(message))
+    }
+
+    /// Poll a command until it succeeds, failing once the timeout has passed
+    fn execute_wait_for_step(step: &WorkflowStep, options: &ExecutionOptions) -> Result<Output> {
+        let wait_for = step.wait_for.as_ref().ok_or_else(|| {
+            ClixError::CommandExecutionFailed("WaitFor step missing wait_for property".to_string())
+        })?;
+        // Without a delay the command would be run back to back until the timeout
+        if wait_for.interval_ms == 0 {
+            return Err(ClixError::ValidationError(format!(
+                "Step '{}' has a wait_for interval_ms of 0; use at least 1",
+                step.name
+            )));
+        }
+
+        let interval = Duration::from_millis(wait_for.interval_ms);
+        let timeout = Duration::from_millis(wait_for.timeout_ms);
+        let started = Instant::now();
+        let mut attempts = 0;
+
+        loop {
+            attempts += 1;
+            let output = Self::execute_command_step(step, options)?;
+
+            if output.status.success() {
+                progress!("{} ready after {} attempt(s)", "Wait:".header(), attempts);
+                return Ok(output);
+            }
+
+            if started.elapsed() + interval > timeout {
+                return Err(ClixError::CommandExecutionFailed(format!(
+                    "'{}' was not ready after {:.1}s ({} attempts)",
+                    step.name,
+                    timeout.as_secs_f64(),
+                    attempts
+                )));
+            }
+
+            std::thread::sleep(interval);
+        }
+    }
+
+    /// Spawn a command without waiting and hand its process to the context
+    fn start_background_step(step: &WorkflowStep, context: &WorkflowContext) -> Result<Output> {
+        let child = if cfg!(target_os = "windows") {
             ProcessCommand::new("cmd")
                 .args(["/C", &step.command])
-                .output()
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
         } else {
             ProcessCommand::new("sh")
                 .args(["-c", &step.command])
-                .output()
-        };
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+        }
+        .map_err(|e| {
+            ClixError::CommandExecutionFailed(format!("Failed to start in background: {}", e))
+        })?;
 
-        match output {
-            Ok(output) => Ok(output),
-            Err(e) => Err(ClixError::CommandExecutionFailed(format!(
-                "Failed to execute: {}",
-                e
-            ))),
+        let process = BackgroundProcess::new(step.name.clone(), child);
+        let message = format!("Started in background (pid {})", process.id());
+        progress!("{} {}", "Background:".header(), message);
+        context.track_background(process);
+
+        Ok(Output {
+            status: std::process::ExitStatus::from_raw(0),
+            stdout: message.into_bytes(),
+            stderr: Vec::new(),
+        })
+    }
+
+    /// Kill every background process the workflow started so far
+    fn stop_background_processes(context: &WorkflowContext) {
+        for step_name in context.stop_background() {
+            progress!(
+                "{} stopped process from step '{}'",
+                "Background:".header(),
+                step_name
+            );
         }
     }
 
-    fn execute_auth_step(step: &WorkflowStep) -> Result<Output> {
+    fn execute_auth_step(step: &WorkflowStep, options: &ExecutionOptions) -> Result<Output> {
         // First, execute the command which typically starts an auth flow
         let output = if cfg!(target_os = "windows") {
-            ProcessCommand::new("cmd")
-                .args(["/C", &step.command])
-                .output()
+            output_limited(
+                ProcessCommand::new("cmd").args(["/C", &step.command]),
+                options.max_output_bytes,
+            )
         } else {
-            ProcessCommand::new("sh")
-                .args(["-c", &step.command])
-                .output()
+            output_limited(
+                ProcessCommand::new("sh").args(["-c", &step.command]),
+                options.max_output_bytes,
+            )
         };
 
         match output {
             Ok(output) => {
                 // Display the output to the user
                 if !output.stdout.is_empty() {
-                    println!("\n{}", "STDOUT:".green().bold());
-                    println!("{}", String::from_utf8_lossy(&output.stdout));
+                    progress!("\n{}", "STDOUT:".success());
+                    progress!("{}", String::from_utf8_lossy(&output.stdout));
                 }
 
                 if !output.stderr.is_empty() {
-                    println!("\n{}", "STDERR:".red().bold());
-                    println!("{}", String::from_utf8_lossy(&output.stderr));
+                    progress!("\n{}", "STDERR:".error());
+                    progress!("{}", String::from_utf8_lossy(&output.stderr));
                 }
 
-                println!(
+                progress!(
                     "\n{}",
                     "This step requires authentication. Please follow the instructions above."
-                        .yellow()
-                        .bold()
+                        .warning()
                 );
-                println!(
+                progress!(
                     "{}",
-                    "Press Enter when you have completed the authentication process...".yellow()
+                    "Press Enter when you have completed the authentication process...".note()
                 );
 
                 // Wait for user to confirm they've completed the auth process
@@ -929,9 +2015,9 @@ impl CommandExecutor {
                     ClixError::CommandExecutionFailed(format!("Failed to read user input: {}", e))
                 })?;
 
-                println!(
+                progress!(
                     "{}",
-                    "Authentication confirmed, continuing workflow.".green()
+                    "Authentication confirmed, continuing workflow.".label()
                 );
                 Ok(output)
             }
@@ -944,20 +2030,18 @@ impl CommandExecutor {
 
     /// Request approval from the user before executing a step
     fn request_approval(step: &WorkflowStep) -> Result<()> {
-        println!(
+        progress!(
             "{}",
-            "⚠️  This step requires approval before execution:"
-                .yellow()
-                .bold()
+            "⚠️  This step requires approval before execution:".warning()
         );
-        println!("{} {}", "Name:".blue().bold(), step.name);
-        println!("{} {}", "Description:".blue().bold(), step.description);
+        progress!("{} {}", "Name:".header(), step.name);
+        progress!("{} {}", "Description:".header(), step.description);
 
         if !step.command.is_empty() {
-            println!("{} {}", "Command:".blue().bold(), step.command);
+            progress!("{} {}", "Command:".header(), step.command);
         }
 
-        print!("{} [y/N]: ", "Do you want to proceed?".yellow().bold());
+        progress_inline!("{} [y/N]: ", "Do you want to proceed?".warning());
         io::stdout().flush().map_err(|e| {
             ClixError::CommandExecutionFailed(format!("Failed to flush stdout: {}", e))
         })?;
@@ -972,7 +2056,7 @@ impl CommandExecutor {
 
         let input = input.trim().to_lowercase();
         if input == "y" || input == "yes" {
-            println!("{}", "Proceeding with step execution.".green());
+            progress!("{}", "Proceeding with step execution.".label());
             Ok(())
         } else {
             Err(ClixError::CommandExecutionFailed(
@@ -982,24 +2066,29 @@ impl CommandExecutor {
     }
 
     pub fn print_command_output(output: &Output) {
-        if !output.stdout.is_empty() {
-            println!("\n{}", "STDOUT:".green().bold());
-            println!("{}", String::from_utf8_lossy(&output.stdout));
+        Self::print_command_result(&CommandResult::from_output("", output, Duration::ZERO));
+    }
+
+    /// Print a command's output and exit status for a person to read
+    pub fn print_command_result(result: &CommandResult) {
+        if !result.stdout.is_empty() {
+            progress!("\n{}", "STDOUT:".success());
+            progress!("{}", result.stdout);
         }
 
-        if !output.stderr.is_empty() {
-            println!("\n{}", "STDERR:".red().bold());
-            println!("{}", String::from_utf8_lossy(&output.stderr));
+        if !result.stderr.is_empty() {
+            progress!("\n{}", "STDERR:".error());
+            progress!("{}", result.stderr);
         }
 
-        println!(
-            "\n{} {}",
-            "Exit status:".blue().bold(),
-            if output.status.success() {
-                "Success".green()
-            } else {
-                format!("Failed ({})", output.status).red()
+        let status = if result.success {
+            "Success".label()
+        } else {
+            match result.exit_code {
+                Some(code) => format!("Failed (exit status: {})", code).error(),
+                None => "Failed (no exit status)".error(),
             }
-        );
+        };
+        progress!("\n{} {}", "Exit status:".header(), status);
     }
 }
@@ -1,7 +1,10 @@
+use crate::commands::models::VarType;
 use crate::error::{ClixError, Result};
 use regex::Regex;
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::process::{Command, Output};
+use std::sync::OnceLock;
 
 pub struct ExpressionEvaluator;
 
@@ -13,6 +16,22 @@ impl ExpressionEvaluator {
         context: &HashMap<String, String>,
         last_output: Option<&Output>,
     ) -> Result<bool> {
+        Self::evaluate_typed(expr, context, &HashMap::new(), last_output)
+    }
+
+    /// Like `evaluate`, but a comparison whose left side is an `Int` or `Bool`
+    /// variable, such as `$replicas > 3` or `[ "$deploy" == yes ]`, compares
+    /// numbers or booleans instead of text
+    pub fn evaluate_typed(
+        expr: &str,
+        context: &HashMap<String, String>,
+        types: &HashMap<String, VarType>,
+        last_output: Option<&Output>,
+    ) -> Result<bool> {
+        if let Some(result) = Self::evaluate_typed_comparison(expr, context, types) {
+            return result;
+        }
+
         // Replace variables in the expression
         let expr_with_vars = Self::replace_variables(expr, context);
 
@@ -55,10 +74,10 @@ impl ExpressionEvaluator {
             let var_name = &cap[1];
             let placeholder = &cap[0];
 
-            if placeholder != "$?" {
-                if let Some(value) = context.get(var_name) {
-                    result = result.replace(placeholder, value);
-                }
+            if placeholder != "$?"
+                && let Some(value) = context.get(var_name)
+            {
+                result = result.replace(placeholder, value);
             }
         }
 
@@ -123,6 +142,72 @@ impl ExpressionEvaluator {
         }
     }
 
+    /// Compare a typed variable with a value, or return None when the
+    /// expression is not such a comparison
+    fn evaluate_typed_comparison(
+        expr: &str,
+        context: &HashMap<String, String>,
+        types: &HashMap<String, VarType>,
+    ) -> Option<Result<bool>> {
+        static COMPARISON: OnceLock<Regex> = OnceLock::new();
+        let re = COMPARISON.get_or_init(|| {
+            Regex::new(
+                r#"^\s*(?:\[\[?\s+)?"?\$\{?([A-Za-z0-9_]+)\}?"?\s*(==|!=|<=|>=|<|>|=|-eq|-ne|-lt|-le|-gt|-ge)\s*"?([^"\s\]]*)"?(?:\s+\]\]?)?\s*$"#,
+            )
+            .unwrap()
+        });
+
+        let caps = re.captures(expr)?;
+        let var_type = types.get(&caps[1])?;
+        let left = context.get(&caps[1])?;
+        let operator = &caps[2];
+        let right = Self::replace_variables(&caps[3], context);
+
+        let ordering = match var_type {
+            VarType::Int => {
+                let parse = |value: &str| {
+                    value.trim().parse::<i64>().map_err(|_| {
+                        ClixError::CommandExecutionFailed(format!(
+                            "Cannot compare '{}' with integer variable '{}'",
+                            value, &caps[1]
+                        ))
+                    })
+                };
+                match (parse(left), parse(&right)) {
+                    (Ok(left), Ok(right)) => left.cmp(&right),
+                    (Err(e), _) | (_, Err(e)) => return Some(Err(e)),
+                }
+            }
+            VarType::Bool => {
+                if !matches!(operator, "==" | "=" | "!=" | "-eq" | "-ne") {
+                    return Some(Err(ClixError::CommandExecutionFailed(format!(
+                        "Boolean variable '{}' can only be compared with == or !=",
+                        &caps[1]
+                    ))));
+                }
+                match (VarType::parse_bool(left), VarType::parse_bool(&right)) {
+                    (Some(left), Some(right)) => left.cmp(&right),
+                    _ => {
+                        return Some(Err(ClixError::CommandExecutionFailed(format!(
+                            "Cannot compare '{}' with boolean variable '{}'",
+                            right, &caps[1]
+                        ))));
+                    }
+                }
+            }
+            VarType::String | VarType::Enum(_) => return None,
+        };
+
+        Some(Ok(match operator {
+            "==" | "=" | "-eq" => ordering == Ordering::Equal,
+            "!=" | "-ne" => ordering != Ordering::Equal,
+            "<" | "-lt" => ordering == Ordering::Less,
+            "<=" | "-le" => ordering != Ordering::Greater,
+            ">" | "-gt" => ordering == Ordering::Greater,
+            _ => ordering != Ordering::Less,
+        }))
+    }
+
     /// Evaluate a file test expression
     fn evaluate_file_test(expr: &str) -> Result<bool> {
         // Just delegate to the shell test command since file tests are complex
@@ -615,6 +615,26 @@ impl FunctionConverter {
         workflow_name: &str,
         description: &str,
         tags: Vec<String>,
+    ) -> Result<Workflow> {
+        Self::convert_function_with_options(
+            file_path,
+            function_name,
+            workflow_name,
+            description,
+            tags,
+            false,
+        )
+    }
+
+    /// Like `convert_function`; with `inline_calls`, calls to other functions
+    /// defined in the same script are replaced by those functions' steps
+    pub fn convert_function_with_options(
+        file_path: &str,
+        function_name: &str,
+        workflow_name: &str,
+        description: &str,
+        tags: Vec<String>,
+        inline_calls: bool,
     ) -> Result<Workflow> {
         // Read the shell script file
         let content = fs::read_to_string(file_path).map_err(|e| {
@@ -628,7 +648,14 @@ impl FunctionConverter {
         let function_content = Self::extract_function(&content, function_name)?;
 
         // Use new advanced parser
-        let steps = Self::convert_with_full_parsing(&function_content)?;
+        let steps = if inline_calls {
+            let statements = ShellParser::new().parse_function(&function_content)?;
+            let mut stack = vec![function_name.to_string()];
+            let statements = Self::inline_calls(statements, &content, &mut stack)?;
+            AstBuilder.build_steps(statements)?
+        } else {
+            Self::convert_with_full_parsing(&function_content)?
+        };
 
         // Extract variables from function parameters
         let variables = Self::extract_function_variables(&function_content)?;
@@ -654,6 +681,86 @@ impl FunctionConverter {
         ast_builder.build_steps(statements)
     }
 
+    /// Replace calls to functions defined in `script` with the statements of
+    /// those functions, recursively. `stack` holds the functions being expanded,
+    /// so a function that calls itself, directly or through others, stays a call.
+    fn inline_calls(
+        statements: Vec<ShellStatement>,
+        script: &str,
+        stack: &mut Vec<String>,
+    ) -> Result<Vec<ShellStatement>> {
+        let mut inlined = Vec::new();
+
+        for statement in statements {
+            match statement {
+                ShellStatement::Command(cmd) => {
+                    let name = cmd.split_whitespace().next().unwrap_or_default();
+                    let body = match Self::extract_function(script, name) {
+                        Ok(body) if !name.is_empty() && !stack.iter().any(|f| f == name) => body,
+                        _ => {
+                            inlined.push(ShellStatement::Command(cmd));
+                            continue;
+                        }
+                    };
+
+                    stack.push(name.to_string());
+                    let called = ShellParser::new().parse_function(&body)?;
+                    inlined.extend(Self::inline_calls(called, script, stack)?);
+                    stack.pop();
+                }
+                ShellStatement::If {
+                    condition,
+                    then_block,
+                    else_block,
+                } => inlined.push(ShellStatement::If {
+                    condition,
+                    then_block: Self::inline_calls(then_block, script, stack)?,
+                    else_block: match else_block {
+                        Some(block) => Some(Self::inline_calls(block, script, stack)?),
+                        None => None,
+                    },
+                }),
+                ShellStatement::Case {
+                    variable,
+                    cases,
+                    default_case,
+                } => {
+                    let mut inlined_cases = Vec::new();
+                    for case in cases {
+                        inlined_cases.push(CaseEntry {
+                            pattern: case.pattern,
+                            commands: Self::inline_calls(case.commands, script, stack)?,
+                        });
+                    }
+                    inlined.push(ShellStatement::Case {
+                        variable,
+                        cases: inlined_cases,
+                        default_case: match default_case {
+                            Some(block) => Some(Self::inline_calls(block, script, stack)?),
+                            None => None,
+                        },
+                    });
+                }
+                ShellStatement::For {
+                    variable,
+                    items,
+                    body,
+                } => inlined.push(ShellStatement::For {
+                    variable,
+                    items,
+                    body: Self::inline_calls(body, script, stack)?,
+                }),
+                ShellStatement::While { condition, body } => inlined.push(ShellStatement::While {
+                    condition,
+                    body: Self::inline_calls(body, script, stack)?,
+                }),
+                other => inlined.push(other),
+            }
+        }
+
+        Ok(inlined)
+    }
+
     /// Extract function parameters as workflow variables
     fn extract_function_variables(function_content: &str) -> Result<Vec<WorkflowVariable>> {
         let mut variables = Vec::new();
@@ -663,10 +770,10 @@ impl FunctionConverter {
         let mut max_param = 0;
 
         for captures in param_regex.captures_iter(function_content) {
-            if let Some(param_match) = captures.get(1) {
-                if let Ok(param_num) = param_match.as_str().parse::<usize>() {
-                    max_param = max_param.max(param_num);
-                }
+            if let Some(param_match) = captures.get(1)
+                && let Ok(param_num) = param_match.as_str().parse::<usize>()
+            {
+                max_param = max_param.max(param_num);
             }
         }
 
@@ -717,10 +824,10 @@ impl FunctionConverter {
 
         let re = Regex::new(&pattern).unwrap();
 
-        if let Some(captures) = re.captures(content) {
-            if let Some(function_body) = captures.get(1) {
-                return Ok(function_body.as_str().to_string());
-            }
+        if let Some(captures) = re.captures(content)
+            && let Some(function_body) = captures.get(1)
+        {
+            return Ok(function_body.as_str().to_string());
         }
 
         Err(ClixError::CommandNotFound(format!(
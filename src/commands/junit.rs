@@ -0,0 +1,161 @@
+use crate::commands::CommandResult;
+use crate::error::Result;
+use std::fs;
+use std::path::Path;
+
+/// A JUnit-style XML report of a workflow run, with one testcase per step
+pub struct JunitReport {
+    suite: String,
+    cases: Vec<CommandResult>,
+}
+
+impl JunitReport {
+    pub fn new(suite: &str, cases: Vec<CommandResult>) -> Self {
+        Self {
+            suite: suite.to_string(),
+            cases,
+        }
+    }
+
+    /// Render the report as a single <testsuite> document
+    pub fn to_xml(&self) -> String {
+        let skipped = self.cases.iter().filter(|case| case.skipped).count();
+        let failures = self
+            .cases
+            .iter()
+            .filter(|case| !case.success && !case.skipped)
+            .count();
+        let total_ms: u64 = self.cases.iter().map(|case| case.duration_ms).sum();
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!(
+            "<testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"{}\" time=\"{}\">\n",
+            escape(&self.suite),
+            self.cases.len(),
+            failures,
+            skipped,
+            seconds(total_ms)
+        ));
+
+        for case in &self.cases {
+            xml.push_str(&format!(
+                "  <testcase classname=\"{}\" name=\"{}\" time=\"{}\"",
+                escape(&self.suite),
+                escape(&case.name),
+                seconds(case.duration_ms)
+            ));
+            if case.success {
+                xml.push_str("/>\n");
+                continue;
+            }
+            if case.skipped {
+                xml.push_str(">\n");
+                xml.push_str(&format!(
+                    "    <skipped message=\"{}\"/>\n",
+                    escape(case.stderr.trim_end())
+                ));
+                xml.push_str("  </testcase>\n");
+                continue;
+            }
+
+            let message = match case.exit_code {
+                Some(code) => format!("exit code {}", code),
+                None => "step did not complete".to_string(),
+            };
+            xml.push_str(">\n");
+            xml.push_str(&format!(
+                "    <failure message=\"{}\">{}</failure>\n",
+                escape(&message),
+                escape(&case.stderr)
+            ));
+            xml.push_str("  </testcase>\n");
+        }
+
+        xml.push_str("</testsuite>\n");
+        xml
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_xml())?;
+        Ok(())
+    }
+}
+
+fn seconds(ms: u64) -> String {
+    format!("{:.3}", ms as f64 / 1000.0)
+}
+
+/// Escape text for use in XML content and attribute values, dropping the
+/// control characters XML 1.0 does not allow
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failure_is_escaped() {
+        let report = JunitReport::new(
+            "deploy",
+            vec![CommandResult {
+                name: "check <env>".to_string(),
+                exit_code: Some(2),
+                stdout: String::new(),
+                stderr: "missing \"KEY\" & \u{1b}[31mvalue".to_string(),
+                duration_ms: 1500,
+                success: false,
+                skipped: false,
+            }],
+        );
+
+        let xml = report.to_xml();
+        assert!(
+            xml.contains(
+                "<testcase classname=\"deploy\" name=\"check &lt;env&gt;\" time=\"1.500\">"
+            )
+        );
+        assert!(xml.contains(
+            "<failure message=\"exit code 2\">missing &quot;KEY&quot; &amp; [31mvalue</failure>"
+        ));
+    }
+
+    #[test]
+    fn test_skipped_step_is_not_a_failure() {
+        let report = JunitReport::new(
+            "deploy",
+            vec![CommandResult {
+                name: "login".to_string(),
+                exit_code: None,
+                stdout: String::new(),
+                stderr: "Skipped: authentication already done (--skip-auth)".to_string(),
+                duration_ms: 0,
+                success: false,
+                skipped: true,
+            }],
+        );
+
+        let xml = report.to_xml();
+        assert!(xml.contains("tests=\"1\" failures=\"0\" errors=\"0\" skipped=\"1\""));
+        assert!(
+            xml.contains(
+                "<skipped message=\"Skipped: authentication already done (--skip-auth)\"/>"
+            )
+        );
+        assert!(!xml.contains("<failure"));
+    }
+}
@@ -0,0 +1,533 @@
+use crate::commands::models::{Command, WorkflowStep, WorkflowVariable};
+use crate::error::{ClixError, Result};
+use regex::{Captures, Regex};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::sync::OnceLock;
+
+/// Deepest chain of variables referring to variables that is expanded
+const MAX_EXPANSION_DEPTH: usize = 16;
+
+/// A rule parsed from a Makefile
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MakeTarget {
+    pub name: String,
+    pub prerequisites: Vec<String>,
+    /// Recipe lines as written, with their `@`, `-` and `+` prefixes
+    pub recipe: Vec<String>,
+    /// Listed as a prerequisite of `.PHONY`
+    pub phony: bool,
+}
+
+/// The parts of a Makefile that convert to clix commands
+#[derive(Debug, Clone, Default)]
+pub struct Makefile {
+    /// Targets in the order they first appear
+    pub targets: Vec<MakeTarget>,
+    /// Variables set with `=`, `:=`, `::=` or `+=`, expanded in place
+    pub variables: HashMap<String, String>,
+    /// Variables set with `?=`, which become workflow variables with a default
+    pub defaults: HashMap<String, String>,
+}
+
+/// Converts simple Makefile targets into commands and workflows.
+///
+/// Only plain rules are understood: pattern rules, conditionals, `define`
+/// blocks and Make functions other than `$(shell ...)` are not translated.
+pub struct MakefileConverter;
+
+impl MakefileConverter {
+    /// Read a Makefile and convert one target, or every target when `target` is
+    /// None. When the Makefile declares `.PHONY` targets, converting everything
+    /// converts only those, since the rest build files rather than run tasks.
+    pub fn convert_file(
+        path: &str,
+        target: Option<&str>,
+        tags: Vec<String>,
+    ) -> Result<Vec<Command>> {
+        let content = fs::read_to_string(path).map_err(|e| {
+            ClixError::Io(std::io::Error::other(format!(
+                "Failed to read Makefile: {}",
+                e
+            )))
+        })?;
+        let makefile = Self::parse(&content);
+        let description = |name: &str| format!("Make target '{}' from {}", name, path);
+
+        if let Some(name) = target {
+            let command = makefile.to_command(name, &description(name), tags)?;
+            return Ok(vec![command]);
+        }
+
+        let any_phony = makefile.targets.iter().any(|target| target.phony);
+        let mut commands = Vec::new();
+        for target in &makefile.targets {
+            if any_phony && !target.phony {
+                continue;
+            }
+            // Targets without anything to run, such as a bare `.PHONY` entry, are skipped
+            if let Ok(command) =
+                makefile.to_command(&target.name, &description(&target.name), tags.clone())
+            {
+                commands.push(command);
+            }
+        }
+
+        if commands.is_empty() {
+            return Err(ClixError::InvalidInput(format!(
+                "No targets with recipes found in {}",
+                path
+            )));
+        }
+        Ok(commands)
+    }
+
+    /// Parse the rules and variable assignments of a Makefile
+    pub fn parse(content: &str) -> Makefile {
+        static ASSIGNMENT: OnceLock<Regex> = OnceLock::new();
+        static RULE: OnceLock<Regex> = OnceLock::new();
+        let assignment = ASSIGNMENT.get_or_init(|| {
+            Regex::new(
+                r"^(?:export\s+|override\s+)?([A-Za-z_][A-Za-z0-9_.-]*)\s*(::=|:=|\?=|\+=|=)\s*(.*)$",
+            )
+            .unwrap()
+        });
+        let rule = RULE.get_or_init(|| Regex::new(r"^([^:#=\s][^:#=]*?)\s*::?\s*(.*)$").unwrap());
+
+        let mut makefile = Makefile::default();
+        let mut phony: HashSet<String> = HashSet::new();
+        // Indices into makefile.targets that the following recipe lines belong to
+        let mut current: Vec<usize> = Vec::new();
+        let mut in_define = false;
+
+        for line in join_continuation_lines(content) {
+            if in_define {
+                in_define = line.trim() != "endef";
+                continue;
+            }
+
+            if let Some(recipe_line) = line.strip_prefix('\t') {
+                let recipe_line = recipe_line.trim();
+                if !recipe_line.is_empty() && !recipe_line.starts_with('#') {
+                    for &index in &current {
+                        makefile.targets[index].recipe.push(recipe_line.to_string());
+                    }
+                }
+                continue;
+            }
+
+            let line = strip_comment(&line);
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let keyword = line.split_whitespace().next().unwrap_or_default();
+            if keyword == "define" {
+                in_define = true;
+                current.clear();
+                continue;
+            }
+            if matches!(
+                keyword,
+                "ifeq" | "ifneq" | "ifdef" | "ifndef" | "else" | "endif" | "include" | "-include"
+            ) {
+                continue;
+            }
+
+            if let Some(captures) = assignment.captures(line) {
+                let name = captures[1].to_string();
+                let value = captures[3].trim().to_string();
+                match &captures[2] {
+                    "?=" => {
+                        if !makefile.variables.contains_key(&name) {
+                            makefile.defaults.entry(name).or_insert(value);
+                        }
+                    }
+                    "+=" => {
+                        let existing = makefile.variables.entry(name).or_default();
+                        if !existing.is_empty() {
+                            existing.push(' ');
+                        }
+                        existing.push_str(&value);
+                    }
+                    _ => {
+                        makefile.defaults.remove(&name);
+                        makefile.variables.insert(name, value);
+                    }
+                }
+                current.clear();
+                continue;
+            }
+
+            let Some(captures) = rule.captures(line) else {
+                current.clear();
+                continue;
+            };
+            let rest = captures.get(2).map_or("", |rest| rest.as_str());
+            let (prerequisites, inline_recipe) = match rest.split_once(';') {
+                Some((prerequisites, recipe)) => (prerequisites, Some(recipe.trim())),
+                None => (rest, None),
+            };
+            let prerequisites: Vec<String> = prerequisites
+                .split_whitespace()
+                .filter(|prerequisite| *prerequisite != "|")
+                .map(str::to_string)
+                .collect();
+
+            current.clear();
+            for name in captures[1].split_whitespace() {
+                if name == ".PHONY" {
+                    phony.extend(prerequisites.iter().cloned());
+                    continue;
+                }
+                // Special targets and pattern rules have no command equivalent
+                if name.starts_with('.') || name.contains('%') {
+                    continue;
+                }
+
+                let index = match makefile
+                    .targets
+                    .iter()
+                    .position(|target| target.name == name)
+                {
+                    Some(index) => index,
+                    None => {
+                        makefile.targets.push(MakeTarget {
+                            name: name.to_string(),
+                            prerequisites: Vec::new(),
+                            recipe: Vec::new(),
+                            phony: false,
+                        });
+                        makefile.targets.len() - 1
+                    }
+                };
+                let target = &mut makefile.targets[index];
+                for prerequisite in &prerequisites {
+                    if !target.prerequisites.contains(prerequisite) {
+                        target.prerequisites.push(prerequisite.clone());
+                    }
+                }
+                if let Some(recipe) = inline_recipe.filter(|recipe| !recipe.is_empty()) {
+                    target.recipe.push(recipe.to_string());
+                }
+                current.push(index);
+            }
+        }
+
+        for target in &mut makefile.targets {
+            target.phony = phony.contains(&target.name);
+        }
+        makefile
+    }
+}
+
+impl Makefile {
+    pub fn target(&self, name: &str) -> Option<&MakeTarget> {
+        self.targets.iter().find(|target| target.name == name)
+    }
+
+    /// Convert a target to a simple command when it is a single recipe line of
+    /// its own, and otherwise to a workflow whose steps run its prerequisites'
+    /// recipes first, in the order Make would
+    pub fn to_command(&self, name: &str, description: &str, tags: Vec<String>) -> Result<Command> {
+        if self.target(name).is_none() {
+            return Err(ClixError::NotFound(format!(
+                "Make target '{}' not found",
+                name
+            )));
+        }
+
+        let mut order = Vec::new();
+        self.visit(name, &mut Vec::new(), &mut HashSet::new(), &mut order)?;
+
+        let mut variables: Vec<WorkflowVariable> = Vec::new();
+        let mut steps = Vec::new();
+        for target in &order {
+            let count = target.recipe.len();
+            for (index, line) in target.recipe.iter().enumerate() {
+                let (command, ignore_errors) = strip_recipe_prefixes(line);
+                let command = self.expand(command, target, &mut variables, 0);
+                let step_name = if count == 1 {
+                    target.name.clone()
+                } else {
+                    format!("{} ({})", target.name, index + 1)
+                };
+                steps.push(WorkflowStep::new_command(
+                    step_name,
+                    command,
+                    format!("Recipe line {} of make target '{}'", index + 1, target.name),
+                    ignore_errors,
+                ));
+            }
+        }
+
+        if steps.is_empty() {
+            return Err(ClixError::InvalidInput(format!(
+                "Make target '{}' has no recipe to convert",
+                name
+            )));
+        }
+
+        if steps.len() == 1
+            && order.len() == 1
+            && variables.is_empty()
+            && !steps[0].continues_on_error()
+        {
+            let command = steps.remove(0).command;
+            return Ok(Command::new(
+                name.to_string(),
+                description.to_string(),
+                command,
+                tags,
+            ));
+        }
+
+        Ok(Command::with_variables(
+            name.to_string(),
+            description.to_string(),
+            steps,
+            tags,
+            variables,
+        ))
+    }
+
+    /// Depth-first walk putting each target after its prerequisites. Prerequisites
+    /// that are not targets are files Make expects to exist, and are skipped.
+    fn visit<'a>(
+        &'a self,
+        name: &str,
+        path: &mut Vec<&'a str>,
+        done: &mut HashSet<&'a str>,
+        order: &mut Vec<&'a MakeTarget>,
+    ) -> Result<()> {
+        let Some(target) = self.target(name) else {
+            return Ok(());
+        };
+        if done.contains(target.name.as_str()) {
+            return Ok(());
+        }
+        if let Some(start) = path.iter().position(|seen| *seen == target.name) {
+            let mut cycle: Vec<&str> = path[start..].to_vec();
+            cycle.push(&target.name);
+            return Err(ClixError::InvalidInput(format!(
+                "Make targets depend on each other in a cycle: {}",
+                cycle.join(" -> ")
+            )));
+        }
+
+        path.push(&target.name);
+        for prerequisite in &target.prerequisites {
+            self.visit(prerequisite, path, done, order)?;
+        }
+        path.pop();
+
+        done.insert(&target.name);
+        order.push(target);
+        Ok(())
+    }
+
+    /// Expand Make references in a recipe line. Variables set with `?=` and
+    /// variables the Makefile never sets become `{{ name }}` placeholders, and
+    /// are added to `variables`.
+    fn expand(
+        &self,
+        text: &str,
+        target: &MakeTarget,
+        variables: &mut Vec<WorkflowVariable>,
+        depth: usize,
+    ) -> String {
+        static REFERENCE: OnceLock<Regex> = OnceLock::new();
+        let reference =
+            REFERENCE.get_or_init(|| Regex::new(r"\$(?:\(([^()]*)\)|\{([^{}]*)\}|(.))").unwrap());
+
+        reference
+            .replace_all(text, |captures: &Captures| {
+                let name = captures
+                    .get(1)
+                    .or_else(|| captures.get(2))
+                    .or_else(|| captures.get(3))
+                    .map_or("", |name| name.as_str());
+
+                match name {
+                    "$" => return "$".to_string(),
+                    "@" => return target.name.clone(),
+                    "<" => return target.prerequisites.first().cloned().unwrap_or_default(),
+                    "^" => return target.prerequisites.join(" "),
+                    _ => {}
+                }
+
+                if let Some(command) = name.strip_prefix("shell ") {
+                    let command = self.expand(command, target, variables, depth + 1);
+                    return format!("$({})", command.trim());
+                }
+
+                if let Some(value) = self.variables.get(name) {
+                    if depth >= MAX_EXPANSION_DEPTH {
+                        return value.clone();
+                    }
+                    return self.expand(value, target, variables, depth + 1);
+                }
+
+                // Names clix cannot template, and Make functions, are left for the shell
+                if name.is_empty() || !name.chars().all(|ch| ch.is_alphanumeric() || ch == '_') {
+                    return captures[0].to_string();
+                }
+
+                if !variables.iter().any(|variable| variable.name == name) {
+                    let variable = match self.defaults.get(name) {
+                        Some(default) => WorkflowVariable::new(
+                            name.to_string(),
+                            format!("Makefile variable {}", name),
+                            Some(self.expand(default, target, variables, depth + 1)),
+                            false,
+                        ),
+                        None => WorkflowVariable::new(
+                            name.to_string(),
+                            format!("Makefile variable {} (not set in the Makefile)", name),
+                            None,
+                            true,
+                        ),
+                    };
+                    variables.push(variable);
+                }
+                format!("{{{{ {} }}}}", name)
+            })
+            .into_owned()
+    }
+}
+
+/// Join lines ending in a backslash with the line after them
+fn join_continuation_lines(content: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut pending: Option<String> = None;
+    for line in content.lines() {
+        let line = match pending.take() {
+            Some(mut joined) => {
+                joined.push(' ');
+                joined.push_str(line.trim_start());
+                joined
+            }
+            None => line.to_string(),
+        };
+        match line.strip_suffix('\\') {
+            Some(continued) => pending = Some(continued.trim_end().to_string()),
+            None => lines.push(line),
+        }
+    }
+    lines.extend(pending);
+    lines
+}
+
+/// Drop a `#` comment from a line outside a recipe
+fn strip_comment(line: &str) -> &str {
+    match line.find('#') {
+        Some(index) => &line[..index],
+        None => line,
+    }
+}
+
+/// Remove the `@` (silent), `-` (ignore errors) and `+` prefixes from a recipe
+/// line, returning whether its errors are ignored
+fn strip_recipe_prefixes(line: &str) -> (&str, bool) {
+    let mut ignore_errors = false;
+    let mut rest = line;
+    while let Some(prefix) = rest
+        .chars()
+        .next()
+        .filter(|ch| matches!(ch, '@' | '-' | '+'))
+    {
+        ignore_errors |= prefix == '-';
+        rest = rest[1..].trim_start();
+    }
+    (rest, ignore_errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAKEFILE: &str = "\
+# Build and ship the app
+.PHONY: build deploy
+
+IMAGE := registry.example.com/app
+ENV ?= staging
+
+build:
+\t@echo building $(IMAGE)
+
+deploy: build ## Ship it
+\tkubectl apply \\
+\t    -f k8s/$(ENV).yaml
+\t-echo deployed $@ to ${CLUSTER} at $$(date)
+";
+
+    #[test]
+    fn test_parse_targets_and_variables() {
+        let makefile = MakefileConverter::parse(MAKEFILE);
+
+        let names: Vec<&str> = makefile
+            .targets
+            .iter()
+            .map(|target| target.name.as_str())
+            .collect();
+        assert_eq!(names, ["build", "deploy"]);
+        assert!(makefile.targets.iter().all(|target| target.phony));
+
+        let deploy = makefile.target("deploy").unwrap();
+        assert_eq!(deploy.prerequisites, ["build"]);
+        assert_eq!(deploy.recipe.len(), 2);
+        assert_eq!(deploy.recipe[0], "kubectl apply -f k8s/$(ENV).yaml");
+
+        assert_eq!(makefile.variables["IMAGE"], "registry.example.com/app");
+        assert_eq!(makefile.defaults["ENV"], "staging");
+    }
+
+    #[test]
+    fn test_prerequisites_become_earlier_steps() {
+        let makefile = MakefileConverter::parse(MAKEFILE);
+        let command = makefile
+            .to_command("deploy", "Deploy the app", vec![])
+            .unwrap();
+
+        assert!(command.is_workflow());
+        let steps = command.steps.as_ref().unwrap();
+        let names: Vec<&str> = steps.iter().map(|step| step.name.as_str()).collect();
+        assert_eq!(names, ["build", "deploy (1)", "deploy (2)"]);
+        assert_eq!(steps[0].command, "echo building registry.example.com/app");
+        assert_eq!(steps[1].command, "kubectl apply -f k8s/{{ ENV }}.yaml");
+        assert_eq!(
+            steps[2].command,
+            "echo deployed deploy to {{ CLUSTER }} at $(date)"
+        );
+        assert!(!steps[1].continues_on_error());
+        assert!(steps[2].continues_on_error());
+
+        let env = &command.variables[0];
+        assert_eq!(env.name, "ENV");
+        assert_eq!(env.default_value.as_deref(), Some("staging"));
+        assert!(!env.required);
+        assert_eq!(command.variables[1].name, "CLUSTER");
+        assert!(command.variables[1].required);
+    }
+
+    #[test]
+    fn test_single_line_target_is_a_simple_command() {
+        let makefile = MakefileConverter::parse(MAKEFILE);
+        let command = makefile.to_command("build", "Build", vec![]).unwrap();
+
+        assert!(!command.is_workflow());
+        assert_eq!(
+            command.command.as_deref(),
+            Some("echo building registry.example.com/app")
+        );
+    }
+
+    #[test]
+    fn test_prerequisite_cycle_is_rejected() {
+        let makefile = MakefileConverter::parse("a: b\n\techo a\nb: a\n\techo b\n");
+        let error = makefile.to_command("a", "A", vec![]).unwrap_err();
+        assert!(error.to_string().contains("a -> b -> a"), "{}", error);
+    }
+}
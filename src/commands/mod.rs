@@ -1,17 +1,41 @@
+pub mod capture;
+pub mod env_diff;
 pub mod executor;
 pub mod expression;
 pub mod function_converter;
+pub mod junit;
+pub mod makefile_converter;
 pub mod models;
+pub mod npm_converter;
+pub mod parallel;
+pub mod plan;
+mod progress;
+pub mod recording;
+pub mod result;
+pub mod scheduler;
+pub mod script_emitter;
 pub mod variables;
 pub mod workflow_validator;
 
-pub use executor::CommandExecutor;
+pub use env_diff::EnvDiff;
+pub use executor::{CommandExecutor, ExecutionOptions, StepObserver, StepReport};
 pub use expression::ExpressionEvaluator;
 pub use function_converter::FunctionConverter;
+pub use junit::JunitReport;
+pub use makefile_converter::{MakeTarget, Makefile, MakefileConverter};
 pub use models::{
     BranchCase, BranchStep, Command, Condition, ConditionalAction, ConditionalBlock,
-    ConditionalStep, LoopStep, StepType, Workflow, WorkflowStep, WorkflowVariable,
-    WorkflowVariableProfile,
+    ConditionalStep, ConfirmStep, ElifBlock, FlatStep, LoopStep, StepType, VarType, WaitForStep,
+    Workflow, WorkflowStep, WorkflowVariable, WorkflowVariableProfile,
+};
+pub use npm_converter::NpmConverter;
+pub use parallel::{ParallelAnalyzer, ParallelGroup, StepEffects};
+pub use plan::{ExecutionPlan, PlanLine};
+pub use recording::CastRecorder;
+pub use result::CommandResult;
+pub use scheduler::{OverrunPolicy, Schedule, ScheduleSummary, parse_duration};
+pub use script_emitter::ScriptEmitter;
+pub use variables::{BackgroundProcess, VariableCache, VariableProcessor, WorkflowContext};
+pub use workflow_validator::{
+    FixResult, Severity, ValidationIssue, ValidationReport, WorkflowValidator,
 };
-pub use variables::{VariableProcessor, WorkflowContext};
-pub use workflow_validator::{Severity, ValidationIssue, ValidationReport, WorkflowValidator};
@@ -1,6 +1,9 @@
+use crate::error::{ClixError, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::time::SystemTime;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Command {
@@ -9,11 +12,37 @@ pub struct Command {
     pub command: Option<String>,          // None for workflows
     pub steps: Option<Vec<WorkflowStep>>, // None for simple commands
     pub created_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<u64>,
     pub last_used: Option<u64>,
     pub use_count: u32,
     pub tags: Vec<String>,
     pub variables: Vec<WorkflowVariable>,
     pub profiles: HashMap<String, WorkflowVariableProfile>,
+    /// continue_on_error used by steps that don't set it themselves
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub default_continue_on_error: bool,
+    /// Refuse to start while another run of this command holds its lockfile
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub lock: bool,
+    /// Always run the workflow as if --strict-vars were passed
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub strict_vars: bool,
+    /// Always run the workflow as if --reject-unsafe-vars were passed
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub reject_unsafe_vars: bool,
+    /// Who maintains the command, for shared stores
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// Version of the definition, e.g. `1.2.0`; see `bump_version`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Short notes on what changed, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub changelog: Vec<String>,
+    /// Free-form notes, e.g. caveats or links; set with `clix note set`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
 }
 
 impl Command {
@@ -29,11 +58,20 @@ impl Command {
             command: Some(command),
             steps: None,
             created_at: now,
+            updated_at: None,
             last_used: None,
             use_count: 0,
             tags,
             variables: Vec::new(),
             profiles: HashMap::new(),
+            default_continue_on_error: false,
+            lock: false,
+            strict_vars: false,
+            reject_unsafe_vars: false,
+            author: None,
+            version: None,
+            changelog: Vec::new(),
+            notes: None,
         }
     }
 
@@ -54,11 +92,20 @@ impl Command {
             command: None,
             steps: Some(steps),
             created_at: now,
+            updated_at: None,
             last_used: None,
             use_count: 0,
             tags,
             variables: Vec::new(),
             profiles: HashMap::new(),
+            default_continue_on_error: false,
+            lock: false,
+            strict_vars: false,
+            reject_unsafe_vars: false,
+            author: None,
+            version: None,
+            changelog: Vec::new(),
+            notes: None,
         }
     }
 
@@ -104,6 +151,43 @@ impl Command {
         self.last_used = Some(now);
         self.use_count += 1;
     }
+
+    pub fn mark_updated(&mut self) {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        self.updated_at = Some(now);
+    }
+
+    /// Time of the last change to the command's definition
+    pub fn modified_at(&self) -> u64 {
+        self.updated_at.unwrap_or(self.created_at)
+    }
+
+    /// Increment the last number of the version, so `1.2.0` becomes `1.2.1`
+    /// and `3` becomes `4`. A command without a version starts at `1`.
+    pub fn bump_version(&mut self) -> Result<()> {
+        let next = match &self.version {
+            None => "1".to_string(),
+            Some(version) => {
+                let (prefix, last) = match version.rsplit_once('.') {
+                    Some((prefix, last)) => (format!("{}.", prefix), last),
+                    None => (String::new(), version.as_str()),
+                };
+                let number: u64 = last.parse().map_err(|_| {
+                    ClixError::InvalidInput(format!(
+                        "Version '{}' doesn't end in a number, so it can't be bumped",
+                        version
+                    ))
+                })?;
+                format!("{}{}", prefix, number + 1)
+            }
+        };
+        self.version = Some(next);
+        Ok(())
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -112,6 +196,86 @@ pub struct WorkflowVariable {
     pub description: String,
     pub default_value: Option<String>,
     pub required: bool,
+    /// Values the variable is expected to take; empty means any value
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_values: Vec<String>,
+    /// Single-quote the value before substituting it into shell commands
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub quote: bool,
+    /// Derive the value from other variables when the run starts, e.g. `$REGISTRY/$APP:$TAG`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub computed: Option<String>,
+    /// The kind of value the variable holds, checked when a run starts
+    #[serde(default, skip_serializing_if = "VarType::is_string")]
+    pub var_type: VarType,
+}
+
+/// The kind of value a workflow variable holds
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum VarType {
+    /// Any text
+    #[default]
+    String,
+    /// A whole number, e.g. `3` or `-10`
+    Int,
+    /// `true` or `false`; `yes`/`no`, `on`/`off` and `1`/`0` are accepted too
+    Bool,
+    /// One of a fixed list of values
+    Enum(Vec<String>),
+}
+
+impl VarType {
+    pub fn is_string(&self) -> bool {
+        *self == VarType::String
+    }
+
+    /// Parse a value the way a `Bool` variable reads it
+    pub fn parse_bool(value: &str) -> Option<bool> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "true" | "yes" | "y" | "on" | "1" => Some(true),
+            "false" | "no" | "n" | "off" | "0" => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Check a value against the type and return it in canonical form:
+    /// integers without padding or a leading `+`, booleans as `true` or `false`
+    pub fn coerce(&self, value: &str) -> std::result::Result<String, String> {
+        match self {
+            VarType::String => Ok(value.to_string()),
+            VarType::Int => value
+                .trim()
+                .parse::<i64>()
+                .map(|n| n.to_string())
+                .map_err(|_| format!("expected an integer, got '{}'", value)),
+            VarType::Bool => Self::parse_bool(value)
+                .map(|b| b.to_string())
+                .ok_or_else(|| format!("expected true or false, got '{}'", value)),
+            VarType::Enum(values) => {
+                if values.iter().any(|v| v == value) {
+                    Ok(value.to_string())
+                } else {
+                    Err(format!(
+                        "expected one of {}, got '{}'",
+                        values.join(", "),
+                        value
+                    ))
+                }
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for VarType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VarType::String => write!(f, "string"),
+            VarType::Int => write!(f, "int"),
+            VarType::Bool => write!(f, "bool"),
+            VarType::Enum(values) => write!(f, "enum({})", values.join(", ")),
+        }
+    }
 }
 
 impl WorkflowVariable {
@@ -126,8 +290,46 @@ impl WorkflowVariable {
             description,
             default_value,
             required,
+            allowed_values: Vec::new(),
+            quote: false,
+            computed: None,
+            var_type: VarType::String,
         }
     }
+
+    // Method to restrict the variable to a set of values
+    pub fn with_allowed_values(mut self, values: Vec<String>) -> Self {
+        self.allowed_values = values;
+        self
+    }
+
+    // Method to quote the value when it is substituted
+    pub fn with_quote(mut self, quote: bool) -> Self {
+        self.quote = quote;
+        self
+    }
+
+    // Method to derive the value from other variables
+    pub fn with_computed(mut self, expression: String) -> Self {
+        self.computed = Some(expression);
+        self
+    }
+
+    // Method to set the kind of value the variable holds
+    pub fn with_type(mut self, var_type: VarType) -> Self {
+        self.var_type = var_type;
+        self
+    }
+
+    /// Check a value for this variable against its type, returning the value to use
+    pub fn coerce(&self, value: &str) -> Result<String> {
+        self.var_type.coerce(value).map_err(|reason| {
+            ClixError::InvalidInput(format!(
+                "Invalid value for variable '{}': {}",
+                self.name, reason
+            ))
+        })
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -153,11 +355,38 @@ pub struct Workflow {
     pub description: String,
     pub steps: Vec<WorkflowStep>,
     pub created_at: u64,
+    /// Set when the stored workflow is replaced; older stores lack it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<u64>,
     pub last_used: Option<u64>,
     pub use_count: u32,
     pub tags: Vec<String>,
     pub variables: Vec<WorkflowVariable>,
     pub profiles: HashMap<String, WorkflowVariableProfile>,
+    /// continue_on_error used by steps that don't set it themselves
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub default_continue_on_error: bool,
+    /// Fail steps that reference an undefined variable
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub strict_vars: bool,
+    /// Refuse to run when an unquoted variable value contains shell metacharacters
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub reject_unsafe_vars: bool,
+    /// Refuse to start while another run of this workflow holds its lock
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub single_instance: bool,
+    /// Who maintains the workflow, for shared stores
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// Version of the definition, e.g. `1.2.0`; see `bump_version`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Short notes on what changed, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub changelog: Vec<String>,
+    /// Free-form notes, e.g. caveats or links; set with `clix note set`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -167,6 +396,9 @@ pub enum StepType {
     Conditional,
     Branch,
     Loop,
+    Confirm,
+    StopBackground,
+    WaitFor,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -193,10 +425,19 @@ pub struct ConditionalBlock {
 pub struct ConditionalStep {
     pub condition: Condition,
     pub then_block: ConditionalBlock,
+    /// Checked in order when the condition is false, before the else block
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub elif_blocks: Vec<ElifBlock>,
     pub else_block: Option<ConditionalBlock>,
     pub action: Option<ConditionalAction>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ElifBlock {
+    pub condition: Condition,
+    pub then_block: ConditionalBlock,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct BranchCase {
     pub value: String,
@@ -216,12 +457,33 @@ pub struct LoopStep {
     pub steps: Vec<WorkflowStep>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct WaitForStep {
+    /// Delay between attempts, in milliseconds
+    pub interval_ms: u64,
+    /// Give up once this many milliseconds have passed
+    pub timeout_ms: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ConfirmStep {
+    pub prompt: String,
+    /// Answer assumed when running non-interactively
+    #[serde(default)]
+    pub default: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct WorkflowStep {
     pub name: String,
     pub command: String,
+    /// Read the command from this file when the workflow starts, instead of using `command`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command_file: Option<String>,
     pub description: String,
-    pub continue_on_error: bool,
+    /// Whether a failure keeps the workflow going; unset falls back to the workflow default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub continue_on_error: Option<bool>,
     pub step_type: StepType,
     #[serde(default = "default_require_approval")]
     pub require_approval: bool,
@@ -231,6 +493,32 @@ pub struct WorkflowStep {
     pub branch: Option<BranchStep>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub loop_data: Option<LoopStep>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confirm: Option<ConfirmStep>,
+    /// Store the step's trimmed stdout in this variable when it succeeds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capture_var: Option<String>,
+    /// Parse the step's stdout as JSON when it succeeds and store the value at
+    /// the path (e.g. `.items[0].id`) in the variable: `[variable, path]`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capture_json: Option<(String, String)>,
+    /// Label grouping consecutive steps under a banner
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub section: Option<String>,
+    /// Start the command without waiting; it is killed when the workflow ends
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub background: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wait_for: Option<WaitForStep>,
+    /// Names of top-level steps that must run before this one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+    /// Replace this step with the steps of the named snippet when the workflow runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include: Option<String>,
+    /// Kill the command and fail the step if it runs longer than this
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
 }
 
 // Default value function for require_approval
@@ -239,6 +527,14 @@ fn default_require_approval() -> bool {
 }
 
 impl WorkflowStep {
+    /// A placeholder step that expands to the steps of a snippet
+    pub fn new_include(name: String, snippet: String) -> Self {
+        let description = format!("Steps from snippet '{}'", snippet);
+        let mut step = Self::new_command(name, String::new(), description, false);
+        step.include = Some(snippet);
+        step
+    }
+
     pub fn new_command(
         name: String,
         command: String,
@@ -249,12 +545,22 @@ impl WorkflowStep {
             name,
             command,
             description,
-            continue_on_error,
+            continue_on_error: Some(continue_on_error),
             step_type: StepType::Command,
             require_approval: false,
             conditional: None,
             branch: None,
             loop_data: None,
+            confirm: None,
+            capture_var: None,
+            capture_json: None,
+            section: None,
+            command_file: None,
+            background: false,
+            wait_for: None,
+            depends_on: Vec::new(),
+            include: None,
+            timeout_secs: None,
         }
     }
 
@@ -268,12 +574,22 @@ impl WorkflowStep {
             name,
             command,
             description,
-            continue_on_error,
+            continue_on_error: Some(continue_on_error),
             step_type: StepType::Command,
             require_approval: true,
             conditional: None,
             branch: None,
             loop_data: None,
+            confirm: None,
+            capture_var: None,
+            capture_json: None,
+            section: None,
+            command_file: None,
+            background: false,
+            wait_for: None,
+            depends_on: Vec::new(),
+            include: None,
+            timeout_secs: None,
         }
     }
 
@@ -282,12 +598,22 @@ impl WorkflowStep {
             name,
             command,
             description,
-            continue_on_error: false, // Auth steps should not continue on error
+            continue_on_error: Some(false), // Auth steps should not continue on error
             step_type: StepType::Auth,
             require_approval: false,
             conditional: None,
             branch: None,
             loop_data: None,
+            confirm: None,
+            capture_var: None,
+            capture_json: None,
+            section: None,
+            command_file: None,
+            background: false,
+            wait_for: None,
+            depends_on: Vec::new(),
+            include: None,
+            timeout_secs: None,
         }
     }
 
@@ -306,17 +632,28 @@ impl WorkflowStep {
             name,
             command: String::new(), // Conditional steps don't have a direct command
             description,
-            continue_on_error: false,
+            continue_on_error: Some(false),
             step_type: StepType::Conditional,
             require_approval: false,
             conditional: Some(ConditionalStep {
                 condition,
                 then_block,
+                elif_blocks: Vec::new(),
                 else_block,
                 action,
             }),
             branch: None,
             loop_data: None,
+            confirm: None,
+            capture_var: None,
+            capture_json: None,
+            section: None,
+            command_file: None,
+            background: false,
+            wait_for: None,
+            depends_on: Vec::new(),
+            include: None,
+            timeout_secs: None,
         }
     }
 
@@ -331,7 +668,7 @@ impl WorkflowStep {
             name,
             command: String::new(), // Branch steps don't have a direct command
             description,
-            continue_on_error: false,
+            continue_on_error: Some(false),
             step_type: StepType::Branch,
             require_approval: false,
             conditional: None,
@@ -341,6 +678,16 @@ impl WorkflowStep {
                 default_case,
             }),
             loop_data: None,
+            confirm: None,
+            capture_var: None,
+            capture_json: None,
+            section: None,
+            command_file: None,
+            background: false,
+            wait_for: None,
+            depends_on: Vec::new(),
+            include: None,
+            timeout_secs: None,
         }
     }
 
@@ -354,12 +701,103 @@ impl WorkflowStep {
             name,
             command: String::new(), // Loop steps don't have a direct command
             description,
-            continue_on_error: false,
+            continue_on_error: Some(false),
             step_type: StepType::Loop,
             require_approval: false,
             conditional: None,
             branch: None,
             loop_data: Some(LoopStep { condition, steps }),
+            confirm: None,
+            capture_var: None,
+            capture_json: None,
+            section: None,
+            command_file: None,
+            background: false,
+            wait_for: None,
+            depends_on: Vec::new(),
+            include: None,
+            timeout_secs: None,
+        }
+    }
+
+    pub fn new_confirm(name: String, description: String, prompt: String, default: bool) -> Self {
+        WorkflowStep {
+            name,
+            command: String::new(), // Confirm steps ask a question instead of running a command
+            description,
+            continue_on_error: Some(false),
+            step_type: StepType::Confirm,
+            require_approval: false,
+            conditional: None,
+            branch: None,
+            loop_data: None,
+            confirm: Some(ConfirmStep { prompt, default }),
+            capture_var: None,
+            capture_json: None,
+            section: None,
+            command_file: None,
+            background: false,
+            wait_for: None,
+            depends_on: Vec::new(),
+            include: None,
+            timeout_secs: None,
+        }
+    }
+
+    pub fn new_wait_for(
+        name: String,
+        description: String,
+        command: String,
+        interval: Duration,
+        timeout: Duration,
+    ) -> Self {
+        WorkflowStep {
+            name,
+            command, // Polled until it exits successfully
+            description,
+            continue_on_error: Some(false),
+            step_type: StepType::WaitFor,
+            require_approval: false,
+            conditional: None,
+            branch: None,
+            loop_data: None,
+            confirm: None,
+            capture_var: None,
+            capture_json: None,
+            section: None,
+            command_file: None,
+            background: false,
+            depends_on: Vec::new(),
+            include: None,
+            timeout_secs: None,
+            wait_for: Some(WaitForStep {
+                interval_ms: interval.as_millis() as u64,
+                timeout_ms: timeout.as_millis() as u64,
+            }),
+        }
+    }
+
+    pub fn new_stop_background(name: String, description: String) -> Self {
+        WorkflowStep {
+            name,
+            command: String::new(), // Stops the processes started by background steps
+            description,
+            continue_on_error: Some(false),
+            step_type: StepType::StopBackground,
+            require_approval: false,
+            conditional: None,
+            branch: None,
+            loop_data: None,
+            confirm: None,
+            capture_var: None,
+            capture_json: None,
+            section: None,
+            command_file: None,
+            background: false,
+            wait_for: None,
+            depends_on: Vec::new(),
+            include: None,
+            timeout_secs: None,
         }
     }
 
@@ -368,6 +806,183 @@ impl WorkflowStep {
         self.require_approval = true;
         self
     }
+
+    /// Names of the variables this step sets from its output
+    pub fn captured_variables(&self) -> impl Iterator<Item = &String> {
+        self.capture_var
+            .iter()
+            .chain(self.capture_json.as_ref().map(|(name, _)| name))
+    }
+
+    /// List the steps nested inside a conditional, branch or loop step, in order
+    pub fn nested_steps(&self) -> Vec<&WorkflowStep> {
+        let mut steps = Vec::new();
+
+        if let Some(conditional) = &self.conditional {
+            steps.extend(conditional.then_block.steps.iter());
+            for elif in &conditional.elif_blocks {
+                steps.extend(elif.then_block.steps.iter());
+            }
+            if let Some(else_block) = &conditional.else_block {
+                steps.extend(else_block.steps.iter());
+            }
+        }
+
+        if let Some(branch) = &self.branch {
+            for case in &branch.cases {
+                steps.extend(case.steps.iter());
+            }
+            if let Some(default_steps) = &branch.default_case {
+                steps.extend(default_steps.iter());
+            }
+        }
+
+        if let Some(loop_data) = &self.loop_data {
+            steps.extend(loop_data.steps.iter());
+        }
+
+        steps
+    }
+
+    /// Name of the first step, nested ones included, whose `timeout_secs` is
+    /// 0, which would kill its command as soon as it starts
+    pub fn zero_timeout_step(steps: &[WorkflowStep]) -> Option<&str> {
+        let mut pending: Vec<&WorkflowStep> = steps.iter().collect();
+        let mut index = 0;
+        while index < pending.len() {
+            let step = pending[index];
+            if step.timeout_secs == Some(0) {
+                return Some(&step.name);
+            }
+            pending.extend(step.nested_steps());
+            index += 1;
+        }
+        None
+    }
+
+    /// Refuse steps whose `timeout_secs` is 0
+    pub fn check_timeouts(steps: &[WorkflowStep]) -> Result<()> {
+        match Self::zero_timeout_step(steps) {
+            Some(name) => Err(ClixError::ValidationError(format!(
+                "Step '{}' has timeout_secs 0; use at least 1, or leave it out for no limit",
+                name
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether a failure of this step keeps the workflow going
+    pub fn continues_on_error(&self) -> bool {
+        self.continue_on_error.unwrap_or(false)
+    }
+
+    /// The step lists nested inside a conditional, branch or loop step
+    pub(crate) fn nested_blocks_mut(&mut self) -> Vec<&mut Vec<WorkflowStep>> {
+        let mut blocks = Vec::new();
+        if let Some(conditional) = &mut self.conditional {
+            blocks.push(&mut conditional.then_block.steps);
+            for elif in &mut conditional.elif_blocks {
+                blocks.push(&mut elif.then_block.steps);
+            }
+            if let Some(else_block) = &mut conditional.else_block {
+                blocks.push(&mut else_block.steps);
+            }
+        }
+        if let Some(branch) = &mut self.branch {
+            for case in &mut branch.cases {
+                blocks.push(&mut case.steps);
+            }
+            if let Some(default_steps) = &mut branch.default_case {
+                blocks.push(default_steps);
+            }
+        }
+        if let Some(loop_data) = &mut self.loop_data {
+            blocks.push(&mut loop_data.steps);
+        }
+        blocks
+    }
+
+    /// Read `command_file` into `command`, here and in nested steps, so the
+    /// security checks and variable guards see the script that will run.
+    /// Relative paths are resolved against `base_dir` when given. A file
+    /// that can't be read keeps its `command_file`, and the step fails when
+    /// it comes up rather than running something unchecked.
+    pub fn load_command_file(&mut self, base_dir: Option<&Path>) {
+        if let Some(command_file) = &self.command_file {
+            let path = match base_dir {
+                Some(base_dir) => base_dir.join(command_file),
+                None => PathBuf::from(command_file),
+            };
+            if let Ok(script) = fs::read_to_string(&path) {
+                self.command = script;
+                self.command_file = None;
+            }
+        }
+
+        for block in self.nested_blocks_mut() {
+            for step in block {
+                step.load_command_file(base_dir);
+            }
+        }
+    }
+
+    /// Fill in continue_on_error wherever it is unset, including nested steps
+    pub fn apply_continue_on_error_default(&mut self, default: bool) {
+        self.continue_on_error.get_or_insert(default);
+
+        for block in self.nested_blocks_mut() {
+            for step in block {
+                step.apply_continue_on_error_default(default);
+            }
+        }
+    }
+
+    // Method to add an elif branch to a conditional step
+    pub fn with_elif(mut self, condition: Condition, steps: Vec<WorkflowStep>) -> Self {
+        if let Some(conditional) = &mut self.conditional {
+            conditional.elif_blocks.push(ElifBlock {
+                condition,
+                then_block: ConditionalBlock { steps },
+            });
+        }
+        self
+    }
+
+    // Method to capture the step's output into a variable
+    pub fn with_capture_var(mut self, name: String) -> Self {
+        self.capture_var = Some(name);
+        self
+    }
+
+    // Method to capture a value from the step's JSON output into a variable
+    pub fn with_capture_json(mut self, name: String, path: String) -> Self {
+        self.capture_json = Some((name, path));
+        self
+    }
+
+    // Method to run the step in the background
+    pub fn in_background(mut self) -> Self {
+        self.background = true;
+        self
+    }
+
+    // Method to load the step's command from a script file
+    pub fn with_command_file(mut self, path: String) -> Self {
+        self.command_file = Some(path);
+        self
+    }
+
+    // Method to place the step in a named section
+    pub fn with_section(mut self, section: String) -> Self {
+        self.section = Some(section);
+        self
+    }
+
+    // Method to run the step only after the named steps
+    pub fn with_depends_on(mut self, steps: Vec<String>) -> Self {
+        self.depends_on = steps;
+        self
+    }
 }
 
 impl Workflow {
@@ -387,11 +1002,20 @@ impl Workflow {
             description,
             steps,
             created_at: now,
+            updated_at: None,
             last_used: None,
             use_count: 0,
             tags,
             variables: Vec::new(),
             profiles: HashMap::new(),
+            default_continue_on_error: false,
+            strict_vars: false,
+            reject_unsafe_vars: false,
+            single_instance: false,
+            author: None,
+            version: None,
+            changelog: Vec::new(),
+            notes: None,
         }
     }
 
@@ -407,6 +1031,118 @@ impl Workflow {
         workflow
     }
 
+    /// Find a step by name, looking inside conditional, branch and loop steps too.
+    /// Top-level steps are checked first.
+    pub fn find_step(&self, name: &str) -> Option<&WorkflowStep> {
+        let mut pending: Vec<&WorkflowStep> = self.steps.iter().collect();
+        let mut index = 0;
+        while index < pending.len() {
+            let step = pending[index];
+            if step.name == name {
+                return Some(step);
+            }
+            pending.extend(step.nested_steps());
+            index += 1;
+        }
+        None
+    }
+
+    /// Every step in run-listing order, with the steps inside conditional,
+    /// branch and loop blocks placed right after the step that holds them
+    pub fn flatten_steps(&self) -> Vec<FlatStep> {
+        let mut flat = Vec::new();
+        flatten_into(&self.steps, 0, None, &mut flat);
+        flat
+    }
+
+    /// Copy of the workflow with every `include` step, nested ones too, replaced by
+    /// the steps of its snippet. Snippets may include other snippets; an unknown
+    /// snippet or one that ends up including itself is an error.
+    pub fn with_includes_expanded(
+        &self,
+        snippets: &HashMap<String, Vec<WorkflowStep>>,
+    ) -> Result<Workflow> {
+        let mut workflow = self.clone();
+        workflow.steps = expand_includes(&self.steps, snippets, &mut Vec::new())?;
+        Ok(workflow)
+    }
+
+    /// Copy of the workflow with every step's `command_file` read into its `command`
+    pub fn with_command_files_loaded(&self, base_dir: Option<&Path>) -> Workflow {
+        let mut workflow = self.clone();
+        for step in &mut workflow.steps {
+            step.load_command_file(base_dir);
+        }
+        workflow
+    }
+
+    /// Copy of the workflow with step defaults such as continue_on_error filled in
+    pub fn with_step_defaults_applied(&self) -> Workflow {
+        let mut workflow = self.clone();
+        for step in &mut workflow.steps {
+            step.apply_continue_on_error_default(self.default_continue_on_error);
+        }
+        workflow
+    }
+
+    /// Indices of the top-level steps in an order that respects `depends_on`.
+    /// Steps keep their listed order wherever their dependencies allow it.
+    pub fn dependency_order(&self) -> Result<Vec<usize>> {
+        let mut dependencies: Vec<BTreeSet<usize>> = Vec::with_capacity(self.steps.len());
+        for step in &self.steps {
+            let mut indices = BTreeSet::new();
+            for dependency in &step.depends_on {
+                let matches: Vec<usize> = self
+                    .steps
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, other)| &other.name == dependency)
+                    .map(|(index, _)| index)
+                    .collect();
+                if matches.is_empty() {
+                    return Err(ClixError::ValidationError(format!(
+                        "Step '{}' depends on unknown step '{}'",
+                        step.name, dependency
+                    )));
+                }
+                indices.extend(matches);
+            }
+            dependencies.push(indices);
+        }
+
+        let mut order = Vec::with_capacity(self.steps.len());
+        let mut done = vec![false; self.steps.len()];
+        while order.len() < self.steps.len() {
+            let ready = (0..self.steps.len())
+                .find(|&index| !done[index] && dependencies[index].iter().all(|&dep| done[dep]));
+            let Some(index) = ready else {
+                let blocked: Vec<&str> = (0..self.steps.len())
+                    .filter(|&index| !done[index])
+                    .map(|index| self.steps[index].name.as_str())
+                    .collect();
+                return Err(ClixError::ValidationError(format!(
+                    "Step dependencies form a cycle between: {}",
+                    blocked.join(", ")
+                )));
+            };
+            done[index] = true;
+            order.push(index);
+        }
+
+        Ok(order)
+    }
+
+    /// A copy of the workflow with its top-level steps sorted by `dependency_order`
+    pub fn with_dependency_order(&self) -> Result<Workflow> {
+        let mut workflow = self.clone();
+        workflow.steps = self
+            .dependency_order()?
+            .into_iter()
+            .map(|index| self.steps[index].clone())
+            .collect();
+        Ok(workflow)
+    }
+
     pub fn add_variable(&mut self, variable: WorkflowVariable) {
         // Replace if exists, add if not
         if let Some(idx) = self.variables.iter().position(|v| v.name == variable.name) {
@@ -433,6 +1169,20 @@ impl Workflow {
         self.last_used = Some(now);
         self.use_count += 1;
     }
+
+    pub fn mark_updated(&mut self) {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        self.updated_at = Some(now);
+    }
+
+    /// Time of the last change to the workflow's definition
+    pub fn modified_at(&self) -> u64 {
+        self.updated_at.unwrap_or(self.created_at)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -457,3 +1207,104 @@ impl Default for CommandStore {
         Self::new()
     }
 }
+
+/// A step in a flattened workflow, see `Workflow::flatten_steps`
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct FlatStep {
+    pub name: String,
+    pub step_type: StepType,
+    pub command: String,
+    /// 0 for top-level steps, one more for each enclosing block
+    pub depth: usize,
+    /// Name of the conditional, branch or loop step holding this one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    /// Which of the parent's blocks holds the step: `then`, `elif N`,
+    /// `else`, `case VALUE`, `default` or `loop`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block: Option<String>,
+}
+
+fn flatten_into(
+    steps: &[WorkflowStep],
+    depth: usize,
+    parent: Option<(&str, &str)>,
+    flat: &mut Vec<FlatStep>,
+) {
+    for step in steps {
+        flat.push(FlatStep {
+            name: step.name.clone(),
+            step_type: step.step_type.clone(),
+            command: step.command.clone(),
+            depth,
+            parent: parent.map(|(name, _)| name.to_string()),
+            block: parent.map(|(_, block)| block.to_string()),
+        });
+
+        let mut blocks: Vec<(String, &[WorkflowStep])> = Vec::new();
+        if let Some(conditional) = &step.conditional {
+            blocks.push(("then".to_string(), &conditional.then_block.steps));
+            for (index, elif) in conditional.elif_blocks.iter().enumerate() {
+                blocks.push((format!("elif {}", index + 1), &elif.then_block.steps));
+            }
+            if let Some(else_block) = &conditional.else_block {
+                blocks.push(("else".to_string(), &else_block.steps));
+            }
+        }
+        if let Some(branch) = &step.branch {
+            for case in &branch.cases {
+                blocks.push((format!("case {}", case.value), &case.steps));
+            }
+            if let Some(default_steps) = &branch.default_case {
+                blocks.push(("default".to_string(), default_steps));
+            }
+        }
+        if let Some(loop_data) = &step.loop_data {
+            blocks.push(("loop".to_string(), &loop_data.steps));
+        }
+
+        for (block, block_steps) in blocks {
+            flatten_into(block_steps, depth + 1, Some((&step.name, &block)), flat);
+        }
+    }
+}
+
+/// Expand the include steps in a list of steps. `stack` holds the snippets
+/// currently being expanded, to catch recursion.
+fn expand_includes(
+    steps: &[WorkflowStep],
+    snippets: &HashMap<String, Vec<WorkflowStep>>,
+    stack: &mut Vec<String>,
+) -> Result<Vec<WorkflowStep>> {
+    let mut expanded = Vec::with_capacity(steps.len());
+    for step in steps {
+        let Some(snippet) = &step.include else {
+            let mut step = step.clone();
+            for block in step.nested_blocks_mut() {
+                *block = expand_includes(block, snippets, stack)?;
+            }
+            expanded.push(step);
+            continue;
+        };
+
+        if stack.contains(snippet) {
+            stack.push(snippet.clone());
+            return Err(ClixError::ValidationError(format!(
+                "Snippet '{}' includes itself: {}",
+                snippet,
+                stack.join(" -> ")
+            )));
+        }
+        let included = snippets.get(snippet).ok_or_else(|| {
+            ClixError::ValidationError(format!(
+                "Step '{}' includes unknown snippet '{}'",
+                step.name, snippet
+            ))
+        })?;
+
+        stack.push(snippet.clone());
+        expanded.extend(expand_includes(included, snippets, stack)?);
+        stack.pop();
+    }
+    Ok(expanded)
+}
@@ -0,0 +1,117 @@
+use crate::commands::models::Command;
+use crate::error::{ClixError, Result};
+use crate::security::CommandSanitizer;
+use serde_json::Value;
+use std::fs;
+
+/// Tag every command converted from a package.json carries
+pub const NPM_TAG: &str = "npm";
+
+/// Converts the `scripts` block of a package.json into clix commands
+pub struct NpmConverter;
+
+impl NpmConverter {
+    /// Read a package.json and convert each of its scripts
+    pub fn convert_file(path: &str, tags: Vec<String>) -> Result<Vec<Command>> {
+        let content = fs::read_to_string(path).map_err(|e| {
+            ClixError::Io(std::io::Error::other(format!(
+                "Failed to read package.json: {}",
+                e
+            )))
+        })?;
+        Self::convert(&content, tags)
+    }
+
+    /// Create one command per script that runs it with `npm run`, described by
+    /// the script's body and tagged `npm` along with `tags`
+    pub fn convert(package_json: &str, tags: Vec<String>) -> Result<Vec<Command>> {
+        let package: Value = serde_json::from_str(package_json)?;
+        let scripts = match package.get("scripts") {
+            Some(Value::Object(scripts)) => scripts,
+            Some(_) => {
+                return Err(ClixError::InvalidInput(
+                    "package.json 'scripts' must be an object".to_string(),
+                ));
+            }
+            None => {
+                return Err(ClixError::InvalidInput(
+                    "package.json has no 'scripts' block".to_string(),
+                ));
+            }
+        };
+
+        let mut tags = tags;
+        if !tags.iter().any(|tag| tag == NPM_TAG) {
+            tags.insert(0, NPM_TAG.to_string());
+        }
+
+        scripts
+            .iter()
+            .map(|(name, body)| {
+                let body = body.as_str().ok_or_else(|| {
+                    ClixError::InvalidInput(format!("npm script '{}' is not a string", name))
+                })?;
+                Ok(Command::new(
+                    name.clone(),
+                    body.to_string(),
+                    format!("npm run {}", Self::quote_script_name(name)),
+                    tags.clone(),
+                ))
+            })
+            .collect()
+    }
+
+    /// Script names such as `build:prod` pass through the shell as they are;
+    /// anything else is quoted
+    fn quote_script_name(name: &str) -> String {
+        let plain = name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, ':' | '-' | '_' | '.' | '/'));
+        if plain && !name.is_empty() {
+            name.to_string()
+        } else {
+            CommandSanitizer::shell_quote(name)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_each_script_becomes_a_tagged_command() {
+        let package = r#"{
+            "name": "web-app",
+            "scripts": {
+                "build": "tsc -p . && vite build",
+                "test:unit": "vitest run"
+            }
+        }"#;
+
+        let commands = NpmConverter::convert(package, vec!["frontend".to_string()]).unwrap();
+
+        assert_eq!(commands.len(), 2);
+        let build = commands.iter().find(|c| c.name == "build").unwrap();
+        assert_eq!(build.command.as_deref(), Some("npm run build"));
+        assert_eq!(build.description, "tsc -p . && vite build");
+        assert_eq!(build.tags, ["npm", "frontend"]);
+
+        let test = commands.iter().find(|c| c.name == "test:unit").unwrap();
+        assert_eq!(test.command.as_deref(), Some("npm run test:unit"));
+        assert_eq!(test.description, "vitest run");
+    }
+
+    #[test]
+    fn test_unusual_script_names_are_quoted() {
+        let package = r#"{"scripts": {"lint; rm": "eslint ."}}"#;
+        let commands = NpmConverter::convert(package, vec![]).unwrap();
+        assert_eq!(commands[0].command.as_deref(), Some("npm run 'lint; rm'"));
+    }
+
+    #[test]
+    fn test_missing_scripts_block_is_an_error() {
+        assert!(NpmConverter::convert(r#"{"name": "empty"}"#, vec![]).is_err());
+        assert!(NpmConverter::convert(r#"{"scripts": {"build": 1}}"#, vec![]).is_err());
+    }
+}
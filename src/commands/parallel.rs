@@ -0,0 +1,305 @@
+use crate::commands::models::{StepType, Workflow, WorkflowStep};
+use crate::commands::variables::VariableProcessor;
+use regex::Regex;
+use std::collections::BTreeSet;
+use std::sync::OnceLock;
+
+/// What a step's command reads and writes, as far as a quick look at the
+/// command text can tell. Best-effort: a script that writes a file it never
+/// names on the command line is not seen.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StepEffects {
+    pub reads_vars: BTreeSet<String>,
+    pub writes_vars: BTreeSet<String>,
+    pub reads_files: BTreeSet<String>,
+    pub writes_files: BTreeSet<String>,
+}
+
+/// Adjacent steps that could run together, and why the group could not be
+/// merged into the one before it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParallelGroup {
+    pub steps: Vec<String>,
+    pub reason: Option<String>,
+}
+
+impl ParallelGroup {
+    pub fn is_parallel(&self) -> bool {
+        self.steps.len() > 1
+    }
+}
+
+/// Suggests which adjacent workflow steps are independent of each other
+pub struct ParallelAnalyzer;
+
+/// Commands whose arguments are files they change
+const WRITING_COMMANDS: &[&str] = &[
+    "touch", "rm", "mkdir", "rmdir", "mv", "chmod", "chown", "tee",
+];
+
+impl ParallelAnalyzer {
+    /// Group the workflow's top-level steps, in the order they run, so that
+    /// each group's steps share no variable or file another one writes
+    pub fn suggest(workflow: &Workflow) -> Vec<ParallelGroup> {
+        let steps: Vec<&WorkflowStep> = match workflow.dependency_order() {
+            Ok(order) => order.into_iter().map(|i| &workflow.steps[i]).collect(),
+            Err(_) => workflow.steps.iter().collect(),
+        };
+
+        let mut groups: Vec<ParallelGroup> = Vec::new();
+        let mut current: Vec<&WorkflowStep> = Vec::new();
+        for step in steps {
+            let reason = current.last().and_then(|previous| {
+                Self::blocker(step)
+                    .or_else(|| {
+                        Self::blocker(previous)
+                            .map(|_| format!("'{}' must finish first", previous.name))
+                    })
+                    .or_else(|| {
+                        current
+                            .iter()
+                            .find_map(|earlier| Self::conflict(earlier, step))
+                    })
+            });
+
+            if current.is_empty() || reason.is_some() {
+                groups.push(ParallelGroup {
+                    steps: Vec::new(),
+                    reason,
+                });
+                current.clear();
+            }
+            current.push(step);
+            if let Some(group) = groups.last_mut() {
+                group.steps.push(step.name.clone());
+            }
+        }
+
+        groups
+    }
+
+    /// Why a step has to run on its own, if it does
+    fn blocker(step: &WorkflowStep) -> Option<String> {
+        let why = if step.step_type != StepType::Command {
+            "is not a plain command step"
+        } else if step.include.is_some() {
+            "includes a snippet"
+        } else if step.command_file.is_some() {
+            "reads its command from a file"
+        } else if step.require_approval {
+            "asks for approval"
+        } else if step.background || step.wait_for.is_some() {
+            "manages a background process"
+        } else {
+            return None;
+        };
+        Some(format!("'{}' {}", step.name, why))
+    }
+
+    /// Why `later` has to wait for `earlier`, if it does
+    pub fn conflict(earlier: &WorkflowStep, later: &WorkflowStep) -> Option<String> {
+        if later.depends_on.contains(&earlier.name) {
+            return Some(format!("'{}' depends on '{}'", later.name, earlier.name));
+        }
+
+        let first = Self::effects(earlier);
+        let second = Self::effects(later);
+
+        if let Some(var) = first.writes_vars.intersection(&second.reads_vars).next() {
+            return Some(format!(
+                "'{}' reads {{{{ {} }}}}, which '{}' captures",
+                later.name, var, earlier.name
+            ));
+        }
+        if let Some(var) = first.writes_vars.intersection(&second.writes_vars).next() {
+            return Some(format!(
+                "'{}' and '{}' both capture {{{{ {} }}}}",
+                earlier.name, later.name, var
+            ));
+        }
+
+        let first_touches: BTreeSet<&String> =
+            first.reads_files.union(&first.writes_files).collect();
+        let second_touches: BTreeSet<&String> =
+            second.reads_files.union(&second.writes_files).collect();
+        if let Some(file) = first
+            .writes_files
+            .iter()
+            .find(|f| second_touches.contains(f))
+        {
+            return Some(format!(
+                "'{}' uses {}, which '{}' writes",
+                later.name, file, earlier.name
+            ));
+        }
+        if let Some(file) = second
+            .writes_files
+            .iter()
+            .find(|f| first_touches.contains(f))
+        {
+            return Some(format!(
+                "'{}' writes {}, which '{}' uses",
+                later.name, file, earlier.name
+            ));
+        }
+
+        None
+    }
+
+    /// Work out what a step reads and writes from its command text
+    pub fn effects(step: &WorkflowStep) -> StepEffects {
+        let mut effects = StepEffects {
+            reads_vars: VariableProcessor::extract_variables(&step.command)
+                .into_iter()
+                .collect(),
+            ..StepEffects::default()
+        };
+        effects
+            .writes_vars
+            .extend(step.captured_variables().cloned());
+
+        for segment in segment_regex().split(&step.command) {
+            let mut words = segment.split_whitespace().map(unquote).peekable();
+            let program = words.peek().cloned().unwrap_or_default();
+            let writes_args = WRITING_COMMANDS.contains(&program.as_str());
+
+            let mut previous = String::new();
+            for word in words {
+                if let Some(target) = redirect_target(&previous, &word) {
+                    effects.writes_files.insert(target);
+                } else if matches!(previous.as_str(), "-o" | "--output") && looks_like_path(&word) {
+                    effects.writes_files.insert(word.clone());
+                } else if let Some(target) = word.strip_prefix("--output=")
+                    && looks_like_path(target)
+                {
+                    effects.writes_files.insert(target.to_string());
+                } else if looks_like_path(&word) {
+                    if writes_args {
+                        effects.writes_files.insert(word.clone());
+                    } else {
+                        effects.reads_files.insert(word.clone());
+                    }
+                }
+                previous = word;
+            }
+        }
+
+        // A file both read and written counts as written
+        let written = effects.writes_files.clone();
+        effects.reads_files.retain(|file| !written.contains(file));
+        effects
+    }
+}
+
+fn segment_regex() -> &'static Regex {
+    static SEGMENT: OnceLock<Regex> = OnceLock::new();
+    SEGMENT.get_or_init(|| Regex::new(r"&&|\|\||;|\|").unwrap())
+}
+
+fn unquote(word: &str) -> String {
+    word.trim_matches(|c| c == '"' || c == '\'').to_string()
+}
+
+/// The file a redirection writes, whether written `> file` or `>file`
+fn redirect_target(previous: &str, word: &str) -> Option<String> {
+    let target = if matches!(previous, ">" | ">>" | "1>" | "2>" | "&>") {
+        word
+    } else {
+        let stripped = word.trim_start_matches(['1', '2', '&']);
+        stripped
+            .strip_prefix(">>")
+            .or_else(|| stripped.strip_prefix('>'))
+            .filter(|rest| !rest.is_empty())?
+    };
+    (looks_like_path(target) && !target.starts_with("/dev/")).then(|| target.to_string())
+}
+
+/// Whether a word names a file: it has a directory separator or an extension
+fn looks_like_path(word: &str) -> bool {
+    static PATH: OnceLock<Regex> = OnceLock::new();
+    let path = PATH.get_or_init(|| Regex::new(r"(/|^[\w.-]*\w\.[A-Za-z]\w*$)").unwrap());
+    !word.is_empty()
+        && !word.starts_with('-')
+        && !word.starts_with('&')
+        && !word.contains("://")
+        && !word.contains("{{")
+        && !word.contains('=')
+        && path.is_match(word)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workflow(steps: Vec<WorkflowStep>) -> Workflow {
+        Workflow::new("build".to_string(), "Build".to_string(), steps, vec![])
+    }
+
+    fn step(name: &str, command: &str) -> WorkflowStep {
+        WorkflowStep::new_command(
+            name.to_string(),
+            command.to_string(),
+            name.to_string(),
+            false,
+        )
+    }
+
+    #[test]
+    fn test_independent_steps_are_grouped() {
+        let groups = ParallelAnalyzer::suggest(&workflow(vec![
+            step("lint", "cargo clippy"),
+            step("docs", "mdbook build docs/"),
+        ]));
+
+        assert_eq!(groups.len(), 1);
+        assert!(groups[0].is_parallel());
+        assert_eq!(groups[0].steps, ["lint", "docs"]);
+    }
+
+    #[test]
+    fn test_captured_variable_keeps_steps_apart() {
+        let mut version = step("version", "git describe --tags");
+        version.capture_var = Some("version".to_string());
+        let groups = ParallelAnalyzer::suggest(&workflow(vec![
+            version,
+            step("tag", "docker tag app app:{{ version }}"),
+        ]));
+
+        assert_eq!(groups.len(), 2);
+        assert!(!groups[0].is_parallel());
+        assert_eq!(groups[1].steps, ["tag"]);
+        assert_eq!(
+            groups[1].reason.as_deref(),
+            Some("'tag' reads {{ version }}, which 'version' captures")
+        );
+    }
+
+    #[test]
+    fn test_file_written_then_read_keeps_steps_apart() {
+        let groups = ParallelAnalyzer::suggest(&workflow(vec![
+            step("report", "cargo test 2>&1 > target/report.txt"),
+            step("fmt", "cargo fmt --check"),
+            step("upload", "curl -T target/report.txt https://example.com"),
+        ]));
+
+        let names: Vec<&[String]> = groups.iter().map(|g| g.steps.as_slice()).collect();
+        assert_eq!(names, [&["report", "fmt"][..], &["upload"][..]]);
+        assert_eq!(
+            groups[1].reason.as_deref(),
+            Some("'upload' uses target/report.txt, which 'report' writes")
+        );
+    }
+
+    #[test]
+    fn test_effects_of_a_command() {
+        let effects = ParallelAnalyzer::effects(&step(
+            "pack",
+            "tar czf dist/app.tgz build/ | tee -a log.txt && rm old.tgz",
+        ));
+        assert!(effects.reads_files.contains("dist/app.tgz"));
+        assert!(effects.reads_files.contains("build/"));
+        assert!(effects.writes_files.contains("log.txt"));
+        assert!(effects.writes_files.contains("old.tgz"));
+        assert!(!effects.writes_files.contains("/dev/null"));
+    }
+}
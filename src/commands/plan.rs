@@ -0,0 +1,268 @@
+use crate::commands::expression::ExpressionEvaluator;
+use crate::commands::models::{ConditionalAction, StepType, Workflow, WorkflowStep};
+use crate::commands::variables::{VariableProcessor, WorkflowContext};
+use crate::error::Result;
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// One line of an execution plan, indented under the step that contains it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlanLine {
+    pub depth: usize,
+    pub text: String,
+}
+
+/// The steps a workflow would run, worked out without running anything
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExecutionPlan {
+    pub lines: Vec<PlanLine>,
+}
+
+impl ExecutionPlan {
+    /// Work out the run order of a workflow given its starting variables.
+    /// Conditions and branches are resolved when their inputs are known up front;
+    /// the rest list every alternative.
+    pub fn build(workflow: &Workflow, context: &WorkflowContext) -> Result<Self> {
+        let workflow = workflow
+            .with_step_defaults_applied()
+            .with_dependency_order()?;
+        let mut planner = Planner {
+            context: WorkflowContext::new(),
+            plan: ExecutionPlan::default(),
+        };
+        planner.context.merge_variables(context.variables.clone());
+
+        planner.plan_steps(&workflow.steps, 0);
+        Ok(planner.plan)
+    }
+
+    /// Render the plan as indented lines
+    pub fn render(&self) -> String {
+        self.lines
+            .iter()
+            .map(|line| format!("{}- {}\n", "  ".repeat(line.depth), line.text))
+            .collect()
+    }
+}
+
+struct Planner {
+    context: WorkflowContext,
+    plan: ExecutionPlan,
+}
+
+/// One alternative of a conditional step; `expression` is None for the else block
+struct Arm<'a> {
+    label: String,
+    expression: Option<&'a str>,
+    steps: &'a [WorkflowStep],
+}
+
+impl Planner {
+    fn push(&mut self, depth: usize, text: String) {
+        self.plan.lines.push(PlanLine { depth, text });
+    }
+
+    fn plan_steps(&mut self, steps: &[WorkflowStep], depth: usize) {
+        for step in steps {
+            let step = VariableProcessor::process_step(step, &self.context);
+            self.plan_step(&step, depth);
+
+            // Captured output is only known once the step has run
+            for name in step.captured_variables() {
+                self.context.variables.remove(name);
+            }
+        }
+    }
+
+    fn plan_step(&mut self, step: &WorkflowStep, depth: usize) {
+        let name = &step.name;
+        match step.step_type {
+            StepType::Conditional => self.plan_conditional(step, depth),
+            StepType::Branch => self.plan_branch(step, depth),
+            StepType::Loop => {
+                if let Some(loop_data) = &step.loop_data {
+                    self.push(
+                        depth,
+                        format!("{}: repeat while {}", name, loop_data.condition.expression),
+                    );
+                    self.plan_steps(&loop_data.steps, depth + 1);
+                }
+            }
+            StepType::Confirm => {
+                let prompt = step.confirm.as_ref().map_or("", |c| c.prompt.as_str());
+                self.push(depth, format!("{}: confirm \"{}\"", name, prompt));
+            }
+            StepType::WaitFor => {
+                self.push(
+                    depth,
+                    format!("{}: wait until `{}` succeeds", name, step.command),
+                );
+            }
+            StepType::StopBackground => {
+                self.push(depth, format!("{}: stop background processes", name));
+            }
+            StepType::Command | StepType::Auth => {
+                let command = match &step.command_file {
+                    Some(path) => format!("run {}", path),
+                    None => step.command.clone(),
+                };
+                let suffix = if step.background { " (background)" } else { "" };
+                self.push(depth, format!("{}: {}{}", name, command, suffix));
+            }
+        }
+    }
+
+    fn plan_conditional(&mut self, step: &WorkflowStep, depth: usize) {
+        let Some(conditional) = &step.conditional else {
+            return;
+        };
+        let name = &step.name;
+
+        let mut arms = vec![Arm {
+            label: "then".to_string(),
+            expression: Some(&conditional.condition.expression),
+            steps: &conditional.then_block.steps,
+        }];
+        for (index, elif) in conditional.elif_blocks.iter().enumerate() {
+            arms.push(Arm {
+                label: format!("elif {}", index + 1),
+                expression: Some(&elif.condition.expression),
+                steps: &elif.then_block.steps,
+            });
+        }
+        if let Some(else_block) = &conditional.else_block {
+            arms.push(Arm {
+                label: "else".to_string(),
+                expression: None,
+                steps: &else_block.steps,
+            });
+        }
+
+        // An explicit action overrides the condition, as it does at run time
+        match &conditional.action {
+            Some(ConditionalAction::RunThen) => arms.truncate(1),
+            Some(ConditionalAction::RunElse) => arms.retain(|arm| arm.expression.is_none()),
+            Some(ConditionalAction::Continue) => arms.clear(),
+            Some(ConditionalAction::Break) => {
+                self.push(depth, format!("{}: stop the workflow", name));
+                return;
+            }
+            Some(ConditionalAction::Return(code)) => {
+                self.push(depth, format!("{}: return exit code {}", name, code));
+                return;
+            }
+            None => {}
+        }
+
+        // Drop the arms known to be false; stop at the first one known to be true
+        let mut candidates = Vec::new();
+        let mut decided = false;
+        for arm in arms {
+            let resolved = match arm.expression {
+                Some(expression) => resolve_condition(expression, &self.context),
+                None => Some(true),
+            };
+            match resolved {
+                Some(false) => continue,
+                Some(true) => {
+                    candidates.push(arm);
+                    decided = true;
+                    break;
+                }
+                None => candidates.push(arm),
+            }
+        }
+
+        match candidates.as_slice() {
+            [] => self.push(depth, format!("{}: no block runs", name)),
+            [arm] if decided => {
+                self.push(depth, format!("{}: run the '{}' block", name, arm.label));
+                self.plan_steps(arm.steps, depth + 1);
+            }
+            _ => {
+                self.push(depth, format!("{}: decided at run time, one of:", name));
+                for arm in &candidates {
+                    match arm.expression {
+                        Some(expression) => {
+                            self.push(depth + 1, format!("{} ({}):", arm.label, expression))
+                        }
+                        None => self.push(depth + 1, format!("{}:", arm.label)),
+                    }
+                    self.plan_steps(arm.steps, depth + 2);
+                }
+                if !decided {
+                    self.push(depth + 1, "otherwise nothing runs".to_string());
+                }
+            }
+        }
+    }
+
+    fn plan_branch(&mut self, step: &WorkflowStep, depth: usize) {
+        let Some(branch) = &step.branch else {
+            return;
+        };
+        let name = &step.name;
+
+        let Some(value) = self.context.variables.get(&branch.variable).cloned() else {
+            self.push(
+                depth,
+                format!(
+                    "{}: branch on {} at run time, one of:",
+                    name, branch.variable
+                ),
+            );
+            for case in &branch.cases {
+                self.push(depth + 1, format!("case '{}':", case.value));
+                self.plan_steps(&case.steps, depth + 2);
+            }
+            if let Some(default_steps) = &branch.default_case {
+                self.push(depth + 1, "default:".to_string());
+                self.plan_steps(default_steps, depth + 2);
+            }
+            return;
+        };
+
+        if let Some(case) = branch.cases.iter().find(|case| case.value == value) {
+            self.push(
+                depth,
+                format!(
+                    "{}: {} = '{}' selects case '{}'",
+                    name, branch.variable, value, case.value
+                ),
+            );
+            self.plan_steps(&case.steps, depth + 1);
+        } else if let Some(default_steps) = &branch.default_case {
+            self.push(
+                depth,
+                format!(
+                    "{}: {} = '{}' selects the default case",
+                    name, branch.variable, value
+                ),
+            );
+            self.plan_steps(default_steps, depth + 1);
+        } else {
+            self.push(
+                depth,
+                format!(
+                    "{}: {} = '{}' matches no case, nothing runs",
+                    name, branch.variable, value
+                ),
+            );
+        }
+    }
+}
+
+/// Evaluate a condition now if that is safe and its result can't change at run
+/// time: a plain `[ ... ]` test whose variables are all filled in, with nothing
+/// that reads runtime state or runs other commands
+fn resolve_condition(expression: &str, context: &WorkflowContext) -> Option<bool> {
+    static STATIC_TEST: OnceLock<Regex> = OnceLock::new();
+    let static_test =
+        STATIC_TEST.get_or_init(|| Regex::new(r#"^\s*\[\[?\s[^$`;|&<>(){}]*\s\]\]?\s*$"#).unwrap());
+
+    if !static_test.is_match(expression) {
+        return None;
+    }
+    ExpressionEvaluator::evaluate_typed(expression, &context.variables, &context.var_types, None)
+        .ok()
+}
@@ -0,0 +1,52 @@
+//! Where workflow runs print their progress
+use std::cell::Cell;
+
+thread_local! {
+    /// Set while a workflow runs with `progress_to_stderr`
+    static PROGRESS_TO_STDERR: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Print a line of run progress to stdout, or to stderr when stdout carries
+/// machine-readable results
+macro_rules! progress {
+    ($($arg:tt)*) => {
+        if $crate::commands::progress::to_stderr() {
+            eprintln!($($arg)*)
+        } else {
+            println!($($arg)*)
+        }
+    };
+}
+
+/// Like `progress!`, without the trailing newline (for prompts)
+macro_rules! progress_inline {
+    ($($arg:tt)*) => {
+        if $crate::commands::progress::to_stderr() {
+            eprint!($($arg)*)
+        } else {
+            print!($($arg)*)
+        }
+    };
+}
+
+pub(crate) use {progress, progress_inline};
+
+/// Whether progress currently goes to stderr
+pub(crate) fn to_stderr() -> bool {
+    PROGRESS_TO_STDERR.with(Cell::get)
+}
+
+/// Sends progress to stderr until dropped, then restores the previous target
+pub(crate) struct ProgressRedirect(bool);
+
+impl ProgressRedirect {
+    pub(crate) fn new(to_stderr: bool) -> Self {
+        ProgressRedirect(PROGRESS_TO_STDERR.with(|flag| flag.replace(to_stderr)))
+    }
+}
+
+impl Drop for ProgressRedirect {
+    fn drop(&mut self) {
+        PROGRESS_TO_STDERR.with(|flag| flag.set(self.0));
+    }
+}
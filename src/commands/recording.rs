@@ -0,0 +1,61 @@
+use crate::error::Result;
+use serde_json::json;
+use std::fs;
+use std::path::Path;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+const CAST_WIDTH: u16 = 80;
+const CAST_HEIGHT: u16 = 24;
+
+/// Collects terminal output with timings as an asciinema v2 cast
+pub struct CastRecorder {
+    started: Instant,
+    header: serde_json::Value,
+    events: Vec<serde_json::Value>,
+}
+
+impl CastRecorder {
+    pub fn new(title: &str) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        Self {
+            started: Instant::now(),
+            header: json!({
+                "version": 2,
+                "width": CAST_WIDTH,
+                "height": CAST_HEIGHT,
+                "timestamp": timestamp,
+                "title": title,
+            }),
+            events: Vec::new(),
+        }
+    }
+
+    /// Record text written to the terminal at the current moment
+    pub fn output(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+
+        // Players expect terminal line endings
+        let data = text.replace("\r\n", "\n").replace('\n', "\r\n");
+        let elapsed = self.started.elapsed().as_secs_f64();
+        self.events.push(json!([elapsed, "o", data]));
+    }
+
+    /// Render the header line followed by one line per event
+    pub fn to_cast(&self) -> String {
+        let mut lines = vec![self.header.to_string()];
+        lines.extend(self.events.iter().map(|event| event.to_string()));
+        lines.push(String::new());
+        lines.join("\n")
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_cast())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+use crate::error::{ClixError, Result};
+use serde::{Deserialize, Serialize};
+use std::process::Output;
+use std::time::Duration;
+
+/// The outcome of running a command or step, in a shape that serializes the
+/// same way wherever it is reported
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommandResult {
+    pub name: String,
+    /// None when the command did not run to an exit status (killed by a
+    /// signal, failed to start, or skipped)
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    pub duration_ms: u64,
+    pub success: bool,
+    /// The step was deliberately not run, so it neither passed nor failed
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub skipped: bool,
+}
+
+impl CommandResult {
+    /// Capture a finished process, decoding its output as UTF-8 (lossily)
+    pub fn from_output(name: &str, output: &Output, duration: Duration) -> Self {
+        CommandResult {
+            name: name.to_string(),
+            exit_code: output.status.code(),
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            duration_ms: duration.as_millis() as u64,
+            success: output.status.success(),
+            skipped: false,
+        }
+    }
+
+    /// Capture an execution attempt. A failed step keeps its exit code and
+    /// output; any other error, including the reason a step was skipped,
+    /// becomes the stderr text.
+    pub fn from_result(name: &str, result: &Result<Output>, duration: Duration) -> Self {
+        let error = match result {
+            Ok(output) => return Self::from_output(name, output, duration),
+            Err(error) => error,
+        };
+
+        let (exit_code, stdout, stderr) = match error {
+            ClixError::StepFailed {
+                exit_code,
+                stdout,
+                stderr,
+                ..
+            } => (*exit_code, stdout.clone(), stderr.clone()),
+            other => (None, String::new(), other.to_string()),
+        };
+        CommandResult {
+            name: name.to_string(),
+            exit_code,
+            stdout,
+            stderr,
+            duration_ms: duration.as_millis() as u64,
+            success: false,
+            skipped: matches!(error, ClixError::StepSkipped(_)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(unix)]
+    use std::os::unix::process::ExitStatusExt;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_from_output_and_serialize() {
+        let output = Output {
+            status: std::process::ExitStatus::from_raw(3 << 8),
+            stdout: b"built\n".to_vec(),
+            stderr: b"warning\n".to_vec(),
+        };
+
+        let result = CommandResult::from_output("build", &output, Duration::from_millis(42));
+        assert_eq!(result.exit_code, Some(3));
+        assert!(!result.success);
+
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "name": "build",
+                "exit_code": 3,
+                "stdout": "built\n",
+                "stderr": "warning\n",
+                "duration_ms": 42,
+                "success": false
+            })
+        );
+        let parsed: CommandResult = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, result);
+    }
+
+    #[test]
+    fn test_from_result_keeps_step_failure_details() {
+        let failed: Result<Output> = Err(ClixError::StepFailed {
+            step: "deploy".to_string(),
+            command: "./deploy.sh".to_string(),
+            exit_code: Some(2),
+            stdout: "deploying\n".to_string(),
+            stderr: "no credentials".to_string(),
+        });
+
+        let result = CommandResult::from_result("deploy", &failed, Duration::ZERO);
+        assert_eq!(result.exit_code, Some(2));
+        assert_eq!(result.stderr, "no credentials");
+        assert_eq!(result.stdout, "deploying\n");
+        assert!(!result.success);
+
+        let missing: Result<Output> = Err(ClixError::CommandExecutionFailed("boom".to_string()));
+        let result = CommandResult::from_result("deploy", &missing, Duration::ZERO);
+        assert_eq!(result.exit_code, None);
+        assert_eq!(result.stderr, "Command execution failed: boom");
+        assert!(!result.skipped);
+    }
+
+    #[test]
+    fn test_from_result_marks_skipped_steps() {
+        let skipped: Result<Output> = Err(ClixError::StepSkipped("not needed".to_string()));
+        let result = CommandResult::from_result("deploy", &skipped, Duration::ZERO);
+        assert!(result.skipped);
+        assert!(!result.success);
+        assert_eq!(result.exit_code, None);
+        assert_eq!(result.stderr, "Skipped: not needed");
+        assert_eq!(serde_json::to_value(&result).unwrap()["skipped"], true);
+    }
+}
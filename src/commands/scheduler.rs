@@ -0,0 +1,162 @@
+use crate::error::{ClixError, Result};
+use clap::ValueEnum;
+use regex::Regex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// What to do with the slots a run overran
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OverrunPolicy {
+    /// Drop the missed slots and wait for the next one
+    Skip,
+    /// Run the missed slots back to back until caught up
+    Queue,
+}
+
+/// Totals for a finished schedule
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ScheduleSummary {
+    pub runs: usize,
+    pub failures: usize,
+    pub skipped: usize,
+}
+
+/// Runs a job on a fixed interval for a fixed length of time
+pub struct Schedule {
+    every: Duration,
+    total: Duration,
+    overrun: OverrunPolicy,
+}
+
+impl Schedule {
+    pub fn new(every: Duration, total: Duration, overrun: OverrunPolicy) -> Result<Self> {
+        if every.is_zero() {
+            return Err(ClixError::InvalidInput(
+                "The interval must be greater than zero".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            every,
+            total,
+            overrun,
+        })
+    }
+
+    /// Call `job` with the 1-based run number at every slot that starts before the
+    /// duration elapses. The job returns whether the run succeeded.
+    pub fn run<F>(&self, mut job: F) -> Result<ScheduleSummary>
+    where
+        F: FnMut(usize) -> Result<bool>,
+    {
+        let start = Instant::now();
+        let end = start + self.total;
+        let mut next = start;
+        let mut summary = ScheduleSummary::default();
+
+        while next < end {
+            let now = Instant::now();
+            if now < next {
+                thread::sleep(next - now);
+            }
+
+            summary.runs += 1;
+            if !job(summary.runs)? {
+                summary.failures += 1;
+            }
+
+            next += self.every;
+            if self.overrun == OverrunPolicy::Skip {
+                let now = Instant::now();
+                while next < now && next < end {
+                    next += self.every;
+                    summary.skipped += 1;
+                }
+            }
+        }
+
+        Ok(summary)
+    }
+}
+
+/// Parse a duration such as `30s`, `5m`, `1h30m`, `7d` or `250ms`
+pub fn parse_duration(input: &str) -> Result<Duration> {
+    let invalid = || {
+        ClixError::InvalidInput(format!(
+            "Invalid duration '{}', expected a number with a unit such as 30s, 5m, 1h, 7d or 250ms",
+            input
+        ))
+    };
+
+    let part_regex = Regex::new(r"(\d+)(ms|s|m|h|d)").unwrap();
+    let mut total = Duration::ZERO;
+    let mut consumed = 0;
+    for captures in part_regex.captures_iter(input.trim()) {
+        let whole = captures.get(0).unwrap();
+        if whole.start() != consumed {
+            return Err(invalid());
+        }
+        consumed = whole.end();
+
+        let amount: u64 = captures[1].parse().map_err(|_| invalid())?;
+        total += match &captures[2] {
+            "ms" => Duration::from_millis(amount),
+            "s" => Duration::from_secs(amount),
+            "m" => Duration::from_secs(amount * 60),
+            "h" => Duration::from_secs(amount * 60 * 60),
+            _ => Duration::from_secs(amount * 60 * 60 * 24),
+        };
+    }
+
+    if consumed == 0 || consumed != input.trim().len() {
+        return Err(invalid());
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30s").unwrap(), Duration::from_secs(30));
+        assert_eq!(parse_duration("5m").unwrap(), Duration::from_secs(300));
+        assert_eq!(parse_duration("1h30m").unwrap(), Duration::from_secs(5400));
+        assert_eq!(parse_duration("250ms").unwrap(), Duration::from_millis(250));
+        assert_eq!(
+            parse_duration("30d").unwrap(),
+            Duration::from_secs(30 * 86400)
+        );
+
+        for bad in ["", "5", "m", "5 minutes", "1h 30m", "-5s"] {
+            assert!(parse_duration(bad).is_err(), "accepted '{}'", bad);
+        }
+    }
+
+    #[test]
+    fn test_overrun_policies() {
+        let slow_job = |_| {
+            thread::sleep(Duration::from_millis(120));
+            Ok(true)
+        };
+        let every = Duration::from_millis(50);
+        let total = Duration::from_millis(300);
+
+        // Queued slots all run eventually, one after another
+        let queued = Schedule::new(every, total, OverrunPolicy::Queue)
+            .unwrap()
+            .run(slow_job)
+            .unwrap();
+        assert_eq!(queued.runs, 6);
+        assert_eq!(queued.skipped, 0);
+
+        // Skipped slots are dropped, so the slow job only fits twice
+        let skipped = Schedule::new(every, total, OverrunPolicy::Skip)
+            .unwrap()
+            .run(slow_job)
+            .unwrap();
+        assert_eq!(skipped.runs, 2);
+        assert_eq!(skipped.runs + skipped.skipped, 6);
+    }
+}
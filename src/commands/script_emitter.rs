@@ -0,0 +1,299 @@
+use crate::commands::models::{StepType, Workflow, WorkflowStep};
+use crate::security::CommandSanitizer;
+use regex::Regex;
+
+/// Converts a workflow back into a standalone bash script
+pub struct ScriptEmitter;
+
+impl ScriptEmitter {
+    /// Render the workflow as a runnable bash script
+    pub fn emit(workflow: &Workflow) -> String {
+        let workflow = &workflow.with_step_defaults_applied();
+        // Callers check the dependencies first; unresolvable ones keep the listed order
+        let workflow = &workflow
+            .with_dependency_order()
+            .unwrap_or_else(|_| workflow.clone());
+        let mut lines = vec![
+            "#!/usr/bin/env bash".to_string(),
+            format!("# {}", workflow.name),
+        ];
+        lines.extend(Self::comment_lines(&workflow.description, 0));
+        lines.push("set -e".to_string());
+
+        if workflow.steps.iter().any(Self::has_background_step) {
+            lines.push(String::new());
+            lines.push("# Background processes are stopped when the script exits".to_string());
+            lines.push("background_pids=()".to_string());
+            lines
+                .push("trap 'kill \"${background_pids[@]}\" 2>/dev/null || true' EXIT".to_string());
+        }
+
+        if !workflow.variables.is_empty() {
+            lines.push(String::new());
+            lines.push("# Variables".to_string());
+            for variable in &workflow.variables {
+                lines.extend(Self::comment_lines(&variable.description, 0));
+                match &variable.default_value {
+                    Some(default) => lines.push(format!(
+                        "{}=\"${{{}:-{}}}\"",
+                        variable.name,
+                        variable.name,
+                        Self::escape_double_quoted(default)
+                    )),
+                    None => lines.push(format!(
+                        ": \"${{{}:?{} is required}}\"",
+                        variable.name, variable.name
+                    )),
+                }
+            }
+        }
+
+        let mut current_section: Option<&str> = None;
+        for step in &workflow.steps {
+            let section = step.section.as_deref();
+            if let Some(name) = section
+                && section != current_section
+            {
+                lines.push(String::new());
+                lines.push(format!("# === {} ===", name));
+            }
+            current_section = section;
+
+            lines.push(String::new());
+            Self::emit_step(step, 0, &mut lines);
+        }
+
+        lines.push(String::new());
+        lines.join("\n")
+    }
+
+    /// Append the lines for a single step at the given nesting depth
+    fn emit_step(step: &WorkflowStep, depth: usize, lines: &mut Vec<String>) {
+        let indent = Self::indent(depth);
+        lines.push(format!("{}# {}", indent, step.name));
+        lines.extend(Self::comment_lines(&step.description, depth));
+
+        match step.step_type {
+            StepType::Command | StepType::Auth => {
+                let command = Self::convert_variables(&step.command);
+                let command = match (&step.capture_var, &step.capture_json) {
+                    (Some(var_name), _) => format!("{}=$({})", var_name, command),
+                    (None, Some((var_name, path))) => {
+                        format!("{}=$({} | {})", var_name, command, Self::jq_filter(path))
+                    }
+                    (None, None) => command,
+                };
+
+                if step.background {
+                    lines.push(format!("{}{} &", indent, command));
+                    lines.push(format!("{}background_pids+=($!)", indent));
+                } else if step.continues_on_error() {
+                    lines.push(format!("{}{} || true", indent, command));
+                } else {
+                    lines.push(format!("{}{}", indent, command));
+                }
+
+                // Both captures: pull the JSON value out of the captured output
+                if let (Some(output_var), Some((var_name, path))) =
+                    (&step.capture_var, &step.capture_json)
+                {
+                    lines.push(format!(
+                        "{}{}=$(printf '%s' \"${{{}}}\" | {})",
+                        indent,
+                        var_name,
+                        output_var,
+                        Self::jq_filter(path)
+                    ));
+                }
+            }
+            StepType::Conditional => {
+                let Some(conditional) = &step.conditional else {
+                    return;
+                };
+
+                lines.push(format!(
+                    "{}if {}; then",
+                    indent,
+                    Self::convert_condition(&conditional.condition.expression)
+                ));
+                Self::emit_block(&conditional.then_block.steps, depth + 1, lines);
+
+                for elif in &conditional.elif_blocks {
+                    lines.push(format!(
+                        "{}elif {}; then",
+                        indent,
+                        Self::convert_condition(&elif.condition.expression)
+                    ));
+                    Self::emit_block(&elif.then_block.steps, depth + 1, lines);
+                }
+
+                if let Some(else_block) = &conditional.else_block {
+                    lines.push(format!("{}else", indent));
+                    Self::emit_block(&else_block.steps, depth + 1, lines);
+                }
+                lines.push(format!("{}fi", indent));
+            }
+            StepType::Branch => {
+                let Some(branch) = &step.branch else {
+                    return;
+                };
+
+                lines.push(format!("{}case \"${{{}}}\" in", indent, branch.variable));
+                for case in &branch.cases {
+                    lines.push(format!(
+                        "{}    {})",
+                        indent,
+                        Self::convert_variables(&case.value)
+                    ));
+                    Self::emit_block(&case.steps, depth + 2, lines);
+                    lines.push(format!("{}        ;;", indent));
+                }
+                if let Some(default_steps) = &branch.default_case {
+                    lines.push(format!("{}    *)", indent));
+                    Self::emit_block(default_steps, depth + 2, lines);
+                    lines.push(format!("{}        ;;", indent));
+                }
+                lines.push(format!("{}esac", indent));
+            }
+            StepType::Loop => {
+                let Some(loop_data) = &step.loop_data else {
+                    return;
+                };
+
+                lines.push(format!(
+                    "{}while {}; do",
+                    indent,
+                    Self::convert_condition(&loop_data.condition.expression)
+                ));
+                Self::emit_block(&loop_data.steps, depth + 1, lines);
+                lines.push(format!("{}done", indent));
+            }
+            StepType::WaitFor => {
+                let Some(wait_for) = &step.wait_for else {
+                    return;
+                };
+
+                lines.push(format!(
+                    "{}deadline=$((SECONDS + {}))",
+                    indent,
+                    wait_for.timeout_ms.div_ceil(1000)
+                ));
+                lines.push(format!(
+                    "{}until {}; do",
+                    indent,
+                    Self::convert_variables(&step.command)
+                ));
+                lines.push(format!(
+                    "{}    if [ \"$SECONDS\" -ge \"$deadline\" ]; then echo \"{} timed out\"; exit 1; fi",
+                    indent,
+                    Self::escape_double_quoted(&step.name)
+                ));
+                lines.push(format!(
+                    "{}    sleep {}",
+                    indent,
+                    wait_for.interval_ms as f64 / 1000.0
+                ));
+                lines.push(format!("{}done", indent));
+            }
+            StepType::StopBackground => {
+                lines.push(format!(
+                    "{}kill \"${{background_pids[@]}}\" 2>/dev/null || true",
+                    indent
+                ));
+                lines.push(format!("{}background_pids=()", indent));
+            }
+            StepType::Confirm => {
+                let Some(confirm) = &step.confirm else {
+                    return;
+                };
+
+                let (hint, default_answer) = if confirm.default {
+                    ("[Y/n]", "y")
+                } else {
+                    ("[y/N]", "n")
+                };
+                lines.push(format!(
+                    "{}read -r -p \"{} {} \" answer",
+                    indent,
+                    Self::escape_double_quoted(&Self::convert_variables(&confirm.prompt)),
+                    hint
+                ));
+                lines.push(format!(
+                    "{}case \"${{answer:-{}}}\" in",
+                    indent, default_answer
+                ));
+                lines.push(format!("{}    [Yy]*) ;;", indent));
+                lines.push(format!("{}    *) echo \"Stopped.\"; exit 0 ;;", indent));
+                lines.push(format!("{}esac", indent));
+            }
+        }
+    }
+
+    fn has_background_step(step: &WorkflowStep) -> bool {
+        step.background
+            || step
+                .nested_steps()
+                .into_iter()
+                .any(Self::has_background_step)
+    }
+
+    /// Append a nested block, keeping bash happy when it is empty
+    fn emit_block(steps: &[WorkflowStep], depth: usize, lines: &mut Vec<String>) {
+        if steps.is_empty() {
+            lines.push(format!("{}:", Self::indent(depth)));
+            return;
+        }
+
+        for step in steps {
+            Self::emit_step(step, depth, lines);
+        }
+    }
+
+    /// Turn a workflow condition into something bash can test
+    fn convert_condition(expression: &str) -> String {
+        let expression = Self::convert_variables(expression.trim());
+        let exit_code_check =
+            Regex::new(r"^\$\?\s*(-eq|-ne|-gt|-lt|-ge|-le|==|!=|>|<|>=|<=)\s*\d+$").unwrap();
+
+        if exit_code_check.is_match(&expression) {
+            format!("[ {} ]", expression)
+        } else {
+            expression
+        }
+    }
+
+    /// Replace `{{ var }}` placeholders with shell `${var}` references
+    /// A jq call selecting the same value as a `capture_json` path
+    fn jq_filter(path: &str) -> String {
+        let path = path.trim();
+        let path = path.strip_prefix('$').unwrap_or(path);
+        let filter = if path.starts_with('.') {
+            path.to_string()
+        } else {
+            format!(".{}", path)
+        };
+        format!("jq -cr {}", CommandSanitizer::shell_quote(&filter))
+    }
+
+    fn convert_variables(text: &str) -> String {
+        let re = Regex::new(r"\{\{\s*([\w_]+)\s*\}\}").unwrap();
+        re.replace_all(text, "$${$1}").to_string()
+    }
+
+    fn comment_lines(text: &str, depth: usize) -> Vec<String> {
+        text.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| format!("{}# {}", Self::indent(depth), line.trim()))
+            .collect()
+    }
+
+    fn escape_double_quoted(text: &str) -> String {
+        text.replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('`', "\\`")
+    }
+
+    fn indent(depth: usize) -> String {
+        "    ".repeat(depth)
+    }
+}
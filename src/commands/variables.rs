@@ -1,16 +1,105 @@
+use crate::cli::theme::Themed;
 use crate::commands::models::{
-    BranchCase, BranchStep, Condition, ConditionalBlock, ConditionalStep, LoopStep, Workflow,
-    WorkflowStep,
+    BranchCase, BranchStep, Condition, ConditionalBlock, ConditionalStep, ConfirmStep, ElifBlock,
+    LoopStep, VarType, Workflow, WorkflowStep, WorkflowVariable,
 };
+use crate::commands::progress::{progress, progress_inline};
 use crate::error::{ClixError, Result};
-use colored::Colorize;
+use crate::security::{CommandSanitizer, SecurityValidator};
+use crate::storage::LoopCheckpoint;
 use regex::Regex;
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
 use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::process::Child;
+use std::rc::Rc;
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime};
+
+/// Placeholder written in place of secret values in dumped contexts
+pub const MASKED_VALUE: &str = "********";
+
+/// Name fragments that mark a variable as holding a secret
+const SECRET_NAME_MARKERS: &[&str] = &[
+    "SECRET",
+    "TOKEN",
+    "PASSWORD",
+    "PASSWD",
+    "API_KEY",
+    "APIKEY",
+    "PRIVATE_KEY",
+    "CREDENTIAL",
+];
+
+/// Matches `$NAME` and `${NAME}` in computed variable expressions
+fn computed_reference_regex() -> &'static Regex {
+    static REFERENCE: OnceLock<Regex> = OnceLock::new();
+    REFERENCE.get_or_init(|| Regex::new(r"\$(?:\{(\w+)\}|(\w+))").unwrap())
+}
+
+/// `$NAME` and `${NAME}` shell references; `${NAME:-default}` and other
+/// expansions with an operator are left alone since they handle unset names
+fn shell_reference_regex() -> &'static Regex {
+    static REFERENCE: OnceLock<Regex> = OnceLock::new();
+    REFERENCE
+        .get_or_init(|| Regex::new(r"(^|[^\\])\$(?:\{([A-Za-z_]\w*)\}|([A-Za-z_]\w*))").unwrap())
+}
+
+/// Names a command sets for itself: `NAME=`, `for NAME in` and `read NAME...`
+fn shell_assignment_regex() -> &'static Regex {
+    static ASSIGNMENT: OnceLock<Regex> = OnceLock::new();
+    ASSIGNMENT.get_or_init(|| {
+        Regex::new(
+            r"(?:^|[\s;&|(])(?:([A-Za-z_]\w*)=|for\s+([A-Za-z_]\w*)\s+in\b|read\s+((?:-\w+\s+)*[A-Za-z_]\w*(?:\s+[A-Za-z_]\w*)*))",
+        )
+        .unwrap()
+    })
+}
+
+/// A process started by a background step; it is killed when dropped
+#[derive(Debug)]
+pub struct BackgroundProcess {
+    pub step_name: String,
+    child: Child,
+}
+
+impl BackgroundProcess {
+    pub fn new(step_name: String, child: Child) -> Self {
+        Self { step_name, child }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.child.id()
+    }
+}
+
+impl Drop for BackgroundProcess {
+    fn drop(&mut self) {
+        // The process may already have exited; either way reap it
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
 
 #[derive(Debug, Clone, Default)]
 pub struct WorkflowContext {
     pub variables: HashMap<String, String>,
+    /// Fail steps that reference a variable which isn't defined
+    pub strict_variables: bool,
+    /// Declared types of the variables that aren't plain strings, used when
+    /// comparing them in conditions
+    pub var_types: HashMap<String, VarType>,
+    background: Rc<RefCell<Vec<BackgroundProcess>>>,
+    /// The workflow and top-level step being run, recorded in loop checkpoints
+    pub(crate) workflow_name: String,
+    pub(crate) step_index: usize,
+    /// Checkpoint a loop resumes from; taken by the loop it belongs to
+    pub(crate) resume_loop: Option<LoopCheckpoint>,
+    /// Variables declared with `quote`, single-quoted when substituted into
+    /// step commands; their values in `variables` stay as given
+    pub(crate) quoted_variables: HashSet<String>,
 }
 
 impl WorkflowContext {
@@ -31,6 +120,131 @@ impl WorkflowContext {
     pub fn has_variable(&self, name: &str) -> bool {
         self.variables.contains_key(name)
     }
+
+    /// Keep a background process alive until the workflow stops it
+    pub fn track_background(&self, process: BackgroundProcess) {
+        self.background.borrow_mut().push(process);
+    }
+
+    /// The context a conditional block runs in: a copy of this one, with its
+    /// variables, their types and the loop checkpoint details. The background
+    /// processes it starts stay tracked here.
+    pub(crate) fn block_context(&self) -> Self {
+        self.clone()
+    }
+
+    /// Number of background processes still tracked
+    pub fn background_count(&self) -> usize {
+        self.background.borrow().len()
+    }
+
+    /// Kill every tracked background process, returning the stopped step names
+    pub fn stop_background(&self) -> Vec<String> {
+        self.background
+            .borrow_mut()
+            .drain(..)
+            .map(|process| process.step_name.clone())
+            .collect()
+    }
+
+    /// Check whether a variable name looks like it holds a secret
+    pub fn is_secret_name(name: &str) -> bool {
+        let upper = name.to_uppercase();
+        SECRET_NAME_MARKERS
+            .iter()
+            .any(|marker| upper.contains(marker))
+    }
+
+    /// Write the variables to a JSON file, masking secrets unless requested
+    pub fn dump_to_file(&self, path: &Path, include_secrets: bool) -> Result<()> {
+        let variables: BTreeMap<&str, &str> = self
+            .variables
+            .iter()
+            .map(|(name, value)| {
+                if !include_secrets && Self::is_secret_name(name) {
+                    (name.as_str(), MASKED_VALUE)
+                } else {
+                    (name.as_str(), value.as_str())
+                }
+            })
+            .collect();
+
+        let content = serde_json::to_string_pretty(&variables)?;
+        fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// Read variables written by `dump_to_file`, dropping masked secrets
+    pub fn load_from_file(path: &Path) -> Result<HashMap<String, String>> {
+        let content = fs::read_to_string(path)?;
+        let mut variables: HashMap<String, String> = serde_json::from_str(&content)?;
+        variables.retain(|_, value| value != MASKED_VALUE);
+        Ok(variables)
+    }
+}
+
+/// Values entered at variable prompts, reused instead of prompting again by
+/// later workflow runs in the same process. When backed by a file, values also
+/// carry over to runs that start before the file expires; secrets never do.
+#[derive(Debug, Default)]
+pub struct VariableCache {
+    values: RefCell<HashMap<String, String>>,
+    file: Option<PathBuf>,
+}
+
+impl VariableCache {
+    /// How long a file-backed cache's values stay usable by later runs
+    pub const FILE_TTL: Duration = Duration::from_secs(15 * 60);
+
+    /// A cache that lasts as long as the process
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A cache that also keeps values in `path`, ignoring the file's contents
+    /// when it was last written more than `ttl` ago
+    pub fn with_file(path: PathBuf, ttl: Duration) -> Result<Self> {
+        let fresh = fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .is_some_and(|age| age <= ttl);
+        let values = if fresh {
+            WorkflowContext::load_from_file(&path)?
+        } else {
+            HashMap::new()
+        };
+
+        Ok(Self {
+            values: RefCell::new(values),
+            file: Some(path),
+        })
+    }
+
+    pub fn get(&self, name: &str) -> Option<String> {
+        self.values.borrow().get(name).cloned()
+    }
+
+    /// Remember a value, rewriting the backing file if there is one
+    pub fn remember(&self, name: &str, value: &str) -> Result<()> {
+        self.values
+            .borrow_mut()
+            .insert(name.to_string(), value.to_string());
+
+        if let Some(path) = &self.file {
+            let values = self.values.borrow();
+            let persisted: BTreeMap<&str, &str> = values
+                .iter()
+                .filter(|(name, _)| !WorkflowContext::is_secret_name(name))
+                .map(|(name, value)| (name.as_str(), value.as_str()))
+                .collect();
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, serde_json::to_string_pretty(&persisted)?)?;
+        }
+        Ok(())
+    }
 }
 
 pub struct VariableProcessor;
@@ -38,15 +252,29 @@ pub struct VariableProcessor;
 impl VariableProcessor {
     /// Process variables in a command string, replacing {{ var_name }} with values
     pub fn process_variables(command: &str, context: &WorkflowContext) -> String {
+        Self::substitute(command, context, false)
+    }
+
+    /// `process_variables` for text that runs in a shell: values of variables
+    /// declared with `quote` are single-quoted
+    pub fn process_command(command: &str, context: &WorkflowContext) -> String {
+        Self::substitute(command, context, true)
+    }
+
+    fn substitute(text: &str, context: &WorkflowContext, quote: bool) -> String {
         let re = Regex::new(r"\{\{\s*([\w_]+)\s*\}\}").unwrap();
-        let mut result = command.to_string();
+        let mut result = text.to_string();
 
-        for cap in re.captures_iter(command) {
+        for cap in re.captures_iter(text) {
             let var_name = &cap[1];
             let placeholder = &cap[0];
 
             if let Some(value) = context.variables.get(var_name) {
-                result = result.replace(placeholder, value);
+                if quote && context.quoted_variables.contains(var_name) {
+                    result = result.replace(placeholder, &CommandSanitizer::shell_quote(value));
+                } else {
+                    result = result.replace(placeholder, value);
+                }
             }
         }
 
@@ -68,6 +296,123 @@ impl VariableProcessor {
         vars
     }
 
+    /// Extract the `$NAME` and `${NAME}` references from a computed variable expression
+    pub fn extract_computed_references(expression: &str) -> Vec<String> {
+        let mut vars = Vec::new();
+        for cap in computed_reference_regex().captures_iter(expression) {
+            let var_name = cap
+                .get(1)
+                .or_else(|| cap.get(2))
+                .map_or("", |m| m.as_str())
+                .to_string();
+            if !vars.contains(&var_name) {
+                vars.push(var_name);
+            }
+        }
+        vars
+    }
+
+    /// Evaluate the workflow's computed variables into the context. Each one is
+    /// evaluated after the computed variables it refers to; references that
+    /// form a cycle, or that have no value, are rejected.
+    pub fn resolve_computed_variables(
+        workflow: &Workflow,
+        context: &mut WorkflowContext,
+    ) -> Result<()> {
+        let mut pending: Vec<&WorkflowVariable> = workflow
+            .variables
+            .iter()
+            .filter(|v| v.computed.is_some())
+            .collect();
+        let mut resolved: Vec<&str> = Vec::new();
+
+        while !pending.is_empty() {
+            // Evaluate, in declared order, every variable whose computed inputs are ready
+            let (ready, blocked): (Vec<_>, Vec<_>) = pending.into_iter().partition(|variable| {
+                let expression = variable.computed.as_deref().unwrap_or_default();
+                Self::extract_computed_references(expression)
+                    .iter()
+                    .all(|name| {
+                        !workflow
+                            .variables
+                            .iter()
+                            .any(|v| &v.name == name && v.computed.is_some())
+                            || resolved.contains(&name.as_str())
+                    })
+            });
+
+            if ready.is_empty() {
+                let names: Vec<&str> = blocked.iter().map(|v| v.name.as_str()).collect();
+                return Err(ClixError::ValidationError(format!(
+                    "Computed variables form a cycle between: {}",
+                    names.join(", ")
+                )));
+            }
+
+            for variable in ready {
+                let expression = variable.computed.as_deref().unwrap_or_default();
+                let value = Self::evaluate_computed(&variable.name, expression, context)?;
+                let value = variable.coerce(&value)?;
+                context.add_variable(variable.name.clone(), value);
+                resolved.push(&variable.name);
+            }
+            pending = blocked;
+        }
+
+        Ok(())
+    }
+
+    /// Check the values of typed variables, from prompts, `--var`, profiles or
+    /// defaults, and replace them with their canonical form. An optional
+    /// variable left empty is not checked.
+    pub fn apply_variable_types(workflow: &Workflow, context: &mut WorkflowContext) -> Result<()> {
+        for variable in &workflow.variables {
+            if variable.var_type.is_string() {
+                continue;
+            }
+            context
+                .var_types
+                .insert(variable.name.clone(), variable.var_type.clone());
+
+            let Some(value) = context.variables.get(&variable.name) else {
+                continue;
+            };
+            if value.is_empty() && !variable.required {
+                continue;
+            }
+            let value = variable.coerce(value)?;
+            context.variables.insert(variable.name.clone(), value);
+        }
+        Ok(())
+    }
+
+    /// Substitute the variable references in a computed expression
+    fn evaluate_computed(
+        name: &str,
+        expression: &str,
+        context: &WorkflowContext,
+    ) -> Result<String> {
+        let mut missing = None;
+        let value = computed_reference_regex().replace_all(expression, |cap: &regex::Captures| {
+            let var_name = cap.get(1).or_else(|| cap.get(2)).map_or("", |m| m.as_str());
+            match context.variables.get(var_name) {
+                Some(value) => value.clone(),
+                None => {
+                    missing.get_or_insert_with(|| var_name.to_string());
+                    String::new()
+                }
+            }
+        });
+
+        match missing {
+            Some(var_name) => Err(ClixError::ValidationError(format!(
+                "Computed variable '{}' uses '{}', which has no value",
+                name, var_name
+            ))),
+            None => Ok(value.into_owned()),
+        }
+    }
+
     /// Scan the workflow for all variables in commands
     pub fn scan_workflow_variables(workflow: &Workflow) -> Vec<String> {
         let mut vars = Vec::new();
@@ -84,10 +429,66 @@ impl VariableProcessor {
         vars
     }
 
+    /// Collect the variables that steps capture from their output
+    pub fn scan_captured_variables(workflow: &Workflow) -> Vec<String> {
+        fn collect(step: &WorkflowStep, vars: &mut Vec<String>) {
+            for var_name in step.captured_variables() {
+                if !vars.contains(var_name) {
+                    vars.push(var_name.clone());
+                }
+            }
+            for nested_step in step.nested_steps() {
+                collect(nested_step, vars);
+            }
+        }
+
+        let mut vars = Vec::new();
+        for step in &workflow.steps {
+            collect(step, &mut vars);
+        }
+        vars
+    }
+
     /// Prompt the user for any missing variables
     pub fn prompt_for_variables(workflow: &Workflow, context: &mut WorkflowContext) -> Result<()> {
-        // Get all variables used in the workflow
-        let all_vars = Self::scan_workflow_variables(workflow);
+        Self::prompt_for_variables_with(workflow, context, None, Self::read_stdin_line)
+    }
+
+    /// Read one trimmed answer to a variable prompt from stdin
+    pub(crate) fn read_stdin_line() -> Result<String> {
+        let mut input = String::new();
+        io::stdin().lock().read_line(&mut input).map_err(|e| {
+            ClixError::CommandExecutionFailed(format!("Failed to read variable input: {}", e))
+        })?;
+        Ok(input.trim().to_string())
+    }
+
+    /// Prompt for missing variables, reading answers with `read_line`. Values
+    /// in `cache` are used without prompting, and answers are added to it.
+    pub fn prompt_for_variables_with(
+        workflow: &Workflow,
+        context: &mut WorkflowContext,
+        cache: Option<&VariableCache>,
+        read_line: impl Fn() -> Result<String>,
+    ) -> Result<()> {
+        // Get all variables used in the workflow, including the inputs of computed ones
+        let mut all_vars = Self::scan_workflow_variables(workflow);
+        for variable in &workflow.variables {
+            if let Some(expression) = &variable.computed {
+                for var in Self::extract_computed_references(expression) {
+                    if !all_vars.contains(&var) {
+                        all_vars.push(var);
+                    }
+                }
+            }
+        }
+        let captured_vars = Self::scan_captured_variables(workflow);
+        let computed_vars: Vec<&str> = workflow
+            .variables
+            .iter()
+            .filter(|v| v.computed.is_some())
+            .map(|v| v.name.as_str())
+            .collect();
 
         // Check for variables that are used but not defined in the workflow
         for var_name in &all_vars {
@@ -96,6 +497,23 @@ impl VariableProcessor {
                 continue;
             }
 
+            // Skip variables filled in by a step's captured output
+            if captured_vars.contains(var_name) {
+                continue;
+            }
+
+            // Skip computed variables, filled in once their inputs are known
+            if computed_vars.contains(&var_name.as_str()) {
+                continue;
+            }
+
+            // Reuse a value entered for an earlier workflow
+            if let Some(value) = cache.and_then(|cache| cache.get(var_name)) {
+                progress!("{} {} (remembered)", "Variable:".header(), var_name);
+                context.variables.insert(var_name.clone(), value);
+                continue;
+            }
+
             // Find variable definition if it exists
             let var_def = workflow.variables.iter().find(|v| &v.name == var_name);
 
@@ -107,29 +525,21 @@ impl VariableProcessor {
             let default = var_def.and_then(|v| v.default_value.clone());
 
             // Prompt for variable value
-            println!("{} {}", "Variable:".blue().bold(), var_name);
-            println!("{} {}", "Description:".blue(), description);
+            progress!("{} {}", "Variable:".header(), var_name);
+            progress!("{} {}", "Description:".header(), description);
 
             if let Some(ref default_value) = default {
-                print!("{} [{}]: ", "Enter value".yellow(), default_value);
+                progress_inline!("{} [{}]: ", "Enter value".note(), default_value);
             } else {
-                print!("{}: ", "Enter value".yellow());
+                progress_inline!("{}: ", "Enter value".note());
             }
 
             io::stdout().flush().map_err(|e| {
                 ClixError::CommandExecutionFailed(format!("Failed to flush stdout: {}", e))
             })?;
 
-            let stdin = io::stdin();
-            let mut handle = stdin.lock();
-            let mut input = String::new();
-
-            handle.read_line(&mut input).map_err(|e| {
-                ClixError::CommandExecutionFailed(format!("Failed to read variable input: {}", e))
-            })?;
-
-            // Trim newline
-            let input = input.trim();
+            let input = read_line()?;
+            let input = input.as_str();
 
             let value = if input.is_empty() && default.is_some() {
                 default.unwrap_or_default() // This should never panic since we checked is_some
@@ -142,15 +552,123 @@ impl VariableProcessor {
                 input.to_string()
             };
 
+            if let Some(cache) = cache {
+                cache.remember(var_name, &value)?;
+            }
             context.variables.insert(var_name.clone(), value);
         }
 
         Ok(())
     }
 
+    /// Guard the workflow's variables against shell injection before substitution.
+    /// Variables declared with `quote` are marked to be single-quoted in step
+    /// commands; other values containing shell metacharacters are reported, or
+    /// rejected when `reject_unsafe` is set.
+    pub fn guard_variables(
+        workflow: &Workflow,
+        context: &mut WorkflowContext,
+        reject_unsafe: bool,
+    ) -> Result<Vec<String>> {
+        let mut warnings = Vec::new();
+
+        let mut names = Self::scan_workflow_variables(workflow);
+        for variable in &workflow.variables {
+            if !names.contains(&variable.name) {
+                names.push(variable.name.clone());
+            }
+        }
+
+        for var_name in names {
+            let Some(value) = context.variables.get(&var_name) else {
+                continue;
+            };
+
+            if workflow
+                .variables
+                .iter()
+                .any(|v| v.name == var_name && v.quote)
+            {
+                context.quoted_variables.insert(var_name);
+                continue;
+            }
+
+            if let Some(issue) = SecurityValidator::check_variable_value(&var_name, value) {
+                if reject_unsafe {
+                    return Err(ClixError::SecurityError(issue));
+                }
+                warnings.push(issue);
+            }
+        }
+
+        Ok(warnings)
+    }
+
+    /// Variables a command references that are defined neither in the context
+    /// nor in the process environment: `{{ name }}` placeholders, and `$NAME`
+    /// or `${NAME}` outside single quotes. Names the command sets itself are
+    /// not reported. This reads the command text, so it is a best effort.
+    pub fn unresolved_variables(command: &str, context: &WorkflowContext) -> Vec<String> {
+        let mut unresolved: Vec<String> = Self::extract_variables(command)
+            .into_iter()
+            .filter(|name| !context.has_variable(name))
+            .collect();
+
+        // Text in single quotes reaches the command literally
+        let unquoted: String = command.split('\'').step_by(2).collect::<Vec<_>>().join(" ");
+
+        let mut assigned = Vec::new();
+        for cap in shell_assignment_regex().captures_iter(&unquoted) {
+            if let Some(name) = cap.get(1).or_else(|| cap.get(2)) {
+                assigned.push(name.as_str().to_string());
+            }
+            if let Some(names) = cap.get(3) {
+                assigned.extend(
+                    names
+                        .as_str()
+                        .split_whitespace()
+                        .filter(|name| !name.starts_with('-'))
+                        .map(str::to_string),
+                );
+            }
+        }
+
+        for cap in shell_reference_regex().captures_iter(&unquoted) {
+            let name = cap.get(2).or_else(|| cap.get(3)).map_or("", |m| m.as_str());
+            if !context.has_variable(name)
+                && std::env::var_os(name).is_none()
+                && !assigned.iter().any(|a| a == name)
+                && !unresolved.iter().any(|u| u == name)
+            {
+                unresolved.push(name.to_string());
+            }
+        }
+
+        unresolved
+    }
+
+    /// Like `process_step`, but when the context is strict a step whose
+    /// command references an undefined variable is an error
+    pub fn try_process_step(
+        step: &WorkflowStep,
+        context: &WorkflowContext,
+    ) -> Result<WorkflowStep> {
+        if context.strict_variables {
+            let unresolved = Self::unresolved_variables(&step.command, context);
+            if !unresolved.is_empty() {
+                return Err(ClixError::ValidationError(format!(
+                    "Step '{}' references undefined variable(s): {}",
+                    step.name,
+                    unresolved.join(", ")
+                )));
+            }
+        }
+        Ok(Self::process_step(step, context))
+    }
+
     /// Process all variables in a workflow step
     pub fn process_step(step: &WorkflowStep, context: &WorkflowContext) -> WorkflowStep {
-        let processed_command = Self::process_variables(&step.command, context);
+        let processed_command = Self::process_command(&step.command, context);
 
         // Process conditional expressions if they exist
         let processed_conditional = step.conditional.as_ref().map(|conditional| {
@@ -180,9 +698,29 @@ impl VariableProcessor {
                             .collect(),
                     });
 
+            let processed_elif_blocks = conditional
+                .elif_blocks
+                .iter()
+                .map(|elif| ElifBlock {
+                    condition: Condition {
+                        expression: Self::process_variables(&elif.condition.expression, context),
+                        variable: elif.condition.variable.clone(),
+                    },
+                    then_block: ConditionalBlock {
+                        steps: elif
+                            .then_block
+                            .steps
+                            .iter()
+                            .map(|step| Self::process_step(step, context))
+                            .collect(),
+                    },
+                })
+                .collect();
+
             ConditionalStep {
                 condition: processed_condition,
                 then_block: processed_then_block,
+                elif_blocks: processed_elif_blocks,
                 else_block: processed_else_block,
                 action: conditional.action.clone(),
             }
@@ -236,16 +774,19 @@ impl VariableProcessor {
             }
         });
 
+        // Process the confirmation prompt if it exists
+        let processed_confirm = step.confirm.as_ref().map(|confirm| ConfirmStep {
+            prompt: Self::process_variables(&confirm.prompt, context),
+            default: confirm.default,
+        });
+
         WorkflowStep {
-            name: step.name.clone(),
             command: processed_command,
-            description: step.description.clone(),
-            continue_on_error: step.continue_on_error,
-            step_type: step.step_type.clone(),
-            require_approval: step.require_approval,
             conditional: processed_conditional,
             branch: processed_branch,
             loop_data: processed_loop,
+            confirm: processed_confirm,
+            ..step.clone()
         }
     }
 }
@@ -1,14 +1,68 @@
 use crate::commands::models::{StepType, Workflow, WorkflowStep};
-use crate::error::Result;
+use crate::commands::variables::VariableProcessor;
+use crate::error::{ClixError, Result};
 use crate::storage::Storage;
+use clap::ValueEnum;
 use regex::Regex;
+use serde::Serialize;
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::OnceLock;
+
+/// A call to another workflow from a step command
+fn workflow_call_regex() -> &'static Regex {
+    static WORKFLOW_CALL: OnceLock<Regex> = OnceLock::new();
+    WORKFLOW_CALL.get_or_init(|| Regex::new(r"clix\s+flow\s+run\s+([\w-]+)").unwrap())
+}
+
+/// A call to a stored command from a step command; options before the name
+/// are not recognised
+fn command_call_regex() -> &'static Regex {
+    static COMMAND_CALL: OnceLock<Regex> = OnceLock::new();
+    COMMAND_CALL.get_or_init(|| Regex::new(r"clix\s+run\s+(\w[\w-]*)").unwrap())
+}
+
+/// Call `f` on every step, each before the steps nested inside it
+fn visit_steps_mut(steps: &mut [WorkflowStep], f: &mut dyn FnMut(&mut WorkflowStep)) {
+    for step in steps {
+        f(step);
+        for block in step.nested_blocks_mut() {
+            visit_steps_mut(block, f);
+        }
+    }
+}
+
+/// Give a step whose name was already seen the first free numbered name,
+/// returning whether it was renamed
+fn rename_duplicate(
+    step: &mut WorkflowStep,
+    used_names: &mut HashSet<String>,
+    seen: &mut HashSet<String>,
+    fixes: &mut Vec<String>,
+) -> bool {
+    if seen.insert(step.name.clone()) {
+        return false;
+    }
+
+    let mut suffix = 2;
+    while used_names.contains(&format!("{} {}", step.name, suffix)) {
+        suffix += 1;
+    }
+    let new_name = format!("{} {}", step.name, suffix);
+    fixes.push(format!(
+        "Renamed duplicate step '{}' to '{}'",
+        step.name, new_name
+    ));
+    used_names.insert(new_name.clone());
+    seen.insert(new_name.clone());
+    step.name = new_name;
+    true
+}
 
 pub struct WorkflowValidator {
     storage: Storage,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ValidationIssue {
     pub severity: Severity,
     pub message: String,
@@ -16,14 +70,29 @@ pub struct ValidationIssue {
     pub suggestion: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, ValueEnum)]
 pub enum Severity {
     Error,
     Warning,
     Info,
 }
 
-#[derive(Debug, Clone)]
+impl Severity {
+    fn rank(&self) -> u8 {
+        match self {
+            Severity::Error => 2,
+            Severity::Warning => 1,
+            Severity::Info => 0,
+        }
+    }
+
+    /// Check whether this severity is as serious as the threshold or more
+    pub fn is_at_least(&self, threshold: &Severity) -> bool {
+        self.rank() >= threshold.rank()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct ValidationReport {
     pub workflow_name: String,
     pub is_valid: bool,
@@ -31,6 +100,24 @@ pub struct ValidationReport {
     pub dependency_graph: HashMap<String, Vec<String>>,
 }
 
+/// A workflow with safe automatic corrections applied
+#[derive(Debug, Clone)]
+pub struct FixResult {
+    pub workflow: Workflow,
+    /// Human-readable description of each change made
+    pub fixes: Vec<String>,
+}
+
+impl ValidationReport {
+    /// Check that no issue reaches the given severity threshold
+    pub fn passes(&self, fail_on: &Severity) -> bool {
+        !self
+            .issues
+            .iter()
+            .any(|issue| issue.severity.is_at_least(fail_on))
+    }
+}
+
 impl WorkflowValidator {
     pub fn new(storage: Storage) -> Self {
         Self { storage }
@@ -41,6 +128,13 @@ impl WorkflowValidator {
         let mut issues = Vec::new();
         let mut dependency_graph = HashMap::new();
 
+        // Check that each step carries the data its type needs
+        self.check_step_types(workflow, &mut issues);
+
+        // Check that step time limits can be met
+        self.check_step_timeouts(workflow, &mut issues);
+        self.check_wait_for_intervals(workflow, &mut issues);
+
         // Check for circular dependencies
         self.check_circular_dependencies(workflow, &mut issues, &mut dependency_graph)?;
 
@@ -50,6 +144,15 @@ impl WorkflowValidator {
         // Validate variable consistency
         self.validate_variables(workflow, &mut issues);
 
+        // Check that step dependencies name real steps and don't form a cycle
+        let ordered = self.check_step_dependencies(workflow, &mut issues);
+
+        // Check that captured variables are read only after they are captured
+        self.check_variable_dataflow(ordered.as_ref().unwrap_or(workflow), &mut issues);
+
+        // Check that branch variables are declared and their cases can match
+        self.check_branch_variables(workflow, &mut issues);
+
         // Check step naming and descriptions
         self.validate_step_metadata(workflow, &mut issues);
 
@@ -72,6 +175,106 @@ impl WorkflowValidator {
         })
     }
 
+    /// Check steps read from a steps file on their own, before they belong to a
+    /// workflow: only the structural checks run, since variables, profiles and
+    /// calls to other workflows are not known yet
+    pub fn check_steps(&self, name: &str, steps: Vec<WorkflowStep>) -> ValidationReport {
+        let workflow = Workflow::new(name.to_string(), String::new(), steps, vec![]);
+        let mut issues = Vec::new();
+
+        self.check_step_types(&workflow, &mut issues);
+        self.check_step_timeouts(&workflow, &mut issues);
+        self.check_wait_for_intervals(&workflow, &mut issues);
+        self.validate_step_metadata(&workflow, &mut issues);
+        self.check_duplicate_step_names(&workflow, &mut issues);
+        self.check_step_dependencies(&workflow, &mut issues);
+        self.validate_command_syntax(&workflow, &mut issues);
+
+        let is_valid = !issues.iter().any(|issue| issue.severity == Severity::Error);
+
+        ValidationReport {
+            workflow_name: workflow.name,
+            is_valid,
+            issues,
+            dependency_graph: HashMap::new(),
+        }
+    }
+
+    /// Apply the fixes that can't change what a workflow does
+    ///
+    /// Empty descriptions get the step name, trailing whitespace is trimmed from
+    /// commands, and duplicate step names get a numeric suffix, in nested steps
+    /// too. A `depends_on` entry naming a renamed step gets its new name as
+    /// well, so the step still waits for every step it waited for. Anything
+    /// else is left for the user to fix.
+    pub fn fix(&self, workflow: &Workflow) -> FixResult {
+        let mut fixed = workflow.clone();
+        let mut fixes = Vec::new();
+
+        visit_steps_mut(&mut fixed.steps, &mut |step| {
+            if step.description.trim().is_empty() && !step.name.trim().is_empty() {
+                step.description = step.name.clone();
+                fixes.push(format!(
+                    "Filled in empty description of step '{}'",
+                    step.name
+                ));
+            }
+
+            let trimmed = step.command.trim_end();
+            if trimmed.len() != step.command.len() {
+                step.command = trimmed.to_string();
+                fixes.push(format!(
+                    "Trimmed trailing whitespace from the command of step '{}'",
+                    step.name
+                ));
+            }
+        });
+
+        let mut used_names: HashSet<String> = fixed
+            .flatten_steps()
+            .into_iter()
+            .map(|step| step.name)
+            .collect();
+        let mut seen = HashSet::new();
+        let mut renamed = Vec::new();
+        for step in &mut fixed.steps {
+            let old_name = step.name.clone();
+            if rename_duplicate(step, &mut used_names, &mut seen, &mut fixes) {
+                renamed.push((old_name, step.name.clone()));
+            }
+            for block in step.nested_blocks_mut() {
+                visit_steps_mut(block, &mut |nested| {
+                    rename_duplicate(nested, &mut used_names, &mut seen, &mut fixes);
+                });
+            }
+        }
+
+        // depends_on waits for every top-level step with the name it gives
+        for step in &mut fixed.steps {
+            let added: Vec<String> = renamed
+                .iter()
+                .filter(|(old_name, new_name)| {
+                    step.depends_on.contains(old_name)
+                        && !step.depends_on.contains(new_name)
+                        && &step.name != new_name
+                })
+                .map(|(_, new_name)| new_name.clone())
+                .collect();
+            for new_name in added {
+                fixes.push(format!(
+                    "Added '{}' to the depends_on of step '{}'",
+                    new_name, step.name
+                ));
+                step.depends_on.push(new_name);
+            }
+        }
+
+        FixResult {
+            workflow: fixed,
+            fixes,
+        }
+    }
+
     /// Check for circular dependencies in workflow calls
     fn check_circular_dependencies(
         &self,
@@ -95,24 +298,24 @@ impl WorkflowValidator {
 
         // Check for indirect circular dependencies
         for called_workflow in &workflow_calls {
-            if let Ok(called_wf) = self.storage.get_workflow(called_workflow) {
-                if self.has_circular_dependency_to(
+            if let Ok(called_wf) = self.storage.get_workflow(called_workflow)
+                && self.has_circular_dependency_to(
                     &called_wf,
                     &workflow.name,
                     &mut HashSet::new(),
-                )? {
-                    issues.push(ValidationIssue {
-                        severity: Severity::Error,
-                        message: format!(
-                            "Circular dependency detected: '{}' -> '{}' -> ... -> '{}'",
-                            workflow.name, called_workflow, workflow.name
-                        ),
-                        step_name: None,
-                        suggestion: Some(
-                            "Restructure workflows to eliminate circular calls".to_string(),
-                        ),
-                    });
-                }
+                )?
+            {
+                issues.push(ValidationIssue {
+                    severity: Severity::Error,
+                    message: format!(
+                        "Circular dependency detected: '{}' -> '{}' -> ... -> '{}'",
+                        workflow.name, called_workflow, workflow.name
+                    ),
+                    step_name: None,
+                    suggestion: Some(
+                        "Restructure workflows to eliminate circular calls".to_string(),
+                    ),
+                });
             }
         }
 
@@ -121,65 +324,85 @@ impl WorkflowValidator {
 
     /// Extract all workflow calls from a workflow (recursive through all steps)
     fn extract_all_workflow_calls(&self, workflow: &Workflow) -> Result<Vec<String>> {
-        let mut calls = Vec::new();
-        let workflow_call_regex = Regex::new(r"clix\s+flow\s+run\s+([\w-]+)").unwrap();
+        Ok(Self::workflow_calls(&workflow.steps))
+    }
 
-        for step in &workflow.steps {
-            self.extract_workflow_calls_from_step(step, &workflow_call_regex, &mut calls);
-        }
+    /// Names of the workflows the steps call with `clix flow run`, including
+    /// calls in nested steps, sorted and without duplicates
+    pub fn workflow_calls(steps: &[WorkflowStep]) -> Vec<String> {
+        Self::calls_in_steps(steps, workflow_call_regex())
+    }
 
+    /// Names of the stored commands the steps call with `clix run`, including
+    /// calls in nested steps, sorted and without duplicates
+    pub fn command_calls(steps: &[WorkflowStep]) -> Vec<String> {
+        Self::calls_in_steps(steps, command_call_regex())
+    }
+
+    /// Names of the stored commands a shell command calls with `clix run`
+    pub fn command_calls_in(command: &str) -> Vec<String> {
+        let mut calls = Vec::new();
+        Self::extract_calls(command, command_call_regex(), &mut calls);
         calls.sort();
         calls.dedup();
-        Ok(calls)
+        calls
     }
 
-    /// Extract workflow calls from a single step (handles nested structures)
-    #[allow(clippy::only_used_in_recursion)]
-    fn extract_workflow_calls_from_step(
-        &self,
-        step: &WorkflowStep,
-        regex: &Regex,
-        calls: &mut Vec<String>,
-    ) {
-        // Check main command
-        if !step.command.is_empty() {
-            for captures in regex.captures_iter(&step.command) {
-                if let Some(workflow_name) = captures.get(1) {
-                    calls.push(workflow_name.as_str().to_string());
-                }
+    /// Names of the stored commands and workflows that call `name` with
+    /// `clix run` or `clix flow run`, sorted and without duplicates
+    pub fn referenced_by(&self, name: &str) -> Result<Vec<String>> {
+        let calls_name = |steps: &[WorkflowStep]| {
+            Self::command_calls(steps).iter().any(|call| call == name)
+                || Self::workflow_calls(steps).iter().any(|call| call == name)
+        };
+
+        let mut referencers = Vec::new();
+        for command in self.storage.list_commands()? {
+            let calls = match (&command.steps, &command.command) {
+                (Some(steps), _) => calls_name(steps),
+                (None, Some(text)) => Self::command_calls_in(text).iter().any(|c| c == name),
+                (None, None) => false,
+            };
+            if calls && command.name != name {
+                referencers.push(command.name);
             }
         }
-
-        // Check conditional blocks
-        if let Some(conditional) = &step.conditional {
-            for then_step in &conditional.then_block.steps {
-                self.extract_workflow_calls_from_step(then_step, regex, calls);
-            }
-            if let Some(else_block) = &conditional.else_block {
-                for else_step in &else_block.steps {
-                    self.extract_workflow_calls_from_step(else_step, regex, calls);
-                }
+        for workflow in self.storage.list_workflows()? {
+            if calls_name(&workflow.steps) && workflow.name != name {
+                referencers.push(workflow.name);
             }
         }
 
-        // Check branch blocks
-        if let Some(branch) = &step.branch {
-            for case in &branch.cases {
-                for case_step in &case.steps {
-                    self.extract_workflow_calls_from_step(case_step, regex, calls);
-                }
-            }
-            if let Some(default_steps) = &branch.default_case {
-                for default_step in default_steps {
-                    self.extract_workflow_calls_from_step(default_step, regex, calls);
-                }
-            }
+        referencers.sort();
+        referencers.dedup();
+        Ok(referencers)
+    }
+
+    fn calls_in_steps(steps: &[WorkflowStep], regex: &Regex) -> Vec<String> {
+        let mut calls = Vec::new();
+        for step in steps {
+            Self::extract_calls_from_step(step, regex, &mut calls);
         }
 
-        // Check loop blocks
-        if let Some(loop_data) = &step.loop_data {
-            for loop_step in &loop_data.steps {
-                self.extract_workflow_calls_from_step(loop_step, regex, calls);
+        calls.sort();
+        calls.dedup();
+        calls
+    }
+
+    /// Extract calls from a single step (handles nested structures)
+    fn extract_calls_from_step(step: &WorkflowStep, regex: &Regex, calls: &mut Vec<String>) {
+        Self::extract_calls(&step.command, regex, calls);
+
+        // Check conditional, branch and loop blocks
+        for nested_step in step.nested_steps() {
+            Self::extract_calls_from_step(nested_step, regex, calls);
+        }
+    }
+
+    fn extract_calls(command: &str, regex: &Regex, calls: &mut Vec<String>) {
+        for captures in regex.captures_iter(command) {
+            if let Some(name) = captures.get(1) {
+                calls.push(name.as_str().to_string());
             }
         }
     }
@@ -204,10 +427,10 @@ impl WorkflowValidator {
         }
 
         for called_workflow_name in calls {
-            if let Ok(called_workflow) = self.storage.get_workflow(&called_workflow_name) {
-                if self.has_circular_dependency_to(&called_workflow, target, visited)? {
-                    return Ok(true);
-                }
+            if let Ok(called_workflow) = self.storage.get_workflow(&called_workflow_name)
+                && self.has_circular_dependency_to(&called_workflow, target, visited)?
+            {
+                return Ok(true);
             }
         }
 
@@ -265,6 +488,14 @@ impl WorkflowValidator {
                             to_visit.push_back(idx);
                         }
                     }
+                    // Elif block steps are reachable
+                    for elif in &conditional.elif_blocks {
+                        for elif_step in &elif.then_block.steps {
+                            if let Some(idx) = self.find_step_index(workflow, &elif_step.name) {
+                                to_visit.push_back(idx);
+                            }
+                        }
+                    }
                     // Else block steps are reachable
                     if let Some(else_block) = &conditional.else_block {
                         for else_step in &else_block.steps {
@@ -374,6 +605,17 @@ impl WorkflowValidator {
                 self.collect_used_variables_from_step(then_step, used_vars);
             }
 
+            for elif in &conditional.elif_blocks {
+                for captures in var_regex.captures_iter(&elif.condition.expression) {
+                    if let Some(var_name) = captures.get(1).or(captures.get(2)) {
+                        used_vars.insert(var_name.as_str().to_string());
+                    }
+                }
+                for elif_step in &elif.then_block.steps {
+                    self.collect_used_variables_from_step(elif_step, used_vars);
+                }
+            }
+
             if let Some(else_block) = &conditional.else_block {
                 for else_step in &else_block.steps {
                     self.collect_used_variables_from_step(else_step, used_vars);
@@ -412,6 +654,185 @@ impl WorkflowValidator {
         }
     }
 
+    /// Check that no step reads a captured variable before the step that captures it
+    fn check_variable_dataflow(&self, workflow: &Workflow, issues: &mut Vec<ValidationIssue>) {
+        let mut capturing_steps = HashMap::new();
+        for step in &workflow.steps {
+            self.collect_captured_variables(step, &mut capturing_steps);
+        }
+
+        if capturing_steps.is_empty() {
+            return;
+        }
+
+        // Declared and profile variables are available from the start
+        let mut available: HashSet<String> =
+            workflow.variables.iter().map(|v| v.name.clone()).collect();
+        for profile in workflow.profiles.values() {
+            available.extend(profile.variables.keys().cloned());
+        }
+
+        for step in &workflow.steps {
+            self.check_step_dataflow(step, &capturing_steps, &mut available, issues);
+        }
+    }
+
+    /// Warn about branches on undeclared variables and cases outside the allowed values
+    fn check_branch_variables(&self, workflow: &Workflow, issues: &mut Vec<ValidationIssue>) {
+        let mut capturing_steps = HashMap::new();
+        for step in &workflow.steps {
+            self.collect_captured_variables(step, &mut capturing_steps);
+        }
+
+        let mut pending: Vec<&WorkflowStep> = workflow.steps.iter().collect();
+        while let Some(step) = pending.pop() {
+            pending.extend(step.nested_steps());
+
+            let Some(branch) = &step.branch else {
+                continue;
+            };
+
+            let declared = workflow
+                .variables
+                .iter()
+                .find(|v| v.name == branch.variable);
+            let Some(variable) = declared else {
+                let in_profile = workflow
+                    .profiles
+                    .values()
+                    .any(|profile| profile.variables.contains_key(&branch.variable));
+                if !in_profile && !capturing_steps.contains_key(&branch.variable) {
+                    issues.push(ValidationIssue {
+                        severity: Severity::Warning,
+                        message: format!(
+                            "Branch step '{}' switches on undeclared variable '{}'",
+                            step.name, branch.variable
+                        ),
+                        step_name: Some(step.name.clone()),
+                        suggestion: Some(format!(
+                            "Declare '{}' with `clix add-var` or capture it in an earlier step",
+                            branch.variable
+                        )),
+                    });
+                }
+                continue;
+            };
+
+            if variable.allowed_values.is_empty() {
+                continue;
+            }
+
+            for case in &branch.cases {
+                // Templated case values are only known at run time
+                if case.value.contains("{{") || variable.allowed_values.contains(&case.value) {
+                    continue;
+                }
+                issues.push(ValidationIssue {
+                    severity: Severity::Warning,
+                    message: format!(
+                        "Case '{}' of branch step '{}' can never match; '{}' only allows: {}",
+                        case.value,
+                        step.name,
+                        branch.variable,
+                        variable.allowed_values.join(", ")
+                    ),
+                    step_name: Some(step.name.clone()),
+                    suggestion: Some(format!(
+                        "Remove the case or add '{}' to the allowed values of '{}'",
+                        case.value, branch.variable
+                    )),
+                });
+            }
+        }
+    }
+
+    /// Map every captured variable to the first step that captures it
+    #[allow(clippy::only_used_in_recursion)]
+    fn collect_captured_variables(
+        &self,
+        step: &WorkflowStep,
+        capturing_steps: &mut HashMap<String, String>,
+    ) {
+        for var_name in step.captured_variables() {
+            capturing_steps
+                .entry(var_name.clone())
+                .or_insert_with(|| step.name.clone());
+        }
+
+        for nested_step in step.nested_steps() {
+            self.collect_captured_variables(nested_step, capturing_steps);
+        }
+    }
+
+    /// Walk a step in execution order, warning on reads of not-yet-captured variables
+    fn check_step_dataflow(
+        &self,
+        step: &WorkflowStep,
+        capturing_steps: &HashMap<String, String>,
+        available: &mut HashSet<String>,
+        issues: &mut Vec<ValidationIssue>,
+    ) {
+        let mut reported = HashSet::new();
+        for var_name in Self::template_variables(step) {
+            if available.contains(&var_name) || !reported.insert(var_name.clone()) {
+                continue;
+            }
+
+            if let Some(capturing_step) = capturing_steps.get(&var_name) {
+                issues.push(ValidationIssue {
+                    severity: Severity::Warning,
+                    message: format!(
+                        "Step '{}' uses variable '{}' before step '{}' captures it",
+                        step.name, var_name, capturing_step
+                    ),
+                    step_name: Some(step.name.clone()),
+                    suggestion: Some(format!(
+                        "Move step '{}' before step '{}'",
+                        capturing_step, step.name
+                    )),
+                });
+            }
+        }
+
+        for nested_step in step.nested_steps() {
+            self.check_step_dataflow(nested_step, capturing_steps, available, issues);
+        }
+
+        for var_name in step.captured_variables() {
+            available.insert(var_name.clone());
+        }
+    }
+
+    /// Collect `{{ var }}` references read by a step itself (not its nested steps)
+    fn template_variables(step: &WorkflowStep) -> Vec<String> {
+        let mut vars = VariableProcessor::extract_variables(&step.command);
+
+        if let Some(conditional) = &step.conditional {
+            vars.extend(VariableProcessor::extract_variables(
+                &conditional.condition.expression,
+            ));
+        }
+
+        if let Some(branch) = &step.branch {
+            vars.push(branch.variable.clone());
+            for case in &branch.cases {
+                vars.extend(VariableProcessor::extract_variables(&case.value));
+            }
+        }
+
+        if let Some(loop_data) = &step.loop_data {
+            vars.extend(VariableProcessor::extract_variables(
+                &loop_data.condition.expression,
+            ));
+        }
+
+        if let Some(confirm) = &step.confirm {
+            vars.extend(VariableProcessor::extract_variables(&confirm.prompt));
+        }
+
+        vars
+    }
+
     /// Check if a variable is a built-in system variable
     fn is_builtin_variable(&self, var_name: &str) -> bool {
         matches!(
@@ -516,6 +937,102 @@ impl WorkflowValidator {
         false
     }
 
+    /// Check that every step, nested ones included, has the block its type runs
+    /// and no block that belongs to another type, which the executor would ignore
+    fn check_step_types(&self, workflow: &Workflow, issues: &mut Vec<ValidationIssue>) {
+        fn check(step: &WorkflowStep, issues: &mut Vec<ValidationIssue>) {
+            let blocks = [
+                (
+                    StepType::Conditional,
+                    "conditional",
+                    step.conditional.is_some(),
+                ),
+                (StepType::Branch, "branch", step.branch.is_some()),
+                (StepType::Loop, "loop_data", step.loop_data.is_some()),
+                (StepType::Confirm, "confirm", step.confirm.is_some()),
+                (StepType::WaitFor, "wait_for", step.wait_for.is_some()),
+            ];
+
+            for (owner, field, present) in blocks {
+                if owner == step.step_type && !present {
+                    issues.push(ValidationIssue {
+                        severity: Severity::Error,
+                        message: format!(
+                            "Step '{}' is a {:?} step but has no '{}' field",
+                            step.name, step.step_type, field
+                        ),
+                        step_name: Some(step.name.clone()),
+                        suggestion: Some(format!(
+                            "Add a '{}' block to the step or change its step_type",
+                            field
+                        )),
+                    });
+                } else if owner != step.step_type && present {
+                    issues.push(ValidationIssue {
+                        severity: Severity::Error,
+                        message: format!(
+                            "Step '{}' is a {:?} step but carries a '{}' block, which only {:?} steps use",
+                            step.name, step.step_type, field, owner
+                        ),
+                        step_name: Some(step.name.clone()),
+                        suggestion: Some(format!(
+                            "Remove the '{}' block or set step_type to {:?}",
+                            field, owner
+                        )),
+                    });
+                }
+            }
+
+            for nested_step in step.nested_steps() {
+                check(nested_step, issues);
+            }
+        }
+
+        for step in &workflow.steps {
+            check(step, issues);
+        }
+    }
+
+    /// Check that no step, nested ones included, has a `timeout_secs` of 0
+    fn check_step_timeouts(&self, workflow: &Workflow, issues: &mut Vec<ValidationIssue>) {
+        if let Some(name) = WorkflowStep::zero_timeout_step(&workflow.steps) {
+            issues.push(ValidationIssue {
+                severity: Severity::Error,
+                message: format!(
+                    "Step '{}' has timeout_secs 0 and would be killed at once",
+                    name
+                ),
+                step_name: Some(name.to_string()),
+                suggestion: Some(
+                    "Set timeout_secs to at least 1, or remove it for no limit".to_string(),
+                ),
+            });
+        }
+    }
+
+    /// Check that every WaitFor step, nested ones included, pauses between polls
+    fn check_wait_for_intervals(&self, workflow: &Workflow, issues: &mut Vec<ValidationIssue>) {
+        let mut pending: Vec<&WorkflowStep> = workflow.steps.iter().collect();
+        while let Some(step) = pending.pop() {
+            if step
+                .wait_for
+                .as_ref()
+                .is_some_and(|wait_for| wait_for.interval_ms == 0)
+            {
+                issues.push(ValidationIssue {
+                    severity: Severity::Error,
+                    message: format!(
+                        "Step '{}' has a wait_for interval_ms of 0 and would poll without pause",
+                        step.name
+                    ),
+                    step_name: Some(step.name.clone()),
+                    suggestion: Some("Set interval_ms to at least 1".to_string()),
+                });
+            }
+            pending.extend(step.nested_steps());
+        }
+    }
+
     /// Check for duplicate step names
     fn check_duplicate_step_names(&self, workflow: &Workflow, issues: &mut Vec<ValidationIssue>) {
         let mut step_names = HashMap::new();
@@ -539,8 +1056,36 @@ impl WorkflowValidator {
         }
     }
 
+    /// Report unknown or cyclic `depends_on` entries, returning the workflow in
+    /// run order when the dependencies resolve
+    fn check_step_dependencies(
+        &self,
+        workflow: &Workflow,
+        issues: &mut Vec<ValidationIssue>,
+    ) -> Option<Workflow> {
+        match workflow.with_dependency_order() {
+            Ok(ordered) => Some(ordered),
+            Err(e) => {
+                issues.push(ValidationIssue {
+                    severity: Severity::Error,
+                    message: match e {
+                        ClixError::ValidationError(message) => message,
+                        other => other.to_string(),
+                    },
+                    step_name: None,
+                    suggestion: Some(
+                        "Point depends_on at existing step names and break the loop".to_string(),
+                    ),
+                });
+                None
+            }
+        }
+    }
+
     /// Validate command syntax for basic issues
     fn validate_command_syntax(&self, workflow: &Workflow, issues: &mut Vec<ValidationIssue>) {
+        let fork_bomb = Regex::new(r":\(\)\s*\{\s*:\s*\|\s*:\s*&\s*\}\s*;\s*:").unwrap();
+
         for step in &workflow.steps {
             if step.step_type == StepType::Command && !step.command.trim().is_empty() {
                 // Check for unmatched quotes
@@ -553,7 +1098,30 @@ impl WorkflowValidator {
                     });
                 }
 
+                if step.command.trim_end().len() != step.command.len() {
+                    issues.push(ValidationIssue {
+                        severity: Severity::Info,
+                        message: format!(
+                            "Step '{}' has trailing whitespace in its command",
+                            step.name
+                        ),
+                        step_name: Some(step.name.clone()),
+                        suggestion: Some("Remove the trailing whitespace".to_string()),
+                    });
+                }
+
                 // Check for suspicious patterns
+                if fork_bomb.is_match(&step.command) {
+                    issues.push(ValidationIssue {
+                        severity: Severity::Warning,
+                        message: format!("Step '{}' contains a fork bomb", step.name),
+                        step_name: Some(step.name.clone()),
+                        suggestion: Some(
+                            "Remove this command; it will exhaust system processes".to_string(),
+                        ),
+                    });
+                }
+
                 if step.command.contains("rm -rf /") {
                     issues.push(ValidationIssue {
                         severity: Severity::Warning,
@@ -596,7 +1164,7 @@ impl WorkflowValidator {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::commands::models::{WorkflowStep, WorkflowVariable};
+    use crate::commands::models::{BranchCase, Condition, WorkflowStep, WorkflowVariable};
     use tempfile::tempdir;
 
     #[test]
@@ -713,4 +1281,363 @@ mod tests {
                     && issue.message.contains("DEFINED_VAR"))
         );
     }
+
+    #[test]
+    fn test_fail_on_threshold() {
+        let storage = Storage::new().unwrap();
+        let validator = WorkflowValidator::new(storage);
+
+        let steps = vec![WorkflowStep::new_command(
+            "No description".to_string(),
+            "echo 'hello'".to_string(),
+            "".to_string(),
+            false,
+        )];
+
+        let workflow = Workflow::new(
+            "test-workflow".to_string(),
+            "Test workflow".to_string(),
+            steps,
+            vec![],
+        );
+
+        let report = validator.validate_workflow(&workflow).unwrap();
+
+        // Only warnings: passes by default, fails when warnings are gated
+        assert!(report.is_valid);
+        assert!(
+            report
+                .issues
+                .iter()
+                .all(|issue| issue.severity == Severity::Warning)
+        );
+        assert!(report.passes(&Severity::Error));
+        assert!(!report.passes(&Severity::Warning));
+        assert!(!report.passes(&Severity::Info));
+    }
+
+    #[test]
+    fn test_report_serializes_to_json() {
+        let report = ValidationReport {
+            workflow_name: "test-workflow".to_string(),
+            is_valid: true,
+            issues: vec![ValidationIssue {
+                severity: Severity::Info,
+                message: "Variable 'X' is defined but never used".to_string(),
+                step_name: None,
+                suggestion: None,
+            }],
+            dependency_graph: HashMap::new(),
+        };
+
+        let json: serde_json::Value = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["workflow_name"], "test-workflow");
+        assert_eq!(json["issues"][0]["severity"], "Info");
+    }
+
+    fn capture_workflow(steps: Vec<WorkflowStep>) -> Workflow {
+        Workflow::new(
+            "test-workflow".to_string(),
+            "Test workflow".to_string(),
+            steps,
+            vec![],
+        )
+    }
+
+    #[test]
+    fn test_forward_reference_to_captured_variable() {
+        let storage = Storage::new().unwrap();
+        let validator = WorkflowValidator::new(storage);
+
+        let workflow = capture_workflow(vec![
+            WorkflowStep::new_command(
+                "Use token".to_string(),
+                "echo {{ token }}".to_string(),
+                "Uses the token".to_string(),
+                false,
+            ),
+            WorkflowStep::new_command(
+                "Get token".to_string(),
+                "echo abc".to_string(),
+                "Captures the token".to_string(),
+                false,
+            )
+            .with_capture_var("token".to_string()),
+        ]);
+
+        let report = validator.validate_workflow(&workflow).unwrap();
+        assert!(report.issues.iter().any(|issue| {
+            issue.severity == Severity::Warning
+                && issue.step_name.as_deref() == Some("Use token")
+                && issue
+                    .message
+                    .contains("before step 'Get token' captures it")
+        }));
+    }
+
+    #[test]
+    fn test_captured_variable_used_in_order() {
+        let storage = Storage::new().unwrap();
+        let validator = WorkflowValidator::new(storage);
+
+        let workflow = capture_workflow(vec![
+            WorkflowStep::new_command(
+                "Get token".to_string(),
+                "echo abc".to_string(),
+                "Captures the token".to_string(),
+                false,
+            )
+            .with_capture_var("token".to_string()),
+            WorkflowStep::new_command(
+                "Use token".to_string(),
+                "echo {{ token }}".to_string(),
+                "Uses the token".to_string(),
+                false,
+            ),
+        ]);
+
+        let report = validator.validate_workflow(&workflow).unwrap();
+        assert!(
+            !report
+                .issues
+                .iter()
+                .any(|issue| issue.message.contains("captures it"))
+        );
+    }
+
+    #[test]
+    fn test_fix_renames_duplicates_and_keeps_fork_bomb() {
+        let storage = Storage::new().unwrap();
+        let validator = WorkflowValidator::new(storage);
+
+        let workflow = capture_workflow(vec![
+            WorkflowStep::new_command(
+                "build".to_string(),
+                "make   ".to_string(),
+                "".to_string(),
+                false,
+            ),
+            WorkflowStep::new_command(
+                "build".to_string(),
+                "make install".to_string(),
+                "Install".to_string(),
+                false,
+            ),
+            WorkflowStep::new_command(
+                "boom".to_string(),
+                ":(){ :|:& };:".to_string(),
+                "Fork bomb".to_string(),
+                false,
+            ),
+        ]);
+
+        let before = validator.validate_workflow(&workflow).unwrap();
+        assert!(!before.is_valid);
+
+        let fixed = validator.fix(&workflow);
+        let names: Vec<&str> = fixed
+            .workflow
+            .steps
+            .iter()
+            .map(|step| step.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["build", "build 2", "boom"]);
+        assert_eq!(fixed.workflow.steps[0].command, "make");
+        assert_eq!(fixed.workflow.steps[0].description, "build");
+        assert_eq!(fixed.fixes.len(), 3);
+
+        // The fork bomb is reported, never rewritten
+        assert_eq!(fixed.workflow.steps[2].command, ":(){ :|:& };:");
+        let after = validator.validate_workflow(&fixed.workflow).unwrap();
+        assert!(after.is_valid);
+        assert!(after.issues.iter().any(|issue| {
+            issue.severity == Severity::Warning && issue.message.contains("fork bomb")
+        }));
+    }
+
+    #[test]
+    fn test_fix_reaches_nested_steps_and_keeps_dependencies() {
+        let storage = Storage::new().unwrap();
+        let validator = WorkflowValidator::new(storage);
+
+        let step = |name: &str, command: &str| {
+            WorkflowStep::new_command(name.to_string(), command.to_string(), "".to_string(), false)
+        };
+        let workflow = capture_workflow(vec![
+            step("build", "make"),
+            step("build", "make docs"),
+            step("publish", "./publish.sh").with_depends_on(vec!["build".to_string()]),
+            WorkflowStep::new_loop(
+                "Retry".to_string(),
+                "Retry until it works".to_string(),
+                Condition {
+                    expression: "false".to_string(),
+                    variable: None,
+                },
+                vec![step("publish", "./publish.sh --retry  ")],
+            ),
+        ]);
+
+        let fixed = validator.fix(&workflow).workflow;
+        let names: Vec<String> = fixed
+            .flatten_steps()
+            .into_iter()
+            .map(|step| step.name)
+            .collect();
+        assert_eq!(
+            names,
+            vec!["build", "build 2", "publish", "Retry", "publish 2"]
+        );
+
+        let nested = fixed.find_step("publish 2").unwrap();
+        assert_eq!(nested.command, "./publish.sh --retry");
+        assert_eq!(nested.description, "publish");
+
+        // publish still runs after both build steps
+        assert_eq!(fixed.steps[2].depends_on, vec!["build", "build 2"]);
+        assert_eq!(
+            workflow.dependency_order().unwrap(),
+            fixed.dependency_order().unwrap()
+        );
+    }
+
+    fn env_branch(cases: &[&str]) -> WorkflowStep {
+        WorkflowStep::new_branch(
+            "Pick env".to_string(),
+            "Branch on the environment".to_string(),
+            "env".to_string(),
+            cases
+                .iter()
+                .map(|value| BranchCase {
+                    value: value.to_string(),
+                    steps: vec![WorkflowStep::new_command(
+                        format!("Deploy {}", value),
+                        format!("echo {}", value),
+                        format!("Deploy to {}", value),
+                        false,
+                    )],
+                })
+                .collect(),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_branch_on_undeclared_variable() {
+        let storage = Storage::new().unwrap();
+        let validator = WorkflowValidator::new(storage);
+
+        let workflow = capture_workflow(vec![env_branch(&["dev", "prod"])]);
+
+        let report = validator.validate_workflow(&workflow).unwrap();
+        assert!(report.issues.iter().any(|issue| {
+            issue.severity == Severity::Warning
+                && issue.step_name.as_deref() == Some("Pick env")
+                && issue.message.contains("undeclared variable 'env'")
+        }));
+    }
+
+    #[test]
+    fn test_branch_case_outside_allowed_values() {
+        let storage = Storage::new().unwrap();
+        let validator = WorkflowValidator::new(storage);
+
+        let mut workflow = capture_workflow(vec![env_branch(&["dev", "prod", "qa"])]);
+        workflow.variables.push(
+            WorkflowVariable::new(
+                "env".to_string(),
+                "Target environment".to_string(),
+                None,
+                true,
+            )
+            .with_allowed_values(vec!["dev".to_string(), "prod".to_string()]),
+        );
+
+        let report = validator.validate_workflow(&workflow).unwrap();
+        let unreachable: Vec<&ValidationIssue> = report
+            .issues
+            .iter()
+            .filter(|issue| issue.message.contains("can never match"))
+            .collect();
+        assert_eq!(unreachable.len(), 1);
+        assert!(unreachable[0].message.starts_with("Case 'qa'"));
+        assert!(
+            !report
+                .issues
+                .iter()
+                .any(|issue| issue.message.contains("undeclared variable"))
+        );
+    }
+
+    fn type_errors(step: WorkflowStep) -> Vec<String> {
+        let validator = WorkflowValidator::new(Storage::new().unwrap());
+        let report = validator
+            .validate_workflow(&capture_workflow(vec![step]))
+            .unwrap();
+        report
+            .issues
+            .into_iter()
+            .filter(|issue| issue.severity == Severity::Error)
+            .map(|issue| issue.message)
+            .collect()
+    }
+
+    fn typed_step(step_type: StepType) -> WorkflowStep {
+        let mut step = WorkflowStep::new_command(
+            "Check".to_string(),
+            String::new(),
+            "Check something".to_string(),
+            false,
+        );
+        step.step_type = step_type;
+        step
+    }
+
+    #[test]
+    fn test_conditional_step_without_conditional() {
+        assert_eq!(
+            type_errors(typed_step(StepType::Conditional)),
+            ["Step 'Check' is a Conditional step but has no 'conditional' field"]
+        );
+    }
+
+    #[test]
+    fn test_branch_step_without_branch() {
+        assert_eq!(
+            type_errors(typed_step(StepType::Branch)),
+            ["Step 'Check' is a Branch step but has no 'branch' field"]
+        );
+    }
+
+    #[test]
+    fn test_loop_step_without_loop_data() {
+        assert_eq!(
+            type_errors(typed_step(StepType::Loop)),
+            ["Step 'Check' is a Loop step but has no 'loop_data' field"]
+        );
+    }
+
+    #[test]
+    fn test_command_step_carrying_a_branch() {
+        let mut step = env_branch(&["dev"]);
+        step.step_type = StepType::Command;
+        assert_eq!(
+            type_errors(step),
+            [
+                "Step 'Pick env' is a Command step but carries a 'branch' block, which only Branch steps use"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mismatch_in_nested_step_is_reported() {
+        let mut branch = env_branch(&["dev"]);
+        if let Some(data) = branch.branch.as_mut() {
+            data.cases[0].steps.push(typed_step(StepType::Loop));
+        }
+        assert_eq!(
+            type_errors(branch),
+            ["Step 'Check' is a Loop step but has no 'loop_data' field"]
+        );
+    }
 }
@@ -0,0 +1,172 @@
+use crate::error::Result;
+use crate::git::{GitRepository, RepoConfig};
+use crate::settings::{DataLocation, Settings, SettingsManager};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A problem with the clix data directory that `clix doctor` reports, and
+/// `clix doctor --fix` can repair
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    /// The data directory itself doesn't exist
+    MissingConfigDir(PathBuf),
+    /// The settings file doesn't parse, or has fields clix doesn't know
+    InvalidSettings { path: PathBuf, reason: String },
+    /// A repository is configured but its checkout is missing
+    MissingRepository(RepoConfig),
+}
+
+impl Problem {
+    /// What is wrong, for the report
+    pub fn describe(&self) -> String {
+        match self {
+            Problem::MissingConfigDir(dir) => {
+                format!("Data directory {} does not exist", dir.display())
+            }
+            Problem::InvalidSettings { path, reason } => {
+                format!("Settings file {} {}", path.display(), reason)
+            }
+            Problem::MissingRepository(config) => {
+                format!("Repository '{}' is configured but not cloned", config.name)
+            }
+        }
+    }
+
+    /// What `fix` would do, for the confirmation prompt
+    pub fn fix_description(&self) -> String {
+        match self {
+            Problem::MissingConfigDir(dir) => format!("Create {}", dir.display()),
+            Problem::InvalidSettings { path, .. } => format!(
+                "Reset {} to the defaults, keeping the old file as {}",
+                path.display(),
+                backup_path(path).display()
+            ),
+            Problem::MissingRepository(config) => {
+                format!("Clone '{}' from {}", config.name, config.url)
+            }
+        }
+    }
+
+    /// Repair the problem in the data directory `config_dir`
+    pub fn fix(&self, config_dir: &Path) -> Result<()> {
+        match self {
+            Problem::MissingConfigDir(dir) => fs::create_dir_all(dir)?,
+            Problem::InvalidSettings { path, .. } => {
+                fs::copy(path, backup_path(path))?;
+                SettingsManager::new_with_dir(config_dir.to_path_buf())?
+                    .save(&Settings::default())?;
+            }
+            Problem::MissingRepository(config) => {
+                GitRepository::new(config.clone(), &DataLocation::Repos.path(config_dir))
+                    .clone_repo()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Look for problems in the data directory `config_dir` without changing anything
+pub fn diagnose(config_dir: &Path) -> Vec<Problem> {
+    if !config_dir.is_dir() {
+        return vec![Problem::MissingConfigDir(config_dir.to_path_buf())];
+    }
+
+    let mut problems = Vec::new();
+    let settings_path = DataLocation::Settings.path(config_dir);
+    if let Some(reason) = settings_problem(&settings_path) {
+        problems.push(Problem::InvalidSettings {
+            path: settings_path,
+            reason,
+        });
+    }
+
+    let repos_dir = DataLocation::Repos.path(config_dir);
+    let configs: Vec<RepoConfig> = fs::read_to_string(repos_dir.join("config.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    for config in configs {
+        if config.enabled && !GitRepository::new(config.clone(), &repos_dir).is_cloned() {
+            problems.push(Problem::MissingRepository(config));
+        }
+    }
+
+    problems
+}
+
+fn settings_problem(path: &Path) -> Option<String> {
+    let content = fs::read_to_string(path).ok()?;
+    let value: Value = match serde_json::from_str(&content) {
+        Ok(value) => value,
+        Err(e) => return Some(format!("is not valid JSON: {}", e)),
+    };
+    let settings: Settings = match serde_json::from_value(value.clone()) {
+        Ok(settings) => settings,
+        Err(e) => return Some(format!("has an invalid value: {}", e)),
+    };
+
+    // Anything that doesn't survive a round trip is a field clix ignores
+    let known = serde_json::to_value(&settings).ok()?;
+    let mut unknown = Vec::new();
+    unknown_fields(&value, &known, "", &mut unknown);
+    if unknown.is_empty() {
+        None
+    } else {
+        Some(format!("has unknown fields: {}", unknown.join(", ")))
+    }
+}
+
+fn unknown_fields(value: &Value, known: &Value, prefix: &str, unknown: &mut Vec<String>) {
+    let (Value::Object(fields), Value::Object(known_fields)) = (value, known) else {
+        return;
+    };
+    for (name, field) in fields {
+        let path = format!("{}{}", prefix, name);
+        match known_fields.get(name) {
+            Some(known_field) => unknown_fields(field, known_field, &format!("{}.", path), unknown),
+            None if !field.is_null() => unknown.push(path),
+            None => {}
+        }
+    }
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    path.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_settings_with_unknown_fields_are_reported_and_reset() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = DataLocation::Settings.path(temp_dir.path());
+        fs::write(
+            &path,
+            r#"{"ai_model": "m", "colour": "blue", "git_settings": {"auto_sync": false, "depth": 1}}"#,
+        )
+        .unwrap();
+
+        let problems = diagnose(temp_dir.path());
+        assert_eq!(
+            problems,
+            [Problem::InvalidSettings {
+                path: path.clone(),
+                reason: "has unknown fields: colour, git_settings.depth".to_string(),
+            }]
+        );
+
+        problems[0].fix(temp_dir.path()).unwrap();
+        assert!(diagnose(temp_dir.path()).is_empty());
+        assert!(
+            fs::read_to_string(temp_dir.path().join("settings.json.bak"))
+                .unwrap()
+                .contains("colour")
+        );
+    }
+}
@@ -1,5 +1,39 @@
+use regex::Regex;
+use std::sync::{Mutex, OnceLock};
 use thiserror::Error;
 
+/// Written in place of secrets in error output
+const REDACTED: &str = "****";
+
+/// Secrets loaded by this process, such as the API key
+static SECRETS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Remember a secret so error output never shows it
+pub fn register_secret(secret: &str) {
+    if secret.is_empty() {
+        return;
+    }
+    let mut secrets = SECRETS.lock().unwrap_or_else(|e| e.into_inner());
+    if !secrets.iter().any(|known| known == secret) {
+        secrets.push(secret.to_string());
+    }
+}
+
+/// Replace registered secrets, and anything shaped like an API key
+/// (`sk-...`), with `****`
+pub fn redact_secrets(text: &str) -> String {
+    let mut redacted = text.to_string();
+    for secret in SECRETS.lock().unwrap_or_else(|e| e.into_inner()).iter() {
+        redacted = redacted.replace(secret.as_str(), REDACTED);
+    }
+
+    static API_KEY: OnceLock<Regex> = OnceLock::new();
+    API_KEY
+        .get_or_init(|| Regex::new(r"\bsk-[A-Za-z0-9_-]{8,}").unwrap())
+        .replace_all(&redacted, REDACTED)
+        .into_owned()
+}
+
 #[derive(Error, Debug)]
 pub enum ClixError {
     #[error("IO error: {0}")]
@@ -46,11 +80,43 @@ pub enum ClixError {
 
     #[error("Git error: {0}")]
     GitError(String),
+
+    #[error("Workflow aborted: {0}")]
+    WorkflowAborted(String),
+
+    #[error("Already running: {0}")]
+    AlreadyRunning(String),
+
+    #[error("Refused by the security gate: {0}")]
+    SecurityGate(String),
+
+    #[error("Skipped: {0}")]
+    StepSkipped(String),
+
+    #[error("Step '{step}' failed{}", exit_code_suffix(.exit_code))]
+    StepFailed {
+        step: String,
+        command: String,
+        exit_code: Option<i32>,
+        stdout: String,
+        stderr: String,
+    },
+}
+
+fn exit_code_suffix(exit_code: &Option<i32>) -> String {
+    exit_code
+        .map(|code| format!(" with exit code {}", code))
+        .unwrap_or_default()
 }
 
 impl ClixError {
-    /// Convert error to user-friendly message with helpful suggestions
+    /// Convert error to user-friendly message with helpful suggestions, with
+    /// secrets redacted
     pub fn to_user_friendly_message(&self) -> String {
+        redact_secrets(&self.unredacted_message())
+    }
+
+    fn unredacted_message(&self) -> String {
         match self {
             ClixError::CommandNotFound(name) => {
                 format!("Command '{}' not found.\n💡 Use 'clix list' to see available commands or 'clix add' to create a new one.", name)
@@ -108,6 +174,28 @@ impl ClixError {
             ClixError::GitError(msg) => {
                 format!("Git operation failed: {}\n💡 Check repository access, git configuration, and network connectivity", msg)
             }
+            ClixError::WorkflowAborted(msg) => {
+                format!("Workflow aborted: {}\n💡 No steps failed; the workflow was stopped on request.", msg)
+            }
+            ClixError::AlreadyRunning(msg) => {
+                format!("Already running: {}\n💡 Only one run at a time is allowed; try again once it finishes.", msg)
+            }
+            ClixError::SecurityGate(msg) => {
+                format!("Refused by the security gate: {}\n💡 No steps were run. Fix the flagged steps, or run without --abort-on-security to be warned instead.", msg)
+            }
+            ClixError::StepSkipped(msg) => {
+                format!("Skipped: {}\n💡 The step was not run, which doesn't count as a failure.", msg)
+            }
+            ClixError::StepFailed {
+                command, stderr, ..
+            } => {
+                let mut message = format!("{}\nCommand: {}", self, command);
+                if !stderr.trim().is_empty() {
+                    message.push_str(&format!("\nStderr: {}", stderr.trim()));
+                }
+                message.push_str("\n💡 Fix the command or set continue_on_error on the step to keep going.");
+                message
+            }
         }
     }
 
@@ -151,6 +239,16 @@ impl ClixError {
         }
     }
 
+    /// Process exit code for this error: 3 when the security gate refused a
+    /// workflow, 5 when another run holds the lock, 1 otherwise
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ClixError::SecurityGate(_) => 3,
+            ClixError::AlreadyRunning(_) => 5,
+            _ => 1,
+        }
+    }
+
     /// Check if this error suggests retrying the operation
     pub fn is_retryable(&self) -> bool {
         matches!(
@@ -170,3 +268,21 @@ impl From<ClixError> for String {
 }
 
 pub type Result<T> = std::result::Result<T, ClixError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registered_secret_and_api_keys_are_redacted() {
+        register_secret("hunter2-loaded-key");
+        let error = ClixError::ApiError(
+            "401 for key hunter2-loaded-key (x-api-key: sk-ant-api03-AbC_123-xyz)".to_string(),
+        );
+
+        let message = error.to_user_friendly_message();
+        assert!(message.starts_with("API error: 401 for key **** (x-api-key: ****)"));
+        assert!(!message.contains("hunter2"));
+        assert!(!message.contains("sk-ant"));
+    }
+}
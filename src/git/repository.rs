@@ -1,11 +1,11 @@
 use crate::error::{ClixError, Result};
-use dirs::home_dir;
+use crate::settings::{DataLocation, config_dir};
 use git2::Repository;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RepoConfig {
     pub name: String,
     pub url: String,
@@ -214,15 +214,12 @@ pub struct GitRepositoryManager {
 
 impl GitRepositoryManager {
     pub fn new() -> Result<Self> {
-        let repos_dir = home_dir()
-            .ok_or_else(|| {
-                ClixError::Io(std::io::Error::new(
-                    std::io::ErrorKind::NotFound,
-                    "Could not determine home directory",
-                ))
-            })?
-            .join(".clix")
-            .join("repos");
+        Self::new_with_dir(config_dir()?)
+    }
+
+    /// Create a manager keeping repositories under a custom config directory
+    pub fn new_with_dir(config_dir: PathBuf) -> Result<Self> {
+        let repos_dir = DataLocation::Repos.path(&config_dir);
 
         fs::create_dir_all(&repos_dir)?;
 
@@ -287,29 +284,42 @@ impl GitRepositoryManager {
     }
 
     pub fn pull_all_repositories(&self) -> Result<Vec<(String, Result<()>)>> {
-        let mut results = Vec::new();
-
-        for config in &self.configs {
-            if !config.enabled {
-                continue;
-            }
-
-            let repo = GitRepository::new(config.clone(), &self.repos_dir);
-            if repo.is_cloned() {
-                let result = repo.pull();
-                results.push((config.name.clone(), result));
-            } else {
-                results.push((
-                    config.name.clone(),
-                    Err(ClixError::GitError(format!(
-                        "Repository '{}' is not cloned",
-                        config.name
-                    ))),
-                ));
-            }
-        }
+        Ok(self
+            .configs
+            .iter()
+            .filter(|c| c.enabled)
+            .map(|config| (config.name.clone(), self.pull_config(config)))
+            .collect())
+    }
+
+    /// Pull a single configured repository by name
+    pub fn pull_repository(&self, name: &str) -> Result<()> {
+        let config = self.find_config(name)?;
+        self.pull_config(config)
+    }
+
+    /// Local checkout path of a single repository, if it has been cloned
+    pub fn get_repo_path(&self, name: &str) -> Result<Option<PathBuf>> {
+        let repo = GitRepository::new(self.find_config(name)?.clone(), &self.repos_dir);
+        Ok(repo.is_cloned().then(|| repo.get_repo_path().to_path_buf()))
+    }
+
+    fn find_config(&self, name: &str) -> Result<&RepoConfig> {
+        self.configs
+            .iter()
+            .find(|c| c.name == name)
+            .ok_or_else(|| ClixError::CommandNotFound(format!("Repository '{}'", name)))
+    }
 
-        Ok(results)
+    fn pull_config(&self, config: &RepoConfig) -> Result<()> {
+        let repo = GitRepository::new(config.clone(), &self.repos_dir);
+        if !repo.is_cloned() {
+            return Err(ClixError::GitError(format!(
+                "Repository '{}' is not cloned",
+                config.name
+            )));
+        }
+        repo.pull()
     }
 
     pub fn get_all_repo_paths(&self) -> Vec<PathBuf> {
@@ -1,6 +1,7 @@
 pub mod ai;
 pub mod cli;
 pub mod commands;
+pub mod doctor;
 pub mod error;
 pub mod git;
 pub mod security;
@@ -1,20 +1,43 @@
 use clap::{CommandFactory, Parser};
 use clap_complete::{Shell as CompletionShell, generate};
 use colored::Colorize;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::fs;
-use std::io::{self, Write};
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
 use std::process::exit;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::rc::Rc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
+use clix::ai::cache::ResponseCache;
+use clix::ai::claude::ActionFilter;
+use clix::ai::fallback;
 use clix::ai::{ConversationSession, ConversationState, MessageRole};
-use clix::cli::app::{CliArgs, Commands, GitCommands, SettingsCommands, Shell};
+use clix::cli::app::{
+    BulkCommands, CliArgs, Commands, DoctorArgs, GitCommands, HistoryCommands, ListFormat,
+    NoteCommands, RunFormat, ScheduleCommands, SettingsCommands, Shell, SnippetCommands,
+    VarTypeArg,
+};
+use clix::cli::{Pager, Table, Themed};
 use clix::commands::{
-    Command, CommandExecutor, Workflow, WorkflowStep, WorkflowVariable, WorkflowVariableProfile,
+    Command, CommandExecutor, CommandResult, EnvDiff, ExecutionOptions, JunitReport,
+    ParallelAnalyzer, ParallelGroup, Schedule, ScriptEmitter, Severity, StepObserver, StepReport,
+    ValidationReport, VarType, VariableCache, Workflow, WorkflowContext, WorkflowStep,
+    WorkflowValidator, WorkflowVariable, WorkflowVariableProfile, parse_duration,
 };
+use clix::doctor;
 use clix::error::{ClixError, Result};
-use clix::share::{ExportManager, ImportManager};
-use clix::storage::{ConversationStorage, GitIntegratedStorage};
+use clix::settings::secrets::{KeychainProvider, SecretProvider, check_secret_name};
+use clix::settings::{SettingsSection, config_dir};
+use clix::share::{
+    ConflictResolution, ExportFormat, ExportManager, ImportConflict, ImportManager, ReportFormat,
+    UsageReport, clipboard,
+};
+use clix::storage::{
+    CheckpointStorage, ConversationStorage, EnvironmentSnapshot, ExecutionKind, ExecutionRecord,
+    GitIntegratedStorage, HistoryStorage, LockStorage, SnippetStorage,
+};
 use clix::{ClaudeAssistant, SettingsManager};
 
 fn main() {
@@ -24,22 +47,61 @@ fn main() {
         // Show suggestions if available
         let suggestions = e.get_suggestions();
         if !suggestions.is_empty() {
-            eprintln!("\n{}", "Suggestions:".yellow().bold());
+            eprintln!("\n{}", "Suggestions:".warning());
             for suggestion in suggestions {
                 eprintln!("  • {}", suggestion);
             }
         }
 
-        exit(1);
+        exit(e.exit_code());
     }
 }
 
 fn run() -> Result<()> {
     let args = CliArgs::parse();
-    let mut storage = GitIntegratedStorage::new()?;
+
+    // Hand the whole run to a pager; if the pager can't start, carry on unpaged
+    if Pager::should_page(args.pager, args.no_pager, io::stdout().is_terminal())
+        && let Some(pager) = Pager::from_env()
+        && let Ok(code) = pager.page_current_process()
+    {
+        exit(code);
+    }
+    let config_dir = match args.config_dir {
+        Some(dir) => dir,
+        None => config_dir()?,
+    };
+    if args.no_color {
+        colored::control::set_override(false);
+    }
+    // Doctor runs before anything creates the data directory it checks
+    if let Commands::Doctor(doctor_args) = &args.command {
+        return run_doctor(&config_dir, doctor_args);
+    }
+    let mut storage = GitIntegratedStorage::new_with_dir(config_dir.clone())?;
+    let variable_cache = if args.remember_vars {
+        let path = config_dir.join("remembered_vars.json");
+        Some(Rc::new(VariableCache::with_file(
+            path,
+            VariableCache::FILE_TTL,
+        )?))
+    } else {
+        None
+    };
+
+    // Unreadable settings keep the defaults for the theme and startup sync
+    let startup_settings = SettingsManager::new_with_dir(config_dir.clone())?
+        .load()
+        .unwrap_or_default();
+    args.theme.unwrap_or(startup_settings.theme).apply();
 
     // Sync with git repositories at startup
-    if let Err(e) = storage.sync_with_repositories() {
+    let git_settings = startup_settings.git_settings;
+    let ai_fallback = startup_settings.execution_settings.ai_fallback;
+    let history_max_entries = startup_settings.execution_settings.history_max_entries;
+    if git_settings.sync_at_startup(args.no_sync)
+        && let Err(e) = storage.sync_with_repositories()
+    {
         eprintln!("Warning: Failed to sync with git repositories: {}", e);
     }
 
@@ -47,89 +109,496 @@ fn run() -> Result<()> {
         Commands::Add(add_args) => {
             let tags = add_args.tags.unwrap_or_else(Vec::new);
 
-            let command = if let Some(command_str) = add_args.command {
+            let mut command = if let Some(command_str) = add_args.command {
                 // Simple command
                 Command::new(add_args.name, add_args.description, command_str, tags)
+            } else if add_args.command_stdin {
+                let command_str = read_command_from_stdin()?;
+                Command::new(add_args.name, add_args.description, command_str, tags)
             } else if let Some(steps_file) = add_args.steps_file {
                 // Workflow from steps file
                 let steps_json = fs::read_to_string(&steps_file).map_err(ClixError::Io)?;
                 let steps: Vec<WorkflowStep> =
                     serde_json::from_str(&steps_json).map_err(ClixError::Serialization)?;
+                WorkflowStep::check_timeouts(&steps)?;
                 Command::new_workflow(add_args.name, add_args.description, steps, tags)
             } else {
                 return Err(ClixError::InvalidCommandFormat(
-                    "Either --command or --steps-file must be provided".to_string(),
+                    "Either --command, --command-stdin or --steps-file must be provided"
+                        .to_string(),
                 ));
             };
+            command.lock = add_args.lock;
+            command.strict_vars = add_args.strict_vars;
+            command.reject_unsafe_vars = add_args.reject_unsafe_vars;
+
+            if add_args.bump {
+                if let Ok(previous) = storage.get_command(&command.name) {
+                    command.author = previous.author;
+                    command.version = previous.version;
+                    command.changelog = previous.changelog;
+                    command.notes = previous.notes;
+                    command.created_at = previous.created_at;
+                    command.mark_updated();
+                }
+                command.bump_version()?;
+            }
+            command.author = add_args.author.or(command.author);
+            command.version = add_args.version.or(command.version);
+            command.changelog.extend(add_args.changelog);
 
             storage.add_command(command)?;
-            println!("{} Command added successfully", "Success:".green().bold());
+            println!("{} Command added successfully", "Success:".success());
         }
 
         Commands::Run(run_args) => {
-            let command = storage.get_command(&run_args.name)?;
+            let command = match storage.get_command(&run_args.name) {
+                Err(ClixError::CommandNotFound(name)) => {
+                    if ask_about_unknown_command(&name, ai_fallback, &storage, &config_dir)? {
+                        return Ok(());
+                    }
+                    return Err(ClixError::CommandNotFound(name));
+                }
+                other => other?,
+            };
+
+            let mut provided_vars = match &run_args.input_json {
+                Some(input) => parse_input_json(input)?,
+                None => HashMap::new(),
+            };
+            for var_str in run_args.var.iter().flatten() {
+                if let Some((key, value)) = var_str.split_once('=') {
+                    provided_vars.insert(key.to_string(), value.to_string());
+                } else {
+                    return Err(ClixError::InvalidCommandFormat(format!(
+                        "Invalid variable format: {}, expected key=value",
+                        var_str
+                    )));
+                }
+            }
+
+            let mut env = HashMap::new();
+            for env_str in &run_args.env {
+                let (key, value) = parse_env_assignment(env_str)?;
+                env.insert(key, value);
+            }
+            if !env.is_empty() && command.is_workflow() {
+                return Err(ClixError::InvalidInput(
+                    "--env only applies to simple commands; use --var for workflows".to_string(),
+                ));
+            }
+
+            // Simple commands fill their {{ placeholders }} from --var
+            let command = if command.is_workflow() {
+                command
+            } else {
+                Command {
+                    command: Some(CommandExecutor::resolve_command(&command, &provided_vars)?),
+                    ..command
+                }
+            };
+
+            if run_args.print_command {
+                if command.is_workflow() {
+                    return Err(ClixError::InvalidInput(
+                        "--print-command only applies to simple commands, not workflows"
+                            .to_string(),
+                    ));
+                }
+                println!("{}", command.command.unwrap_or_default());
+                return Ok(());
+            }
+
+            let settings = SettingsManager::new_with_dir(config_dir.clone())?.load()?;
+            let max_output_bytes = settings.execution_settings.max_output_bytes;
+            let command_options = ExecutionOptions {
+                max_output_bytes,
+                allow_dangerous: run_args.allow_dangerous,
+                env,
+                preview_destructive: run_args.preview_destructive,
+                ..ExecutionOptions::default()
+            };
+            // Workflows take their lock in the executor, once per run
+            let lock = if command.lock && !command.is_workflow() {
+                Some(LockStorage::new_with_dir(config_dir.clone())?.acquire(&command.name)?)
+            } else {
+                None
+            };
+
+            if run_args.raw {
+                if command.is_workflow() {
+                    return Err(ClixError::InvalidInput(
+                        "--raw only applies to simple commands, not workflows".to_string(),
+                    ));
+                }
+
+                let output = CommandExecutor::execute_command_raw(&command, &command_options)?;
+                storage.update_command_usage(&run_args.name)?;
+
+                io::stdout().write_all(&output.stdout)?;
+                io::stdout().flush()?;
+                io::stderr().write_all(&output.stderr)?;
+                io::stderr().flush()?;
+                // exit() skips destructors, so release the lock first
+                drop(lock);
+                exit(output.status.code().unwrap_or(1));
+            }
+
+            if run_args.diff_env && command.is_workflow() {
+                return Err(ClixError::InvalidInput(
+                    "--diff-env only applies to simple commands, not workflows".to_string(),
+                ));
+            }
+
+            if run_args.explain_plan && !command.is_workflow() {
+                return Err(ClixError::InvalidInput(
+                    "--explain-plan only applies to workflows, not simple commands".to_string(),
+                ));
+            }
+
+            if run_args.format == RunFormat::Ndjson && !command.is_workflow() {
+                return Err(ClixError::InvalidInput(
+                    "--format ndjson only applies to workflows, not simple commands".to_string(),
+                ));
+            }
+
+            if run_args.format == RunFormat::Junit && !command.is_workflow() {
+                return Err(ClixError::InvalidInput(
+                    "--format junit only applies to workflows, not simple commands".to_string(),
+                ));
+            }
+
+            if (run_args.format == RunFormat::Junit) != run_args.output.is_some() {
+                return Err(ClixError::InvalidInput(
+                    "--format junit and --output FILE must be used together".to_string(),
+                ));
+            }
+
+            if run_args.record.is_some() && !command.is_workflow() {
+                return Err(ClixError::InvalidInput(
+                    "--record only applies to workflows, not simple commands".to_string(),
+                ));
+            }
+
+            if run_args.save_outputs.is_some() && !command.is_workflow() {
+                return Err(ClixError::InvalidInput(
+                    "--save-outputs only applies to workflows, not simple commands".to_string(),
+                ));
+            }
+
+            if run_args.max_step_failures.is_some() && !command.is_workflow() {
+                return Err(ClixError::InvalidInput(
+                    "--max-step-failures only applies to workflows, not simple commands"
+                        .to_string(),
+                ));
+            }
+
+            if run_args.retry > 0 && !command.is_workflow() {
+                return Err(ClixError::InvalidInput(
+                    "--retry only applies to workflows, not simple commands".to_string(),
+                ));
+            }
+
+            if run_args.summary_only && !command.is_workflow() {
+                return Err(ClixError::InvalidInput(
+                    "--summary-only only applies to workflows, not simple commands".to_string(),
+                ));
+            }
+
+            if run_args.abort_on_security && !command.is_workflow() {
+                return Err(ClixError::InvalidInput(
+                    "--abort-on-security only applies to workflows, not simple commands"
+                        .to_string(),
+                ));
+            }
+
+            if run_args.capture_timing_csv.is_some() && !command.is_workflow() {
+                return Err(ClixError::InvalidInput(
+                    "--capture-timing-csv only applies to workflows, not simple commands"
+                        .to_string(),
+                ));
+            }
+
+            if run_args.skip_unsafe && !command.is_workflow() {
+                return Err(ClixError::InvalidInput(
+                    "--skip-unsafe only applies to workflows, not simple commands".to_string(),
+                ));
+            }
+
+            if (run_args.checkpoint_every.is_some() || run_args.resume) && !command.is_workflow() {
+                return Err(ClixError::InvalidInput(
+                    "--checkpoint-every and --continue only apply to workflows, not simple commands"
+                        .to_string(),
+                ));
+            }
+
+            if run_args.skip_auth && !command.is_workflow() {
+                return Err(ClixError::InvalidInput(
+                    "--skip-auth only applies to workflows, not simple commands".to_string(),
+                ));
+            }
+
+            if run_args.step_timeout.is_some() && !command.is_workflow() {
+                return Err(ClixError::InvalidInput(
+                    "--step-timeout only applies to workflows, not simple commands".to_string(),
+                ));
+            }
+
+            if run_args.step_timeout == Some(0) {
+                return Err(ClixError::InvalidInput(
+                    "--step-timeout must be at least 1 second".to_string(),
+                ));
+            }
+
+            if run_args.checkpoint_every == Some(0) {
+                return Err(ClixError::InvalidInput(
+                    "--checkpoint-every must be at least 1".to_string(),
+                ));
+            }
+
+            if run_args.on_step_fail.is_some() && !command.is_workflow() {
+                return Err(ClixError::InvalidInput(
+                    "--on-step-fail only applies to workflows, not simple commands".to_string(),
+                ));
+            }
+
+            // A saved simple command is run by its command line; anything else
+            // is taken as a shell command
+            let on_step_fail = match &run_args.on_step_fail {
+                Some(handler) => match storage.get_command(handler) {
+                    Ok(saved) if saved.is_workflow() => {
+                        return Err(ClixError::InvalidInput(format!(
+                            "--on-step-fail '{}' is a workflow; use a simple command or a shell command",
+                            handler
+                        )));
+                    }
+                    Ok(saved) => saved.command,
+                    Err(_) => Some(handler.clone()),
+                },
+                None => None,
+            };
+
+            let environment = run_args
+                .capture_env
+                .then(|| EnvironmentSnapshot::capture(run_args.profile.as_deref(), &provided_vars));
 
             if command.is_workflow() {
                 // Handle workflow execution
-                let vars = if let Some(var_args) = &run_args.var {
-                    let mut vars_map = HashMap::new();
-                    for var_str in var_args {
-                        if let Some((key, value)) = var_str.split_once('=') {
-                            vars_map.insert(key.to_string(), value.to_string());
-                        } else {
-                            return Err(ClixError::InvalidCommandFormat(format!(
-                                "Invalid variable format: {}, expected key=value",
-                                var_str
-                            )));
-                        }
-                    }
+                let mut vars_map = HashMap::new();
+
+                // Seed from a previously dumped context (overridden by --var)
+                if let Some(context_file) = &run_args.load_context {
+                    vars_map.extend(WorkflowContext::load_from_file(Path::new(context_file))?);
+                }
+
+                vars_map.extend(provided_vars);
+
+                let vars = if vars_map.is_empty() {
+                    None
+                } else {
                     Some(vars_map)
+                };
+
+                let workflow = workflow_from_command(&command);
+                let resume_from = if run_args.resume {
+                    Some(
+                        CheckpointStorage::new_with_dir(config_dir.clone())?
+                            .load(&workflow.name)?
+                            .ok_or_else(|| {
+                                ClixError::InvalidInput(format!(
+                                    "No checkpoint saved for '{}'; run it with --checkpoint-every first",
+                                    workflow.name
+                                ))
+                            })?,
+                    )
                 } else {
                     None
                 };
+                let ndjson = run_args.format == RunFormat::Ndjson;
+                let junit_output = run_args.output.as_ref().map(PathBuf::from);
+                let step_reports: Rc<RefCell<Vec<StepReport>>> = Rc::default();
+
+                let options = ExecutionOptions {
+                    non_interactive: run_args.non_interactive,
+                    step_through: run_args.step_through,
+                    strict_variables: run_args.strict_vars,
+                    reject_unsafe_vars: run_args.reject_unsafe_vars,
+                    var_defaults: settings.workflow_var_defaults,
+                    command_base_dir: settings.command_files_dir.clone(),
+                    dump_context: run_args.dump_context.as_ref().map(PathBuf::from),
+                    include_secrets: run_args.include_secrets,
+                    record_cast: run_args.record.as_ref().map(PathBuf::from),
+                    save_outputs: run_args.save_outputs.as_ref().map(PathBuf::from),
+                    max_step_failures: run_args.max_step_failures,
+                    on_step_fail,
+                    max_output_bytes,
+                    variable_cache: variable_cache.clone(),
+                    allow_dangerous: run_args.allow_dangerous,
+                    preview_destructive: run_args.preview_destructive,
+                    abort_on_security: run_args.abort_on_security,
+                    skip_unsafe: run_args.skip_unsafe,
+                    checkpoint_every: run_args.checkpoint_every,
+                    resume_from,
+                    step_timeout_secs: run_args.step_timeout,
+                    skip_auth: run_args.skip_auth,
+                    snippets: SnippetStorage::new_with_dir(config_dir.clone())?.load()?,
+                    // Reports are kept for --summary-only, --format junit and
+                    // --capture-timing-csv
+                    on_step_finished: {
+                        let step_reports = Rc::clone(&step_reports);
+                        Some(Box::new(move |report: &StepReport| {
+                            if ndjson && let Ok(line) = serde_json::to_string(report) {
+                                println!("{}", line);
+                            }
+                            step_reports.borrow_mut().push(report.clone());
+                        }) as StepObserver)
+                    },
+                    progress_to_stderr: ndjson,
+                    config_dir: Some(config_dir.clone()),
+                    ..ExecutionOptions::default()
+                };
 
-                // Create a temporary workflow for execution
-                let mut workflow = Workflow::new(
-                    command.name.clone(),
-                    command.description.clone(),
-                    command.steps.clone().unwrap_or_default(),
-                    command.tags.clone(),
-                );
-
-                // Add variables and profiles from the command
-                workflow.variables = command.variables.clone();
-                workflow.profiles = command.profiles.clone();
-
-                let results = CommandExecutor::execute_workflow(
-                    &workflow,
-                    run_args.profile.as_deref(),
-                    vars,
-                )?;
+                if run_args.explain_plan {
+                    let plan = CommandExecutor::plan_workflow(
+                        &workflow,
+                        run_args.profile.as_deref(),
+                        vars,
+                        &options,
+                    )?;
+                    println!("{} {}", "Execution plan:".header(), workflow.name);
+                    print!("{}", plan.render());
+                    return Ok(());
+                }
 
-                // Print all results
-                println!("\n{}", "Workflow Results:".blue().bold());
-                println!("{}", "=".repeat(50));
+                let history = HistoryStorage::new_with_dir(config_dir.clone())?
+                    .with_max_entries(history_max_entries);
+                let print_results = |results| {
+                    // Each step has already been written as a JSON line
+                    if run_args.summary_only {
+                        print_workflow_summary(&step_reports.borrow());
+                    } else if !ndjson {
+                        print_workflow_results(results);
+                    }
+                };
 
-                for (name, result) in results {
-                    println!("{}: {}", "Step".green().bold(), name);
+                let mut attempt = 1;
+                let results = loop {
+                    let started = Instant::now();
+                    let results = CommandExecutor::execute_workflow_with_options(
+                        &workflow,
+                        run_args.profile.as_deref(),
+                        vars.clone(),
+                        &options,
+                    );
+                    let success = results
+                        .as_ref()
+                        .is_ok_and(|results| all_steps_succeeded(results));
+                    history.record(&ExecutionRecord {
+                        allowed_dangerous: run_args.allow_dangerous,
+                        environment: environment.clone(),
+                        ..execution_record(&command.name, ExecutionKind::Workflow, started, success)
+                    })?;
+
+                    // Only step failures are retried; errors before the steps
+                    // run, denied approvals and declined confirmations are not
+                    let retryable = results.as_ref().is_ok_and(|results| {
+                        !success && !results.iter().any(|(_, result)| was_refused(result))
+                    });
+                    if !retryable || attempt > run_args.retry {
+                        break results;
+                    }
 
-                    match result {
-                        Ok(output) => CommandExecutor::print_command_output(&output),
-                        Err(e) => println!("{} {}", "Error:".red().bold(), e),
+                    print_results(results?);
+                    step_reports.borrow_mut().clear();
+                    attempt += 1;
+                    let notice = format!(
+                        "{} Workflow failed; retrying in {}s (attempt {} of {})",
+                        "Retry:".warning(),
+                        run_args.retry_delay,
+                        attempt,
+                        run_args.retry + 1
+                    );
+                    if ndjson {
+                        eprintln!("{}", notice);
+                    } else {
+                        println!("{}", notice);
                     }
+                    std::thread::sleep(Duration::from_secs(run_args.retry_delay));
+                };
 
-                    println!("{}", "-".repeat(50));
+                let results = results?;
+                if let Some(path) = &run_args.capture_timing_csv {
+                    fs::write(path, StepReport::timing_csv(&step_reports.borrow()))?;
                 }
+                if let Some(path) = &junit_output {
+                    junit_report(&workflow.name, &results, &step_reports.borrow()).save(path)?;
+                    println!(
+                        "{} Wrote JUnit report to {}",
+                        "Success:".success(),
+                        path.display()
+                    );
+                }
+                print_results(results);
             } else {
                 // Handle simple command execution
-                let output = CommandExecutor::execute_command(&command)?;
-                CommandExecutor::print_command_output(&output);
+                let history = HistoryStorage::new_with_dir(config_dir.clone())?
+                    .with_max_entries(history_max_entries);
+                let started = Instant::now();
+                let (result, env_diff) = if run_args.diff_env {
+                    match CommandExecutor::execute_command_with_env_diff(&command, &command_options)
+                    {
+                        Ok((output, diff)) => (
+                            Ok(CommandResult::from_output(
+                                &command.name,
+                                &output,
+                                started.elapsed(),
+                            )),
+                            Some(diff),
+                        ),
+                        Err(e) => (Err(e), None),
+                    }
+                } else {
+                    (
+                        CommandExecutor::execute_command_result(&command, &command_options),
+                        None,
+                    )
+                };
+                let success = result.as_ref().is_ok_and(|result| result.success);
+                history.record(&ExecutionRecord {
+                    allowed_dangerous: run_args.allow_dangerous,
+                    environment,
+                    ..execution_record(&command.name, ExecutionKind::Command, started, success)
+                })?;
+
+                CommandExecutor::print_command_result(&result?);
+                if let Some(diff) = env_diff {
+                    print_env_diff(&diff);
+                }
             }
 
             // Update usage statistics
             storage.update_command_usage(&run_args.name)?;
         }
 
+        Commands::Tags(tags_args) => {
+            let tags = storage.tag_counts()?;
+            if tags_args.json {
+                println!("{}", serde_json::to_string_pretty(&tags)?);
+            } else if tags_args.names {
+                for tag in &tags {
+                    println!("{}", tag.tag);
+                }
+            } else if tags.is_empty() {
+                println!("No tags found.");
+            } else {
+                let mut table = Table::new(&["Tag", "Items"]);
+                for tag in &tags {
+                    table.add_row(vec![tag.tag.clone(), tag.count.to_string()]);
+                }
+                print!("{}", table.render());
+            }
+        }
+
         Commands::List(list_args) => {
             let all_commands = storage.list_commands()?;
             // Get old workflows for backward compatibility during migration
@@ -177,22 +646,113 @@ fn run() -> Result<()> {
                 old_workflows
             };
 
+            // Filter to the items that call the given one
+            let (filtered_simple_commands, filtered_workflow_commands, filtered_old_workflows) =
+                if let Some(ref name) = list_args.referenced_by {
+                    let validator = WorkflowValidator::new(storage.get_local_storage().clone());
+                    let referencers = validator.referenced_by(name)?;
+                    if referencers.is_empty() {
+                        println!("Nothing references '{}'.", name);
+                        return Ok(());
+                    }
+                    let keep = |item: &str| referencers.iter().any(|r| r == item);
+                    (
+                        filtered_simple_commands
+                            .into_iter()
+                            .filter(|cmd| keep(&cmd.name))
+                            .collect(),
+                        filtered_workflow_commands
+                            .into_iter()
+                            .filter(|cmd| keep(&cmd.name))
+                            .collect(),
+                        filtered_old_workflows
+                            .into_iter()
+                            .filter(|wf| keep(&wf.name))
+                            .collect(),
+                    )
+                } else {
+                    (
+                        filtered_simple_commands,
+                        filtered_workflow_commands,
+                        filtered_old_workflows,
+                    )
+                };
+
+            if list_args.format == ListFormat::Table {
+                let mut table = Table::new(&["Name", "Type", "Tags", "Uses", "Last-Used"]);
+                let mut rows = Vec::new();
+                if show_commands {
+                    rows.extend(filtered_simple_commands.iter().map(|cmd| {
+                        (
+                            cmd.name.clone(),
+                            "command",
+                            &cmd.tags,
+                            cmd.use_count,
+                            cmd.last_used,
+                        )
+                    }));
+                }
+                if show_workflows {
+                    rows.extend(filtered_workflow_commands.iter().map(|cmd| {
+                        (
+                            cmd.name.clone(),
+                            "workflow",
+                            &cmd.tags,
+                            cmd.use_count,
+                            cmd.last_used,
+                        )
+                    }));
+                    rows.extend(filtered_old_workflows.iter().map(|wf| {
+                        (
+                            wf.name.clone(),
+                            "workflow",
+                            &wf.tags,
+                            wf.use_count,
+                            wf.last_used,
+                        )
+                    }));
+                }
+                rows.sort_by(|a, b| a.1.cmp(b.1).then_with(|| a.0.cmp(&b.0)));
+
+                for (name, kind, tags, use_count, last_used) in rows {
+                    table.add_row(vec![
+                        name,
+                        kind.to_string(),
+                        tags.join(","),
+                        use_count.to_string(),
+                        format_last_used(last_used),
+                    ]);
+                }
+                print!("{}", table.render());
+                return Ok(());
+            }
+
             // Print simple commands
             if show_commands && !filtered_simple_commands.is_empty() {
-                println!("\n{}", "Commands:".blue().bold());
+                println!("\n{}", "Commands:".header());
                 println!("{}", "=".repeat(50));
 
                 for cmd in &filtered_simple_commands {
-                    println!("{}: {}", "Name".green().bold(), cmd.name);
-                    println!("{}: {}", "Description".green(), cmd.description);
+                    println!("{}: {}", "Name".success(), cmd.name);
+                    println!("{}: {}", "Description".label(), cmd.description);
                     println!(
                         "{}: {}",
-                        "Command".green(),
+                        "Command".label(),
                         cmd.command.as_ref().unwrap_or(&"<no command>".to_string())
                     );
 
                     if !cmd.tags.is_empty() {
-                        println!("{}: {}", "Tags".green(), cmd.tags.join(", "));
+                        println!("{}: {}", "Tags".label(), cmd.tags.join(", "));
+                    }
+
+                    print_metadata(
+                        cmd.author.as_deref(),
+                        cmd.version.as_deref(),
+                        &cmd.changelog,
+                    );
+
+                    if list_args.verbose {
+                        print_notes(cmd.notes.as_deref());
                     }
 
                     if let Some(last_used) = cmd.last_used {
@@ -204,7 +764,7 @@ fn run() -> Result<()> {
 
                         println!(
                             "{}: {} ({} days ago)",
-                            "Last used".green(),
+                            "Last used".label(),
                             cmd.use_count,
                             days_ago
                         );
@@ -216,20 +776,30 @@ fn run() -> Result<()> {
 
             // Print workflow commands (from unified structure)
             if show_workflows && !filtered_workflow_commands.is_empty() {
-                println!("\n{}", "Workflows:".blue().bold());
+                println!("\n{}", "Workflows:".header());
                 println!("{}", "=".repeat(50));
 
                 for cmd in &filtered_workflow_commands {
-                    println!("{}: {}", "Name".green().bold(), cmd.name);
-                    println!("{}: {}", "Description".green(), cmd.description);
+                    println!("{}: {}", "Name".success(), cmd.name);
+                    println!("{}: {}", "Description".label(), cmd.description);
                     println!(
                         "{}: {}",
-                        "Steps".green(),
+                        "Steps".label(),
                         cmd.steps.as_ref().map_or(0, |s| s.len())
                     );
 
                     if !cmd.tags.is_empty() {
-                        println!("{}: {}", "Tags".green(), cmd.tags.join(", "));
+                        println!("{}: {}", "Tags".label(), cmd.tags.join(", "));
+                    }
+
+                    print_metadata(
+                        cmd.author.as_deref(),
+                        cmd.version.as_deref(),
+                        &cmd.changelog,
+                    );
+
+                    if list_args.verbose {
+                        print_notes(cmd.notes.as_deref());
                     }
 
                     if let Some(last_used) = cmd.last_used {
@@ -241,7 +811,7 @@ fn run() -> Result<()> {
 
                         println!(
                             "{}: {} ({} days ago)",
-                            "Last used".green(),
+                            "Last used".label(),
                             cmd.use_count,
                             days_ago
                         );
@@ -254,19 +824,25 @@ fn run() -> Result<()> {
             // Print old workflows (for backward compatibility during migration)
             if show_workflows && !filtered_old_workflows.is_empty() {
                 if filtered_workflow_commands.is_empty() {
-                    println!("\n{}", "Workflows (legacy):".blue().bold());
+                    println!("\n{}", "Workflows (legacy):".header());
                 } else {
-                    println!("\n{}", "Legacy Workflows:".blue().bold());
+                    println!("\n{}", "Legacy Workflows:".header());
                 }
                 println!("{}", "=".repeat(50));
 
                 for wf in filtered_old_workflows {
-                    println!("{}: {}", "Name".green().bold(), wf.name);
-                    println!("{}: {}", "Description".green(), wf.description);
-                    println!("{}: {}", "Steps".green(), wf.steps.len());
+                    println!("{}: {}", "Name".success(), wf.name);
+                    println!("{}: {}", "Description".label(), wf.description);
+                    println!("{}: {}", "Steps".label(), wf.steps.len());
 
                     if !wf.tags.is_empty() {
-                        println!("{}: {}", "Tags".green(), wf.tags.join(", "));
+                        println!("{}: {}", "Tags".label(), wf.tags.join(", "));
+                    }
+
+                    print_metadata(wf.author.as_deref(), wf.version.as_deref(), &wf.changelog);
+
+                    if list_args.verbose {
+                        print_notes(wf.notes.as_deref());
                     }
 
                     if let Some(last_used) = wf.last_used {
@@ -278,7 +854,7 @@ fn run() -> Result<()> {
 
                         println!(
                             "{}: {} ({} days ago)",
-                            "Last used".green(),
+                            "Last used".label(),
                             wf.use_count,
                             days_ago
                         );
@@ -290,44 +866,81 @@ fn run() -> Result<()> {
         }
 
         Commands::Remove(remove_args) => {
+            let validator = WorkflowValidator::new(storage.get_local_storage().clone());
+            let referencers = validator.referenced_by(&remove_args.name)?;
             storage.remove_command(&remove_args.name)?;
+            if !referencers.is_empty() {
+                println!(
+                    "{} '{}' is still called by: {}",
+                    "Warning:".warning(),
+                    remove_args.name,
+                    referencers.join(", ")
+                );
+            }
             println!(
                 "{} Command '{}' removed successfully",
-                "Success:".green().bold(),
+                "Success:".success(),
                 remove_args.name
             );
         }
 
         Commands::AddVar(add_var_args) => {
-            let mut command = storage.get_command(&add_var_args.command_name)?;
-
-            if !command.is_workflow() {
+            if !storage
+                .get_command(&add_var_args.command_name)?
+                .is_workflow()
+            {
                 return Err(ClixError::InvalidCommandFormat(
                     "Variables can only be added to workflows".to_string(),
                 ));
             }
 
+            let var_type = match add_var_args.var_type {
+                VarTypeArg::String => VarType::String,
+                VarTypeArg::Int => VarType::Int,
+                VarTypeArg::Bool => VarType::Bool,
+                VarTypeArg::Enum if add_var_args.allowed_values.is_empty() => {
+                    return Err(ClixError::InvalidInput(
+                        "--type enum needs the values in --allowed-values".to_string(),
+                    ));
+                }
+                VarTypeArg::Enum => VarType::Enum(add_var_args.allowed_values.clone()),
+            };
+            if let Some(default) = &add_var_args.default {
+                var_type.coerce(default).map_err(|reason| {
+                    ClixError::InvalidInput(format!("Invalid --default: {}", reason))
+                })?;
+            }
+
             let variable = WorkflowVariable::new(
                 add_var_args.name,
                 add_var_args.description,
                 add_var_args.default,
                 add_var_args.required,
-            );
+            )
+            .with_allowed_values(add_var_args.allowed_values)
+            .with_quote(add_var_args.quote)
+            .with_type(var_type);
+            let variable = match add_var_args.computed {
+                Some(expression) => variable.with_computed(expression),
+                None => variable,
+            };
 
-            command.add_variable(variable);
-            storage.update_command(&command)?;
+            storage.modify_command(&add_var_args.command_name, |command| {
+                command.add_variable(variable)
+            })?;
 
             println!(
                 "{} Variable added to workflow '{}'",
-                "Success:".green().bold(),
+                "Success:".success(),
                 add_var_args.command_name
             );
         }
 
         Commands::AddProfile(add_profile_args) => {
-            let mut command = storage.get_command(&add_profile_args.command_name)?;
-
-            if !command.is_workflow() {
+            if !storage
+                .get_command(&add_profile_args.command_name)?
+                .is_workflow()
+            {
                 return Err(ClixError::InvalidCommandFormat(
                     "Profiles can only be added to workflows".to_string(),
                 ));
@@ -352,12 +965,13 @@ fn run() -> Result<()> {
                 vars_map,
             );
 
-            command.add_profile(profile);
-            storage.update_command(&command)?;
+            storage.modify_command(&add_profile_args.command_name, |command| {
+                command.add_profile(profile)
+            })?;
 
             println!(
                 "{} Profile added to workflow '{}'",
-                "Success:".green().bold(),
+                "Success:".success(),
                 add_profile_args.command_name
             );
         }
@@ -379,16 +993,16 @@ fn run() -> Result<()> {
                 return Ok(());
             }
 
-            println!("{}", "Workflow Profiles:".blue().bold());
+            println!("{}", "Workflow Profiles:".header());
             println!("{}", "=".repeat(50));
 
             for (name, profile) in &command.profiles {
-                println!("{}: {}", "Profile".green().bold(), name);
-                println!("{}: {}", "Description".green(), profile.description);
-                println!("{}: {}", "Variables".green(), profile.variables.len());
+                println!("{}: {}", "Profile".success(), name);
+                println!("{}: {}", "Description".label(), profile.description);
+                println!("{}: {}", "Variables".label(), profile.variables.len());
 
                 for (var_name, var_value) in &profile.variables {
-                    println!("{}: {} = {}", "  Variable".yellow(), var_name, var_value);
+                    println!("{}: {} = {}", "  Variable".note(), var_name, var_value);
                 }
 
                 println!("{}", "-".repeat(50));
@@ -398,9 +1012,7 @@ fn run() -> Result<()> {
         Commands::AddCondition(args) => {
             use clix::commands::models::{Condition, ConditionalAction, WorkflowStep};
 
-            let mut command = storage.get_command(&args.command_name)?;
-
-            if !command.is_workflow() {
+            if !storage.get_command(&args.command_name)?.is_workflow() {
                 return Err(ClixError::InvalidCommandFormat(
                     "Conditions can only be added to workflows".to_string(),
                 ));
@@ -449,7 +1061,7 @@ fn run() -> Result<()> {
             };
 
             // Create conditional step
-            let conditional_step = WorkflowStep::new_conditional(
+            let mut conditional_step = WorkflowStep::new_conditional(
                 args.name.clone(),
                 args.description.clone(),
                 condition,
@@ -458,15 +1070,28 @@ fn run() -> Result<()> {
                 action,
             );
 
-            // Add the conditional step to the workflow
-            if let Some(ref mut steps) = command.steps {
-                steps.push(conditional_step);
+            // Elif branches come in (condition, steps file) pairs
+            for pair in args.elif.chunks(2) {
+                let elif_steps_json = fs::read_to_string(&pair[1]).map_err(ClixError::Io)?;
+                let elif_steps: Vec<WorkflowStep> =
+                    serde_json::from_str(&elif_steps_json).map_err(ClixError::Serialization)?;
+                let elif_condition = Condition {
+                    expression: pair[0].clone(),
+                    variable: None,
+                };
+                conditional_step = conditional_step.with_elif(elif_condition, elif_steps);
             }
-            storage.update_command(&command)?;
+
+            // Add the conditional step to the workflow
+            storage.modify_command(&args.command_name, |command| {
+                if let Some(ref mut steps) = command.steps {
+                    steps.push(conditional_step);
+                }
+            })?;
 
             println!(
                 "{} Conditional step '{}' added to workflow '{}'",
-                "Success:".green().bold(),
+                "Success:".success(),
                 args.name,
                 args.command_name
             );
@@ -475,9 +1100,7 @@ fn run() -> Result<()> {
         Commands::AddBranch(args) => {
             use clix::commands::models::{BranchCase, WorkflowStep};
 
-            let mut command = storage.get_command(&args.command_name)?;
-
-            if !command.is_workflow() {
+            if !storage.get_command(&args.command_name)?.is_workflow() {
                 return Err(ClixError::InvalidCommandFormat(
                     "Branches can only be added to workflows".to_string(),
                 ));
@@ -508,38 +1131,129 @@ fn run() -> Result<()> {
             );
 
             // Add the branch step to the workflow
-            if let Some(ref mut steps) = command.steps {
-                steps.push(branch_step);
-            }
-            storage.update_command(&command)?;
+            storage.modify_command(&args.command_name, |command| {
+                if let Some(ref mut steps) = command.steps {
+                    steps.push(branch_step);
+                }
+            })?;
 
             println!(
                 "{} Branch step '{}' added to workflow '{}'",
-                "Success:".green().bold(),
+                "Success:".success(),
                 args.name,
                 args.command_name
             );
         }
 
+        Commands::CopyStep(args) => {
+            let source = storage.get_command(&args.from)?;
+            let mut target = storage.get_command(&args.command_name)?;
+            if !source.is_workflow() || !target.is_workflow() {
+                return Err(ClixError::InvalidCommandFormat(
+                    "Steps can only be copied between workflows".to_string(),
+                ));
+            }
+
+            let step = workflow_from_command(&source)
+                .find_step(&args.step)
+                .cloned()
+                .ok_or_else(|| {
+                    ClixError::CommandNotFound(format!(
+                        "Step '{}' not found in workflow '{}'",
+                        args.step, args.from
+                    ))
+                })?;
+
+            let validator = WorkflowValidator::new(storage.get_local_storage().clone());
+            let error_count = |report: &ValidationReport| {
+                report
+                    .issues
+                    .iter()
+                    .filter(|issue| issue.severity == Severity::Error)
+                    .count()
+            };
+            let errors_before =
+                error_count(&validator.validate_workflow(&workflow_from_command(&target))?);
+
+            let steps = target.steps.get_or_insert_with(Vec::new);
+            if steps.iter().any(|existing| existing.name == step.name) {
+                return Err(ClixError::InvalidInput(format!(
+                    "Workflow '{}' already has a step named '{}'",
+                    args.command_name, step.name
+                )));
+            }
+            let index = match args.position {
+                Some(position) if position == 0 || position > steps.len() + 1 => {
+                    return Err(ClixError::InvalidInput(format!(
+                        "Position must be between 1 and {}",
+                        steps.len() + 1
+                    )));
+                }
+                Some(position) => position - 1,
+                None => steps.len(),
+            };
+            steps.insert(index, step);
+
+            // Don't save a workflow the copy has broken, e.g. by a missing dependency
+            let report = validator.validate_workflow(&workflow_from_command(&target))?;
+            if error_count(&report) > errors_before {
+                print_validation_report(&report);
+                return Err(ClixError::ValidationError(format!(
+                    "Copying '{}' would leave workflow '{}' invalid; nothing was changed",
+                    args.step, args.command_name
+                )));
+            }
+
+            let steps = target.steps.take();
+            storage.modify_command(&args.command_name, |command| command.steps = steps)?;
+
+            println!(
+                "{} Step '{}' copied from '{}' to workflow '{}' at position {}",
+                "Success:".success(),
+                args.step,
+                args.from,
+                args.command_name,
+                index + 1
+            );
+        }
+
         Commands::ConvertFunction(args) => {
             use clix::commands::FunctionConverter;
 
             println!(
                 "{} Converting function '{}' from '{}'...",
-                "Info:".blue().bold(),
+                "Info:".header(),
                 args.function,
                 args.file
             );
 
             let tags = args.tags.unwrap_or_else(Vec::new);
 
-            match FunctionConverter::convert_function(
+            match FunctionConverter::convert_function_with_options(
                 &args.file,
                 &args.function,
                 &args.command_name,
                 &args.description,
                 tags.clone(),
+                args.inline_calls,
             ) {
+                Ok(workflow) if args.preview => {
+                    let mut table = Table::new(&["#", "Name", "Type", "Command"]);
+                    for (index, step) in workflow.steps.iter().enumerate() {
+                        table.add_row(vec![
+                            (index + 1).to_string(),
+                            step.name.clone(),
+                            format!("{:?}", step.step_type),
+                            step.command.clone(),
+                        ]);
+                    }
+                    print!("{}", table.render());
+                    println!(
+                        "{} Preview only; '{}' was not saved",
+                        "Note:".warning(),
+                        args.command_name
+                    );
+                }
                 Ok(workflow) => {
                     // Convert the workflow to a unified command
                     let command = Command::new_workflow(
@@ -551,48 +1265,777 @@ fn run() -> Result<()> {
                     storage.add_command(command)?;
                     println!(
                         "{} Function '{}' successfully converted to workflow '{}'",
-                        "Success:".green().bold(),
+                        "Success:".success(),
                         args.function,
                         args.command_name
                     );
                 }
                 Err(e) => {
+                    println!("{} Failed to convert function: {}", "Error:".error(), e);
+                    return Err(e);
+                }
+            }
+        }
+
+        Commands::ConvertMakefile(args) => {
+            use clix::commands::MakefileConverter;
+
+            let tags = args.tags.unwrap_or_default();
+            let commands =
+                MakefileConverter::convert_file(&args.path, args.target.as_deref(), tags)?;
+
+            for command in commands {
+                let kind = if command.is_workflow() {
+                    "workflow"
+                } else {
+                    "command"
+                };
+                let name = command.name.clone();
+                storage.add_command(command)?;
+                println!(
+                    "{} Make target '{}' converted to {} '{}'",
+                    "Success:".success(),
+                    name,
+                    kind,
+                    name
+                );
+            }
+        }
+
+        Commands::ConvertNpm(args) => {
+            use clix::commands::NpmConverter;
+
+            let tags = args.tags.unwrap_or_default();
+            let commands = NpmConverter::convert_file(&args.path, tags)?;
+            let count = commands.len();
+
+            for command in commands {
+                storage.add_command(command)?;
+            }
+            println!(
+                "{} Converted {} npm script(s) from '{}'",
+                "Success:".success(),
+                count,
+                args.path
+            );
+        }
+
+        Commands::Open(open_args) => {
+            let path = match open_args.what {
+                Some(location) => location.path(&config_dir),
+                None => config_dir.clone(),
+            };
+            println!("{}", path.display());
+
+            if open_args.reveal {
+                // File managers open directories, so reveal a file by opening its folder
+                let folder = if path.is_dir() {
+                    path.as_path()
+                } else {
+                    path.parent().unwrap_or(&config_dir)
+                };
+                reveal_in_file_manager(folder)?;
+            }
+        }
+
+        Commands::History(HistoryCommands::Clear(args)) => {
+            if !args.yes && !confirm("Delete the whole run history?")? {
+                println!("Cancelled.");
+                return Ok(());
+            }
+
+            let cleared = HistoryStorage::new_with_dir(config_dir.clone())?.clear()?;
+            println!(
+                "{} Cleared {} run(s) from the history",
+                "Success:".success(),
+                cleared
+            );
+        }
+
+        Commands::TimeEstimate(args) => {
+            let history = HistoryStorage::new_with_dir(config_dir.clone())?;
+            let Some(estimate) = history.duration_estimate(&args.name)? else {
+                println!(
+                    "No successful runs of '{}' recorded yet, so there is nothing to estimate from.",
+                    args.name
+                );
+                return Ok(());
+            };
+
+            let seconds = |ms: u64| format!("{:.1}s", ms as f64 / 1000.0);
+            println!(
+                "{} '{}' from {} successful run(s)",
+                "Time estimate:".header(),
+                args.name,
+                estimate.runs
+            );
+            println!("  {}: {}", "Average".label(), seconds(estimate.average_ms));
+            println!("  {}: {}", "Last".label(), seconds(estimate.last_ms));
+            println!("  {}: {}", "p90".label(), seconds(estimate.p90_ms));
+        }
+
+        Commands::Report(report_args) => {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            let since = match &report_args.since {
+                Some(since) => Some(now.saturating_sub(parse_duration(since)?.as_secs())),
+                None => None,
+            };
+
+            let mut tags = HashMap::new();
+            for command in storage.list_commands()? {
+                tags.insert(command.name, command.tags);
+            }
+            for workflow in storage.list_workflows()? {
+                tags.entry(workflow.name).or_insert(workflow.tags);
+            }
+
+            let history = HistoryStorage::new_with_dir(config_dir.clone())?.load()?;
+            let report = UsageReport::build(&history, &tags, since, now);
+            let content = match report_args.format {
+                ReportFormat::Json => serde_json::to_string_pretty(&report)? + "\n",
+                ReportFormat::Csv => report.to_csv(),
+            };
+
+            match &report_args.output {
+                Some(output) => {
+                    fs::write(output, content)?;
                     println!(
-                        "{} Failed to convert function: {}",
-                        "Error:".red().bold(),
-                        e
+                        "{} Usage report with {} run(s) written to: {}",
+                        "Success:".success(),
+                        report.total_runs,
+                        output
                     );
-                    return Err(e);
                 }
+                None => print!("{}", content),
+            }
+        }
+
+        Commands::RetryFailed(retry_args) => {
+            let history = HistoryStorage::new_with_dir(config_dir.clone())?
+                .with_max_entries(history_max_entries);
+            let locks = LockStorage::new_with_dir(config_dir.clone())?;
+            let settings = SettingsManager::new_with_dir(config_dir.clone())?.load()?;
+            let snippets = SnippetStorage::new_with_dir(config_dir.clone())?.load()?;
+
+            // Only retry workflows that still exist and match the tag
+            let mut to_retry = Vec::new();
+            for name in history.failed_workflows()? {
+                let Ok(command) = storage.get_command(&name) else {
+                    continue;
+                };
+                if !command.is_workflow() {
+                    continue;
+                }
+                if let Some(tag) = &retry_args.tag
+                    && !command.tags.contains(tag)
+                {
+                    continue;
+                }
+                to_retry.push(command);
+            }
+
+            if to_retry.is_empty() {
+                println!("No failed workflows to retry.");
+                return Ok(());
+            }
+
+            let mut still_failing = Vec::new();
+            for command in &to_retry {
+                println!("\n{} Retrying workflow: {}", "Clix:".header(), command.name);
+
+                let options = ExecutionOptions {
+                    var_defaults: settings.workflow_var_defaults.clone(),
+                    command_base_dir: settings.command_files_dir.clone(),
+                    max_output_bytes: settings.execution_settings.max_output_bytes,
+                    variable_cache: variable_cache.clone(),
+                    snippets: snippets.clone(),
+                    config_dir: Some(config_dir.clone()),
+                    ..ExecutionOptions::default()
+                };
+                let success = run_command_recorded(command, &options, &history, &locks)?;
+                storage.update_command_usage(&command.name)?;
+
+                if !success {
+                    still_failing.push(command.name.clone());
+                }
+            }
+
+            if still_failing.is_empty() {
+                println!(
+                    "{} All {} workflow(s) succeeded on retry",
+                    "Success:".success(),
+                    to_retry.len()
+                );
+            } else {
+                return Err(ClixError::CommandExecutionFailed(format!(
+                    "Still failing: {}",
+                    still_failing.join(", ")
+                )));
+            }
+        }
+
+        Commands::Schedule(ScheduleCommands::Run(schedule_args)) => {
+            let every = parse_duration(&schedule_args.every)?;
+            let duration = parse_duration(&schedule_args.duration)?;
+            let schedule = Schedule::new(every, duration, schedule_args.overrun)?;
+            let command = storage.get_command(&schedule_args.name)?;
+
+            let history = HistoryStorage::new_with_dir(config_dir.clone())?
+                .with_max_entries(history_max_entries);
+            let locks = LockStorage::new_with_dir(config_dir.clone())?;
+            let settings = SettingsManager::new_with_dir(config_dir.clone())?.load()?;
+            let options = ExecutionOptions {
+                non_interactive: true,
+                var_defaults: settings.workflow_var_defaults,
+                command_base_dir: settings.command_files_dir.clone(),
+                max_output_bytes: settings.execution_settings.max_output_bytes,
+                variable_cache: variable_cache.clone(),
+                snippets: SnippetStorage::new_with_dir(config_dir.clone())?.load()?,
+                config_dir: Some(config_dir.clone()),
+                ..ExecutionOptions::default()
+            };
+
+            println!(
+                "{} Running '{}' every {} for {} (Ctrl-C to stop)",
+                "Schedule:".header(),
+                command.name,
+                schedule_args.every,
+                schedule_args.duration
+            );
+
+            let summary = schedule.run(|run| {
+                let started = Instant::now();
+                let success = run_command_recorded(&command, &options, &history, &locks)?;
+                storage.update_command_usage(&command.name)?;
+
+                let status = if success {
+                    "succeeded".label()
+                } else {
+                    "failed".error()
+                };
+                println!(
+                    "{} Run {} of '{}' {} in {} ms",
+                    format!("[{}]", utc_clock()).dimmed(),
+                    run,
+                    command.name,
+                    status,
+                    started.elapsed().as_millis()
+                );
+                Ok(success)
+            })?;
+
+            println!(
+                "{} {} run(s), {} failed, {} skipped",
+                "Schedule finished:".header(),
+                summary.runs,
+                summary.failures,
+                summary.skipped
+            );
+        }
+
+        Commands::Bulk(bulk_cmd) => {
+            let target = match &bulk_cmd {
+                BulkCommands::Run(args) | BulkCommands::Remove(args) => args,
+                BulkCommands::Export(args) => &args.target,
+            };
+            let (action, past) = match &bulk_cmd {
+                BulkCommands::Run(_) => ("Run", "Ran"),
+                BulkCommands::Remove(_) => ("Remove", "Removed"),
+                BulkCommands::Export(_) => ("Export", "Exported"),
+            };
+
+            let items = storage.find_by_tag(&target.tag)?;
+            if items.is_empty() {
+                println!("Nothing is tagged '{}'.", target.tag);
+                return Ok(());
+            }
+
+            println!(
+                "{} {} item(s) tagged '{}':",
+                "Bulk:".header(),
+                items.len(),
+                target.tag
+            );
+            for command in &items.commands {
+                let kind = if command.is_workflow() {
+                    "workflow"
+                } else {
+                    "command"
+                };
+                println!("  {:<9} {}", kind, command.name);
+            }
+            for workflow in &items.workflows {
+                println!("  {:<9} {}", "workflow", workflow.name);
+            }
+
+            if target.dry_run {
+                println!("Dry run: nothing was changed.");
+                return Ok(());
+            }
+
+            if !target.yes && !confirm(&format!("{} these {} item(s)?", action, items.len()))? {
+                println!("Cancelled.");
+                return Ok(());
+            }
+
+            let mut failed = Vec::new();
+            match &bulk_cmd {
+                BulkCommands::Run(_) => {
+                    let history = HistoryStorage::new_with_dir(config_dir.clone())?
+                        .with_max_entries(history_max_entries);
+                    let locks = LockStorage::new_with_dir(config_dir.clone())?;
+                    let settings = SettingsManager::new_with_dir(config_dir.clone())?.load()?;
+                    let options = ExecutionOptions {
+                        var_defaults: settings.workflow_var_defaults,
+                        command_base_dir: settings.command_files_dir.clone(),
+                        max_output_bytes: settings.execution_settings.max_output_bytes,
+                        variable_cache: variable_cache.clone(),
+                        snippets: SnippetStorage::new_with_dir(config_dir.clone())?.load()?,
+                        config_dir: Some(config_dir.clone()),
+                        ..ExecutionOptions::default()
+                    };
+
+                    for command in &items.commands {
+                        let success = run_command_recorded(command, &options, &history, &locks)?;
+                        storage.update_command_usage(&command.name)?;
+
+                        if !success {
+                            failed.push(command.name.clone());
+                        }
+                    }
+
+                    for workflow in &items.workflows {
+                        if !run_workflow_recorded(workflow, &options, &history)? {
+                            failed.push(workflow.name.clone());
+                        }
+                        storage.update_workflow_usage(&workflow.name)?;
+                    }
+                }
+                BulkCommands::Remove(_) => {
+                    for command in &items.commands {
+                        storage.remove_command(&command.name)?;
+                    }
+                    for workflow in &items.workflows {
+                        storage.remove_workflow(&workflow.name)?;
+                    }
+                }
+                BulkCommands::Export(args) => {
+                    let export_manager = ExportManager::new(storage.get_local_storage().clone());
+                    export_manager.export_with_filter(
+                        &args.output,
+                        Some(target.tag.clone()),
+                        false,
+                        false,
+                    )?;
+                }
+            }
+
+            if failed.is_empty() {
+                println!("{} {} {} item(s)", "Success:".success(), past, items.len());
+            } else {
+                return Err(ClixError::CommandExecutionFailed(format!(
+                    "{} of {} item(s) failed: {}",
+                    failed.len(),
+                    items.len(),
+                    failed.join(", ")
+                )));
+            }
+        }
+
+        Commands::Snippet(snippet_cmd) => {
+            let snippets = SnippetStorage::new_with_dir(config_dir.clone())?;
+
+            match snippet_cmd {
+                SnippetCommands::Add(add_args) => {
+                    let steps_json =
+                        fs::read_to_string(&add_args.steps_file).map_err(ClixError::Io)?;
+                    let steps: Vec<WorkflowStep> =
+                        serde_json::from_str(&steps_json).map_err(ClixError::Serialization)?;
+                    let count = steps.len();
+
+                    let replaced = snippets.add(&add_args.name, steps)?;
+                    println!(
+                        "{} Snippet '{}' {} with {} step(s)",
+                        "Success:".success(),
+                        add_args.name,
+                        if replaced { "replaced" } else { "added" },
+                        count
+                    );
+                }
+                SnippetCommands::List => {
+                    let mut all: Vec<_> = snippets.load()?.into_iter().collect();
+                    if all.is_empty() {
+                        println!("No snippets stored.");
+                        return Ok(());
+                    }
+
+                    all.sort_by(|a, b| a.0.cmp(&b.0));
+                    for (name, steps) in all {
+                        let names: Vec<&str> =
+                            steps.iter().map(|step| step.name.as_str()).collect();
+                        println!("{}: {}", name.success(), names.join(", "));
+                    }
+                }
+                SnippetCommands::Remove(remove_args) => {
+                    snippets.remove(&remove_args.name)?;
+                    println!(
+                        "{} Snippet '{}' removed",
+                        "Success:".success(),
+                        remove_args.name
+                    );
+                }
+            }
+        }
+
+        Commands::Note(note_cmd) => match note_cmd {
+            NoteCommands::Set(set_args) => {
+                let current = storage.get_command(&set_args.name)?.notes;
+                let text = match set_args.text {
+                    Some(text) => text,
+                    None => edit_in_editor(current.as_deref().unwrap_or(""))?,
+                };
+                let text = text.trim_end().to_string();
+                if text.trim().is_empty() {
+                    return Err(ClixError::InvalidInput(
+                        "Notes are empty; use `clix note clear` to remove them".to_string(),
+                    ));
+                }
+
+                storage.modify_command(&set_args.name, |command| command.notes = Some(text))?;
+                println!(
+                    "{} Notes saved for '{}'",
+                    "Success:".success(),
+                    set_args.name
+                );
+            }
+            NoteCommands::Clear(clear_args) => {
+                storage.modify_command(&clear_args.name, |command| command.notes = None)?;
+                println!(
+                    "{} Notes removed from '{}'",
+                    "Success:".success(),
+                    clear_args.name
+                );
+            }
+        },
+
+        Commands::Validate(validate_args) => {
+            let command = storage.get_command(&validate_args.name)?;
+            if !command.is_workflow() {
+                return Err(ClixError::InvalidInput(format!(
+                    "'{}' is a simple command, not a workflow",
+                    validate_args.name
+                )));
+            }
+
+            let snippets = SnippetStorage::new_with_dir(config_dir.clone())?.load()?;
+            let workflow = workflow_from_command(&command).with_includes_expanded(&snippets)?;
+
+            let validator = WorkflowValidator::new(storage.get_local_storage().clone());
+            let report = validator.validate_workflow(&workflow)?;
+
+            if validate_args.json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                print_validation_report(&report);
+            }
+
+            if !report.passes(&validate_args.fail_on) {
+                return Err(ClixError::ValidationError(format!(
+                    "Workflow '{}' has issues at or above {:?} severity",
+                    report.workflow_name, validate_args.fail_on
+                )));
+            }
+        }
+
+        Commands::CheckSteps(args) => {
+            let steps_json = fs::read_to_string(&args.file).map_err(ClixError::Io)?;
+            let steps: Vec<WorkflowStep> = serde_json::from_str(&steps_json).map_err(|e| {
+                ClixError::ValidationError(format!(
+                    "'{}' is not a valid steps file: {}",
+                    args.file, e
+                ))
+            })?;
+
+            let validator = WorkflowValidator::new(storage.get_local_storage().clone());
+            let report = validator.check_steps(&args.file, steps);
+
+            if args.json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                print_validation_report(&report);
+            }
+
+            if !report.is_valid {
+                return Err(ClixError::ValidationError(format!(
+                    "'{}' has errors",
+                    args.file
+                )));
+            }
+        }
+
+        Commands::Lint(lint_args) => {
+            let mut command = storage.get_command(&lint_args.name)?;
+            if !command.is_workflow() {
+                return Err(ClixError::InvalidInput(format!(
+                    "'{}' is a simple command, not a workflow",
+                    lint_args.name
+                )));
+            }
+
+            let validator = WorkflowValidator::new(storage.get_local_storage().clone());
+            let mut workflow = workflow_from_command(&command);
+            let fixed = validator.fix(&workflow);
+
+            if lint_args.fix {
+                for fix in &fixed.fixes {
+                    println!("{} {}", "Fixed:".success(), fix);
+                }
+                if !fixed.fixes.is_empty() {
+                    command.steps = Some(fixed.workflow.steps.clone());
+                    storage.update_command(&command)?;
+                }
+                workflow = fixed.workflow;
+            }
+
+            let report = validator.validate_workflow(&workflow)?;
+            print_validation_report(&report);
+
+            if !lint_args.fix && !fixed.fixes.is_empty() {
+                println!(
+                    "{} {} issue(s) can be fixed automatically with --fix",
+                    "Hint:".warning(),
+                    fixed.fixes.len()
+                );
+            }
+
+            if !report.is_valid {
+                return Err(ClixError::ValidationError(format!(
+                    "Workflow '{}' has errors that need fixing by hand",
+                    report.workflow_name
+                )));
+            }
+        }
+
+        Commands::ExportScript(script_args) => {
+            let command = storage.get_command(&script_args.name)?;
+            if !command.is_workflow() {
+                return Err(ClixError::InvalidInput(format!(
+                    "'{}' is a simple command, not a workflow",
+                    script_args.name
+                )));
+            }
+
+            let snippets = SnippetStorage::new_with_dir(config_dir.clone())?.load()?;
+            let workflow = workflow_from_command(&command).with_includes_expanded(&snippets)?;
+            workflow.dependency_order()?;
+
+            fs::write(&script_args.output, ScriptEmitter::emit(&workflow))?;
+
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                fs::set_permissions(&script_args.output, fs::Permissions::from_mode(0o755))?;
+            }
+
+            println!(
+                "{} Workflow exported as a script to: {}",
+                "Success:".success(),
+                script_args.output
+            );
+        }
+
+        Commands::Steps(args) => {
+            let command = storage.get_command(&args.name)?;
+            if !command.is_workflow() {
+                return Err(ClixError::InvalidInput(format!(
+                    "'{}' is a simple command, not a workflow",
+                    args.name
+                )));
+            }
+
+            let mut steps = workflow_from_command(&command).flatten_steps();
+            if !args.flatten {
+                steps.retain(|step| step.depth == 0);
+            }
+
+            if args.json {
+                println!("{}", serde_json::to_string_pretty(&steps)?);
+                return Ok(());
+            }
+
+            let mut table = Table::new(&["#", "Step", "Type", "Depth", "Parent"]);
+            for (index, step) in steps.iter().enumerate() {
+                let parent = match (&step.parent, &step.block) {
+                    (Some(parent), Some(block)) => format!("{} ({})", parent, block),
+                    _ => "-".to_string(),
+                };
+                table.add_row(vec![
+                    (index + 1).to_string(),
+                    format!("{}{}", "  ".repeat(step.depth), step.name),
+                    format!("{:?}", step.step_type),
+                    step.depth.to_string(),
+                    parent,
+                ]);
+            }
+            print!("{}", table.render());
+        }
+
+        Commands::SuggestParallel(args) => {
+            let command = storage.get_command(&args.name)?;
+            if !command.is_workflow() {
+                return Err(ClixError::InvalidInput(format!(
+                    "'{}' is a simple command, not a workflow",
+                    args.name
+                )));
+            }
+
+            let snippets = SnippetStorage::new_with_dir(config_dir.clone())?.load()?;
+            let workflow = workflow_from_command(&command).with_includes_expanded(&snippets)?;
+            let groups = ParallelAnalyzer::suggest(&workflow);
+
+            println!(
+                "{} {}",
+                "Proposed grouping for workflow:".header(),
+                workflow.name
+            );
+            for (index, group) in groups.iter().enumerate() {
+                let label = if group.is_parallel() {
+                    "in parallel".label().to_string()
+                } else {
+                    "alone".dimmed().to_string()
+                };
+                println!("  {}. {} ({})", index + 1, group.steps.join(", "), label);
+                if let Some(reason) = &group.reason {
+                    println!("     {} {}", "after the previous group:".dimmed(), reason);
+                }
+            }
+
+            if !groups.iter().any(ParallelGroup::is_parallel) {
+                println!("No adjacent steps look independent of each other.");
+            }
+            println!(
+                "{}",
+                "This is a heuristic reading of the step commands; check it before relying on it."
+                    .dimmed()
+            );
+        }
+
+        Commands::Export(export_args) => {
+            let export_manager = ExportManager::new(storage.get_local_storage().clone());
+
+            if !export_args.only.is_empty() {
+                let bundle =
+                    export_manager.bundle(&export_args.only, export_args.with_dependencies)?;
+                for (missing, referenced_by) in &bundle.unresolved {
+                    println!(
+                        "{} '{}' calls '{}', which isn't stored; it was left out of the export",
+                        "Warning:".warning(),
+                        referenced_by,
+                        missing
+                    );
+                }
+
+                let mut names: Vec<&String> = bundle
+                    .data
+                    .commands
+                    .iter()
+                    .flat_map(|commands| commands.keys())
+                    .chain(bundle.data.workflows.iter().flat_map(|w| w.keys()))
+                    .collect();
+                names.sort();
+
+                let json = match export_args.format {
+                    ExportFormat::Json => serde_json::to_string_pretty(&bundle.data)?,
+                    ExportFormat::Csv => bundle.data.to_csv(),
+                };
+                let destination = match &export_args.output {
+                    Some(output) => {
+                        fs::write(output, json)?;
+                        output.as_str()
+                    }
+                    None => {
+                        clipboard::write_text(&json)?;
+                        "the clipboard"
+                    }
+                };
+                println!(
+                    "{} Exported {} to: {}",
+                    "Success:".success(),
+                    names
+                        .iter()
+                        .map(|name| name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    destination
+                );
+                return Ok(());
             }
-        }
 
-        Commands::Export(export_args) => {
-            let export_manager = ExportManager::new(storage.get_local_storage().clone());
+            if export_args.format == ExportFormat::Csv {
+                let csv = export_manager.export_csv_to_string(
+                    export_args.tag,
+                    export_args.commands_only,
+                    export_args.workflows_only,
+                )?;
+                let destination = match &export_args.output {
+                    Some(output) => {
+                        fs::write(output, csv)?;
+                        output.as_str()
+                    }
+                    None => {
+                        clipboard::write_text(&csv)?;
+                        "the clipboard"
+                    }
+                };
+                println!(
+                    "{} Command inventory exported as CSV to: {}",
+                    "Success:".success(),
+                    destination
+                );
+                return Ok(());
+            }
 
-            export_manager.export_with_filter(
-                &export_args.output,
-                export_args.tag,
-                export_args.commands_only,
-                export_args.workflows_only,
-            )?;
+            let Some(output) = &export_args.output else {
+                let json = export_manager.export_to_string(
+                    export_args.tag,
+                    export_args.commands_only,
+                    export_args.workflows_only,
+                )?;
+                clipboard::write_text(&json)?;
+                println!(
+                    "{} Commands and workflows copied to the clipboard",
+                    "Success:".success()
+                );
+                return Ok(());
+            };
+
+            if export_args.only_changed {
+                export_manager.export_only_changed(
+                    output,
+                    export_args.tag,
+                    export_args.commands_only,
+                    export_args.workflows_only,
+                )?;
+            } else {
+                export_manager.export_with_filter(
+                    output,
+                    export_args.tag,
+                    export_args.commands_only,
+                    export_args.workflows_only,
+                )?;
+            }
 
             println!(
                 "{} Commands and workflows exported to: {}",
-                "Success:".green().bold(),
-                export_args.output
+                "Success:".success(),
+                output
             );
         }
 
         Commands::Ask(ask_args) => {
-            // Load settings
-            let settings_manager = SettingsManager::new()?;
-            let settings = settings_manager.load()?;
-
-            // Initialize Claude Assistant and conversation storage
-            let assistant = ClaudeAssistant::new(settings)?;
-            let conversation_storage = ConversationStorage::new()?;
-
             // Get all commands and workflows for context
             let commands = storage.list_commands()?;
             let workflows = storage.list_workflows()?;
@@ -601,6 +2044,34 @@ fn run() -> Result<()> {
             let command_refs: Vec<&Command> = commands.iter().collect();
             let workflow_refs: Vec<&Workflow> = workflows.iter().collect();
 
+            if ask_args.dry_run {
+                print!(
+                    "{}",
+                    ClaudeAssistant::preview_prompt(
+                        &ask_args.question,
+                        &command_refs,
+                        &workflow_refs
+                    )
+                );
+                return Ok(());
+            }
+
+            // Load settings
+            let settings_manager = SettingsManager::new_with_dir(config_dir.clone())?;
+            let settings = settings_manager.load()?;
+
+            // Initialize Claude Assistant and conversation storage
+            let cache_ttl = Duration::from_secs(settings.ai_settings.cache_ttl_secs);
+            let mut assistant = ClaudeAssistant::new(settings)?;
+            if let Some(model) = &ask_args.model {
+                assistant = assistant.with_model_override(model.clone());
+            }
+            if !ask_args.no_cache {
+                assistant = assistant
+                    .with_cache(ResponseCache::new(config_dir.join("ai_cache"), cache_ttl));
+            }
+            let conversation_storage = ConversationStorage::new_with_dir(config_dir.clone())?;
+
             // Handle interactive mode or session continuation
             if ask_args.interactive || ask_args.session.is_some() {
                 handle_conversational_ask(
@@ -615,6 +2086,7 @@ fn run() -> Result<()> {
                 // Handle single-shot ask (legacy behavior)
                 handle_single_ask(
                     &ask_args.question,
+                    ActionFilter::from_flags(ask_args.create_only, ask_args.run_only),
                     &assistant,
                     &storage,
                     command_refs,
@@ -624,34 +2096,39 @@ fn run() -> Result<()> {
         }
 
         Commands::Settings(settings_cmd) => {
-            let settings_manager = SettingsManager::new()?;
+            let settings_manager = SettingsManager::new_with_dir(config_dir.clone())?;
 
             match settings_cmd {
                 SettingsCommands::List => {
                     let settings = settings_manager.load()?;
 
-                    println!("{}", "Current Settings:".blue().bold());
+                    println!("{}", "Current Settings:".header());
                     println!("{}", "=".repeat(50));
-                    println!("{}: {}", "AI Model".green().bold(), settings.ai_model);
+                    println!("{}: {}", "AI Model".success(), settings.ai_model);
                     println!(
                         "{}: {}",
-                        "AI Temperature".green().bold(),
+                        "AI Temperature".success(),
                         settings.ai_settings.temperature
                     );
                     println!(
                         "{}: {}",
-                        "AI Max Tokens".green().bold(),
+                        "AI Max Tokens".success(),
                         settings.ai_settings.max_tokens
                     );
+
+                    if !settings.workflow_var_defaults.is_empty() {
+                        println!("{}:", "Workflow Variable Defaults".success());
+                        let mut defaults: Vec<_> = settings.workflow_var_defaults.iter().collect();
+                        defaults.sort();
+                        for (name, value) in defaults {
+                            println!("  {} = {}", name, value);
+                        }
+                    }
                 }
 
                 SettingsCommands::SetAiModel(args) => {
                     settings_manager.update_ai_model(&args.model)?;
-                    println!(
-                        "{} AI model set to: {}",
-                        "Success:".green().bold(),
-                        args.model
-                    );
+                    println!("{} AI model set to: {}", "Success:".success(), args.model);
                 }
 
                 SettingsCommands::ListAiModels => {
@@ -661,11 +2138,11 @@ fn run() -> Result<()> {
                     // Initialize Claude Assistant
                     let assistant = ClaudeAssistant::new(settings)?;
 
-                    println!("{} Fetching available models...", "Info:".blue().bold());
+                    println!("{} Fetching available models...", "Info:".header());
 
                     match assistant.list_models() {
                         Ok(models) => {
-                            println!("{}", "Available AI Models:".blue().bold());
+                            println!("{}", "Available AI Models:".header());
                             println!("{}", "=".repeat(50));
 
                             for model in models {
@@ -673,10 +2150,10 @@ fn run() -> Result<()> {
                             }
                         }
                         Err(e) => {
-                            eprintln!("{} Failed to fetch models: {}", "Error:".red().bold(), e);
+                            eprintln!("{} Failed to fetch models: {}", "Error:".error(), e);
                             eprintln!(
                                 "{} Make sure your Anthropic API key is set correctly.",
-                                "Hint:".yellow().bold()
+                                "Hint:".warning()
                             );
                         }
                     }
@@ -692,7 +2169,7 @@ fn run() -> Result<()> {
                     settings_manager.update_ai_temperature(args.temperature)?;
                     println!(
                         "{} AI temperature set to: {}",
-                        "Success:".green().bold(),
+                        "Success:".success(),
                         args.temperature
                     );
                 }
@@ -701,65 +2178,175 @@ fn run() -> Result<()> {
                     settings_manager.update_ai_max_tokens(args.max_tokens)?;
                     println!(
                         "{} AI max tokens set to: {}",
-                        "Success:".green().bold(),
+                        "Success:".success(),
                         args.max_tokens
                     );
                 }
+
+                SettingsCommands::SetSecret(args) => {
+                    check_secret_name(&args.name)?;
+                    let value = read_secret(&format!("Enter value for {}: ", args.name))?;
+                    if value.is_empty() {
+                        return Err(ClixError::InvalidInput(
+                            "Secret value must not be empty".to_string(),
+                        ));
+                    }
+
+                    KeychainProvider.set_secret(&args.name, &value)?;
+                    println!(
+                        "{} Stored {} in the OS keychain",
+                        "Success:".success(),
+                        args.name
+                    );
+                }
+
+                SettingsCommands::SetVarDefault(args) => {
+                    let (name, value) = args.assignment.split_once('=').ok_or_else(|| {
+                        ClixError::InvalidCommandFormat(format!(
+                            "Invalid variable format: {}, expected key=value",
+                            args.assignment
+                        ))
+                    })?;
+
+                    settings_manager.set_workflow_var_default(name, value)?;
+                    println!(
+                        "{} Default for {} set to: {}",
+                        "Success:".success(),
+                        name,
+                        value
+                    );
+                }
+
+                SettingsCommands::Get(args) => match settings_manager.get_value(&args.key)? {
+                    serde_json::Value::String(value) => println!("{}", value),
+                    value => println!("{}", serde_json::to_string_pretty(&value)?),
+                },
+
+                SettingsCommands::Reset(args) => {
+                    let scope = match args.section {
+                        SettingsSection::Ai => "AI settings",
+                        SettingsSection::Git => "git settings",
+                        SettingsSection::All => "all settings",
+                    };
+                    if !args.yes && !confirm(&format!("Reset {} to their defaults?", scope))? {
+                        println!("Cancelled.");
+                        return Ok(());
+                    }
+
+                    settings_manager.reset(args.section)?;
+                    println!("{} Reset {} to defaults", "Success:".success(), scope);
+                }
+
+                SettingsCommands::Set(args) => {
+                    let value = settings_manager.set_value(&args.key, &args.value)?;
+                    println!(
+                        "{} {} set to: {}",
+                        "Success:".success(),
+                        args.key,
+                        display_setting(&value)?
+                    );
+                }
             }
         }
 
         Commands::Import(import_args) => {
             let import_manager = ImportManager::new(storage.get_local_storage().clone());
 
-            let summary =
-                import_manager.import_from_file(&import_args.input, import_args.overwrite)?;
+            let overwrite = import_args.overwrite;
+            let prompt = import_args.interactive && !import_args.yes;
+            let resolve = |conflict: &ImportConflict| {
+                if prompt {
+                    prompt_conflict_resolution(conflict)
+                } else if overwrite {
+                    Ok(ConflictResolution::Overwrite)
+                } else {
+                    Ok(ConflictResolution::Skip)
+                }
+            };
 
-            println!(
-                "{} Import completed from: {}",
-                "Success:".green().bold(),
-                import_args.input
-            );
+            let (summary, source) = match &import_args.input {
+                Some(input) => (
+                    import_manager.import_from_file_resolving(input, resolve)?,
+                    input.as_str(),
+                ),
+                None => (
+                    import_manager.import_from_str_resolving(&clipboard::read_text()?, resolve)?,
+                    "the clipboard",
+                ),
+            };
 
-            println!("\n{}", "Import Summary:".blue().bold());
+            println!("{} Import completed from: {}", "Success:".success(), source);
+
+            println!("\n{}", "Import Summary:".header());
             println!("{}", "=".repeat(50));
-            println!("{}: {}", "Commands Added".green(), summary.commands_added);
+            println!("{}: {}", "Commands Added".label(), summary.commands_added);
             println!(
                 "{}: {}",
-                "Commands Updated".green(),
+                "Commands Updated".label(),
                 summary.commands_updated
             );
             println!(
                 "{}: {}",
-                "Commands Skipped".green(),
+                "Commands Skipped".label(),
                 summary.commands_skipped
             );
-            println!("{}: {}", "Workflows Added".green(), summary.workflows_added);
+            println!("{}: {}", "Workflows Added".label(), summary.workflows_added);
             println!(
                 "{}: {}",
-                "Workflows Updated".green(),
+                "Workflows Updated".label(),
                 summary.workflows_updated
             );
             println!(
                 "{}: {}",
-                "Workflows Skipped".green(),
+                "Workflows Skipped".label(),
                 summary.workflows_skipped
             );
+            if !summary.decisions.is_empty() {
+                println!("{}", "-".repeat(50));
+                for decision in &summary.decisions {
+                    println!(
+                        "{} '{}': {}",
+                        decision.kind.to_string().note(),
+                        decision.name,
+                        decision.resolution
+                    );
+                }
+            }
             println!("{}", "-".repeat(50));
             println!(
                 "{}: {}",
-                "Exported By".green(),
+                "Exported By".label(),
                 summary.metadata.exported_by
             );
             println!(
                 "{}: {}",
-                "Export Description".green(),
+                "Export Description".label(),
                 summary.metadata.description
             );
         }
 
+        Commands::Doctor(_) => unreachable!("doctor runs before storage is opened"),
+
         Commands::Completions(completions_args) => {
-            let mut app = CliArgs::command();
+            if completions_args.list_shells {
+                for name in Shell::names() {
+                    println!("{}", name);
+                }
+                return Ok(());
+            }
+
             let shell = match completions_args.shell {
+                Some(shell) => shell,
+                None => Shell::detect(|name| std::env::var(name).ok()).ok_or_else(|| {
+                    ClixError::InvalidInput(format!(
+                        "Could not detect your shell from $SHELL; pass one of: {}",
+                        Shell::names().join(", ")
+                    ))
+                })?,
+            };
+
+            let mut app = CliArgs::command();
+            let generator = match shell {
                 Shell::Bash => CompletionShell::Bash,
                 Shell::Zsh => CompletionShell::Zsh,
                 Shell::Fish => CompletionShell::Fish,
@@ -767,8 +2354,13 @@ fn run() -> Result<()> {
                 Shell::Elvish => CompletionShell::Elvish,
             };
 
-            println!("# Generating shell completions for {:?}", shell);
-            generate(shell, &mut app, "clix", &mut io::stdout());
+            println!("# Generating shell completions for {:?}", generator);
+            let mut script = Vec::new();
+            generate(generator, &mut app, "clix", &mut script);
+            print!(
+                "{}",
+                shell.with_tag_completion(String::from_utf8_lossy(&script).into_owned())
+            );
         }
 
         Commands::Git(git_command) => match git_command {
@@ -779,7 +2371,7 @@ fn run() -> Result<()> {
 
                 println!(
                     "{} Repository '{}' added and cloned successfully",
-                    "Success:".green().bold(),
+                    "Success:".success(),
                     add_repo_args.name
                 );
 
@@ -794,7 +2386,7 @@ fn run() -> Result<()> {
 
                 println!(
                     "{} Repository '{}' removed successfully",
-                    "Success:".green().bold(),
+                    "Success:".success(),
                     remove_repo_args.name
                 );
             }
@@ -809,25 +2401,25 @@ fn run() -> Result<()> {
                     return Ok(());
                 }
 
-                println!("{}", "Configured Git Repositories:".blue().bold());
+                println!("{}", "Configured Git Repositories:".header());
                 println!("{}", "=".repeat(50));
 
                 for repo in repos {
-                    println!("{}: {}", "Name".green().bold(), repo.name);
-                    println!("{}: {}", "URL".green(), repo.url);
+                    println!("{}: {}", "Name".success(), repo.name);
+                    println!("{}: {}", "URL".label(), repo.url);
                     println!(
                         "{}: {}",
-                        "Enabled".green(),
+                        "Enabled".label(),
                         if repo.enabled { "✓" } else { "✗" }
                     );
 
                     // Check if repository is cloned
                     if let Some(git_repo) = git_manager.get_repository(&repo.name) {
                         if git_repo.is_cloned() {
-                            println!("{}: ✓ Cloned", "Status".green());
-                            println!("{}: {}", "Path".green(), git_repo.get_repo_path().display());
+                            println!("{}: ✓ Cloned", "Status".label());
+                            println!("{}: {}", "Path".label(), git_repo.get_repo_path().display());
                         } else {
-                            println!("{}: ✗ Not cloned", "Status".yellow());
+                            println!("{}: ✗ Not cloned", "Status".note());
                         }
                     }
 
@@ -836,12 +2428,12 @@ fn run() -> Result<()> {
             }
 
             GitCommands::Pull => {
-                println!("{} Pulling from all repositories...", "Info:".blue().bold());
+                println!("{} Pulling from all repositories...", "Info:".header());
 
                 let git_manager = storage.get_git_manager();
                 let results = git_manager.pull_all_repositories()?;
 
-                println!("\n{}", "Pull Results:".blue().bold());
+                println!("\n{}", "Pull Results:".header());
                 println!("{}", "=".repeat(50));
 
                 for (repo_name, result) in results {
@@ -855,23 +2447,23 @@ fn run() -> Result<()> {
                 storage.load_from_repositories()?;
                 println!(
                     "\n{} Local commands updated with repository changes",
-                    "Success:".green().bold()
+                    "Success:".success()
                 );
             }
 
             GitCommands::Status => {
-                println!("{} Checking repository status...", "Info:".blue().bold());
+                println!("{} Checking repository status...", "Info:".header());
 
                 // Pull first
                 let git_manager = storage.get_git_manager();
                 let pull_results = git_manager.pull_all_repositories()?;
 
-                println!("\n{}", "Repository Status:".blue().bold());
+                println!("\n{}", "Repository Status:".header());
                 println!("{}", "=".repeat(50));
 
                 let repos = git_manager.list_repositories();
                 for repo in repos {
-                    println!("{}: {}", "Repository".green().bold(), repo.name);
+                    println!("{}: {}", "Repository".success(), repo.name);
 
                     if let Some(git_repo) = git_manager.get_repository(&repo.name) {
                         if git_repo.is_cloned() {
@@ -895,35 +2487,648 @@ fn run() -> Result<()> {
                 // Load changes after status check
                 storage.load_from_repositories()?;
             }
+
+            GitCommands::Sync(sync_args) => {
+                match &sync_args.repo {
+                    Some(repo) => {
+                        println!("{} Syncing repository {}...", "Info:".header(), repo)
+                    }
+                    None => println!("{} Syncing all repositories...", "Info:".header()),
+                }
+
+                let results = storage.sync_repositories(sync_args.repo.as_deref())?;
+
+                println!("\n{}", "Sync Results:".header());
+                println!("{}", "=".repeat(50));
+
+                let mut failures = 0;
+                for (repo_name, result) in results {
+                    match result {
+                        Ok(()) => println!("✓ {}: Synced", repo_name),
+                        Err(e) => {
+                            failures += 1;
+                            println!("✗ {}: Failed - {}", repo_name, e);
+                        }
+                    }
+                }
+
+                if failures == 0 {
+                    println!(
+                        "\n{} Local commands updated with repository changes",
+                        "Success:".success()
+                    );
+                } else {
+                    println!(
+                        "\n{} Loaded local checkouts, but {} repositories failed to pull",
+                        "Warning:".warning(),
+                        failures
+                    );
+                }
+            }
         },
     }
 
     Ok(())
 }
 
+fn print_validation_report(report: &ValidationReport) {
+    if report.issues.is_empty() {
+        println!("{} No issues found", "Success:".success());
+        return;
+    }
+
+    println!("{} {}", "Validation Report:".header(), report.workflow_name);
+    println!("{}", "=".repeat(50));
+
+    for issue in &report.issues {
+        let label = match issue.severity {
+            Severity::Error => "Error:".error(),
+            Severity::Warning => "Warning:".warning(),
+            Severity::Info => "Info:".header(),
+        };
+        println!("{} {}", label, issue.message);
+        if let Some(suggestion) = &issue.suggestion {
+            println!("  {} {}", "Suggestion:".label(), suggestion);
+        }
+    }
+}
+
+/// Describe when an item was last used, in whole days
+fn format_last_used(last_used: Option<u64>) -> String {
+    let Some(last_used) = last_used else {
+        return "never".to_string();
+    };
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    match now.saturating_sub(last_used) / (60 * 60 * 24) {
+        0 => "today".to_string(),
+        1 => "1 day ago".to_string(),
+        days => format!("{} days ago", days),
+    }
+}
+
+/// Build a runnable workflow from a stored workflow command
+fn workflow_from_command(command: &Command) -> Workflow {
+    let mut workflow = Workflow::new(
+        command.name.clone(),
+        command.description.clone(),
+        command.steps.clone().unwrap_or_default(),
+        command.tags.clone(),
+    );
+
+    // Add variables and profiles from the command
+    workflow.variables = command.variables.clone();
+    workflow.profiles = command.profiles.clone();
+    workflow.default_continue_on_error = command.default_continue_on_error;
+    workflow.strict_vars = command.strict_vars;
+    workflow.reject_unsafe_vars = command.reject_unsafe_vars;
+    workflow.single_instance = command.lock;
+    workflow.author = command.author.clone();
+    workflow.version = command.version.clone();
+    workflow.changelog = command.changelog.clone();
+    workflow.notes = command.notes.clone();
+    workflow
+}
+
+/// Print the author, version and latest changelog note, where set
+fn print_metadata(author: Option<&str>, version: Option<&str>, changelog: &[String]) {
+    if let Some(author) = author {
+        println!("{}: {}", "Author".label(), author);
+    }
+    if let Some(version) = version {
+        println!("{}: {}", "Version".label(), version);
+    }
+    if let Some(latest) = changelog.last() {
+        println!(
+            "{}: {} ({} note(s))",
+            "Latest change".label(),
+            latest,
+            changelog.len()
+        );
+    }
+}
+
+/// Print notes under a heading, indented so multi-line notes stay readable
+fn print_notes(notes: Option<&str>) {
+    if let Some(notes) = notes {
+        println!("{}:", "Notes".label());
+        for line in notes.lines() {
+            println!("  {}", line);
+        }
+    }
+}
+
+fn print_workflow_results(results: Vec<(String, Result<std::process::Output>)>) {
+    println!("\n{}", "Workflow Results:".header());
+    println!("{}", "=".repeat(50));
+
+    for (name, result) in results {
+        println!("{}: {}", "Step".success(), name);
+
+        match result {
+            Ok(output) => CommandExecutor::print_command_output(&output),
+            Err(e @ ClixError::StepSkipped(_)) => println!("{}", e.to_string().note()),
+            Err(e) => println!("{} {}", "Error:".error(), e),
+        }
+
+        println!("{}", "-".repeat(50));
+    }
+}
+
+/// Print one line per step with its status and duration, leaving out its output
+fn print_workflow_summary(reports: &[StepReport]) {
+    println!("\n{}", "Workflow Summary:".header());
+
+    let mut table = Table::new(&["Step", "Status", "Duration"]);
+    for report in reports {
+        let status = match report.exit_code {
+            _ if report.skipped => "skipped".to_string(),
+            Some(0) => "ok".to_string(),
+            Some(code) => format!("failed (exit {})", code),
+            None => "failed".to_string(),
+        };
+        table.add_row(vec![
+            report.step.clone(),
+            status,
+            format!("{:.1}s", report.duration_ms as f64 / 1000.0),
+        ]);
+    }
+    print!("{}", table.render());
+
+    let skipped = reports.iter().filter(|r| r.skipped).count();
+    let failed = reports
+        .iter()
+        .filter(|r| !r.skipped && r.exit_code != Some(0))
+        .count();
+    if failed == 0 {
+        println!(
+            "{} {} step(s) succeeded",
+            "Success:".success(),
+            reports.len() - skipped
+        );
+    } else {
+        println!(
+            "{} {} of {} step(s) failed",
+            "Error:".error(),
+            failed,
+            reports.len()
+        );
+    }
+    if skipped > 0 {
+        println!("{} {} step(s) skipped", "Note:".warning(), skipped);
+    }
+}
+
+/// Build a JUnit report from a workflow's step results, taking each step's
+/// duration from its report
+fn junit_report(
+    workflow: &str,
+    results: &[(String, Result<std::process::Output>)],
+    reports: &[StepReport],
+) -> JunitReport {
+    let cases = results
+        .iter()
+        .map(|(name, result)| {
+            let duration_ms = reports
+                .iter()
+                .find(|report| &report.step == name)
+                .map_or(0, |report| report.duration_ms);
+            CommandResult::from_result(name, result, Duration::from_millis(duration_ms))
+        })
+        .collect();
+    JunitReport::new(workflow, cases)
+}
+
+/// Run a workflow, print its results and record the outcome in the history
+fn run_workflow_recorded(
+    workflow: &Workflow,
+    options: &ExecutionOptions,
+    history: &HistoryStorage,
+) -> Result<bool> {
+    let started = Instant::now();
+    let results = CommandExecutor::execute_workflow_with_options(workflow, None, None, options);
+    let success = results
+        .as_ref()
+        .is_ok_and(|results| all_steps_succeeded(results));
+    history.record(&execution_record(
+        &workflow.name,
+        ExecutionKind::Workflow,
+        started,
+        success,
+    ))?;
+
+    match results {
+        Ok(results) => print_workflow_results(results),
+        Err(e) => println!("{} {}", "Error:".error(), e),
+    }
+    Ok(success)
+}
+
+/// Run a stored command or workflow, recording the outcome in the history
+fn run_command_recorded(
+    command: &Command,
+    options: &ExecutionOptions,
+    history: &HistoryStorage,
+    locks: &LockStorage,
+) -> Result<bool> {
+    // A command locked by another run counts as a failed run rather than aborting the batch.
+    // Workflows take their lock in the executor.
+    let _lock = match (command.lock && !command.is_workflow())
+        .then(|| locks.acquire(&command.name))
+        .transpose()
+    {
+        Ok(lock) => lock,
+        Err(e) => {
+            println!("{} {}", "Error:".error(), e);
+            return Ok(false);
+        }
+    };
+
+    if command.is_workflow() {
+        return run_workflow_recorded(&workflow_from_command(command), options, history);
+    }
+
+    let started = Instant::now();
+    let result = CommandExecutor::execute_command_result(command, options);
+    let success = result.as_ref().is_ok_and(|result| result.success);
+    history.record(&execution_record(
+        &command.name,
+        ExecutionKind::Command,
+        started,
+        success,
+    ))?;
+
+    match result {
+        Ok(result) => CommandExecutor::print_command_result(&result),
+        Err(e) => println!("{} {}", "Error:".error(), e),
+    }
+    Ok(success)
+}
+
+/// Print the environment changes a command made, masking secret-looking values
+fn print_env_diff(diff: &EnvDiff) {
+    println!("\n{}", "Environment changes:".header());
+    if diff.is_empty() {
+        println!("  (none)");
+        return;
+    }
+
+    let shown = |name: &str, value: &str| {
+        if WorkflowContext::is_secret_name(name) {
+            "********".to_string()
+        } else {
+            value.to_string()
+        }
+    };
+
+    for (name, value) in &diff.added {
+        println!("  {} {}={}", "+".success(), name, shown(name, value));
+    }
+    for (name, old, new) in &diff.changed {
+        println!(
+            "  {} {}: {} -> {}",
+            "~".warning(),
+            name,
+            shown(name, old),
+            shown(name, new)
+        );
+    }
+    for name in &diff.removed {
+        println!("  {} {}", "-".error(), name);
+    }
+}
+
+/// Current UTC wall-clock time as HH:MM:SS
+/// Split a `--env` value into a variable name and value
+fn parse_env_assignment(assignment: &str) -> Result<(String, String)> {
+    let (key, value) = assignment.split_once('=').ok_or_else(|| {
+        ClixError::InvalidCommandFormat(format!(
+            "Invalid environment variable format: {}, expected KEY=VALUE",
+            assignment
+        ))
+    })?;
+
+    let mut chars = key.chars();
+    let valid_name = chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_');
+    if !valid_name {
+        return Err(ClixError::InvalidCommandFormat(format!(
+            "Invalid environment variable name: '{}'",
+            key
+        )));
+    }
+
+    Ok((key.to_string(), value.to_string()))
+}
+
+/// Open a folder with the platform's file manager
+fn reveal_in_file_manager(folder: &Path) -> Result<()> {
+    let opener = if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(windows) {
+        "explorer"
+    } else {
+        "xdg-open"
+    };
+
+    std::process::Command::new(opener)
+        .arg(folder)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| ClixError::CommandExecutionFailed(format!("Failed to run {}: {}", opener, e)))
+}
+
+/// Let the user edit `initial` in $VISUAL or $EDITOR (falling back to vi)
+/// and return what they saved. The editor value may carry arguments, such
+/// as `code --wait`.
+fn edit_in_editor(initial: &str) -> Result<String> {
+    let editor = std::env::var("VISUAL")
+        .ok()
+        .filter(|editor| !editor.trim().is_empty())
+        .or_else(|| std::env::var("EDITOR").ok())
+        .filter(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string());
+
+    let path = std::env::temp_dir().join(format!("clix-note-{}.md", std::process::id()));
+    fs::write(&path, initial)?;
+
+    let status = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$1\"", editor))
+        .arg("sh")
+        .arg(&path)
+        .status();
+    let edited = match status {
+        Ok(status) if status.success() => fs::read_to_string(&path).map_err(ClixError::Io),
+        Ok(status) => Err(ClixError::CommandExecutionFailed(format!(
+            "Editor '{}' exited with {}",
+            editor, status
+        ))),
+        Err(e) => Err(ClixError::CommandExecutionFailed(format!(
+            "Failed to run {}: {}",
+            editor, e
+        ))),
+    };
+    let _ = fs::remove_file(&path);
+    edited
+}
+
+fn utc_clock() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        % 86_400;
+    format!(
+        "{:02}:{:02}:{:02} UTC",
+        secs / 3600,
+        (secs / 60) % 60,
+        secs % 60
+    )
+}
+
+/// Ask on stdin what to do with an imported item whose name is taken,
+/// defaulting to skip (also when stdin is closed)
+fn prompt_conflict_resolution(conflict: &ImportConflict) -> Result<ConflictResolution> {
+    loop {
+        print!(
+            "{} '{}' already exists. [s]kip, [o]verwrite, [m]erge, [r]ename? [s] ",
+            conflict.kind, conflict.name
+        );
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input)? == 0 {
+            println!();
+            return Ok(ConflictResolution::Skip);
+        }
+        match input.trim().to_lowercase().as_str() {
+            "" | "s" | "skip" => return Ok(ConflictResolution::Skip),
+            "o" | "overwrite" => return Ok(ConflictResolution::Overwrite),
+            "m" | "merge" => return Ok(ConflictResolution::Merge),
+            "r" | "rename" => {
+                print!("New name [{}]: ", conflict.suggested_name);
+                io::stdout().flush()?;
+                let mut name = String::new();
+                io::stdin().read_line(&mut name)?;
+                let name = name.trim();
+                let name = if name.is_empty() {
+                    conflict.suggested_name.clone()
+                } else {
+                    name.to_string()
+                };
+                return Ok(ConflictResolution::Rename(name));
+            }
+            other => println!("Unknown choice '{}'", other),
+        }
+    }
+}
+
+/// Report problems with the data directory, repairing them with `--fix`
+fn run_doctor(config_dir: &Path, args: &DoctorArgs) -> Result<()> {
+    let problems = doctor::diagnose(config_dir);
+    if problems.is_empty() {
+        println!("{} No problems found.", "Success:".success());
+        return Ok(());
+    }
+
+    let mut fixed = 0;
+    for problem in &problems {
+        println!("{} {}", "Problem:".warning(), problem.describe());
+        if !args.fix || !(args.yes || confirm(&format!("{}?", problem.fix_description()))?) {
+            continue;
+        }
+        match problem.fix(config_dir) {
+            Ok(()) => {
+                fixed += 1;
+                println!("{} {}", "Fixed:".success(), problem.fix_description());
+            }
+            Err(e) => println!("{} {}", "Error:".error(), e),
+        }
+    }
+
+    if args.fix {
+        println!("\nFixed {} of {} problem(s)", fixed, problems.len());
+    }
+    if fixed < problems.len() {
+        return Err(ClixError::ValidationError(format!(
+            "{} problem(s) left unfixed",
+            problems.len() - fixed
+        )));
+    }
+    Ok(())
+}
+
+/// Ask a yes/no question on stdin, defaulting to no
+fn confirm(prompt: &str) -> Result<bool> {
+    print!("{} [y/N] ", prompt);
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let input = input.trim().to_lowercase();
+    Ok(input == "y" || input == "yes")
+}
+
+/// Read a command body for `add --command-stdin`. Lines are kept as given;
+/// only the final line break a heredoc or `echo` adds is dropped.
+fn read_command_from_stdin() -> Result<String> {
+    let mut command = io::read_to_string(io::stdin())?;
+    if command.ends_with('\n') {
+        command.pop();
+        if command.ends_with('\r') {
+            command.pop();
+        }
+    }
+    if command.trim().is_empty() {
+        return Err(ClixError::InvalidCommandFormat(
+            "No command was given on stdin".to_string(),
+        ));
+    }
+    Ok(command)
+}
+
+/// A run succeeded only if every executed step exited cleanly
+/// Read `--input-json`: a JSON object of variable values, given inline or on
+/// stdin as `-`. Numbers and booleans are taken as their text.
+fn parse_input_json(input: &str) -> Result<HashMap<String, String>> {
+    let text = if input == "-" {
+        io::read_to_string(io::stdin())?
+    } else {
+        input.to_string()
+    };
+
+    let value: serde_json::Value = serde_json::from_str(&text)
+        .map_err(|e| ClixError::InvalidInput(format!("--input-json is not valid JSON: {}", e)))?;
+    let serde_json::Value::Object(object) = value else {
+        return Err(ClixError::InvalidInput(
+            "--input-json must be a JSON object of variable names to values".to_string(),
+        ));
+    };
+
+    object
+        .into_iter()
+        .map(|(name, value)| match value {
+            serde_json::Value::String(text) => Ok((name, text)),
+            serde_json::Value::Number(_) | serde_json::Value::Bool(_) => {
+                Ok((name, value.to_string()))
+            }
+            _ => Err(ClixError::InvalidInput(format!(
+                "--input-json value for '{}' must be a string, number or boolean",
+                name
+            ))),
+        })
+        .collect()
+}
+
+/// Whether a step was stopped by a denied approval or declined confirmation
+/// rather than by failing
+fn was_refused(result: &Result<std::process::Output>) -> bool {
+    matches!(
+        result,
+        Err(ClixError::SecurityError(_) | ClixError::WorkflowAborted(_))
+    )
+}
+
+/// Whether no step failed; skipped steps don't count against the run
+fn all_steps_succeeded(results: &[(String, Result<std::process::Output>)]) -> bool {
+    results.iter().all(|(_, result)| {
+        CommandExecutor::was_skipped(result)
+            || result.as_ref().is_ok_and(|output| output.status.success())
+    })
+}
+
+fn execution_record(
+    name: &str,
+    kind: ExecutionKind,
+    started: Instant,
+    success: bool,
+) -> ExecutionRecord {
+    ExecutionRecord {
+        name: name.to_string(),
+        kind,
+        started_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+            .saturating_sub(started.elapsed().as_secs()),
+        duration_ms: started.elapsed().as_millis() as u64,
+        success,
+        allowed_dangerous: false,
+        environment: None,
+    }
+}
+
 fn handle_single_ask(
     question: &str,
+    filter: ActionFilter,
     assistant: &ClaudeAssistant,
     storage: &GitIntegratedStorage,
     command_refs: Vec<&Command>,
     workflow_refs: Vec<&Workflow>,
 ) -> Result<()> {
     // Format question and get response
-    println!("{} {}", "Question:".green().bold(), question);
+    println!("{} {}", "Question:".success(), question);
 
     // Ask Claude (legacy single-shot mode)
     let (response, action) = assistant.ask(question, command_refs, workflow_refs)?;
 
     // Print Claude's response
-    println!("{}", "\nClaude's Response:".blue().bold());
+    println!("{}", "\nClaude's Response:".header());
     println!("{}", response);
 
     // Handle suggested action
-    execute_claude_action(action, assistant, storage)?;
+    execute_claude_action(action, filter, assistant, storage)?;
 
     Ok(())
 }
 
+/// Ask the assistant how to do what an unknown command name describes and
+/// offer to create what it suggests. Returns false, without asking, when the
+/// `execution_settings.ai_fallback` setting is off.
+fn ask_about_unknown_command(
+    name: &str,
+    enabled: bool,
+    storage: &GitIntegratedStorage,
+    config_dir: &Path,
+) -> Result<bool> {
+    let commands = storage.list_commands()?;
+    let workflows = storage.list_workflows()?;
+    let command_refs: Vec<&Command> = commands.iter().collect();
+    let workflow_refs: Vec<&Workflow> = workflows.iter().collect();
+
+    let settings = SettingsManager::new_with_dir(config_dir.to_path_buf())?.load()?;
+    let cache_ttl = Duration::from_secs(settings.ai_settings.cache_ttl_secs);
+    let mut assistant = None;
+
+    let answer = fallback::ask_about_unknown(name, enabled, |question| {
+        println!(
+            "{} No command named '{}'; asking the assistant",
+            "Info:".header(),
+            name
+        );
+        println!("{} {}", "Question:".success(), question);
+        let assistant = assistant.insert(
+            ClaudeAssistant::new(settings)?
+                .with_cache(ResponseCache::new(config_dir.join("ai_cache"), cache_ttl)),
+        );
+        assistant.ask(question, command_refs, workflow_refs)
+    })?;
+
+    let (Some((response, action)), Some(assistant)) = (answer, &assistant) else {
+        return Ok(false);
+    };
+    println!("{}", "\nClaude's Response:".header());
+    println!("{}", response);
+    execute_claude_action(action, ActionFilter::All, assistant, storage)?;
+    Ok(true)
+}
+
 fn handle_conversational_ask(
     ask_args: clix::cli::app::AskArgs,
     assistant: &ClaudeAssistant,
@@ -938,7 +3143,7 @@ fn handle_conversational_ask(
             Some(session) => {
                 println!(
                     "{} Continuing conversation session: {}",
-                    "Info:".blue().bold(),
+                    "Info:".header(),
                     session_id
                 );
                 session
@@ -956,14 +3161,14 @@ fn handle_conversational_ask(
             ConversationSession::with_context(command_refs.clone(), workflow_refs.clone());
         println!(
             "{} Started new conversation session: {}",
-            "Info:".blue().bold(),
+            "Info:".header(),
             session.id
         );
 
         if ask_args.interactive {
             println!(
                 "{} Interactive mode enabled. Type 'exit' or 'quit' to end the conversation.",
-                "Info:".yellow().bold()
+                "Info:".warning()
             );
         }
 
@@ -976,10 +3181,10 @@ fn handle_conversational_ask(
 
     // Main conversation loop
     loop {
-        println!("{} {}", "Question:".green().bold(), current_question);
+        println!("{} {}", "Question:".success(), current_question);
 
         // Ask Claude in conversational mode
-        let (response, action) = assistant.ask_conversational(
+        let (response, mut action) = assistant.ask_conversational(
             &current_question,
             &session,
             command_refs.clone(),
@@ -989,12 +3194,18 @@ fn handle_conversational_ask(
         // Add Claude's response to session
         session.add_message(MessageRole::Assistant, response.clone());
 
+        // A workflow built up over several turns is offered once it is complete
+        if let Some(workflow) = session.track_workflow_creation(&response) {
+            action = workflow;
+        }
+
         // Print Claude's response
-        println!("{}", "\nClaude's Response:".blue().bold());
+        println!("{}", "\nClaude's Response:".header());
         println!("{}", response);
 
         // Handle suggested action
-        execute_claude_action(action, assistant, storage)?;
+        let filter = ActionFilter::from_flags(ask_args.create_only, ask_args.run_only);
+        execute_claude_action(action, filter, assistant, storage)?;
 
         // Save session state
         conversation_storage.save_session(&session)?;
@@ -1007,16 +3218,14 @@ fn handle_conversational_ask(
         // Check conversation state
         match session.state {
             ConversationState::Completed => {
-                println!("{} Conversation completed.", "Info:".green().bold());
+                println!("{} Conversation completed.", "Info:".success());
                 break;
             }
             _ => {
                 // Continue conversation - get next input
                 print!(
                     "\n{} ",
-                    "Continue conversation (or 'exit'/'quit' to end):"
-                        .cyan()
-                        .bold()
+                    "Continue conversation (or 'exit'/'quit' to end):".header()
                 );
                 io::stdout().flush().map_err(|e| {
                     ClixError::CommandExecutionFailed(format!("Failed to flush stdout: {}", e))
@@ -1033,7 +3242,7 @@ fn handle_conversational_ask(
                     conversation_storage.update_session(&session)?;
                     println!(
                         "{} Conversation ended. Session ID: {}",
-                        "Info:".green().bold(),
+                        "Info:".success(),
                         session.id
                     );
                     break;
@@ -1051,11 +3260,17 @@ fn handle_conversational_ask(
 
 fn execute_claude_action(
     action: clix::ai::claude::ClaudeAction,
+    filter: ActionFilter,
     assistant: &ClaudeAssistant,
     storage: &GitIntegratedStorage,
 ) -> Result<()> {
     use clix::ai::claude::ClaudeAction;
 
+    let (action, note) = filter.apply(action);
+    if let Some(note) = note {
+        println!("{} {}", "Note:".warning(), note.note());
+    }
+
     match action {
         ClaudeAction::RunCommand(ref name) => {
             if assistant.confirm_action(&action)? {
@@ -1073,15 +3288,15 @@ fn execute_claude_action(
                 let results = CommandExecutor::execute_workflow(&workflow, None, None)?;
 
                 // Print all results
-                println!("\n{}", "Workflow Results:".blue().bold());
+                println!("\n{}", "Workflow Results:".header());
                 println!("{}", "=".repeat(50));
 
                 for (step_name, result) in results {
-                    println!("{}: {}", "Step".green().bold(), step_name);
+                    println!("{}: {}", "Step".success(), step_name);
 
                     match result {
                         Ok(output) => CommandExecutor::print_command_output(&output),
-                        Err(e) => println!("{} {}", "Error:".red().bold(), e),
+                        Err(e) => println!("{} {}", "Error:".error(), e),
                     }
 
                     println!("{}", "-".repeat(50));
@@ -1107,7 +3322,7 @@ fn execute_claude_action(
                 storage.add_command(command)?;
                 println!(
                     "{} Command '{}' added successfully",
-                    "Success:".green().bold(),
+                    "Success:".success(),
                     name
                 );
             }
@@ -1128,7 +3343,7 @@ fn execute_claude_action(
                 storage.add_workflow(workflow)?;
                 println!(
                     "{} Workflow '{}' added successfully",
-                    "Success:".green().bold(),
+                    "Success:".success(),
                     name
                 );
             }
@@ -1138,3 +3353,24 @@ fn execute_claude_action(
 
     Ok(())
 }
+
+/// Read a secret from the terminal without echoing it
+#[cfg(feature = "keyring")]
+fn read_secret(prompt: &str) -> Result<String> {
+    Ok(rpassword::prompt_password(prompt)?.trim().to_string())
+}
+
+#[cfg(not(feature = "keyring"))]
+fn read_secret(_prompt: &str) -> Result<String> {
+    Err(ClixError::ConfigurationError(
+        "clix was built without keychain support; rebuild with --features keyring".to_string(),
+    ))
+}
+
+/// Strings print bare; everything else prints as JSON
+fn display_setting(value: &serde_json::Value) -> Result<String> {
+    Ok(match value {
+        serde_json::Value::String(text) => text.clone(),
+        other => serde_json::to_string_pretty(other)?,
+    })
+}
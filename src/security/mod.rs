@@ -1,5 +1,7 @@
+pub mod preview;
 pub mod sanitizer;
 pub mod validator;
 
+pub use preview::DestructivePreview;
 pub use sanitizer::CommandSanitizer;
 pub use validator::{SecurityConfig, SecurityValidator};
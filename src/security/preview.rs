@@ -0,0 +1,130 @@
+/// Turns common deletion commands into a listing of what they would remove.
+///
+/// This is a heuristic: only a single `rm`, `rmdir`, `find ... -delete` or
+/// `git clean` is recognized, and anything with pipes, redirections, command
+/// substitution or several commands chained together is left alone.
+pub struct DestructivePreview;
+
+impl DestructivePreview {
+    /// A read-only command that lists what `command` would delete, or None
+    /// when it isn't a deletion clix recognizes
+    pub fn listing_command(command: &str) -> Option<String> {
+        if command
+            .chars()
+            .any(|c| matches!(c, ';' | '|' | '&' | '`' | '<' | '>' | '\n'))
+            || command.contains("$(")
+        {
+            return None;
+        }
+
+        let words: Vec<&str> = command.split_whitespace().collect();
+        match words.as_slice() {
+            ["rm", args @ ..] => Self::list_paths("ls -la", args),
+            ["rmdir", args @ ..] => Self::list_paths("ls -lad", args),
+            ["find", args @ ..] if args.contains(&"-delete") => {
+                let args: Vec<&str> = args
+                    .iter()
+                    .map(|&arg| if arg == "-delete" { "-print" } else { arg })
+                    .collect();
+                Some(format!("find {}", args.join(" ")))
+            }
+            ["git", "clean", args @ ..] => Self::dry_run_git_clean(args),
+            _ => None,
+        }
+    }
+
+    /// `listing` followed by the paths in `args`, leaving out the options
+    fn list_paths(listing: &str, args: &[&str]) -> Option<String> {
+        let mut paths = Vec::new();
+        let mut options_done = false;
+        for &arg in args {
+            if options_done || !arg.starts_with('-') {
+                paths.push(arg);
+            } else if arg == "--" {
+                options_done = true;
+            }
+        }
+
+        if paths.is_empty() {
+            return None;
+        }
+        let separator = if options_done { " --" } else { "" };
+        Some(format!("{}{} {}", listing, separator, paths.join(" ")))
+    }
+
+    /// `git clean` with its force flags replaced by `-n`, which only prints
+    /// what would be removed
+    fn dry_run_git_clean(args: &[&str]) -> Option<String> {
+        let mut command = "git clean -n".to_string();
+        for &arg in args {
+            let kept = match arg {
+                "--force" | "--dry-run" => continue,
+                // Interactive mode would prompt instead of listing
+                "--interactive" => return None,
+                flags if flags.starts_with('-') && !flags.starts_with("--") => {
+                    if flags.contains('i') {
+                        return None;
+                    }
+                    let flags: String = flags[1..]
+                        .chars()
+                        .filter(|&c| c != 'f' && c != 'n')
+                        .collect();
+                    if flags.is_empty() {
+                        continue;
+                    }
+                    format!("-{}", flags)
+                }
+                other => other.to_string(),
+            };
+            command.push(' ');
+            command.push_str(&kept);
+        }
+        Some(command)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rm_becomes_a_listing() {
+        assert_eq!(
+            DestructivePreview::listing_command("rm -rf build"),
+            Some("ls -la build".to_string())
+        );
+        assert_eq!(
+            DestructivePreview::listing_command("rm -r -f dist \"my dir\" -- -odd"),
+            Some("ls -la -- dist \"my dir\" -odd".to_string())
+        );
+        assert_eq!(
+            DestructivePreview::listing_command("find . -name '*.tmp' -delete"),
+            Some("find . -name '*.tmp' -print".to_string())
+        );
+        assert_eq!(
+            DestructivePreview::listing_command("git clean -fdx"),
+            Some("git clean -n -dx".to_string())
+        );
+    }
+
+    #[test]
+    fn test_other_commands_are_unaffected() {
+        for command in [
+            "ls -la",
+            "echo rm -rf build",
+            "cargo build --release",
+            "find . -name '*.rs'",
+            "rm -rf",
+            "rm -rf build && make",
+            "rm -rf $(cat dirs.txt)",
+            "git clean -i",
+        ] {
+            assert_eq!(
+                DestructivePreview::listing_command(command),
+                None,
+                "{}",
+                command
+            );
+        }
+    }
+}
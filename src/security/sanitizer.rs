@@ -142,6 +142,11 @@ impl CommandSanitizer {
         Ok(sanitized)
     }
 
+    /// Wrap a value in single quotes so the shell treats it as one literal word
+    pub fn shell_quote(value: &str) -> String {
+        format!("'{}'", value.replace('\'', r"'\''"))
+    }
+
     /// Sanitize file paths to prevent directory traversal
     pub fn sanitize_file_path(path: &str) -> Result<String> {
         let mut sanitized = path.to_string();
@@ -297,4 +302,14 @@ mod tests {
         let result = CommandSanitizer::sanitize_user_input(input).unwrap();
         assert_eq!(result, "Line1\n\nLine2");
     }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(CommandSanitizer::shell_quote("plain"), "'plain'");
+        assert_eq!(
+            CommandSanitizer::shell_quote("tmp; rm -rf ~"),
+            "'tmp; rm -rf ~'"
+        );
+        assert_eq!(CommandSanitizer::shell_quote("it's"), r"'it'\''s'");
+    }
 }
@@ -8,6 +8,8 @@ pub struct SecurityValidator {
     dangerous_commands: HashSet<String>,
     dangerous_patterns: Vec<Regex>,
     require_approval_patterns: Vec<Regex>,
+    allowed_paths: Vec<String>,
+    sandbox_mode: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -17,6 +19,10 @@ pub struct SecurityConfig {
     pub sandbox_mode: bool,
     pub max_command_length: usize,
     pub allowed_file_extensions: Vec<String>,
+    /// Absolute path prefixes commands may reference. When non-empty, any other
+    /// absolute path in a command is flagged, and in sandbox mode it also
+    /// requires approval. Empty means no restriction.
+    pub allowed_paths: Vec<String>,
 }
 
 impl Default for SecurityConfig {
@@ -38,6 +44,7 @@ impl Default for SecurityConfig {
                 "yaml".to_string(),
                 "yml".to_string(),
             ],
+            allowed_paths: Vec::new(),
         }
     }
 }
@@ -87,6 +94,8 @@ impl SecurityValidator {
             dangerous_commands,
             dangerous_patterns,
             require_approval_patterns,
+            allowed_paths: config.allowed_paths,
+            sandbox_mode: config.sandbox_mode,
         }
     }
 
@@ -121,10 +130,10 @@ impl SecurityValidator {
         }
 
         // Check for dangerous commands at the start
-        if let Some(first_word) = command.split_whitespace().next() {
-            if self.dangerous_commands.contains(first_word) {
-                issues.push(format!("Potentially dangerous command: {}", first_word));
-            }
+        if let Some(first_word) = command.split_whitespace().next()
+            && self.dangerous_commands.contains(first_word)
+        {
+            issues.push(format!("Potentially dangerous command: {}", first_word));
         }
 
         // Check for suspicious redirections
@@ -141,6 +150,18 @@ impl SecurityValidator {
             issues.push("Command substitution detected - review carefully".to_string());
         }
 
+        // Check for absolute paths outside the allowlist
+        let outside = self.paths_outside_allowlist(command);
+        if !outside.is_empty() {
+            issues.push(format!(
+                "References paths outside the allowed paths: {}",
+                outside.join(", ")
+            ));
+            if self.sandbox_mode {
+                requires_approval = true;
+            }
+        }
+
         Ok(SecurityCheck {
             command: command.to_string(),
             is_safe: issues.is_empty(),
@@ -149,6 +170,60 @@ impl SecurityValidator {
         })
     }
 
+    /// Absolute paths in `command` that fall under none of the allowed paths.
+    ///
+    /// This is best-effort pattern matching on the command text: it only sees
+    /// literal absolute paths, so paths built from variables, `~`, relative
+    /// paths or `cd` followed by a relative write slip through.
+    fn paths_outside_allowlist(&self, command: &str) -> Vec<String> {
+        if self.allowed_paths.is_empty() {
+            return Vec::new();
+        }
+
+        static ABSOLUTE_PATH: OnceLock<Regex> = OnceLock::new();
+        let absolute_path = ABSOLUTE_PATH
+            .get_or_init(|| Regex::new(r#"(?:^|[\s=<>'"])(/[^\s;|&'"<>()]*)"#).unwrap());
+
+        let mut outside: Vec<String> = Vec::new();
+        for captures in absolute_path.captures_iter(command) {
+            let path = &captures[1];
+            let allowed = self
+                .allowed_paths
+                .iter()
+                .any(|allowed| path_is_under(path, allowed));
+            if !allowed && !outside.iter().any(|seen| seen == path) {
+                outside.push(path.to_string());
+            }
+        }
+        outside
+    }
+
+    /// Describe the injection risk of a variable value that will be substituted
+    /// into a shell command unquoted, or None when it has no shell metacharacters
+    pub fn check_variable_value(name: &str, value: &str) -> Option<String> {
+        const METACHARACTERS: &[char] = &[
+            ';', '|', '&', '$', '`', '>', '<', '(', ')', '{', '}', '*', '?', '\\', '"', '\'', '\n',
+            '\r',
+        ];
+
+        let mut found: Vec<char> = value
+            .chars()
+            .filter(|ch| METACHARACTERS.contains(ch))
+            .collect();
+        if found.is_empty() {
+            return None;
+        }
+        found.sort_unstable();
+        found.dedup();
+
+        let listed: Vec<String> = found.iter().map(|ch| format!("{:?}", ch)).collect();
+        Some(format!(
+            "Variable '{}' contains shell metacharacters ({}) - possible command injection",
+            name,
+            listed.join(" ")
+        ))
+    }
+
     /// Validate an entire workflow for security issues
     pub fn validate_workflow(&self, workflow: &Workflow) -> Result<WorkflowSecurityReport> {
         let mut all_issues = Vec::new();
@@ -190,8 +265,8 @@ impl SecurityValidator {
         })
     }
 
-    /// Validate a single workflow step
-    fn validate_workflow_step(&self, step: &WorkflowStep) -> Result<StepSecurityReport> {
+    /// Validate a single workflow step, including the steps nested inside it
+    pub fn validate_workflow_step(&self, step: &WorkflowStep) -> Result<StepSecurityReport> {
         let mut issues = Vec::new();
         let mut requires_approval = step.require_approval;
 
@@ -215,6 +290,16 @@ impl SecurityValidator {
                 }
             }
 
+            for elif in &conditional.elif_blocks {
+                for elif_step in &elif.then_block.steps {
+                    let sub_report = self.validate_workflow_step(elif_step)?;
+                    issues.extend(sub_report.issues);
+                    if sub_report.requires_approval {
+                        requires_approval = true;
+                    }
+                }
+            }
+
             if let Some(else_block) = &conditional.else_block {
                 for else_step in &else_block.steps {
                     let sub_report = self.validate_workflow_step(else_step)?;
@@ -278,10 +363,10 @@ impl SecurityValidator {
         for step in &workflow.steps {
             if step.command.contains("clix flow run") {
                 // Extract workflow name from "clix flow run workflow_name"
-                if let Some(captures) = regex.captures(&step.command) {
-                    if let Some(workflow_name) = captures.get(1) {
-                        calls.push(workflow_name.as_str().to_string());
-                    }
+                if let Some(captures) = regex.captures(&step.command)
+                    && let Some(workflow_name) = captures.get(1)
+                {
+                    calls.push(workflow_name.as_str().to_string());
                 }
             }
         }
@@ -332,6 +417,17 @@ impl SecurityValidator {
     }
 }
 
+/// Whether `path` is `prefix` itself or lies inside it, compared component-wise
+/// so `/tmpfoo` is not under `/tmp`
+fn path_is_under(path: &str, prefix: &str) -> bool {
+    let prefix = prefix.trim_end_matches('/');
+    if prefix.is_empty() {
+        return true;
+    }
+    path.strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
 #[derive(Debug, Clone)]
 pub struct SecurityCheck {
     pub command: String,
@@ -402,6 +498,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_allowed_path_write_passes() {
+        let validator = SecurityValidator::new(SecurityConfig {
+            allowed_paths: vec!["/tmp/work".to_string()],
+            ..SecurityConfig::default()
+        });
+
+        let result = validator
+            .validate_command("echo done > /tmp/work/status.txt")
+            .unwrap();
+        assert!(result.is_safe, "issues: {:?}", result.issues);
+    }
+
+    #[test]
+    fn test_disallowed_path_write_is_flagged() {
+        let validator = SecurityValidator::new(SecurityConfig {
+            allowed_paths: vec!["/tmp/work".to_string()],
+            ..SecurityConfig::default()
+        });
+
+        let result = validator
+            .validate_command("cp settings.conf /tmp/workshop/settings.conf")
+            .unwrap();
+        assert!(!result.is_safe);
+        assert!(!result.requires_approval);
+        assert!(
+            result
+                .issues
+                .iter()
+                .any(|issue| issue.contains("/tmp/workshop/settings.conf"))
+        );
+
+        let strict = SecurityValidator::new(SecurityConfig {
+            allowed_paths: vec!["/tmp/work".to_string()],
+            sandbox_mode: true,
+            ..SecurityConfig::default()
+        });
+        let result = strict.validate_command("tee /etc/hosts < hosts").unwrap();
+        assert!(!result.is_safe);
+        assert!(result.requires_approval);
+    }
+
     #[test]
     fn test_approval_requirement() {
         let validator = SecurityValidator::new(SecurityConfig::default());
@@ -448,4 +586,15 @@ mod tests {
         assert!(!report.is_safe);
         assert!(!report.issues.is_empty());
     }
+
+    #[test]
+    fn test_variable_injection_detection() {
+        let issue = SecurityValidator::check_variable_value("dir", "tmp; rm -rf ~")
+            .expect("metacharacters should be flagged");
+        assert!(issue.contains("'dir'"));
+        assert!(issue.contains("';'"));
+
+        assert!(SecurityValidator::check_variable_value("dir", "my-dir_2.txt").is_none());
+        assert!(SecurityValidator::check_variable_value("msg", "$(whoami)").is_some());
+    }
 }
@@ -1,8 +1,15 @@
+pub mod secrets;
+
+use crate::cli::theme::Theme;
+use crate::commands::capture::DEFAULT_MAX_OUTPUT_BYTES;
 use crate::error::{ClixError, Result};
+use clap::ValueEnum;
 use dirs::home_dir;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Settings {
@@ -14,6 +21,20 @@ pub struct Settings {
 
     #[serde(default)]
     pub git_settings: GitSettings,
+
+    #[serde(default)]
+    pub execution_settings: ExecutionSettings,
+
+    /// Variable values applied to every workflow before profiles and --var
+    #[serde(default)]
+    pub workflow_var_defaults: HashMap<String, String>,
+
+    /// Directory that relative step `command_file` paths are resolved against
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command_files_dir: Option<PathBuf>,
+    /// Palette for colored output: default, high-contrast or monochrome
+    #[serde(default)]
+    pub theme: Theme,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -23,6 +44,10 @@ pub struct AiSettings {
 
     #[serde(default = "default_max_tokens")]
     pub max_tokens: usize,
+
+    /// How long cached `ask` answers stay valid, in seconds
+    #[serde(default = "default_cache_ttl_secs")]
+    pub cache_ttl_secs: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -37,6 +62,19 @@ pub struct GitSettings {
     pub commit_message_prefix: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExecutionSettings {
+    /// Most stdout, and separately stderr, kept from a command; the rest is dropped
+    #[serde(default = "default_max_output_bytes")]
+    pub max_output_bytes: usize,
+    /// Ask the AI assistant about names `clix run` can't find, offering to create them
+    #[serde(default)]
+    pub ai_fallback: bool,
+    /// Most runs kept in the history, dropping the oldest; 0 keeps every run
+    #[serde(default = "default_history_max_entries")]
+    pub history_max_entries: usize,
+}
+
 fn default_ai_model() -> String {
     "claude-3-opus-20240229".to_string()
 }
@@ -49,6 +87,18 @@ fn default_max_tokens() -> usize {
     4000
 }
 
+fn default_cache_ttl_secs() -> u64 {
+    24 * 60 * 60
+}
+
+fn default_max_output_bytes() -> usize {
+    DEFAULT_MAX_OUTPUT_BYTES
+}
+
+fn default_history_max_entries() -> usize {
+    10_000
+}
+
 fn default_auto_sync() -> bool {
     true
 }
@@ -67,6 +117,10 @@ impl Default for Settings {
             ai_model: default_ai_model(),
             ai_settings: AiSettings::default(),
             git_settings: GitSettings::default(),
+            execution_settings: ExecutionSettings::default(),
+            workflow_var_defaults: HashMap::new(),
+            command_files_dir: None,
+            theme: Theme::default(),
         }
     }
 }
@@ -76,6 +130,17 @@ impl Default for AiSettings {
         AiSettings {
             temperature: default_temperature(),
             max_tokens: default_max_tokens(),
+            cache_ttl_secs: default_cache_ttl_secs(),
+        }
+    }
+}
+
+impl Default for ExecutionSettings {
+    fn default() -> Self {
+        ExecutionSettings {
+            max_output_bytes: default_max_output_bytes(),
+            ai_fallback: false,
+            history_max_entries: default_history_max_entries(),
         }
     }
 }
@@ -90,32 +155,88 @@ impl Default for GitSettings {
     }
 }
 
+/// A group of settings that can be reset together
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SettingsSection {
+    /// The AI model and AI settings
+    Ai,
+    /// Git repository settings
+    Git,
+    /// Every setting
+    All,
+}
+
+/// Environment variable that overrides where clix keeps its data
+pub const CONFIG_DIR_ENV: &str = "CLIX_CONFIG_DIR";
+
+/// Environment variable that turns off the git sync clix runs at startup
+pub const NO_SYNC_ENV: &str = "CLIX_NO_SYNC";
+
+impl GitSettings {
+    /// Whether to sync with git repositories at startup, given the `--no-sync` flag
+    pub fn sync_at_startup(&self, no_sync_flag: bool) -> bool {
+        let env_disabled = std::env::var(NO_SYNC_ENV)
+            .map(|value| !matches!(value.trim(), "" | "0" | "false"))
+            .unwrap_or(false);
+
+        self.auto_sync && !no_sync_flag && !env_disabled
+    }
+}
+
+/// Resolve the clix data directory: `CLIX_CONFIG_DIR` if set, else `~/.clix`
+pub fn config_dir() -> Result<PathBuf> {
+    if let Some(dir) = std::env::var_os(CONFIG_DIR_ENV).filter(|dir| !dir.is_empty()) {
+        return Ok(PathBuf::from(dir));
+    }
+
+    Ok(home_dir()
+        .ok_or_else(|| {
+            ClixError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "Could not determine home directory",
+            ))
+        })?
+        .join(".clix"))
+}
+
+/// A file or directory clix keeps under its data directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DataLocation {
+    /// Stored commands and workflows
+    Store,
+    /// User settings
+    Settings,
+    /// Saved AI conversations
+    Conversations,
+    /// Checkouts of shared git repositories
+    Repos,
+}
+
+impl DataLocation {
+    /// Where this lives under the clix data directory `config_dir`
+    pub fn path(self, config_dir: &Path) -> PathBuf {
+        config_dir.join(match self {
+            DataLocation::Store => "commands.json",
+            DataLocation::Settings => "settings.json",
+            DataLocation::Conversations => "conversations.json",
+            DataLocation::Repos => "repos",
+        })
+    }
+}
+
 pub struct SettingsManager {
     settings_path: PathBuf,
 }
 
 impl SettingsManager {
     pub fn new() -> Result<Self> {
-        let settings_dir = home_dir()
-            .ok_or_else(|| {
-                ClixError::Io(std::io::Error::new(
-                    std::io::ErrorKind::NotFound,
-                    "Could not determine home directory",
-                ))
-            })?
-            .join(".clix");
-
-        fs::create_dir_all(&settings_dir)?;
-
-        let settings_path = settings_dir.join("settings.json");
-
-        Ok(SettingsManager { settings_path })
+        Self::new_with_dir(config_dir()?)
     }
 
-    /// Create settings manager with custom directory for testing
+    /// Create settings manager with a custom config directory
     pub fn new_with_dir(settings_dir: PathBuf) -> Result<Self> {
         fs::create_dir_all(&settings_dir)?;
-        let settings_path = settings_dir.join("settings.json");
+        let settings_path = DataLocation::Settings.path(&settings_dir);
 
         Ok(SettingsManager { settings_path })
     }
@@ -143,13 +264,7 @@ impl SettingsManager {
     }
 
     pub fn update_ai_temperature(&self, temperature: f32) -> Result<()> {
-        // Validate temperature range (0.0 to 1.0)
-        if !(0.0..=1.0).contains(&temperature) {
-            return Err(ClixError::InvalidInput(format!(
-                "Temperature must be between 0.0 and 1.0, got: {}",
-                temperature
-            )));
-        }
+        validate_temperature(temperature)?;
 
         let mut settings = self.load()?;
         settings.ai_settings.temperature = temperature;
@@ -161,4 +276,130 @@ impl SettingsManager {
         settings.ai_settings.max_tokens = max_tokens;
         self.save(&settings)
     }
+
+    pub fn set_workflow_var_default(&self, name: &str, value: &str) -> Result<()> {
+        if name.is_empty() {
+            return Err(ClixError::InvalidInput(
+                "Variable name must not be empty".to_string(),
+            ));
+        }
+
+        let mut settings = self.load()?;
+        settings
+            .workflow_var_defaults
+            .insert(name.to_string(), value.to_string());
+        self.save(&settings)
+    }
+
+    /// Restore a section of the settings, or all of them, to the defaults
+    pub fn reset(&self, section: SettingsSection) -> Result<()> {
+        let defaults = Settings::default();
+        let settings = match section {
+            SettingsSection::All => defaults,
+            SettingsSection::Ai => Settings {
+                ai_model: defaults.ai_model,
+                ai_settings: defaults.ai_settings,
+                ..self.load()?
+            },
+            SettingsSection::Git => Settings {
+                git_settings: defaults.git_settings,
+                ..self.load()?
+            },
+        };
+        self.save(&settings)
+    }
+
+    /// Read a setting by dotted path, e.g. `ai_settings.temperature`
+    pub fn get_value(&self, key: &str) -> Result<Value> {
+        let settings = settings_to_value(&self.load()?)?;
+        lookup(&settings, key)
+            .cloned()
+            .ok_or_else(|| ClixError::NotFound(format!("Unknown setting '{}'", key)))
+    }
+
+    /// Write a setting by dotted path. The value is parsed to match the type of the
+    /// current value, and the result must still be valid settings.
+    pub fn set_value(&self, key: &str, raw: &str) -> Result<Value> {
+        let mut settings = settings_to_value(&self.load()?)?;
+
+        let (parent_path, field) = key.rsplit_once('.').unwrap_or(("", key));
+        let parent = if parent_path.is_empty() {
+            Some(&mut settings)
+        } else {
+            lookup_mut(&mut settings, parent_path)
+        }
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| ClixError::NotFound(format!("Unknown setting '{}'", key)))?;
+
+        let value = parse_setting_value(key, parent.get(field), raw)?;
+        parent.insert(field.to_string(), value.clone());
+
+        // Unknown fields are dropped on deserialisation, so a missing value means a bad key
+        let updated: Settings = serde_json::from_value(settings)
+            .map_err(|e| ClixError::InvalidInput(format!("Invalid value for '{}': {}", key, e)))?;
+        if lookup(&settings_to_value(&updated)?, key) != Some(&value) {
+            return Err(ClixError::NotFound(format!("Unknown setting '{}'", key)));
+        }
+        validate_temperature(updated.ai_settings.temperature)?;
+
+        self.save(&updated)?;
+        Ok(value)
+    }
+}
+
+fn validate_temperature(temperature: f32) -> Result<()> {
+    if !(0.0..=1.0).contains(&temperature) {
+        return Err(ClixError::InvalidInput(format!(
+            "Temperature must be between 0.0 and 1.0, got: {}",
+            temperature
+        )));
+    }
+    Ok(())
+}
+
+/// Go through the JSON text so f32 fields read back as written (0.7, not 0.699999988)
+fn settings_to_value(settings: &Settings) -> Result<Value> {
+    Ok(serde_json::from_str(&serde_json::to_string(settings)?)?)
+}
+
+fn lookup<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    key.split('.')
+        .try_fold(value, |value, part| value.get(part))
+}
+
+fn lookup_mut<'a>(value: &'a mut Value, key: &str) -> Option<&'a mut Value> {
+    key.split('.')
+        .try_fold(value, |value, part| value.get_mut(part))
+}
+
+/// Parse a command-line value into the JSON type of the setting it replaces
+fn parse_setting_value(key: &str, current: Option<&Value>, raw: &str) -> Result<Value> {
+    let invalid = |expected: &str| {
+        ClixError::InvalidInput(format!(
+            "Invalid value '{}' for '{}': expected {}",
+            raw, key, expected
+        ))
+    };
+
+    match current {
+        Some(Value::Bool(_)) => raw
+            .parse::<bool>()
+            .map(Value::Bool)
+            .map_err(|_| invalid("true or false")),
+        Some(Value::Number(number)) if number.is_f64() => raw
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number)
+            .ok_or_else(|| invalid("a number")),
+        Some(Value::Number(_)) => raw
+            .parse::<u64>()
+            .map(Value::from)
+            .map_err(|_| invalid("a whole number")),
+        Some(Value::Object(_)) | Some(Value::Array(_)) => {
+            serde_json::from_str(raw).map_err(|_| invalid("JSON matching the existing structure"))
+        }
+        // Strings, and optional settings that are currently unset
+        Some(Value::String(_)) | Some(Value::Null) | None => Ok(Value::String(raw.to_string())),
+    }
 }
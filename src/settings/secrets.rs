@@ -0,0 +1,157 @@
+use crate::error::{ClixError, Result};
+
+/// Keychain service name under which clix stores its secrets
+pub const KEYCHAIN_SERVICE: &str = "clix";
+
+/// Secret name used for the Anthropic API key
+pub const ANTHROPIC_API_KEY_SECRET: &str = "anthropic-api-key";
+
+/// Every secret clix reads from the keychain
+pub const KNOWN_SECRETS: &[&str] = &[ANTHROPIC_API_KEY_SECRET];
+
+/// Refuse a secret name clix would never read, such as a misspelt one
+pub fn check_secret_name(name: &str) -> Result<()> {
+    if KNOWN_SECRETS.contains(&name) {
+        return Ok(());
+    }
+    Err(ClixError::InvalidInput(format!(
+        "Unknown secret '{}'; expected one of: {}",
+        name,
+        KNOWN_SECRETS.join(", ")
+    )))
+}
+
+/// A place where secrets can be stored and looked up by name
+pub trait SecretProvider {
+    fn get_secret(&self, name: &str) -> Result<Option<String>>;
+    fn set_secret(&self, name: &str, value: &str) -> Result<()>;
+}
+
+/// Secrets kept in the operating system keychain
+#[cfg(feature = "keyring")]
+pub struct KeychainProvider;
+
+#[cfg(feature = "keyring")]
+impl SecretProvider for KeychainProvider {
+    fn get_secret(&self, name: &str) -> Result<Option<String>> {
+        let entry = keyring::Entry::new(KEYCHAIN_SERVICE, name)
+            .map_err(|e| ClixError::ConfigurationError(format!("Keychain error: {}", e)))?;
+
+        match entry.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(ClixError::ConfigurationError(format!(
+                "Keychain error: {}",
+                e
+            ))),
+        }
+    }
+
+    fn set_secret(&self, name: &str, value: &str) -> Result<()> {
+        keyring::Entry::new(KEYCHAIN_SERVICE, name)
+            .and_then(|entry| entry.set_password(value))
+            .map_err(|e| ClixError::ConfigurationError(format!("Keychain error: {}", e)))
+    }
+}
+
+/// Stand-in used when clix is built without keychain support
+#[cfg(not(feature = "keyring"))]
+pub struct KeychainProvider;
+
+#[cfg(not(feature = "keyring"))]
+impl SecretProvider for KeychainProvider {
+    fn get_secret(&self, _name: &str) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    fn set_secret(&self, _name: &str, _value: &str) -> Result<()> {
+        Err(ClixError::ConfigurationError(
+            "clix was built without keychain support; rebuild with --features keyring".to_string(),
+        ))
+    }
+}
+
+/// Pick a secret from the environment first, then from the provider
+pub fn resolve_secret(
+    env_value: Option<String>,
+    provider: &dyn SecretProvider,
+    name: &str,
+) -> Result<Option<String>> {
+    if let Some(value) = env_value.filter(|value| !value.is_empty()) {
+        return Ok(Some(value));
+    }
+
+    provider.get_secret(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    #[derive(Default)]
+    struct MockProvider {
+        secrets: RefCell<HashMap<String, String>>,
+    }
+
+    impl SecretProvider for MockProvider {
+        fn get_secret(&self, name: &str) -> Result<Option<String>> {
+            Ok(self.secrets.borrow().get(name).cloned())
+        }
+
+        fn set_secret(&self, name: &str, value: &str) -> Result<()> {
+            self.secrets
+                .borrow_mut()
+                .insert(name.to_string(), value.to_string());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_env_takes_precedence_over_keychain() {
+        let provider = MockProvider::default();
+        provider
+            .set_secret(ANTHROPIC_API_KEY_SECRET, "from-keychain")
+            .unwrap();
+
+        let key = resolve_secret(
+            Some("from-env".to_string()),
+            &provider,
+            ANTHROPIC_API_KEY_SECRET,
+        )
+        .unwrap();
+        assert_eq!(key.as_deref(), Some("from-env"));
+    }
+
+    #[test]
+    fn test_keychain_used_when_env_unset() {
+        let provider = MockProvider::default();
+        provider
+            .set_secret(ANTHROPIC_API_KEY_SECRET, "from-keychain")
+            .unwrap();
+
+        let key = resolve_secret(None, &provider, ANTHROPIC_API_KEY_SECRET).unwrap();
+        assert_eq!(key.as_deref(), Some("from-keychain"));
+
+        // An empty variable counts as unset
+        let key = resolve_secret(Some(String::new()), &provider, ANTHROPIC_API_KEY_SECRET).unwrap();
+        assert_eq!(key.as_deref(), Some("from-keychain"));
+    }
+
+    #[test]
+    fn test_only_known_secret_names_are_accepted() {
+        assert!(check_secret_name(ANTHROPIC_API_KEY_SECRET).is_ok());
+        assert!(matches!(
+            check_secret_name("anthropic_api_key"),
+            Err(ClixError::InvalidInput(message)) if message.contains("anthropic-api-key")
+        ));
+    }
+
+    #[test]
+    fn test_missing_everywhere() {
+        let provider = MockProvider::default();
+        let key = resolve_secret(None, &provider, ANTHROPIC_API_KEY_SECRET).unwrap();
+        assert!(key.is_none());
+    }
+}
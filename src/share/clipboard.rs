@@ -0,0 +1,50 @@
+use crate::error::{ClixError, Result};
+
+/// Put text on the system clipboard
+#[cfg(feature = "clipboard")]
+pub fn write_text(text: &str) -> Result<()> {
+    open()?
+        .set_text(text)
+        .map_err(|e| ClixError::ConfigurationError(format!("Clipboard error: {}", e)))
+}
+
+/// Read text from the system clipboard
+#[cfg(feature = "clipboard")]
+pub fn read_text() -> Result<String> {
+    open()?.get_text().map_err(|e| match e {
+        arboard::Error::ContentNotAvailable => {
+            ClixError::InvalidInput("The clipboard does not contain any text".to_string())
+        }
+        e => ClixError::ConfigurationError(format!("Clipboard error: {}", e)),
+    })
+}
+
+#[cfg(feature = "clipboard")]
+fn open() -> Result<arboard::Clipboard> {
+    // Fails on headless machines, e.g. over SSH or in CI without a display server
+    arboard::Clipboard::new().map_err(|e| {
+        ClixError::ConfigurationError(format!(
+            "No system clipboard is available ({}); use --output/--input with a file instead",
+            e
+        ))
+    })
+}
+
+/// Stand-in used when clix is built without clipboard support
+#[cfg(not(feature = "clipboard"))]
+pub fn write_text(_text: &str) -> Result<()> {
+    Err(unsupported())
+}
+
+/// Stand-in used when clix is built without clipboard support
+#[cfg(not(feature = "clipboard"))]
+pub fn read_text() -> Result<String> {
+    Err(unsupported())
+}
+
+#[cfg(not(feature = "clipboard"))]
+fn unsupported() -> ClixError {
+    ClixError::ConfigurationError(
+        "clix was built without clipboard support; rebuild with --features clipboard".to_string(),
+    )
+}
@@ -1,9 +1,25 @@
+use crate::commands::WorkflowValidator;
 use crate::commands::models::{Command, CommandStore, Workflow};
 use crate::error::{ClixError, Result};
+use crate::share::report::csv_field;
 use crate::storage::Storage;
+use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::fs;
+use std::path::PathBuf;
+
+/// File next to the command store that remembers the last incremental export
+const EXPORT_STATE_FILE: &str = "export_state.json";
+
+/// File format of an export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// Commands and workflows in full, for `clix import`
+    Json,
+    /// One row per command or workflow, for spreadsheets; can't be imported
+    Csv,
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ExportData {
@@ -13,6 +29,73 @@ pub struct ExportData {
     pub workflows: Option<HashMap<String, Workflow>>,
 }
 
+impl ExportData {
+    /// Render an inventory of the exported items as CSV with a header line,
+    /// one row per command or workflow sorted by name
+    pub fn to_csv(&self) -> String {
+        let mut rows: Vec<[String; 7]> = Vec::new();
+        for command in self.commands.iter().flat_map(|commands| commands.values()) {
+            let kind = if command.is_workflow() {
+                "workflow"
+            } else {
+                "command"
+            };
+            rows.push(Self::csv_row(
+                &command.name,
+                kind,
+                &command.description,
+                &command.tags,
+                command.use_count,
+                command.last_used,
+                command.created_at,
+            ));
+        }
+        for workflow in self
+            .workflows
+            .iter()
+            .flat_map(|workflows| workflows.values())
+        {
+            rows.push(Self::csv_row(
+                &workflow.name,
+                "workflow",
+                &workflow.description,
+                &workflow.tags,
+                workflow.use_count,
+                workflow.last_used,
+                workflow.created_at,
+            ));
+        }
+        rows.sort();
+
+        let mut csv = String::from("name,type,description,tags,use_count,last_used,created_at\n");
+        for row in rows {
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    fn csv_row(
+        name: &str,
+        kind: &str,
+        description: &str,
+        tags: &[String],
+        use_count: u32,
+        last_used: Option<u64>,
+        created_at: u64,
+    ) -> [String; 7] {
+        [
+            csv_field(name),
+            kind.to_string(),
+            csv_field(description),
+            csv_field(&tags.join(";")),
+            use_count.to_string(),
+            last_used.map(|time| time.to_string()).unwrap_or_default(),
+            created_at.to_string(),
+        ]
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ExportMetadata {
     pub exported_at: u64,
@@ -20,6 +103,19 @@ pub struct ExportMetadata {
     pub description: String,
 }
 
+/// Named items picked for export along with the workflows they call
+#[derive(Debug)]
+pub struct ExportBundle {
+    pub data: ExportData,
+    /// Referenced names that aren't in the store, with the item referring to each
+    pub unresolved: Vec<(String, String)>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct ExportState {
+    last_exported_at: Option<u64>,
+}
+
 pub struct ExportManager {
     storage: Storage,
 }
@@ -31,7 +127,7 @@ impl ExportManager {
 
     pub fn export_all(&self, output_path: &str) -> Result<()> {
         let store = self.storage.load()?;
-        self.write_export_file(output_path, store, None, false, false)
+        self.write_export_file(output_path, store, None, false, false, None)
     }
 
     pub fn export_with_filter(
@@ -48,9 +144,152 @@ impl ExportManager {
             tag_filter,
             commands_only,
             workflows_only,
+            None,
         )
     }
 
+    /// Serialize the filtered commands and workflows to the JSON an export file holds
+    pub fn export_to_string(
+        &self,
+        tag_filter: Option<String>,
+        commands_only: bool,
+        workflows_only: bool,
+    ) -> Result<String> {
+        let store = self.storage.load()?;
+        let export_data =
+            Self::build_export_data(store, tag_filter, commands_only, workflows_only, None);
+        serde_json::to_string_pretty(&export_data).map_err(ClixError::Serialization)
+    }
+
+    /// Render the filtered commands and workflows as a CSV inventory
+    pub fn export_csv_to_string(
+        &self,
+        tag_filter: Option<String>,
+        commands_only: bool,
+        workflows_only: bool,
+    ) -> Result<String> {
+        let store = self.storage.load()?;
+        Ok(
+            Self::build_export_data(store, tag_filter, commands_only, workflows_only, None)
+                .to_csv(),
+        )
+    }
+
+    /// Export only items created or updated since the previous incremental export
+    pub fn export_only_changed(
+        &self,
+        output_path: &str,
+        tag_filter: Option<String>,
+        commands_only: bool,
+        workflows_only: bool,
+    ) -> Result<()> {
+        let state_path = self.export_state_path();
+        let mut state = if state_path.exists() {
+            let content = fs::read_to_string(&state_path)?;
+            serde_json::from_str::<ExportState>(&content)?
+        } else {
+            ExportState::default()
+        };
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        let store = self.storage.load()?;
+        self.write_export_file(
+            output_path,
+            store,
+            tag_filter,
+            commands_only,
+            workflows_only,
+            state.last_exported_at,
+        )?;
+
+        // Only move the marker once the export has been written
+        state.last_exported_at = Some(now);
+        fs::write(&state_path, serde_json::to_string_pretty(&state)?)?;
+        Ok(())
+    }
+
+    /// Export exactly the named commands and workflows, plus the workflows they
+    /// call with `clix flow run`, followed transitively so the bundle runs on
+    /// its own. With `with_dependencies`, commands they call with `clix run`
+    /// are followed too. A name that isn't stored is an error; a call that
+    /// can't be resolved is reported in the bundle instead.
+    pub fn bundle(&self, names: &[String], with_dependencies: bool) -> Result<ExportBundle> {
+        let store = self.storage.load()?;
+
+        for name in names {
+            if !store.commands.contains_key(name) && !store.workflows.contains_key(name) {
+                return Err(ClixError::CommandNotFound(name.clone()));
+            }
+        }
+
+        let mut commands = HashMap::new();
+        let mut workflows = HashMap::new();
+        let mut unresolved = Vec::new();
+        let mut pending: VecDeque<(String, Option<String>)> =
+            names.iter().map(|name| (name.clone(), None)).collect();
+
+        while let Some((name, referenced_by)) = pending.pop_front() {
+            if commands.contains_key(&name) || workflows.contains_key(&name) {
+                continue;
+            }
+
+            let (steps, text) = if let Some(command) = store.commands.get(&name) {
+                commands.insert(name.clone(), command.clone());
+                (
+                    command.steps.as_deref().unwrap_or_default(),
+                    command.command.as_deref(),
+                )
+            } else if let Some(workflow) = store.workflows.get(&name) {
+                workflows.insert(name.clone(), workflow.clone());
+                (workflow.steps.as_slice(), None)
+            } else {
+                if let Some(referenced_by) = referenced_by
+                    && !unresolved.iter().any(|(missing, _)| missing == &name)
+                {
+                    unresolved.push((name, referenced_by));
+                }
+                continue;
+            };
+
+            let mut calls = WorkflowValidator::workflow_calls(steps);
+            if with_dependencies {
+                calls.extend(WorkflowValidator::command_calls(steps));
+                calls.extend(
+                    text.map(WorkflowValidator::command_calls_in)
+                        .unwrap_or_default(),
+                );
+            }
+            pending.extend(calls.into_iter().map(|call| (call, Some(name.clone()))));
+        }
+
+        let metadata = ExportMetadata {
+            exported_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+            exported_by: std::env::var("USER").unwrap_or_else(|_| "unknown".to_string()),
+            description: format!("Exported {} and the workflows they call", names.join(", ")),
+        };
+
+        Ok(ExportBundle {
+            data: ExportData {
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                metadata,
+                commands: Some(commands),
+                workflows: Some(workflows),
+            },
+            unresolved,
+        })
+    }
+
+    fn export_state_path(&self) -> PathBuf {
+        self.storage.data_dir().join(EXPORT_STATE_FILE)
+    }
+
     fn write_export_file(
         &self,
         output_path: &str,
@@ -58,7 +297,31 @@ impl ExportManager {
         tag_filter: Option<String>,
         commands_only: bool,
         workflows_only: bool,
+        changed_since: Option<u64>,
     ) -> Result<()> {
+        let export_data = Self::build_export_data(
+            store,
+            tag_filter,
+            commands_only,
+            workflows_only,
+            changed_since,
+        );
+
+        // Serialize to JSON and write to file
+        let json = serde_json::to_string_pretty(&export_data).map_err(ClixError::Serialization)?;
+
+        fs::write(output_path, json).map_err(ClixError::Io)?;
+
+        Ok(())
+    }
+
+    fn build_export_data(
+        store: CommandStore,
+        tag_filter: Option<String>,
+        commands_only: bool,
+        workflows_only: bool,
+        changed_since: Option<u64>,
+    ) -> ExportData {
         // Filter commands if needed
         let commands = if !workflows_only {
             let mut filtered_commands = store.commands;
@@ -67,6 +330,11 @@ impl ExportManager {
                 filtered_commands.retain(|_, cmd| cmd.tags.contains(tag));
             }
 
+            // Changes within the marker's second are re-exported rather than missed
+            if let Some(since) = changed_since {
+                filtered_commands.retain(|_, cmd| cmd.modified_at() >= since);
+            }
+
             Some(filtered_commands)
         } else {
             None
@@ -80,6 +348,10 @@ impl ExportManager {
                 filtered_workflows.retain(|_, wf| wf.tags.contains(tag));
             }
 
+            if let Some(since) = changed_since {
+                filtered_workflows.retain(|_, wf| wf.modified_at() >= since);
+            }
+
             Some(filtered_workflows)
         } else {
             None
@@ -97,7 +369,7 @@ impl ExportManager {
             exported_at: now,
             exported_by: username,
             description: format!(
-                "Exported {} {}{}",
+                "Exported {} {}{}{}",
                 if tag_filter.is_some() {
                     "with tag filter"
                 } else {
@@ -114,23 +386,20 @@ impl ExportManager {
                     format!(": {}", tag)
                 } else {
                     "".to_string()
+                },
+                if changed_since.is_some() {
+                    " changed since the last export"
+                } else {
+                    ""
                 }
             ),
         };
 
-        // Create export data
-        let export_data = ExportData {
+        ExportData {
             version: env!("CARGO_PKG_VERSION").to_string(),
             metadata,
             commands,
             workflows,
-        };
-
-        // Serialize to JSON and write to file
-        let json = serde_json::to_string_pretty(&export_data).map_err(ClixError::Serialization)?;
-
-        fs::write(output_path, json).map_err(ClixError::Io)?;
-
-        Ok(())
+        }
     }
 }
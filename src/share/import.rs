@@ -1,12 +1,70 @@
+use crate::commands::models::{WorkflowVariable, WorkflowVariableProfile};
 use crate::error::{ClixError, Result};
 use crate::share::export::ExportData;
 use crate::storage::Storage;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::fmt;
 use std::fs;
 
 pub struct ImportManager {
     storage: Storage,
 }
 
+/// What to do with an imported item whose name is already taken
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConflictResolution {
+    Skip,
+    Overwrite,
+    /// Keep the local item and add the imported tags, variables and profiles it lacks
+    Merge,
+    /// Import the item under a different name
+    Rename(String),
+}
+
+impl fmt::Display for ConflictResolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConflictResolution::Skip => write!(f, "skipped"),
+            ConflictResolution::Overwrite => write!(f, "overwritten"),
+            ConflictResolution::Merge => write!(f, "merged"),
+            ConflictResolution::Rename(name) => write!(f, "renamed to '{}'", name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportKind {
+    Command,
+    Workflow,
+}
+
+impl fmt::Display for ImportKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportKind::Command => write!(f, "command"),
+            ImportKind::Workflow => write!(f, "workflow"),
+        }
+    }
+}
+
+/// An imported item whose name is already in the store
+#[derive(Debug, Clone)]
+pub struct ImportConflict {
+    pub kind: ImportKind,
+    pub name: String,
+    /// A free name to offer when renaming
+    pub suggested_name: String,
+}
+
+/// How one conflict was resolved
+#[derive(Debug, Clone)]
+pub struct ImportDecision {
+    pub kind: ImportKind,
+    pub name: String,
+    pub resolution: ConflictResolution,
+}
+
 impl ImportManager {
     pub fn new(storage: Storage) -> Self {
         ImportManager { storage }
@@ -16,9 +74,46 @@ impl ImportManager {
         // Read the file
         let file_content = fs::read_to_string(input_path).map_err(ClixError::Io)?;
 
+        self.import_from_str(&file_content, overwrite)
+    }
+
+    /// Import from export JSON held in memory, e.g. pasted from the clipboard
+    pub fn import_from_str(&self, content: &str, overwrite: bool) -> Result<ImportSummary> {
+        self.import_from_str_resolving(content, |_| {
+            Ok(if overwrite {
+                ConflictResolution::Overwrite
+            } else {
+                ConflictResolution::Skip
+            })
+        })
+    }
+
+    /// Like `import_from_file`, asking `resolve` what to do with each conflict
+    pub fn import_from_file_resolving<F>(
+        &self,
+        input_path: &str,
+        resolve: F,
+    ) -> Result<ImportSummary>
+    where
+        F: FnMut(&ImportConflict) -> Result<ConflictResolution>,
+    {
+        let file_content = fs::read_to_string(input_path).map_err(ClixError::Io)?;
+        self.import_from_str_resolving(&file_content, resolve)
+    }
+
+    /// Import from export JSON, asking `resolve` what to do with each item
+    /// whose name is taken. Conflicts are offered in name order.
+    pub fn import_from_str_resolving<F>(
+        &self,
+        content: &str,
+        mut resolve: F,
+    ) -> Result<ImportSummary>
+    where
+        F: FnMut(&ImportConflict) -> Result<ConflictResolution>,
+    {
         // Parse the JSON
         let export_data: ExportData =
-            serde_json::from_str(&file_content).map_err(ClixError::Serialization)?;
+            serde_json::from_str(content).map_err(ClixError::Serialization)?;
 
         // Load the current store
         let mut store = self.storage.load()?;
@@ -31,41 +126,114 @@ impl ImportManager {
             workflows_added: 0,
             workflows_updated: 0,
             workflows_skipped: 0,
+            decisions: Vec::new(),
             metadata: export_data.metadata,
         };
 
         // Import commands
-        if let Some(commands) = export_data.commands {
-            for (name, command) in commands {
-                if store.commands.contains_key(&name) {
-                    if overwrite {
-                        store.commands.insert(name.clone(), command);
-                        summary.commands_updated += 1;
-                    } else {
-                        summary.commands_skipped += 1;
+        let mut commands: Vec<_> = export_data
+            .commands
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+        commands.sort_by(|a, b| a.0.cmp(&b.0));
+        for (name, mut command) in commands {
+            if let Entry::Vacant(slot) = store.commands.entry(name.clone()) {
+                slot.insert(command);
+                summary.commands_added += 1;
+                continue;
+            }
+
+            let conflict = ImportConflict {
+                kind: ImportKind::Command,
+                suggested_name: free_name(&name, |n| store.commands.contains_key(n)),
+                name: name.clone(),
+            };
+            let resolution = resolve(&conflict)?;
+            match &resolution {
+                ConflictResolution::Skip => summary.commands_skipped += 1,
+                ConflictResolution::Overwrite => {
+                    store.commands.insert(name.clone(), command);
+                    summary.commands_updated += 1;
+                }
+                ConflictResolution::Merge => {
+                    if let Some(existing) = store.commands.get_mut(&name) {
+                        merge_into(
+                            (
+                                &mut existing.tags,
+                                &mut existing.variables,
+                                &mut existing.profiles,
+                            ),
+                            (command.tags, command.variables, command.profiles),
+                        );
                     }
-                } else {
-                    store.commands.insert(name, command);
+                    summary.commands_updated += 1;
+                }
+                ConflictResolution::Rename(new_name) => {
+                    check_free(&store.commands, ImportKind::Command, new_name)?;
+                    command.name = new_name.clone();
+                    store.commands.insert(new_name.clone(), command);
                     summary.commands_added += 1;
                 }
             }
+            summary.decisions.push(ImportDecision {
+                kind: conflict.kind,
+                name,
+                resolution,
+            });
         }
 
         // Import workflows
-        if let Some(workflows) = export_data.workflows {
-            for (name, workflow) in workflows {
-                if store.workflows.contains_key(&name) {
-                    if overwrite {
-                        store.workflows.insert(name.clone(), workflow);
-                        summary.workflows_updated += 1;
-                    } else {
-                        summary.workflows_skipped += 1;
+        let mut workflows: Vec<_> = export_data
+            .workflows
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+        workflows.sort_by(|a, b| a.0.cmp(&b.0));
+        for (name, mut workflow) in workflows {
+            if let Entry::Vacant(slot) = store.workflows.entry(name.clone()) {
+                slot.insert(workflow);
+                summary.workflows_added += 1;
+                continue;
+            }
+
+            let conflict = ImportConflict {
+                kind: ImportKind::Workflow,
+                suggested_name: free_name(&name, |n| store.workflows.contains_key(n)),
+                name: name.clone(),
+            };
+            let resolution = resolve(&conflict)?;
+            match &resolution {
+                ConflictResolution::Skip => summary.workflows_skipped += 1,
+                ConflictResolution::Overwrite => {
+                    store.workflows.insert(name.clone(), workflow);
+                    summary.workflows_updated += 1;
+                }
+                ConflictResolution::Merge => {
+                    if let Some(existing) = store.workflows.get_mut(&name) {
+                        merge_into(
+                            (
+                                &mut existing.tags,
+                                &mut existing.variables,
+                                &mut existing.profiles,
+                            ),
+                            (workflow.tags, workflow.variables, workflow.profiles),
+                        );
                     }
-                } else {
-                    store.workflows.insert(name, workflow);
+                    summary.workflows_updated += 1;
+                }
+                ConflictResolution::Rename(new_name) => {
+                    check_free(&store.workflows, ImportKind::Workflow, new_name)?;
+                    workflow.name = new_name.clone();
+                    store.workflows.insert(new_name.clone(), workflow);
                     summary.workflows_added += 1;
                 }
             }
+            summary.decisions.push(ImportDecision {
+                kind: conflict.kind,
+                name,
+                resolution,
+            });
         }
 
         // Save the updated store
@@ -75,6 +243,67 @@ impl ImportManager {
     }
 }
 
+type Extras<'a> = (
+    &'a mut Vec<String>,
+    &'a mut Vec<WorkflowVariable>,
+    &'a mut HashMap<String, WorkflowVariableProfile>,
+);
+
+type ImportedExtras = (
+    Vec<String>,
+    Vec<WorkflowVariable>,
+    HashMap<String, WorkflowVariableProfile>,
+);
+
+/// Add the imported tags, variables and profiles the local item doesn't have;
+/// anything defined locally is kept as it is
+fn merge_into(local: Extras, imported: ImportedExtras) {
+    let (tags, variables, profiles) = local;
+    let (imported_tags, imported_variables, imported_profiles) = imported;
+
+    for tag in imported_tags {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    for variable in imported_variables {
+        if !variables.iter().any(|v| v.name == variable.name) {
+            variables.push(variable);
+        }
+    }
+    for (name, profile) in imported_profiles {
+        profiles.entry(name).or_insert(profile);
+    }
+}
+
+/// `name-imported`, or `name-imported-2` and so on when that is taken too
+fn free_name(name: &str, taken: impl Fn(&str) -> bool) -> String {
+    let base = format!("{}-imported", name);
+    let mut candidate = base.clone();
+    let mut counter = 2;
+    while taken(&candidate) {
+        candidate = format!("{}-{}", base, counter);
+        counter += 1;
+    }
+    candidate
+}
+
+fn check_free<T>(items: &HashMap<String, T>, kind: ImportKind, name: &str) -> Result<()> {
+    if name.trim().is_empty() {
+        return Err(ClixError::InvalidInput(format!(
+            "Cannot rename the imported {} to an empty name",
+            kind
+        )));
+    }
+    if items.contains_key(name) {
+        return Err(ClixError::InvalidInput(format!(
+            "Cannot rename the imported {} to '{}': that name is taken",
+            kind, name
+        )));
+    }
+    Ok(())
+}
+
 pub struct ImportSummary {
     pub commands_added: usize,
     pub commands_updated: usize,
@@ -82,5 +311,7 @@ pub struct ImportSummary {
     pub workflows_added: usize,
     pub workflows_updated: usize,
     pub workflows_skipped: usize,
+    /// How each name conflict was resolved, in the order they were met
+    pub decisions: Vec<ImportDecision>,
     pub metadata: crate::share::export::ExportMetadata,
 }
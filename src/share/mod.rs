@@ -1,5 +1,10 @@
+pub mod clipboard;
 pub mod export;
 pub mod import;
+pub mod report;
 
-pub use export::ExportManager;
-pub use import::ImportManager;
+pub use export::{ExportFormat, ExportManager};
+pub use import::{
+    ConflictResolution, ImportConflict, ImportDecision, ImportKind, ImportManager, ImportSummary,
+};
+pub use report::{ReportFormat, UsageReport};
@@ -0,0 +1,157 @@
+use crate::storage::{ExecutionKind, ExecutionRecord};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// File format of a usage report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    /// The full report as pretty-printed JSON
+    Json,
+    /// One row per command or workflow
+    Csv,
+}
+
+/// Run counts for one command or workflow
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CommandUsage {
+    pub name: String,
+    pub kind: ExecutionKind,
+    pub runs: usize,
+    pub failures: usize,
+    /// Share of runs that failed, from 0 to 1
+    pub failure_rate: f64,
+    pub avg_duration_ms: u64,
+    /// Seconds since the Unix epoch when the latest run started
+    pub last_run_at: u64,
+    /// Tags the command has now; empty if it has since been removed
+    pub tags: Vec<String>,
+}
+
+/// Runs of everything carrying a tag
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TagUsage {
+    pub tag: String,
+    pub runs: usize,
+}
+
+/// Aggregate usage built only from local run history and the command store
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UsageReport {
+    /// Seconds since the Unix epoch
+    pub generated_at: u64,
+    /// Earliest run start counted, or None for the whole history
+    pub since: Option<u64>,
+    pub total_runs: usize,
+    pub total_failures: usize,
+    /// Most-run first
+    pub commands: Vec<CommandUsage>,
+    /// Most-run first
+    pub tags: Vec<TagUsage>,
+}
+
+impl UsageReport {
+    /// Summarise the runs that started at or after `since`. `tags` maps
+    /// command and workflow names to their current tags.
+    pub fn build(
+        history: &[ExecutionRecord],
+        tags: &HashMap<String, Vec<String>>,
+        since: Option<u64>,
+        generated_at: u64,
+    ) -> Self {
+        let mut by_name: HashMap<&str, CommandUsage> = HashMap::new();
+        let mut total_duration: HashMap<&str, u64> = HashMap::new();
+
+        let counted = history
+            .iter()
+            .filter(|record| since.is_none_or(|since| record.started_at >= since));
+        for record in counted {
+            let usage = by_name
+                .entry(record.name.as_str())
+                .or_insert_with(|| CommandUsage {
+                    name: record.name.clone(),
+                    kind: record.kind,
+                    runs: 0,
+                    failures: 0,
+                    failure_rate: 0.0,
+                    avg_duration_ms: 0,
+                    last_run_at: 0,
+                    tags: tags.get(&record.name).cloned().unwrap_or_default(),
+                });
+            usage.runs += 1;
+            if !record.success {
+                usage.failures += 1;
+            }
+            if record.started_at >= usage.last_run_at {
+                usage.last_run_at = record.started_at;
+                usage.kind = record.kind;
+            }
+            *total_duration.entry(record.name.as_str()).or_default() += record.duration_ms;
+        }
+
+        let mut commands: Vec<CommandUsage> = by_name.into_values().collect();
+        for usage in &mut commands {
+            usage.failure_rate = usage.failures as f64 / usage.runs as f64;
+            usage.avg_duration_ms = total_duration[usage.name.as_str()] / usage.runs as u64;
+        }
+        commands.sort_by(|a, b| b.runs.cmp(&a.runs).then_with(|| a.name.cmp(&b.name)));
+
+        let mut tag_runs: HashMap<&str, usize> = HashMap::new();
+        for usage in &commands {
+            for tag in &usage.tags {
+                *tag_runs.entry(tag.as_str()).or_default() += usage.runs;
+            }
+        }
+        let mut tag_usage: Vec<TagUsage> = tag_runs
+            .into_iter()
+            .map(|(tag, runs)| TagUsage {
+                tag: tag.to_string(),
+                runs,
+            })
+            .collect();
+        tag_usage.sort_by(|a, b| b.runs.cmp(&a.runs).then_with(|| a.tag.cmp(&b.tag)));
+
+        UsageReport {
+            generated_at,
+            since,
+            total_runs: commands.iter().map(|usage| usage.runs).sum(),
+            total_failures: commands.iter().map(|usage| usage.failures).sum(),
+            commands,
+            tags: tag_usage,
+        }
+    }
+
+    /// Render the per-command rows as CSV with a header line
+    pub fn to_csv(&self) -> String {
+        let mut csv =
+            String::from("name,kind,runs,failures,failure_rate,avg_duration_ms,last_run_at,tags\n");
+        for usage in &self.commands {
+            let kind = match usage.kind {
+                ExecutionKind::Command => "command",
+                ExecutionKind::Workflow => "workflow",
+            };
+            let row = [
+                csv_field(&usage.name),
+                kind.to_string(),
+                usage.runs.to_string(),
+                usage.failures.to_string(),
+                format!("{:.3}", usage.failure_rate),
+                usage.avg_duration_ms.to_string(),
+                usage.last_run_at.to_string(),
+                csv_field(&usage.tags.join(";")),
+            ];
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
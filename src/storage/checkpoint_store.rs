@@ -0,0 +1,129 @@
+use crate::error::Result;
+use crate::settings::config_dir;
+use crate::storage::file_stem;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+
+/// Progress through a loop step, saved so an interrupted run can pick up
+/// where it left off with `run --continue`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LoopCheckpoint {
+    pub workflow: String,
+    /// Index of the top-level step the loop belongs to
+    pub step_index: usize,
+    /// Name of the loop step itself
+    pub loop_step: String,
+    /// Iterations completed when the checkpoint was taken
+    pub iteration: usize,
+    /// Workflow variables at that point, so a resumed run sees the same
+    /// values. Secret-named variables are left out; the resumed run supplies
+    /// them again.
+    pub variables: HashMap<String, String>,
+    pub saved_at: u64,
+}
+
+/// One checkpoint file per workflow, replaced as the loop makes progress
+pub struct CheckpointStorage {
+    checkpoints_dir: PathBuf,
+}
+
+impl CheckpointStorage {
+    pub fn new() -> Result<Self> {
+        Self::new_with_dir(config_dir()?)
+    }
+
+    /// Create checkpoint storage with a custom config directory
+    pub fn new_with_dir(store_dir: PathBuf) -> Result<Self> {
+        let checkpoints_dir = store_dir.join("checkpoints");
+        fs::create_dir_all(&checkpoints_dir)?;
+
+        Ok(CheckpointStorage { checkpoints_dir })
+    }
+
+    /// Save a checkpoint, replacing the workflow's previous one. The file is
+    /// written next to its final name and renamed into place, so a crash
+    /// mid-write leaves the last good checkpoint. Only the owner can read it.
+    pub fn save(&self, checkpoint: &LoopCheckpoint) -> Result<()> {
+        let path = self.checkpoint_path(&checkpoint.workflow);
+        let partial = path.with_extension("json.tmp");
+        match fs::remove_file(&partial) {
+            Err(e) if e.kind() != ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&partial)?;
+        file.write_all(serde_json::to_string_pretty(checkpoint)?.as_bytes())?;
+        drop(file);
+
+        fs::rename(&partial, &path)?;
+        Ok(())
+    }
+
+    /// The workflow's checkpoint, if a run left one behind
+    pub fn load(&self, workflow: &str) -> Result<Option<LoopCheckpoint>> {
+        match fs::read_to_string(self.checkpoint_path(workflow)) {
+            Ok(json) => Ok(Some(serde_json::from_str(&json)?)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Remove the workflow's checkpoint; a missing one is not an error
+    pub fn clear(&self, workflow: &str) -> Result<()> {
+        match fs::remove_file(self.checkpoint_path(workflow)) {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn checkpoint_path(&self, workflow: &str) -> PathBuf {
+        self.checkpoints_dir
+            .join(format!("{}.json", file_stem(workflow)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_checkpoint_round_trips_and_clears() {
+        let temp_dir = TempDir::new().unwrap();
+        let checkpoints = CheckpointStorage::new_with_dir(temp_dir.path().to_path_buf()).unwrap();
+        assert_eq!(checkpoints.load("sync/all").unwrap(), None);
+
+        let checkpoint = LoopCheckpoint {
+            workflow: "sync/all".to_string(),
+            step_index: 1,
+            loop_step: "Process items".to_string(),
+            iteration: 4,
+            variables: HashMap::from([("BATCH".to_string(), "7".to_string())]),
+            saved_at: 0,
+        };
+        checkpoints.save(&checkpoint).unwrap();
+        assert_eq!(checkpoints.load("sync/all").unwrap(), Some(checkpoint));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let path = temp_dir.path().join("checkpoints").join("sync%2Fall.json");
+            let mode = fs::metadata(path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        checkpoints.clear("sync/all").unwrap();
+        checkpoints.clear("sync/all").unwrap();
+        assert_eq!(checkpoints.load("sync/all").unwrap(), None);
+    }
+}
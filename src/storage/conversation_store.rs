@@ -1,8 +1,15 @@
 use crate::ai::conversation::{ConversationSession, ConversationStore};
 use crate::error::{ClixError, Result};
-use dirs::home_dir;
-use std::fs;
+use crate::settings::{DataLocation, config_dir};
+use crate::storage::lock_store::process_is_running;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
 use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long to wait for another `ask` session to finish writing the store
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
 
 pub struct ConversationStorage {
     store_path: PathBuf,
@@ -10,18 +17,14 @@ pub struct ConversationStorage {
 
 impl ConversationStorage {
     pub fn new() -> Result<Self> {
-        let store_dir = home_dir()
-            .ok_or_else(|| {
-                ClixError::Io(std::io::Error::new(
-                    std::io::ErrorKind::NotFound,
-                    "Could not determine home directory",
-                ))
-            })?
-            .join(".clix");
+        Self::new_with_dir(config_dir()?)
+    }
 
+    /// Create conversation storage with a custom config directory
+    pub fn new_with_dir(store_dir: PathBuf) -> Result<Self> {
         fs::create_dir_all(&store_dir)?;
 
-        let store_path = store_dir.join("conversations.json");
+        let store_path = DataLocation::Conversations.path(&store_dir);
 
         Ok(ConversationStorage { store_path })
     }
@@ -36,16 +39,71 @@ impl ConversationStorage {
         Ok(store)
     }
 
-    pub fn save(&self, store: &ConversationStore) -> Result<()> {
+    /// Write the whole store; only `modify` calls this, with the lock held.
+    /// It is written to a temporary file named after this process and renamed
+    /// into place, so readers never see a half-written file.
+    fn save(&self, store: &ConversationStore) -> Result<()> {
         let content = serde_json::to_string_pretty(store)?;
-        fs::write(&self.store_path, content)?;
+        let partial = self
+            .store_path
+            .with_extension(format!("json.{}.tmp", std::process::id()));
+        fs::write(&partial, content)?;
+        fs::rename(&partial, &self.store_path)?;
         Ok(())
     }
 
-    pub fn save_session(&self, session: &ConversationSession) -> Result<()> {
+    /// Load, change and save the store while holding its lock, so concurrent
+    /// `ask` sessions can't overwrite each other's changes
+    fn modify<T>(&self, f: impl FnOnce(&mut ConversationStore) -> Result<T>) -> Result<T> {
+        let _lock = self.lock()?;
         let mut store = self.load()?;
-        store.add_session(session.clone());
-        self.save(&store)
+        let value = f(&mut store)?;
+        self.save(&store)?;
+        Ok(value)
+    }
+
+    /// Take the store's lockfile, waiting while another process holds it.
+    /// A lock left behind by a process that no longer exists is taken over.
+    fn lock(&self) -> Result<StoreLock> {
+        let path = self.store_path.with_extension("json.lock");
+        let started = Instant::now();
+
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    write!(file, "{}", std::process::id())?;
+                    return Ok(StoreLock { path });
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    let holder = fs::read_to_string(&path)
+                        .ok()
+                        .and_then(|pid| pid.trim().parse::<u32>().ok());
+                    if holder.is_some_and(|pid| !process_is_running(pid)) {
+                        let _ = fs::remove_file(&path);
+                        continue;
+                    }
+
+                    if started.elapsed() > LOCK_TIMEOUT {
+                        return Err(ClixError::Io(std::io::Error::new(
+                            ErrorKind::TimedOut,
+                            format!(
+                                "Timed out waiting for the conversation store lock; delete {} if no other clix is running",
+                                path.display()
+                            ),
+                        )));
+                    }
+                    thread::sleep(Duration::from_millis(10));
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    pub fn save_session(&self, session: &ConversationSession) -> Result<()> {
+        self.modify(|store| {
+            store.add_session(session.clone());
+            Ok(())
+        })
     }
 
     pub fn get_session(&self, id: &str) -> Result<Option<ConversationSession>> {
@@ -54,17 +112,16 @@ impl ConversationStorage {
     }
 
     pub fn update_session(&self, session: &ConversationSession) -> Result<()> {
-        let mut store = self.load()?;
-        if store.sessions.contains_key(&session.id) {
+        self.modify(|store| {
+            if !store.sessions.contains_key(&session.id) {
+                return Err(ClixError::NotFound(format!(
+                    "Conversation session '{}' not found",
+                    session.id
+                )));
+            }
             store.sessions.insert(session.id.clone(), session.clone());
-            self.save(&store)?;
-        } else {
-            return Err(ClixError::NotFound(format!(
-                "Conversation session '{}' not found",
-                session.id
-            )));
-        }
-        Ok(())
+            Ok(())
+        })
     }
 
     pub fn list_active_sessions(&self) -> Result<Vec<ConversationSession>> {
@@ -73,20 +130,25 @@ impl ConversationStorage {
     }
 
     pub fn cleanup_expired_sessions(&self, max_age_hours: u64) -> Result<usize> {
-        let mut store = self.load()?;
-        let initial_count = store.sessions.len();
-        store.cleanup_expired_sessions(max_age_hours);
-        let final_count = store.sessions.len();
-        self.save(&store)?;
-        Ok(initial_count - final_count)
+        self.modify(|store| {
+            let initial_count = store.sessions.len();
+            store.cleanup_expired_sessions(max_age_hours);
+            Ok(initial_count - store.sessions.len())
+        })
     }
 
     pub fn remove_session(&self, id: &str) -> Result<bool> {
-        let mut store = self.load()?;
-        let removed = store.remove_session(id).is_some();
-        if removed {
-            self.save(&store)?;
-        }
-        Ok(removed)
+        self.modify(|store| Ok(store.remove_session(id).is_some()))
+    }
+}
+
+/// A held store lock; the lockfile is removed when this is dropped
+struct StoreLock {
+    path: PathBuf,
+}
+
+impl Drop for StoreLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
     }
 }
@@ -1,25 +1,33 @@
 use crate::commands::models::{Command, CommandStore, Workflow};
-use crate::error::Result;
+use crate::error::{ClixError, Result};
 use crate::git::GitRepositoryManager;
-use crate::settings::SettingsManager;
-use crate::storage::Storage;
+use crate::settings::{SettingsManager, config_dir};
+use crate::storage::{Storage, TagCount, TaggedItems};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 pub struct GitIntegratedStorage {
     local_storage: Storage,
     git_manager: GitRepositoryManager,
+    config_dir: PathBuf,
 }
 
 impl GitIntegratedStorage {
     pub fn new() -> Result<Self> {
-        let local_storage = Storage::new()?;
-        let mut git_manager = GitRepositoryManager::new()?;
+        Self::new_with_dir(config_dir()?)
+    }
+
+    /// Create storage whose store, settings and repositories live in a custom directory
+    pub fn new_with_dir(config_dir: PathBuf) -> Result<Self> {
+        let local_storage = Storage::new_with_dir(config_dir.clone())?;
+        let mut git_manager = GitRepositoryManager::new_with_dir(config_dir.clone())?;
         git_manager.load_configs()?;
 
         Ok(Self {
             local_storage,
             git_manager,
+            config_dir,
         })
     }
 
@@ -48,6 +56,26 @@ impl GitIntegratedStorage {
         Ok(())
     }
 
+    /// Pull and load every enabled repository, or only the named one. Items are
+    /// loaded from whatever is checked out even when the pull fails, and the
+    /// per-repository pull results are returned for reporting.
+    pub fn sync_repositories(&self, repo: Option<&str>) -> Result<Vec<(String, Result<()>)>> {
+        let Some(name) = repo else {
+            let results = self.git_manager.pull_all_repositories()?;
+            self.load_from_repositories()?;
+            return Ok(results);
+        };
+
+        let result = self.git_manager.pull_repository(name);
+        if let Some(repo_path) = self.git_manager.get_repo_path(name)? {
+            let mut local_store = self.local_storage.load()?;
+            self.load_from_repository(&repo_path, &mut local_store)?;
+            self.local_storage.save(&local_store)?;
+        }
+
+        Ok(vec![(name.to_string(), result)])
+    }
+
     pub fn load_from_repositories(&self) -> Result<()> {
         let repo_paths = self.git_manager.get_all_repo_paths();
         let mut local_store = self.local_storage.load()?;
@@ -60,10 +88,34 @@ impl GitIntegratedStorage {
         Ok(())
     }
 
+    /// Load `commands.json` and every `commands/*.json` file in the repository,
+    /// skipping files matched by the repository's `.clixignore`
     fn load_from_repository(&self, repo_path: &Path, local_store: &mut CommandStore) -> Result<()> {
-        // Look for commands.json in the repository
-        let commands_file = repo_path.join("commands.json");
-        if commands_file.exists() {
+        let ignored = Self::load_ignore_patterns(repo_path)?;
+
+        let mut command_files = vec![PathBuf::from("commands.json")];
+        let commands_dir = repo_path.join("commands");
+        if commands_dir.is_dir() {
+            let mut extra: Vec<PathBuf> = fs::read_dir(&commands_dir)?
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "json"))
+                .filter_map(|path| {
+                    path.strip_prefix(repo_path)
+                        .ok()
+                        .map(|relative| relative.to_path_buf())
+                })
+                .collect();
+            extra.sort();
+            command_files.extend(extra);
+        }
+
+        for relative in command_files {
+            let commands_file = repo_path.join(&relative);
+            if !commands_file.exists() || ignored.is_match(&relative) {
+                continue;
+            }
+
             let content = fs::read_to_string(&commands_file)?;
             let repo_store: CommandStore = serde_json::from_str(&content)?;
 
@@ -75,6 +127,43 @@ impl GitIntegratedStorage {
         Ok(())
     }
 
+    /// Read `.clixignore` at the repository root. Like `.gitignore`, each line is
+    /// a glob relative to the root, blank lines and `#` comments are skipped, and
+    /// a pattern without a `/` matches a file name in any directory.
+    fn load_ignore_patterns(repo_path: &Path) -> Result<GlobSet> {
+        let mut builder = GlobSetBuilder::new();
+        let ignore_file = repo_path.join(".clixignore");
+        if ignore_file.exists() {
+            for line in fs::read_to_string(&ignore_file)?.lines() {
+                let pattern = line.trim();
+                if pattern.is_empty() || pattern.starts_with('#') {
+                    continue;
+                }
+
+                let pattern = match pattern.strip_prefix('/') {
+                    Some(anchored) => anchored.to_string(),
+                    None if pattern.contains('/') => pattern.to_string(),
+                    None => format!("**/{}", pattern),
+                };
+                let glob = GlobBuilder::new(&pattern)
+                    .literal_separator(true)
+                    .build()
+                    .map_err(|e| {
+                        ClixError::ConfigurationError(format!(
+                            "Invalid pattern in {}: {}",
+                            ignore_file.display(),
+                            e
+                        ))
+                    })?;
+                builder.add(glob);
+            }
+        }
+
+        builder.build().map_err(|e| {
+            ClixError::ConfigurationError(format!("Invalid {}: {}", ignore_file.display(), e))
+        })
+    }
+
     fn merge_commands(
         &self,
         repo_commands: &std::collections::HashMap<String, Command>,
@@ -114,7 +203,7 @@ impl GitIntegratedStorage {
     }
 
     pub fn commit_changes_to_repositories(&self, message: &str) -> Result<()> {
-        let settings_manager = SettingsManager::new()?;
+        let settings_manager = SettingsManager::new_with_dir(self.config_dir.clone())?;
         let settings = settings_manager.load()?;
         let prefixed_message = format!(
             "{} {}",
@@ -138,10 +227,10 @@ impl GitIntegratedStorage {
         fs::write(&commands_file, content)?;
 
         // Find the repository config and commit
-        if let Some(repo_name) = repo_path.file_name().and_then(|n| n.to_str()) {
-            if let Some(repo) = self.git_manager.get_repository(repo_name) {
-                repo.commit_and_push(message, &["commands.json"])?;
-            }
+        if let Some(repo_name) = repo_path.file_name().and_then(|n| n.to_str())
+            && let Some(repo) = self.git_manager.get_repository(repo_name)
+        {
+            repo.commit_and_push(message, &["commands.json"])?;
         }
 
         Ok(())
@@ -152,10 +241,10 @@ impl GitIntegratedStorage {
         let result = self.local_storage.add_command(command);
 
         // If successful, try to commit to repositories
-        if result.is_ok() {
-            if let Err(e) = self.commit_changes_to_repositories("Add new command via clix") {
-                eprintln!("Warning: Failed to sync to git repositories: {}", e);
-            }
+        if result.is_ok()
+            && let Err(e) = self.commit_changes_to_repositories("Add new command via clix")
+        {
+            eprintln!("Warning: Failed to sync to git repositories: {}", e);
         }
 
         result
@@ -169,16 +258,23 @@ impl GitIntegratedStorage {
         self.local_storage.list_commands()
     }
 
+    pub fn find_by_tag(&self, tag: &str) -> Result<TaggedItems> {
+        self.local_storage.find_by_tag(tag)
+    }
+
+    pub fn tag_counts(&self) -> Result<Vec<TagCount>> {
+        self.local_storage.tag_counts()
+    }
+
     pub fn remove_command(&self, name: &str) -> Result<()> {
         let result = self.local_storage.remove_command(name);
 
         // If successful, try to commit to repositories
-        if result.is_ok() {
-            if let Err(e) =
+        if result.is_ok()
+            && let Err(e) =
                 self.commit_changes_to_repositories(&format!("Remove command: {}", name))
-            {
-                eprintln!("Warning: Failed to sync to git repositories: {}", e);
-            }
+        {
+            eprintln!("Warning: Failed to sync to git repositories: {}", e);
         }
 
         result
@@ -192,12 +288,28 @@ impl GitIntegratedStorage {
         let result = self.local_storage.update_command(command);
 
         // If successful, try to commit to repositories
-        if result.is_ok() {
-            if let Err(e) =
+        if result.is_ok()
+            && let Err(e) =
                 self.commit_changes_to_repositories(&format!("Update command: {}", command.name))
-            {
-                eprintln!("Warning: Failed to sync to git repositories: {}", e);
-            }
+        {
+            eprintln!("Warning: Failed to sync to git repositories: {}", e);
+        }
+
+        result
+    }
+
+    pub fn modify_command<F>(&self, name: &str, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut Command),
+    {
+        let result = self.local_storage.modify_command(name, f);
+
+        // If successful, try to commit to repositories
+        if result.is_ok()
+            && let Err(e) =
+                self.commit_changes_to_repositories(&format!("Update command: {}", name))
+        {
+            eprintln!("Warning: Failed to sync to git repositories: {}", e);
         }
 
         result
@@ -207,10 +319,10 @@ impl GitIntegratedStorage {
         let result = self.local_storage.add_workflow(workflow);
 
         // If successful, try to commit to repositories
-        if result.is_ok() {
-            if let Err(e) = self.commit_changes_to_repositories("Add new workflow via clix") {
-                eprintln!("Warning: Failed to sync to git repositories: {}", e);
-            }
+        if result.is_ok()
+            && let Err(e) = self.commit_changes_to_repositories("Add new workflow via clix")
+        {
+            eprintln!("Warning: Failed to sync to git repositories: {}", e);
         }
 
         result
@@ -228,12 +340,11 @@ impl GitIntegratedStorage {
         let result = self.local_storage.remove_workflow(name);
 
         // If successful, try to commit to repositories
-        if result.is_ok() {
-            if let Err(e) =
+        if result.is_ok()
+            && let Err(e) =
                 self.commit_changes_to_repositories(&format!("Remove workflow: {}", name))
-            {
-                eprintln!("Warning: Failed to sync to git repositories: {}", e);
-            }
+        {
+            eprintln!("Warning: Failed to sync to git repositories: {}", e);
         }
 
         result
@@ -247,12 +358,11 @@ impl GitIntegratedStorage {
         let result = self.local_storage.update_workflow(workflow);
 
         // If successful, try to commit to repositories
-        if result.is_ok() {
-            if let Err(e) =
+        if result.is_ok()
+            && let Err(e) =
                 self.commit_changes_to_repositories(&format!("Update workflow: {}", workflow.name))
-            {
-                eprintln!("Warning: Failed to sync to git repositories: {}", e);
-            }
+        {
+            eprintln!("Warning: Failed to sync to git repositories: {}", e);
         }
 
         result
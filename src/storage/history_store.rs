@@ -0,0 +1,240 @@
+use crate::commands::WorkflowContext;
+use crate::commands::variables::MASKED_VALUE;
+use crate::error::Result;
+use crate::settings::config_dir;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// What kind of stored entry was run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExecutionKind {
+    Command,
+    Workflow,
+}
+
+/// A single run of a stored command or workflow
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionRecord {
+    pub name: String,
+    pub kind: ExecutionKind,
+    /// Seconds since the Unix epoch when the run started
+    pub started_at: u64,
+    pub duration_ms: u64,
+    pub success: bool,
+    /// Security warnings were suppressed with `--allow-dangerous`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub allowed_dangerous: bool,
+    /// The conditions the run started in, recorded with `--capture-env`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<EnvironmentSnapshot>,
+}
+
+/// Environment variables kept in a snapshot, when set
+const CAPTURED_ENV_VARS: &[&str] = &["PATH", "PWD", "USER", "LANG", "TERM", "CI"];
+
+/// The conditions a run started in, for reproducing it later. Values of
+/// variables whose names look secret are masked.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnvironmentSnapshot {
+    pub os: String,
+    pub arch: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shell: Option<String>,
+    pub clix_version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// Workflow or command variables the run was given
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub variables: BTreeMap<String, String>,
+    /// A fixed selection of the process environment
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+}
+
+impl EnvironmentSnapshot {
+    /// Snapshot the current process for a run using `profile` and `variables`
+    pub fn capture(profile: Option<&str>, variables: &HashMap<String, String>) -> Self {
+        let env: BTreeMap<String, String> = CAPTURED_ENV_VARS
+            .iter()
+            .filter_map(|name| {
+                std::env::var(name)
+                    .ok()
+                    .map(|value| (name.to_string(), value))
+            })
+            .collect();
+
+        EnvironmentSnapshot {
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            shell: std::env::var("SHELL").ok(),
+            clix_version: env!("CARGO_PKG_VERSION").to_string(),
+            profile: profile.map(str::to_string),
+            variables: Self::redacted(variables.iter()),
+            env: Self::redacted(&env),
+        }
+    }
+
+    fn redacted<'a>(
+        values: impl IntoIterator<Item = (&'a String, &'a String)>,
+    ) -> BTreeMap<String, String> {
+        values
+            .into_iter()
+            .map(|(name, value)| {
+                let value = if WorkflowContext::is_secret_name(name) {
+                    MASKED_VALUE.to_string()
+                } else {
+                    value.clone()
+                };
+                (name.clone(), value)
+            })
+            .collect()
+    }
+}
+
+/// How long a command or workflow took over its recorded successful runs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DurationEstimate {
+    pub runs: usize,
+    pub average_ms: u64,
+    pub last_ms: u64,
+    /// 90th percentile, by nearest rank
+    pub p90_ms: u64,
+}
+
+/// Append-only log of runs, one JSON record per line
+pub struct HistoryStorage {
+    history_path: PathBuf,
+    /// Most runs kept; 0 keeps every run
+    max_entries: usize,
+}
+
+impl HistoryStorage {
+    pub fn new() -> Result<Self> {
+        Self::new_with_dir(config_dir()?)
+    }
+
+    /// Create history storage with a custom config directory
+    pub fn new_with_dir(store_dir: PathBuf) -> Result<Self> {
+        fs::create_dir_all(&store_dir)?;
+
+        let history_path = store_dir.join("history.jsonl");
+
+        Ok(HistoryStorage {
+            history_path,
+            max_entries: 0,
+        })
+    }
+
+    /// Keep only the newest `max_entries` runs, dropping the oldest as new
+    /// ones are recorded. 0 keeps every run.
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    /// Append a run to the history
+    pub fn record(&self, record: &ExecutionRecord) -> Result<()> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.history_path)?;
+        writeln!(file, "{}", serde_json::to_string(record)?)?;
+
+        if self.max_entries > 0 {
+            self.trim(self.max_entries)?;
+        }
+        Ok(())
+    }
+
+    /// Drop the oldest runs until at most `keep` are left
+    fn trim(&self, keep: usize) -> Result<()> {
+        let content = fs::read_to_string(&self.history_path)?;
+        let lines: Vec<&str> = content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .collect();
+        if lines.len() <= keep {
+            return Ok(());
+        }
+
+        let mut kept = lines[lines.len() - keep..].join("\n");
+        kept.push('\n');
+        fs::write(&self.history_path, kept)?;
+        Ok(())
+    }
+
+    /// Delete every recorded run, returning how many there were
+    pub fn clear(&self) -> Result<usize> {
+        let count = self.load()?.len();
+        if self.history_path.exists() {
+            fs::remove_file(&self.history_path)?;
+        }
+        Ok(count)
+    }
+
+    /// Load every recorded run, oldest first
+    pub fn load(&self) -> Result<Vec<ExecutionRecord>> {
+        if !self.history_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&self.history_path)?;
+        let mut records = Vec::new();
+        for line in content.lines().filter(|line| !line.trim().is_empty()) {
+            records.push(serde_json::from_str(line)?);
+        }
+        Ok(records)
+    }
+
+    /// Whether the most recent run of each workflow succeeded
+    pub fn last_status_per_workflow(&self) -> Result<HashMap<String, bool>> {
+        let mut statuses = HashMap::new();
+        for record in self.load()? {
+            if record.kind == ExecutionKind::Workflow {
+                statuses.insert(record.name, record.success);
+            }
+        }
+        Ok(statuses)
+    }
+
+    /// Estimate how long `name` takes from its successful runs, or None
+    /// when it has none recorded
+    pub fn duration_estimate(&self, name: &str) -> Result<Option<DurationEstimate>> {
+        let durations: Vec<u64> = self
+            .load()?
+            .into_iter()
+            .filter(|record| record.name == name && record.success)
+            .map(|record| record.duration_ms)
+            .collect();
+        let Some(&last_ms) = durations.last() else {
+            return Ok(None);
+        };
+
+        let mut sorted = durations.clone();
+        sorted.sort_unstable();
+        let p90_rank = (sorted.len() * 9).div_ceil(10);
+
+        Ok(Some(DurationEstimate {
+            runs: durations.len(),
+            average_ms: durations.iter().sum::<u64>() / durations.len() as u64,
+            last_ms,
+            p90_ms: sorted[p90_rank - 1],
+        }))
+    }
+
+    /// Names of the workflows whose last run failed, sorted
+    pub fn failed_workflows(&self) -> Result<Vec<String>> {
+        let mut failed: Vec<String> = self
+            .last_status_per_workflow()?
+            .into_iter()
+            .filter(|(_, success)| !success)
+            .map(|(name, _)| name)
+            .collect();
+        failed.sort();
+        Ok(failed)
+    }
+}
@@ -0,0 +1,113 @@
+use crate::error::{ClixError, Result};
+use crate::settings::config_dir;
+use crate::storage::file_stem;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+
+/// Per-command lockfiles that stop two runs of the same command overlapping
+pub struct LockStorage {
+    locks_dir: PathBuf,
+}
+
+/// A held lock; the lockfile is removed when this is dropped
+#[derive(Debug)]
+pub struct RunLock {
+    path: PathBuf,
+}
+
+impl LockStorage {
+    pub fn new() -> Result<Self> {
+        Self::new_with_dir(config_dir()?)
+    }
+
+    /// Create lock storage with a custom config directory
+    pub fn new_with_dir(store_dir: PathBuf) -> Result<Self> {
+        let locks_dir = store_dir.join("locks");
+        fs::create_dir_all(&locks_dir)?;
+
+        Ok(LockStorage { locks_dir })
+    }
+
+    /// Take the lock for a command, failing straight away if another run holds it.
+    /// Locks left behind by a process that no longer exists are taken over.
+    pub fn acquire(&self, name: &str) -> Result<RunLock> {
+        let path = self.lock_path(name);
+
+        for _ in 0..2 {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    write!(file, "{}", std::process::id())?;
+                    return Ok(RunLock { path });
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    let holder = fs::read_to_string(&path)
+                        .ok()
+                        .and_then(|pid| pid.trim().parse::<u32>().ok());
+
+                    if holder.is_some_and(|pid| !process_is_running(pid)) {
+                        fs::remove_file(&path)?;
+                        continue;
+                    }
+
+                    return Err(ClixError::AlreadyRunning(format!(
+                        "'{}' is already running{}. Wait for it to finish, or delete {} if that run is gone",
+                        name,
+                        holder
+                            .map(|pid| format!(" (pid {})", pid))
+                            .unwrap_or_default(),
+                        path.display()
+                    )));
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Err(ClixError::CommandExecutionFailed(format!(
+            "Could not take the lock for '{}'",
+            name
+        )))
+    }
+
+    fn lock_path(&self, name: &str) -> PathBuf {
+        self.locks_dir.join(format!("{}.lock", file_stem(name)))
+    }
+}
+
+impl Drop for RunLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Whether a process is still alive. Only Linux can tell cheaply; elsewhere
+/// every lock is assumed to be live.
+pub(super) fn process_is_running(pid: u32) -> bool {
+    if cfg!(target_os = "linux") {
+        PathBuf::from(format!("/proc/{}", pid)).exists()
+    } else {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_lock_is_exclusive_until_dropped() {
+        let temp_dir = TempDir::new().unwrap();
+        let locks = LockStorage::new_with_dir(temp_dir.path().to_path_buf()).unwrap();
+
+        let held = locks.acquire("deploy").unwrap();
+        assert!(matches!(
+            locks.acquire("deploy"),
+            Err(ClixError::AlreadyRunning(_))
+        ));
+        assert!(locks.acquire("other").is_ok());
+
+        drop(held);
+        assert!(locks.acquire("deploy").is_ok());
+    }
+}
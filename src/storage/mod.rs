@@ -1,7 +1,46 @@
+mod checkpoint_store;
 mod conversation_store;
 mod git_storage;
+mod history_store;
+mod lock_store;
+mod snippet_store;
 mod store;
 
+pub use checkpoint_store::{CheckpointStorage, LoopCheckpoint};
 pub use conversation_store::ConversationStorage;
 pub use git_storage::GitIntegratedStorage;
-pub use store::Storage;
+pub use history_store::{
+    DurationEstimate, EnvironmentSnapshot, ExecutionKind, ExecutionRecord, HistoryStorage,
+};
+pub use lock_store::{LockStorage, RunLock};
+pub use snippet_store::SnippetStorage;
+pub use store::{Storage, TagCount, TaggedItems};
+
+/// A file name for `name` made of letters, digits, `-` and `_`. Any other
+/// byte is written as `%XX`, so different names never share a file.
+pub(crate) fn file_stem(name: &str) -> String {
+    let mut stem = String::with_capacity(name.len());
+    for byte in name.bytes() {
+        if byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_' {
+            stem.push(byte as char);
+        } else {
+            stem.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    stem
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_stem_keeps_distinct_names_apart() {
+        assert_eq!(file_stem("deploy-app_2"), "deploy-app_2");
+        assert_eq!(file_stem("a/b"), "a%2Fb");
+        assert_eq!(file_stem("a_b"), "a_b");
+        assert_eq!(file_stem("a%2Fb"), "a%252Fb");
+        assert_eq!(file_stem("../x"), "%2E%2E%2Fx");
+        assert_eq!(file_stem("café"), "caf%C3%A9");
+    }
+}
@@ -0,0 +1,66 @@
+use crate::commands::models::WorkflowStep;
+use crate::error::{ClixError, Result};
+use crate::settings::config_dir;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+
+/// Named lists of steps that workflows pull in with `include`
+pub struct SnippetStorage {
+    store_path: PathBuf,
+}
+
+impl SnippetStorage {
+    pub fn new() -> Result<Self> {
+        Self::new_with_dir(config_dir()?)
+    }
+
+    /// Create snippet storage with a custom config directory
+    pub fn new_with_dir(store_dir: PathBuf) -> Result<Self> {
+        fs::create_dir_all(&store_dir)?;
+
+        let store_path = store_dir.join("snippets.json");
+
+        Ok(SnippetStorage { store_path })
+    }
+
+    /// Load every snippet by name
+    pub fn load(&self) -> Result<HashMap<String, Vec<WorkflowStep>>> {
+        if !self.store_path.exists() {
+            return Ok(HashMap::new());
+        }
+
+        let content = fs::read_to_string(&self.store_path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    fn save(&self, snippets: &HashMap<String, Vec<WorkflowStep>>) -> Result<()> {
+        // Sorted so the file diffs cleanly
+        let sorted: BTreeMap<_, _> = snippets.iter().collect();
+        fs::write(&self.store_path, serde_json::to_string_pretty(&sorted)?)?;
+        Ok(())
+    }
+
+    /// Store a snippet, replacing any with the same name. Returns whether one was replaced.
+    pub fn add(&self, name: &str, steps: Vec<WorkflowStep>) -> Result<bool> {
+        if steps.is_empty() {
+            return Err(ClixError::InvalidInput(format!(
+                "Snippet '{}' needs at least one step",
+                name
+            )));
+        }
+
+        let mut snippets = self.load()?;
+        let replaced = snippets.insert(name.to_string(), steps).is_some();
+        self.save(&snippets)?;
+        Ok(replaced)
+    }
+
+    pub fn remove(&self, name: &str) -> Result<()> {
+        let mut snippets = self.load()?;
+        if snippets.remove(name).is_none() {
+            return Err(ClixError::NotFound(format!("Snippet '{}' not found", name)));
+        }
+        self.save(&snippets)
+    }
+}
@@ -1,7 +1,9 @@
 use crate::commands::models::{Command, CommandStore, Workflow};
 use crate::error::{ClixError, Result};
-use dirs::home_dir;
+use crate::settings::{DataLocation, config_dir};
+use serde::Serialize;
 use std::cell::RefCell;
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 use std::time::SystemTime;
@@ -10,6 +12,34 @@ use std::time::SystemTime;
 pub struct Storage {
     store_path: PathBuf,
     cache: RefCell<Option<CachedStore>>,
+    /// Number of times the store file has been written
+    #[cfg(test)]
+    writes: std::cell::Cell<usize>,
+}
+
+/// Stored items that carry a given tag
+#[derive(Debug, Clone, Default)]
+pub struct TaggedItems {
+    pub commands: Vec<Command>,
+    /// Workflows still kept in the legacy workflow map
+    pub workflows: Vec<Workflow>,
+}
+
+impl TaggedItems {
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty() && self.workflows.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len() + self.workflows.len()
+    }
+}
+
+/// A tag and how many stored items carry it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TagCount {
+    pub tag: String,
+    pub count: usize,
 }
 
 #[derive(Clone)]
@@ -21,36 +51,30 @@ struct CachedStore {
 
 impl Storage {
     pub fn new() -> Result<Self> {
-        let store_dir = home_dir()
-            .ok_or_else(|| {
-                ClixError::Io(std::io::Error::new(
-                    std::io::ErrorKind::NotFound,
-                    "Could not determine home directory",
-                ))
-            })?
-            .join(".clix");
-
-        fs::create_dir_all(&store_dir)?;
-
-        let store_path = store_dir.join("commands.json");
-
-        Ok(Storage {
-            store_path,
-            cache: RefCell::new(None),
-        })
+        Self::new_with_dir(config_dir()?)
     }
 
-    /// Create storage with custom directory for testing
+    /// Create storage with a custom config directory
     pub fn new_with_dir(store_dir: PathBuf) -> Result<Self> {
         fs::create_dir_all(&store_dir)?;
-        let store_path = store_dir.join("commands.json");
+        let store_path = DataLocation::Store.path(&store_dir);
 
         Ok(Storage {
             store_path,
             cache: RefCell::new(None),
+            #[cfg(test)]
+            writes: std::cell::Cell::new(0),
         })
     }
 
+    /// Directory holding the store and its companion state files
+    pub fn data_dir(&self) -> PathBuf {
+        self.store_path
+            .parent()
+            .map(PathBuf::from)
+            .unwrap_or_default()
+    }
+
     /// Load store with caching for improved performance
     pub fn load(&self) -> Result<CommandStore> {
         self.load_with_cache()
@@ -68,10 +92,11 @@ impl Storage {
 
         // Check cache validity
         let mut cache = self.cache.borrow_mut();
-        if let Some(ref cached) = *cache {
-            if cached.last_modified >= file_modified && !cached.dirty {
-                return Ok(cached.store.clone());
-            }
+        if let Some(ref cached) = *cache
+            && cached.last_modified >= file_modified
+            && !cached.dirty
+        {
+            return Ok(cached.store.clone());
         }
 
         // Load from disk and update cache
@@ -102,6 +127,8 @@ impl Storage {
     pub fn save(&self, store: &CommandStore) -> Result<()> {
         let content = serde_json::to_string_pretty(store)?;
         fs::write(&self.store_path, content)?;
+        #[cfg(test)]
+        self.writes.set(self.writes.get() + 1);
 
         // Update cache with new data
         let file_modified = fs::metadata(&self.store_path)?.modified()?;
@@ -187,7 +214,9 @@ impl Storage {
         let mut store = self.load()?;
 
         if store.commands.contains_key(&command.name) {
-            store.commands.insert(command.name.clone(), command.clone());
+            let mut command = command.clone();
+            command.mark_updated();
+            store.commands.insert(command.name.clone(), command);
             self.save(&store)?;
             Ok(())
         } else {
@@ -195,6 +224,75 @@ impl Storage {
         }
     }
 
+    /// Apply `f` to a stored command and save the result with a single write
+    pub fn modify_command<F>(&self, name: &str, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut Command),
+    {
+        let mut store = self.load()?;
+        let command = store
+            .commands
+            .get_mut(name)
+            .ok_or_else(|| ClixError::CommandNotFound(name.to_string()))?;
+
+        f(command);
+        command.mark_updated();
+        self.save(&store)
+    }
+
+    /// Find the commands and workflows tagged with `tag`, sorted by name
+    pub fn find_by_tag(&self, tag: &str) -> Result<TaggedItems> {
+        let store = self.load_with_cache()?;
+        let has_tag = |tags: &[String]| tags.iter().any(|t| t == tag);
+
+        let mut commands: Vec<Command> = store
+            .commands
+            .into_values()
+            .filter(|command| has_tag(&command.tags))
+            .collect();
+        commands.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut workflows: Vec<Workflow> = store
+            .workflows
+            .into_values()
+            .filter(|workflow| has_tag(&workflow.tags))
+            .collect();
+        workflows.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(TaggedItems {
+            commands,
+            workflows,
+        })
+    }
+
+    /// Every tag on a stored command or workflow, sorted by tag, with the
+    /// number of items that carry it. An item listing a tag twice counts once.
+    pub fn tag_counts(&self) -> Result<Vec<TagCount>> {
+        let store = self.load_with_cache()?;
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        let item_tags = store
+            .commands
+            .values()
+            .map(|command| &command.tags)
+            .chain(store.workflows.values().map(|workflow| &workflow.tags));
+        for tags in item_tags {
+            let mut seen: Vec<&str> = tags.iter().map(String::as_str).collect();
+            seen.sort_unstable();
+            seen.dedup();
+            for tag in seen {
+                *counts.entry(tag).or_default() += 1;
+            }
+        }
+
+        Ok(counts
+            .into_iter()
+            .map(|(tag, count)| TagCount {
+                tag: tag.to_string(),
+                count,
+            })
+            .collect())
+    }
+
     pub fn add_workflow(&self, workflow: Workflow) -> Result<()> {
         let mut store = self.load()?;
         store.workflows.insert(workflow.name.clone(), workflow);
@@ -252,9 +350,9 @@ impl Storage {
         let mut store = self.load()?;
 
         if store.workflows.contains_key(&workflow.name) {
-            store
-                .workflows
-                .insert(workflow.name.clone(), workflow.clone());
+            let mut workflow = workflow.clone();
+            workflow.mark_updated();
+            store.workflows.insert(workflow.name.clone(), workflow);
             self.save(&store)?;
             Ok(())
         } else {
@@ -262,3 +360,101 @@ impl Storage {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::WorkflowVariable;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_modify_command_persists_with_one_write() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = Storage::new_with_dir(temp_dir.path().to_path_buf()).unwrap();
+        storage
+            .add_command(Command::new_workflow(
+                "deploy".to_string(),
+                "Deploy the app".to_string(),
+                vec![],
+                vec![],
+            ))
+            .unwrap();
+        let writes_before = storage.writes.get();
+
+        storage
+            .modify_command("deploy", |command| {
+                command.add_variable(WorkflowVariable::new(
+                    "env".to_string(),
+                    "Target environment".to_string(),
+                    None,
+                    true,
+                ))
+            })
+            .unwrap();
+
+        assert_eq!(storage.writes.get(), writes_before + 1);
+
+        // A fresh instance reads the change back from disk
+        let reloaded = Storage::new_with_dir(temp_dir.path().to_path_buf()).unwrap();
+        let command = reloaded.get_command("deploy").unwrap();
+        assert_eq!(command.variables.len(), 1);
+        assert_eq!(command.variables[0].name, "env");
+    }
+
+    #[test]
+    fn test_modify_missing_command_does_not_write() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = Storage::new_with_dir(temp_dir.path().to_path_buf()).unwrap();
+
+        let result = storage.modify_command("missing", |_| panic!("should not be called"));
+
+        assert!(matches!(result, Err(ClixError::CommandNotFound(_))));
+        assert_eq!(storage.writes.get(), 0);
+    }
+
+    #[test]
+    fn test_tag_counts_cover_commands_and_workflows() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = Storage::new_with_dir(temp_dir.path().to_path_buf()).unwrap();
+        let tags = |names: &[&str]| names.iter().map(|name| name.to_string()).collect();
+        storage
+            .add_command(Command::new(
+                "build".to_string(),
+                "Build".to_string(),
+                "make".to_string(),
+                tags(&["ci", "rust", "ci"]),
+            ))
+            .unwrap();
+        storage
+            .add_command(Command::new(
+                "lint".to_string(),
+                "Lint".to_string(),
+                "cargo clippy".to_string(),
+                tags(&["rust"]),
+            ))
+            .unwrap();
+        storage
+            .add_workflow(Workflow::new(
+                "release".to_string(),
+                "Release".to_string(),
+                vec![],
+                tags(&["ci", "deploy"]),
+            ))
+            .unwrap();
+
+        let counts: Vec<(String, usize)> = storage
+            .tag_counts()
+            .unwrap()
+            .into_iter()
+            .map(|tag| (tag.tag, tag.count))
+            .collect();
+        assert_eq!(
+            counts,
+            [
+                ("ci".to_string(), 2),
+                ("deploy".to_string(), 1),
+                ("rust".to_string(), 2),
+            ]
+        );
+    }
+}
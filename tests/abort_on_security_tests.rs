@@ -0,0 +1,82 @@
+mod common;
+
+use common::clix;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn add_workflow(home: &Path, name: &str, steps: serde_json::Value) {
+    let steps_file = home.join(format!("{}.json", name));
+    fs::write(&steps_file, steps.to_string()).unwrap();
+    let added = clix(
+        home,
+        &[
+            "add",
+            name,
+            "--description",
+            "Security gate test",
+            "--steps-file",
+            steps_file.to_str().unwrap(),
+        ],
+    );
+    assert!(added.status.success());
+}
+
+#[test]
+fn test_flagged_workflow_exits_3_without_running_a_step() {
+    let home = TempDir::new().unwrap();
+    let marker = home.path().join("ran");
+    add_workflow(
+        home.path(),
+        "cleanup",
+        serde_json::json!([
+            {
+                "name": "Mark",
+                "command": format!("touch {}", marker.display()),
+                "description": "Harmless first step",
+                "continue_on_error": false,
+                "step_type": "Command",
+                "require_approval": false
+            },
+            {
+                "name": "Wipe",
+                "command": "rm -rf /tmp/clix-gate-test/*",
+                "description": "Flagged by the security checks",
+                "continue_on_error": false,
+                "step_type": "Command",
+                "require_approval": false
+            }
+        ]),
+    );
+
+    let output = clix(home.path(), &["run", "cleanup", "--abort-on-security"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("security gate"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(!marker.exists());
+}
+
+#[test]
+fn test_clean_workflow_runs_with_the_gate() {
+    let home = TempDir::new().unwrap();
+    add_workflow(
+        home.path(),
+        "greet",
+        serde_json::json!([
+            {
+                "name": "Hello",
+                "command": "echo hello",
+                "description": "Nothing to flag",
+                "continue_on_error": false,
+                "step_type": "Command",
+                "require_approval": false
+            }
+        ]),
+    );
+
+    let output = clix(home.path(), &["run", "greet", "--abort-on-security"]);
+    assert!(output.status.success());
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use clix::ai::claude::ClaudeAction;
+use clix::ai::fallback;
+use clix::ai::mock::MockClaudeAssistant;
+use common::clix_command;
+use std::cell::RefCell;
+use std::path::Path;
+use std::process::Output;
+use tempfile::TempDir;
+
+fn clix(home: &Path, args: &[&str]) -> Output {
+    clix_command(home, args)
+        .env_remove("ANTHROPIC_API_KEY")
+        .output()
+        .expect("failed to run clix")
+}
+
+#[test]
+fn test_unknown_name_is_asked_about_when_enabled() {
+    let asked = RefCell::new(Vec::new());
+    let answer = fallback::ask_about_unknown("create-command", true, |question| {
+        asked.borrow_mut().push(question.to_string());
+        Ok(MockClaudeAssistant::mock_response(question))
+    })
+    .unwrap();
+
+    assert_eq!(
+        asked.into_inner(),
+        [
+            "How do I create command? If none of my commands does this, suggest a command named 'create-command' to create."
+        ]
+    );
+    let (_, action) = answer.unwrap();
+    assert!(matches!(action, ClaudeAction::CreateCommand { .. }));
+}
+
+#[test]
+fn test_unknown_name_is_not_asked_about_when_disabled() {
+    let answer = fallback::ask_about_unknown("list-pods", false, |_| {
+        panic!("the assistant must not be asked when the fallback is off")
+    })
+    .unwrap();
+    assert!(answer.is_none());
+}
+
+#[test]
+fn test_run_of_unknown_name_routes_to_the_assistant_only_when_enabled() {
+    let home = TempDir::new().unwrap();
+
+    let output = clix(home.path(), &["run", "list-pods"]);
+    assert!(!output.status.success());
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("asking the assistant"));
+
+    let set = clix(
+        home.path(),
+        &["settings", "set", "execution_settings.ai_fallback", "true"],
+    );
+    assert!(set.status.success());
+
+    // With no API key the question can't be sent, but it is routed there
+    let output = clix(home.path(), &["run", "list-pods"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success());
+    assert!(
+        stdout.contains("No command named 'list-pods'; asking the assistant"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("How do I list pods?"), "{}", stdout);
+}
@@ -0,0 +1,46 @@
+mod common;
+
+use common::clix_command;
+use std::fs;
+use std::process::Output;
+use tempfile::TempDir;
+
+fn clix(home: &TempDir, args: &[&str]) -> Output {
+    // Commands refer to files relative to the home directory
+    clix_command(home.path(), args)
+        .current_dir(home.path())
+        .output()
+        .expect("failed to run clix")
+}
+
+#[test]
+fn test_allow_dangerous_suppresses_security_warnings() {
+    let home = TempDir::new().unwrap();
+    let added = clix(
+        &home,
+        &[
+            "add",
+            "clean",
+            "--description",
+            "Remove the build log",
+            "--command",
+            "rm -f build.log",
+        ],
+    );
+    assert!(added.status.success());
+
+    let warned = clix(&home, &["run", "clean"]);
+    assert!(warned.status.success());
+    assert!(String::from_utf8_lossy(&warned.stdout).contains("Security Warning"));
+
+    let allowed = clix(&home, &["run", "clean", "--allow-dangerous"]);
+    assert!(allowed.status.success());
+    assert!(!String::from_utf8_lossy(&allowed.stdout).contains("Security Warning"));
+
+    // Only the run that used the override is marked in the history
+    let history = fs::read_to_string(home.path().join(".clix").join("history.jsonl")).unwrap();
+    let lines: Vec<&str> = history.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(!lines[0].contains("allowed_dangerous"));
+    assert!(lines[1].contains("\"allowed_dangerous\":true"));
+}
@@ -0,0 +1,56 @@
+use clix::ai::claude::{ActionFilter, ClaudeAction};
+use clix::ai::mock::MockClaudeAssistant;
+
+fn suggested(question: &str) -> ClaudeAction {
+    MockClaudeAssistant::mock_response(question).1
+}
+
+#[test]
+fn test_create_only_ignores_run_suggestions() {
+    let filter = ActionFilter::from_flags(true, false);
+
+    let (action, note) = filter.apply(ClaudeAction::RunCommand("list-pods".to_string()));
+    assert_eq!(action, ClaudeAction::NoAction);
+    assert_eq!(
+        note.as_deref(),
+        Some("Ignored the suggestion to run command 'list-pods' because of --create-only")
+    );
+
+    let (action, note) = filter.apply(ClaudeAction::RunWorkflow("deploy".to_string()));
+    assert_eq!(action, ClaudeAction::NoAction);
+    assert!(note.is_some());
+
+    let (action, note) = filter.apply(suggested("create command"));
+    assert!(matches!(action, ClaudeAction::CreateCommand { .. }));
+    assert!(note.is_none());
+}
+
+#[test]
+fn test_run_only_ignores_create_suggestions() {
+    let filter = ActionFilter::from_flags(false, true);
+
+    let (action, note) = filter.apply(suggested("create command"));
+    assert_eq!(action, ClaudeAction::NoAction);
+    assert_eq!(
+        note.as_deref(),
+        Some("Ignored the suggestion to create command 'test-echo' because of --run-only")
+    );
+
+    let (action, note) = filter.apply(suggested("create workflow"));
+    assert_eq!(action, ClaudeAction::NoAction);
+    assert!(note.is_some());
+
+    let (action, note) = filter.apply(ClaudeAction::RunCommand("list-pods".to_string()));
+    assert_eq!(action, ClaudeAction::RunCommand("list-pods".to_string()));
+    assert!(note.is_none());
+}
+
+#[test]
+fn test_without_flags_every_suggestion_passes() {
+    let filter = ActionFilter::from_flags(false, false);
+    assert_eq!(filter, ActionFilter::All);
+
+    let (action, note) = filter.apply(suggested("create workflow"));
+    assert!(matches!(action, ClaudeAction::CreateWorkflow { .. }));
+    assert!(note.is_none());
+}
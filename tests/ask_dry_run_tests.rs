@@ -0,0 +1,42 @@
+mod common;
+
+use common::clix_command;
+use std::path::Path;
+use std::process::Output;
+use tempfile::TempDir;
+
+fn clix(home: &Path, args: &[&str]) -> Output {
+    clix_command(home, args)
+        .env_remove("ANTHROPIC_API_KEY")
+        .output()
+        .expect("failed to run clix")
+}
+
+#[test]
+fn test_dry_run_prints_prompt_without_calling_claude() {
+    let home = TempDir::new().unwrap();
+    let added = clix(
+        home.path(),
+        &[
+            "add",
+            "list-pods",
+            "--description",
+            "List running pods",
+            "--command",
+            "kubectl get pods",
+        ],
+    );
+    assert!(added.status.success());
+
+    // No API key is set, so this only succeeds if no request is made
+    let output = clix(home.path(), &["ask", "--dry-run", "How do I see my pods?"]);
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("=== System prompt ==="));
+    assert!(stdout.contains("Available commands:"));
+    assert!(stdout.contains("- list-pods: List running pods"));
+    assert!(stdout.contains("Command: kubectl get pods"));
+    assert!(stdout.contains("=== User message ===\nHow do I see my pods?"));
+    assert!(stdout.contains("Estimated input tokens:"));
+}
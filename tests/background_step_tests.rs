@@ -0,0 +1,178 @@
+use clix::commands::{CommandExecutor, Condition, ExecutionOptions, Workflow, WorkflowStep};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+fn options() -> ExecutionOptions {
+    ExecutionOptions {
+        require_approval: false,
+        ..ExecutionOptions::default()
+    }
+}
+
+/// Start a `sleep` that records its pid, so the test can check on it later
+fn background_sleep(pid_file: &Path) -> WorkflowStep {
+    WorkflowStep::new_command(
+        "Server".to_string(),
+        format!("echo $$ > {}; exec sleep 30", pid_file.display()),
+        "Long-running process".to_string(),
+        false,
+    )
+    .in_background()
+}
+
+fn wait_for_pid(pid_file: &Path) -> String {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while Instant::now() < deadline {
+        if let Ok(pid) = fs::read_to_string(pid_file)
+            && !pid.trim().is_empty()
+        {
+            return pid.trim().to_string();
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    panic!("background step never wrote its pid");
+}
+
+fn is_running(pid: &str) -> bool {
+    Command::new("kill")
+        .args(["-0", pid])
+        .status()
+        .unwrap()
+        .success()
+}
+
+#[cfg(unix)]
+#[test]
+fn test_background_step_killed_at_workflow_end() {
+    let temp_dir = TempDir::new().unwrap();
+    let pid_file = temp_dir.path().join("server.pid");
+
+    let workflow = Workflow::new(
+        "background_test".to_string(),
+        "Workflow with a background step".to_string(),
+        vec![
+            background_sleep(&pid_file),
+            WorkflowStep::new_command(
+                "Wait for start".to_string(),
+                format!(
+                    "for i in $(seq 50); do [ -s {} ] && exit 0; sleep 0.1; done; exit 1",
+                    pid_file.display()
+                ),
+                "Wait until the server recorded its pid".to_string(),
+                false,
+            ),
+        ],
+        vec![],
+    );
+
+    let started = Instant::now();
+    let results =
+        CommandExecutor::execute_workflow_with_options(&workflow, None, None, &options()).unwrap();
+
+    // The workflow did not wait for the 30 second sleep
+    assert!(started.elapsed() < Duration::from_secs(20));
+    assert_eq!(results.len(), 2);
+    assert!(results.iter().all(|(_, result)| result.is_ok()));
+
+    let pid = wait_for_pid(&pid_file);
+    assert!(
+        !is_running(&pid),
+        "background process {} still running",
+        pid
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_stop_background_step_kills_processes() {
+    let temp_dir = TempDir::new().unwrap();
+    let pid_file = temp_dir.path().join("server.pid");
+    let check_file = temp_dir.path().join("running.txt");
+
+    let workflow = Workflow::new(
+        "stop_background_test".to_string(),
+        "Workflow stopping its background step explicitly".to_string(),
+        vec![
+            background_sleep(&pid_file),
+            WorkflowStep::new_command(
+                "Wait for start".to_string(),
+                format!(
+                    "for i in $(seq 50); do [ -s {} ] && exit 0; sleep 0.1; done; exit 1",
+                    pid_file.display()
+                ),
+                "Wait until the server recorded its pid".to_string(),
+                false,
+            ),
+            WorkflowStep::new_stop_background(
+                "Stop server".to_string(),
+                "Tear down background processes".to_string(),
+            ),
+            WorkflowStep::new_command(
+                "Check stopped".to_string(),
+                format!(
+                    "if kill -0 $(cat {}) 2>/dev/null; then echo yes; else echo no; fi > {}",
+                    pid_file.display(),
+                    check_file.display()
+                ),
+                "Record whether the server still runs".to_string(),
+                false,
+            ),
+        ],
+        vec![],
+    );
+
+    CommandExecutor::execute_workflow_with_options(&workflow, None, None, &options()).unwrap();
+
+    assert_eq!(fs::read_to_string(&check_file).unwrap().trim(), "no");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_background_step_in_conditional_outlives_the_block() {
+    let temp_dir = TempDir::new().unwrap();
+    let pid_file = temp_dir.path().join("server.pid");
+    let check_file = temp_dir.path().join("running.txt");
+
+    let workflow = Workflow::new(
+        "conditional_background_test".to_string(),
+        "Workflow starting a background step inside a conditional".to_string(),
+        vec![
+            WorkflowStep::new_conditional(
+                "Maybe start server".to_string(),
+                "Start the server when asked to".to_string(),
+                Condition {
+                    expression: "true".to_string(),
+                    variable: None,
+                },
+                vec![background_sleep(&pid_file)],
+                None,
+                None,
+            ),
+            WorkflowStep::new_command(
+                "Check running".to_string(),
+                format!(
+                    "for i in $(seq 50); do [ -s {pid} ] && break; sleep 0.1; done; \
+                     if kill -0 $(cat {pid}) 2>/dev/null; then echo yes; else echo no; fi > {check}",
+                    pid = pid_file.display(),
+                    check = check_file.display()
+                ),
+                "Record whether the server still runs".to_string(),
+                false,
+            ),
+        ],
+        vec![],
+    );
+
+    CommandExecutor::execute_workflow_with_options(&workflow, None, None, &options()).unwrap();
+
+    assert_eq!(fs::read_to_string(&check_file).unwrap().trim(), "yes");
+    let pid = wait_for_pid(&pid_file);
+    assert!(
+        !is_running(&pid),
+        "background process {} still running",
+        pid
+    );
+}
@@ -0,0 +1,107 @@
+mod common;
+
+use clix::storage::Storage;
+use common::clix;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn add(home: &Path, name: &str, tags: &[&str]) {
+    let mut args = vec![
+        "add",
+        name,
+        "--description",
+        "Bulk test command",
+        "--command",
+        "echo hi",
+    ];
+    for tag in tags {
+        args.extend(["--tags", tag]);
+    }
+    assert!(clix(home, &args).status.success());
+}
+
+fn stored_names(home: &Path) -> Vec<String> {
+    let storage = Storage::new_with_dir(home.join(".clix")).unwrap();
+    let mut names: Vec<String> = storage
+        .list_commands()
+        .unwrap()
+        .into_iter()
+        .map(|command| command.name)
+        .collect();
+    names.sort();
+    names
+}
+
+fn seed(home: &Path) {
+    add(home, "old-build", &["deprecated"]);
+    add(home, "old-deploy", &["deprecated", "ci"]);
+    add(home, "build", &["ci"]);
+}
+
+#[test]
+fn test_bulk_remove_only_affects_tagged_items() {
+    let home = TempDir::new().unwrap();
+    seed(home.path());
+
+    let output = clix(
+        home.path(),
+        &["bulk", "remove", "--tag", "deprecated", "--yes"],
+    );
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("2 item(s) tagged 'deprecated'"));
+    assert!(stdout.contains("Removed 2 item(s)"));
+    assert_eq!(stored_names(home.path()), vec!["build"]);
+}
+
+#[test]
+fn test_bulk_dry_run_and_unconfirmed_leave_items_intact() {
+    let home = TempDir::new().unwrap();
+    seed(home.path());
+
+    let output = clix(
+        home.path(),
+        &["bulk", "remove", "--tag", "deprecated", "--dry-run"],
+    );
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("old-build"));
+    assert!(stdout.contains("old-deploy"));
+    assert!(!stdout.contains("  command   build\n"));
+    assert_eq!(
+        stored_names(home.path()),
+        vec!["build", "old-build", "old-deploy"]
+    );
+
+    // Without --yes the prompt reads no answer from stdin and nothing is removed
+    let output = clix(home.path(), &["bulk", "remove", "--tag", "deprecated"]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Cancelled."));
+    assert_eq!(stored_names(home.path()).len(), 3);
+}
+
+#[test]
+fn test_bulk_export_writes_only_tagged_items() {
+    let home = TempDir::new().unwrap();
+    seed(home.path());
+    let bundle = home.path().join("bundle.json");
+
+    let output = clix(
+        home.path(),
+        &[
+            "bulk",
+            "export",
+            "--tag",
+            "ci",
+            "--yes",
+            "--output",
+            bundle.to_str().unwrap(),
+        ],
+    );
+    assert!(output.status.success());
+
+    let exported = std::fs::read_to_string(&bundle).unwrap();
+    assert!(exported.contains("\"old-deploy\""));
+    assert!(exported.contains("\"build\""));
+    assert!(!exported.contains("\"old-build\""));
+}
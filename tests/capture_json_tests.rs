@@ -0,0 +1,63 @@
+use clix::commands::{CommandExecutor, ExecutionOptions, Workflow, WorkflowStep};
+use clix::error::{ClixError, Result};
+use std::process::Output;
+
+fn step(name: &str, command: &str, continue_on_error: bool) -> WorkflowStep {
+    WorkflowStep::new_command(
+        name.to_string(),
+        command.to_string(),
+        name.to_string(),
+        continue_on_error,
+    )
+}
+
+fn run(steps: Vec<WorkflowStep>) -> Result<Vec<(String, Result<Output>)>> {
+    let workflow = Workflow::new("api".to_string(), "Call an API".to_string(), steps, vec![]);
+    let options = ExecutionOptions {
+        require_approval: false,
+        non_interactive: true,
+        ..ExecutionOptions::default()
+    };
+    CommandExecutor::execute_workflow_with_options(&workflow, None, None, &options)
+}
+
+#[test]
+fn test_json_field_is_captured_for_later_steps() {
+    let results = run(vec![
+        step("Create", r#"echo '{"id":"abc"}'"#, false)
+            .with_capture_json("id".to_string(), ".id".to_string()),
+        step("Use", "echo created {{ id }}", false),
+    ])
+    .unwrap();
+
+    let output = results[1].1.as_ref().unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "created abc\n");
+}
+
+#[test]
+fn test_missing_path_fails_unless_step_continues_on_error() {
+    let results = run(vec![
+        step("Create", r#"echo '{"name":"web"}'"#, false)
+            .with_capture_json("id".to_string(), ".id".to_string()),
+        step("Use", "echo created", false),
+    ])
+    .unwrap();
+
+    assert_eq!(results.len(), 1);
+    match &results[0].1 {
+        Err(ClixError::CommandExecutionFailed(message)) => {
+            assert!(message.contains("could not capture 'id'"), "{}", message);
+            assert!(message.contains("nothing at '.id'"), "{}", message);
+        }
+        other => panic!("expected a capture error, got {:?}", other.is_ok()),
+    }
+
+    let results = run(vec![
+        step("Create", "echo not json", true)
+            .with_capture_json("id".to_string(), ".id".to_string()),
+        step("Use", "echo still running", false),
+    ])
+    .unwrap();
+    assert_eq!(results.len(), 2);
+    assert!(results[1].1.is_ok());
+}
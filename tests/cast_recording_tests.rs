@@ -0,0 +1,65 @@
+use clix::commands::{CommandExecutor, ExecutionOptions, Workflow, WorkflowStep};
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn test_recorded_cast_is_well_formed() {
+    let dir = TempDir::new().unwrap();
+    let cast_path = dir.path().join("run.cast");
+
+    let workflow = Workflow::new(
+        "demo".to_string(),
+        "Short workflow to record".to_string(),
+        vec![
+            WorkflowStep::new_command(
+                "Greet".to_string(),
+                "echo hello".to_string(),
+                "Say hello".to_string(),
+                false,
+            ),
+            WorkflowStep::new_command(
+                "Two lines".to_string(),
+                "printf 'one\\ntwo\\n'".to_string(),
+                "Print two lines".to_string(),
+                false,
+            ),
+        ],
+        vec![],
+    );
+    let options = ExecutionOptions {
+        require_approval: false,
+        non_interactive: true,
+        record_cast: Some(cast_path.clone()),
+        ..ExecutionOptions::default()
+    };
+    CommandExecutor::execute_workflow_with_options(&workflow, None, None, &options).unwrap();
+
+    let cast = fs::read_to_string(&cast_path).unwrap();
+    let mut lines = cast.lines();
+
+    let header: serde_json::Value = serde_json::from_str(lines.next().unwrap()).unwrap();
+    assert_eq!(header["version"], 2);
+    assert_eq!(header["title"], "demo");
+    assert!(header["width"].as_u64().unwrap() > 0);
+    assert!(header["height"].as_u64().unwrap() > 0);
+
+    let events: Vec<serde_json::Value> = lines
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert!(!events.is_empty());
+
+    let mut last_time = 0.0;
+    let mut output = String::new();
+    for event in &events {
+        let event = event.as_array().unwrap();
+        assert_eq!(event.len(), 3);
+        let time = event[0].as_f64().unwrap();
+        assert!(time >= last_time);
+        last_time = time;
+        assert_eq!(event[1], "o");
+        output.push_str(event[2].as_str().unwrap());
+    }
+
+    assert!(output.contains("$ echo hello\r\nhello\r\n"));
+    assert!(output.contains("one\r\ntwo\r\n"));
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use common::clix;
+use std::fs;
+use std::process::Output;
+use tempfile::TempDir;
+
+fn check_steps(home: &TempDir, steps: &str) -> Output {
+    let file = home.path().join("steps.json");
+    fs::write(&file, steps).unwrap();
+
+    clix(home.path(), &["check-steps", file.to_str().unwrap()])
+}
+
+#[test]
+fn test_conditional_step_without_conditional_is_reported() {
+    let home = TempDir::new().unwrap();
+    let output = check_steps(
+        &home,
+        r#"[
+            {"name": "build", "description": "Build", "step_type": "Command", "command": "make"},
+            {"name": "maybe-deploy", "description": "Deploy on main", "step_type": "Conditional", "command": ""}
+        ]"#,
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success());
+    assert!(
+        stdout.contains("Step 'maybe-deploy' is a Conditional step but has no 'conditional' field"),
+        "{}",
+        stdout
+    );
+    // Nothing is stored
+    assert!(!home.path().join(".clix/commands.json").exists());
+}
+
+#[test]
+fn test_well_formed_steps_pass() {
+    let home = TempDir::new().unwrap();
+    let output = check_steps(
+        &home,
+        r#"[{"name": "build", "description": "Build", "step_type": "Command", "command": "make"}]"#,
+    );
+
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No issues found"));
+}
+
+#[test]
+fn test_unreadable_steps_file_is_reported() {
+    let home = TempDir::new().unwrap();
+    let output = check_steps(&home, r#"[{"name": "build", "step_type": "Nope"}]"#);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("is not a valid steps file"));
+}
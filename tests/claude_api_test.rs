@@ -33,8 +33,10 @@ fn test_claude_api_connection() {
         ai_settings: AiSettings {
             temperature: 0.7,
             max_tokens: 200, // Small for testing
+            ..AiSettings::default()
         },
         git_settings: GitSettings::default(),
+        ..Settings::default()
     };
 
     // Initialize the assistant
@@ -108,8 +110,10 @@ fn test_claude_list_models_api() {
         ai_settings: AiSettings {
             temperature: 0.7,
             max_tokens: 200,
+            ..AiSettings::default()
         },
         git_settings: GitSettings::default(),
+        ..Settings::default()
     };
 
     // Initialize the assistant
@@ -104,7 +104,7 @@ fn test_parse_create_workflow_action() {
 
             assert_eq!(steps[1].name, "Echo Step 2");
             assert_eq!(steps[1].command, "echo Step 2");
-            assert!(steps[1].continue_on_error);
+            assert!(steps[1].continues_on_error());
 
             // Verify that these values are also in the text response
             assert!(text.contains(&name));
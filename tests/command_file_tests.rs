@@ -0,0 +1,114 @@
+use clix::commands::{CommandExecutor, ExecutionOptions, Workflow, WorkflowStep};
+use clix::error::ClixError;
+use std::collections::HashMap;
+use std::fs;
+use tempfile::TempDir;
+
+fn options(base_dir: &TempDir) -> ExecutionOptions {
+    ExecutionOptions {
+        require_approval: false,
+        non_interactive: true,
+        command_base_dir: Some(base_dir.path().to_path_buf()),
+        ..ExecutionOptions::default()
+    }
+}
+
+fn script_workflow(file: &str) -> Workflow {
+    let step = WorkflowStep::new_command(
+        "Run script".to_string(),
+        String::new(),
+        "Multi-line script kept in a file".to_string(),
+        false,
+    )
+    .with_command_file(file.to_string());
+
+    Workflow::new(
+        "scripted".to_string(),
+        "Workflow with a file-backed step".to_string(),
+        vec![step],
+        vec![],
+    )
+}
+
+#[test]
+fn test_step_command_is_read_from_file() {
+    let dir = TempDir::new().unwrap();
+    fs::write(
+        dir.path().join("greet.sh"),
+        "greeting=\"hello\"\necho \"$greeting {{ name }}\"\necho done\n",
+    )
+    .unwrap();
+
+    let vars = HashMap::from([("name".to_string(), "world".to_string())]);
+    let results = CommandExecutor::execute_workflow_with_options(
+        &script_workflow("greet.sh"),
+        None,
+        Some(vars),
+        &options(&dir),
+    )
+    .unwrap();
+
+    let output = results[0].1.as_ref().unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "hello world\ndone\n"
+    );
+}
+
+#[test]
+fn test_missing_command_file_fails_the_step() {
+    let dir = TempDir::new().unwrap();
+
+    let results = CommandExecutor::execute_workflow_with_options(
+        &script_workflow("missing.sh"),
+        None,
+        None,
+        &options(&dir),
+    )
+    .unwrap();
+
+    let error = results[0].1.as_ref().unwrap_err().to_string();
+    assert!(error.contains("missing.sh"));
+}
+
+#[test]
+fn test_dangerous_command_file_is_refused() {
+    let dir = TempDir::new().unwrap();
+    let victim = dir.path().join("victim");
+    fs::create_dir(&victim).unwrap();
+    fs::write(
+        dir.path().join("cleanup.sh"),
+        format!("rm -rf {}\n", victim.display()),
+    )
+    .unwrap();
+
+    let gated = CommandExecutor::execute_workflow_with_options(
+        &script_workflow("cleanup.sh"),
+        None,
+        None,
+        &ExecutionOptions {
+            abort_on_security: true,
+            ..options(&dir)
+        },
+    );
+    assert!(matches!(gated, Err(ClixError::SecurityGate(_))));
+    assert!(victim.exists());
+
+    let skipped = CommandExecutor::execute_workflow_with_options(
+        &script_workflow("cleanup.sh"),
+        None,
+        None,
+        &ExecutionOptions {
+            skip_unsafe: true,
+            ..options(&dir)
+        },
+    )
+    .unwrap();
+    match &skipped[0].1 {
+        Err(ClixError::StepSkipped(reason)) => {
+            assert!(reason.contains("flagged by the security checks"))
+        }
+        other => panic!("expected the step to be skipped, got {:?}", other),
+    }
+    assert!(victim.exists());
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use common::{clix, clix_command};
+use std::thread;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+#[test]
+fn test_second_run_of_locked_command_is_rejected() {
+    let home = TempDir::new().unwrap();
+    let added = clix(
+        home.path(),
+        &[
+            "add",
+            "slow",
+            "-d",
+            "Takes a while",
+            "-c",
+            "sleep 2",
+            "--lock",
+        ],
+    );
+    assert!(added.status.success());
+
+    let mut first = clix_command(home.path(), &["run", "slow"])
+        .stdout(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+
+    // Wait for the first run to take the lock
+    let lock_path = home.path().join(".clix").join("locks").join("slow.lock");
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !lock_path.exists() {
+        assert!(Instant::now() < deadline, "first run never took the lock");
+        thread::sleep(Duration::from_millis(20));
+    }
+
+    let second = clix(home.path(), &["run", "slow"]);
+    assert!(!second.status.success());
+    assert!(String::from_utf8_lossy(&second.stderr).contains("'slow' is already running"));
+
+    assert!(first.wait().unwrap().success());
+    assert!(!lock_path.exists());
+
+    // Once the first run is done the command can run again
+    assert!(clix(home.path(), &["run", "slow"]).status.success());
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use common::clix;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn stored(home: &Path, name: &str) -> serde_json::Value {
+    let store = std::fs::read_to_string(home.join(".clix").join("commands.json")).unwrap();
+    let store: serde_json::Value = serde_json::from_str(&store).unwrap();
+    store["commands"][name].clone()
+}
+
+#[test]
+fn test_bump_increments_version_and_keeps_metadata() {
+    let home = TempDir::new().unwrap();
+    let added = clix(
+        home.path(),
+        &[
+            "add",
+            "deploy",
+            "--description",
+            "Deploy the app",
+            "--command",
+            "echo deploying",
+            "--author",
+            "ops",
+            "--version",
+            "1.2.9",
+            "--changelog",
+            "First version",
+        ],
+    );
+    assert!(added.status.success());
+
+    let bumped = clix(
+        home.path(),
+        &[
+            "add",
+            "deploy",
+            "--description",
+            "Deploy the app to staging",
+            "--command",
+            "echo deploying to staging",
+            "--bump",
+            "--changelog",
+            "Deploy to staging",
+        ],
+    );
+    assert!(bumped.status.success());
+
+    let command = stored(home.path(), "deploy");
+    assert_eq!(command["version"], "1.2.10");
+    assert_eq!(command["author"], "ops");
+    assert_eq!(
+        command["changelog"],
+        serde_json::json!(["First version", "Deploy to staging"])
+    );
+
+    let listed = String::from_utf8_lossy(&clix(home.path(), &["list"]).stdout).into_owned();
+    assert!(listed.contains("Author: ops"), "{}", listed);
+    assert!(listed.contains("Version: 1.2.10"), "{}", listed);
+    assert!(
+        listed.contains("Latest change: Deploy to staging (2 note(s))"),
+        "{}",
+        listed
+    );
+}
+
+#[test]
+fn test_bump_of_new_command_starts_at_one() {
+    let home = TempDir::new().unwrap();
+    let added = clix(
+        home.path(),
+        &[
+            "add",
+            "greet",
+            "--description",
+            "Say hello",
+            "--command",
+            "echo hello",
+            "--bump",
+        ],
+    );
+    assert!(added.status.success());
+    assert_eq!(stored(home.path(), "greet")["version"], "1");
+}
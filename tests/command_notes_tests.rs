@@ -0,0 +1,94 @@
+mod common;
+
+use common::clix_command;
+use std::path::Path;
+use std::process::Output;
+use tempfile::TempDir;
+
+fn run(home: &Path, editor: Option<&Path>, args: &[&str]) -> Output {
+    let mut command = clix_command(home, args);
+    command.env_remove("VISUAL");
+    match editor {
+        Some(editor) => command.env("EDITOR", editor),
+        None => command.env_remove("EDITOR"),
+    };
+    command.output().expect("failed to run clix")
+}
+
+fn add_command(home: &Path) {
+    let added = run(
+        home,
+        None,
+        &[
+            "add",
+            "deploy",
+            "--description",
+            "Deploy the app",
+            "--command",
+            "echo deploying",
+        ],
+    );
+    assert!(added.status.success());
+}
+
+#[test]
+fn test_notes_written_in_editor_show_in_verbose_list() {
+    let home = TempDir::new().unwrap();
+    add_command(home.path());
+
+    // An "editor" that replaces the file with two lines of notes
+    let editor = home.path().join("editor.sh");
+    std::fs::write(
+        &editor,
+        "#!/bin/sh\nprintf 'Needs VPN access.\\nAsk #ops before running on Fridays.\\n' > \"$1\"\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&editor, std::os::unix::fs::PermissionsExt::from_mode(0o755)).unwrap();
+
+    let set = run(home.path(), Some(&editor), &["note", "set", "deploy"]);
+    assert!(
+        set.status.success(),
+        "{}",
+        String::from_utf8_lossy(&set.stderr)
+    );
+
+    let verbose = run(home.path(), None, &["list", "--verbose"]);
+    let verbose = String::from_utf8_lossy(&verbose.stdout);
+    assert!(
+        verbose.contains("Notes:\n  Needs VPN access.\n  Ask #ops before running on Fridays.\n"),
+        "{}",
+        verbose
+    );
+
+    let plain = run(home.path(), None, &["list"]);
+    assert!(!String::from_utf8_lossy(&plain.stdout).contains("Needs VPN access."));
+}
+
+#[test]
+fn test_note_text_flag_and_clear() {
+    let home = TempDir::new().unwrap();
+    add_command(home.path());
+
+    let set = run(
+        home.path(),
+        None,
+        &["note", "set", "deploy", "--text", "Run from main only"],
+    );
+    assert!(set.status.success());
+    let listed = run(home.path(), None, &["list", "-v"]);
+    assert!(String::from_utf8_lossy(&listed.stdout).contains("  Run from main only"));
+
+    let cleared = run(home.path(), None, &["note", "clear", "deploy"]);
+    assert!(cleared.status.success());
+    let listed = run(home.path(), None, &["list", "-v"]);
+    assert!(!String::from_utf8_lossy(&listed.stdout).contains("Notes:"));
+
+    let empty = run(
+        home.path(),
+        None,
+        &["note", "set", "deploy", "--text", "  "],
+    );
+    assert!(!empty.status.success());
+    let missing = run(home.path(), None, &["note", "set", "nope", "--text", "x"]);
+    assert!(!missing.status.success());
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use common::{clix, clix_with_stdin};
+use tempfile::TempDir;
+
+#[test]
+fn test_multi_line_command_is_stored_verbatim_and_runs() {
+    let home = TempDir::new().unwrap();
+    let script = "greeting=hello\necho \"$greeting from line two\"";
+
+    let added = clix_with_stdin(
+        home.path(),
+        &[
+            "add",
+            "greet",
+            "--description",
+            "Two-line script",
+            "--command-stdin",
+        ],
+        &format!("{}\n", script),
+    );
+    assert!(
+        added.status.success(),
+        "{}",
+        String::from_utf8_lossy(&added.stderr)
+    );
+
+    let store = std::fs::read_to_string(home.path().join(".clix").join("commands.json")).unwrap();
+    let store: serde_json::Value = serde_json::from_str(&store).unwrap();
+    assert_eq!(store["commands"]["greet"]["command"], script);
+
+    let ran = clix(home.path(), &["run", "greet"]);
+    assert!(ran.status.success());
+    assert!(String::from_utf8_lossy(&ran.stdout).contains("hello from line two"));
+}
+
+#[test]
+fn test_empty_stdin_is_rejected() {
+    let home = TempDir::new().unwrap();
+    let added = clix_with_stdin(
+        home.path(),
+        &[
+            "add",
+            "empty",
+            "--description",
+            "Nothing",
+            "--command-stdin",
+        ],
+        " \n\n",
+    );
+    assert!(!added.status.success());
+    assert!(String::from_utf8_lossy(&added.stderr).contains("No command was given on stdin"));
+}
@@ -0,0 +1,56 @@
+//! Helpers for the tests that run the clix binary
+#![allow(dead_code)]
+
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+
+/// A clix invocation that keeps its data under `home`, with the startup sync,
+/// colors and stdin turned off. Add to it for a test's own environment.
+pub fn clix_command(home: &Path, args: &[&str]) -> Command {
+    let mut command = clix_command_with_sync(home, &["--no-sync"]);
+    command.args(args);
+    command
+}
+
+/// `clix_command` for the tests of the startup sync itself
+pub fn clix_command_with_sync(home: &Path, args: &[&str]) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_clix"));
+    command
+        .env("HOME", home)
+        .env_remove("CLIX_CONFIG_DIR")
+        .env_remove("CLIX_NO_SYNC")
+        .env("NO_COLOR", "1")
+        .args(args)
+        .stdin(Stdio::null());
+    command
+}
+
+/// Run clix with its data under `home` and wait for it to finish
+pub fn clix(home: &Path, args: &[&str]) -> Output {
+    clix_command(home, args)
+        .output()
+        .expect("failed to run clix")
+}
+
+/// Run clix with `input` written to its stdin
+pub fn clix_with_stdin(home: &Path, args: &[&str], input: &str) -> Output {
+    output_with_stdin(&mut clix_command(home, args), input)
+}
+
+/// Run a prepared command with `input` written to its stdin
+pub fn output_with_stdin(command: &mut Command, input: &str) -> Output {
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run clix");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
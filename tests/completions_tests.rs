@@ -0,0 +1,64 @@
+mod common;
+
+use clix::cli::app::Shell;
+use common::clix_command;
+use std::collections::HashMap;
+use tempfile::TempDir;
+
+fn detect(vars: &[(&str, &str)]) -> Option<Shell> {
+    let env: HashMap<String, String> = vars
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+    Shell::detect(|name| env.get(name).cloned())
+}
+
+#[test]
+fn test_shell_detection_from_environment() {
+    assert_eq!(detect(&[("SHELL", "/bin/zsh")]), Some(Shell::Zsh));
+    assert_eq!(
+        detect(&[("SHELL", "/usr/local/bin/fish")]),
+        Some(Shell::Fish)
+    );
+    assert_eq!(
+        Shell::from_path(r"C:\Program Files\PowerShell\7\pwsh.exe"),
+        Some(Shell::PowerShell)
+    );
+
+    // An unsupported $SHELL falls back to PowerShell only when its module path is set
+    assert_eq!(detect(&[("SHELL", "/bin/tcsh")]), None);
+    assert_eq!(
+        detect(&[("SHELL", "/bin/tcsh"), ("PSModulePath", "/opt/modules")]),
+        Some(Shell::PowerShell)
+    );
+    assert_eq!(detect(&[]), None);
+}
+
+#[test]
+fn test_list_shells_and_detection_failure() {
+    let home = TempDir::new().unwrap();
+    let clix = |args: &[&str], shell: &str| {
+        clix_command(home.path(), args)
+            .env_remove("PSModulePath")
+            .env("SHELL", shell)
+            .output()
+            .unwrap()
+    };
+
+    let output = clix(&["completions", "--list-shells"], "/bin/sh");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "bash\nzsh\nfish\npower-shell\nelvish\n"
+    );
+
+    let output = clix(&["completions"], "/bin/zsh");
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("#compdef clix"));
+
+    let output = clix(&["completions"], "/bin/tcsh");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Could not detect your shell"), "{}", stderr);
+    assert!(stderr.contains("bash, zsh, fish, power-shell, elvish"));
+}
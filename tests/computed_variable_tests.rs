@@ -0,0 +1,73 @@
+use clix::commands::{CommandExecutor, ExecutionOptions, Workflow, WorkflowStep, WorkflowVariable};
+use clix::error::ClixError;
+use std::collections::HashMap;
+
+fn variable(name: &str) -> WorkflowVariable {
+    WorkflowVariable::new(name.to_string(), format!("The {}", name), None, false)
+}
+
+fn computed(name: &str, expression: &str) -> WorkflowVariable {
+    variable(name).with_computed(expression.to_string())
+}
+
+fn image_workflow(variables: Vec<WorkflowVariable>) -> Workflow {
+    let mut workflow = Workflow::new(
+        "push".to_string(),
+        "Push an image".to_string(),
+        vec![WorkflowStep::new_command(
+            "show".to_string(),
+            "echo {{ IMAGE }}".to_string(),
+            "Print the image reference".to_string(),
+            false,
+        )],
+        vec![],
+    );
+    for variable in variables {
+        workflow.add_variable(variable);
+    }
+    workflow
+}
+
+fn run(workflow: &Workflow) -> clix::error::Result<String> {
+    let vars = HashMap::from([
+        ("REGISTRY".to_string(), "ghcr.io/acme".to_string()),
+        ("APP".to_string(), "web".to_string()),
+    ]);
+    let options = ExecutionOptions {
+        require_approval: false,
+        ..ExecutionOptions::default()
+    };
+    let results =
+        CommandExecutor::execute_workflow_with_options(workflow, None, Some(vars), &options)?;
+    let output = results[0].1.as_ref().unwrap();
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[test]
+fn test_computed_variable_concatenates_others() {
+    // TAG is declared after IMAGE but computed first because IMAGE refers to it
+    let workflow = image_workflow(vec![
+        variable("REGISTRY"),
+        variable("APP"),
+        computed("IMAGE", "$REGISTRY/${APP}:$TAG"),
+        computed("TAG", "$APP-latest"),
+    ]);
+
+    assert_eq!(run(&workflow).unwrap(), "ghcr.io/acme/web:web-latest\n");
+}
+
+#[test]
+fn test_computed_variable_cycle_is_rejected() {
+    let workflow = image_workflow(vec![
+        computed("IMAGE", "$REGISTRY/$APP:$TAG"),
+        computed("TAG", "$IMAGE-next"),
+    ]);
+
+    match run(&workflow) {
+        Err(ClixError::ValidationError(message)) => {
+            assert!(message.contains("cycle"));
+            assert!(message.contains("IMAGE") && message.contains("TAG"));
+        }
+        other => panic!("expected a cycle error, got {:?}", other),
+    }
+}
@@ -0,0 +1,113 @@
+mod common;
+
+use clix::ai::ConversationSession;
+use clix::commands::Command;
+use clix::settings::CONFIG_DIR_ENV;
+use clix::{ConversationStorage, GitIntegratedStorage, SettingsManager};
+use common::clix_command;
+use tempfile::TempDir;
+
+#[test]
+fn test_config_dirs_are_independent() {
+    let first_dir = TempDir::new().unwrap();
+    let second_dir = TempDir::new().unwrap();
+
+    let first = GitIntegratedStorage::new_with_dir(first_dir.path().to_path_buf()).unwrap();
+    let second = GitIntegratedStorage::new_with_dir(second_dir.path().to_path_buf()).unwrap();
+
+    first
+        .add_command(Command::new(
+            "only-in-first".to_string(),
+            "Lives in the first config dir".to_string(),
+            "echo first".to_string(),
+            vec![],
+        ))
+        .unwrap();
+
+    assert!(first.get_command("only-in-first").is_ok());
+    assert!(second.get_command("only-in-first").is_err());
+    assert!(second.list_commands().unwrap().is_empty());
+
+    let first_settings = SettingsManager::new_with_dir(first_dir.path().to_path_buf()).unwrap();
+    let second_settings = SettingsManager::new_with_dir(second_dir.path().to_path_buf()).unwrap();
+    first_settings
+        .update_ai_model("claude-3-haiku-20240307")
+        .unwrap();
+    assert_eq!(
+        first_settings.load().unwrap().ai_model,
+        "claude-3-haiku-20240307"
+    );
+    assert_ne!(
+        second_settings.load().unwrap().ai_model,
+        "claude-3-haiku-20240307"
+    );
+
+    let first_conversations =
+        ConversationStorage::new_with_dir(first_dir.path().to_path_buf()).unwrap();
+    let second_conversations =
+        ConversationStorage::new_with_dir(second_dir.path().to_path_buf()).unwrap();
+    let session = ConversationSession::new();
+    first_conversations.save_session(&session).unwrap();
+    assert!(
+        first_conversations
+            .get_session(&session.id)
+            .unwrap()
+            .is_some()
+    );
+    assert!(
+        second_conversations
+            .get_session(&session.id)
+            .unwrap()
+            .is_none()
+    );
+
+    assert!(first_dir.path().join("commands.json").exists());
+    assert!(first_dir.path().join("repos").is_dir());
+}
+
+#[test]
+fn test_config_dir_flag_and_env_override_home() {
+    let home = TempDir::new().unwrap();
+    let flag_dir = TempDir::new().unwrap();
+    let env_dir = TempDir::new().unwrap();
+
+    let run = |args: &[&str], env_value: Option<&std::path::Path>| {
+        let mut command = clix_command(home.path(), args);
+        if let Some(dir) = env_value {
+            command.env(CONFIG_DIR_ENV, dir);
+        }
+        command.output().unwrap()
+    };
+
+    let added = run(
+        &[
+            "--config-dir",
+            flag_dir.path().to_str().unwrap(),
+            "add",
+            "from-flag",
+            "-d",
+            "Added with --config-dir",
+            "-c",
+            "echo flag",
+        ],
+        None,
+    );
+    assert!(added.status.success());
+
+    let added = run(
+        &[
+            "add",
+            "from-env",
+            "-d",
+            "Added with CLIX_CONFIG_DIR",
+            "-c",
+            "echo env",
+        ],
+        Some(env_dir.path()),
+    );
+    assert!(added.status.success());
+
+    assert!(flag_dir.path().join("commands.json").exists());
+    assert!(env_dir.path().join("commands.json").exists());
+    assert!(!home.path().join(".clix").join("commands.json").exists());
+}
@@ -0,0 +1,110 @@
+use clix::commands::models::StepType;
+use clix::commands::{CommandExecutor, ExecutionOptions, Workflow, WorkflowStep};
+use std::io::Cursor;
+
+fn confirm_workflow(default: bool) -> Workflow {
+    Workflow::new(
+        "confirm_test".to_string(),
+        "Workflow gated by a confirm step".to_string(),
+        vec![
+            WorkflowStep::new_command(
+                "Before".to_string(),
+                "echo 'before'".to_string(),
+                "Runs before the gate".to_string(),
+                false,
+            ),
+            WorkflowStep::new_confirm(
+                "Gate".to_string(),
+                "Ask before continuing".to_string(),
+                "Continue with the deployment?".to_string(),
+                default,
+            ),
+            WorkflowStep::new_command(
+                "After".to_string(),
+                "echo 'after'".to_string(),
+                "Runs after the gate".to_string(),
+                false,
+            ),
+        ],
+        vec![],
+    )
+}
+
+fn options_with_answer(answer: &str) -> ExecutionOptions {
+    ExecutionOptions {
+        require_approval: false,
+        ..ExecutionOptions::default()
+    }
+    .with_input(Cursor::new(answer.to_string()))
+}
+
+#[test]
+fn test_confirm_step_constructor() {
+    let step = WorkflowStep::new_confirm(
+        "Gate".to_string(),
+        "Ask before continuing".to_string(),
+        "Proceed?".to_string(),
+        true,
+    );
+
+    assert_eq!(step.step_type, StepType::Confirm);
+    assert!(step.command.is_empty());
+    let confirm = step.confirm.unwrap();
+    assert_eq!(confirm.prompt, "Proceed?");
+    assert!(confirm.default);
+}
+
+#[test]
+fn test_confirm_no_stops_workflow_without_failure() {
+    let workflow = confirm_workflow(true);
+    let options = options_with_answer("n\n");
+
+    let results =
+        CommandExecutor::execute_workflow_with_options(&workflow, None, None, &options).unwrap();
+
+    // Only the step before the gate ran; nothing was recorded as failed
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].0, "Before");
+    assert!(results.iter().all(|(_, result)| result.is_ok()));
+}
+
+#[test]
+fn test_confirm_yes_continues_workflow() {
+    let workflow = confirm_workflow(false);
+    let options = options_with_answer("yes\n");
+
+    let results =
+        CommandExecutor::execute_workflow_with_options(&workflow, None, None, &options).unwrap();
+
+    let names: Vec<&str> = results.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, vec!["Before", "Gate", "After"]);
+    let after = results[2].1.as_ref().unwrap();
+    assert!(String::from_utf8_lossy(&after.stdout).contains("after"));
+}
+
+#[test]
+fn test_confirm_uses_default_when_non_interactive() {
+    let options = ExecutionOptions {
+        require_approval: false,
+        non_interactive: true,
+        ..ExecutionOptions::default()
+    };
+
+    let declined = CommandExecutor::execute_workflow_with_options(
+        &confirm_workflow(false),
+        None,
+        None,
+        &options,
+    )
+    .unwrap();
+    assert_eq!(declined.len(), 1);
+
+    let accepted = CommandExecutor::execute_workflow_with_options(
+        &confirm_workflow(true),
+        None,
+        None,
+        &options,
+    )
+    .unwrap();
+    assert_eq!(accepted.len(), 3);
+}
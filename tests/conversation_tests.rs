@@ -80,3 +80,90 @@ fn test_conversation_context_retrieval() {
     assert_eq!(recent_context[0].content, "Message 2");
     assert_eq!(recent_context[1].content, "Response 2");
 }
+
+#[test]
+fn test_workflow_built_across_turns_is_offered_when_complete() {
+    use clix::ai::claude::ClaudeAction;
+
+    let mut session = ConversationSession::new();
+
+    let first = "Let's start with the build.\n[WORKFLOW DRAFT]\nName: release\nSteps:\n- Step 1: name=\"build\", command=\"cargo build --release\", description=\"Build\", continue_on_error=false, step_type=\"Command\"\n\nWhat should happen after the build?";
+    assert!(session.track_workflow_creation(first).is_none());
+    match &session.state {
+        ConversationState::CreatingWorkflow(state) => {
+            assert_eq!(state.name.as_deref(), Some("release"));
+            assert_eq!(state.steps.len(), 1);
+        }
+        other => panic!("expected a workflow being created, got {:?}", other),
+    }
+
+    let second = "[WORKFLOW DRAFT]\nDescription: Build and publish a release\nSteps:\n- Step 2: name=\"publish\", command=\"cargo publish\", description=\"Publish\", continue_on_error=false, step_type=\"Command\"\n[WORKFLOW COMPLETE]";
+    let action = session.track_workflow_creation(second);
+
+    match action {
+        Some(ClaudeAction::CreateWorkflow {
+            name,
+            description,
+            steps,
+        }) => {
+            assert_eq!(name, "release");
+            assert_eq!(description, "Build and publish a release");
+            let names: Vec<&str> = steps.iter().map(|step| step.name.as_str()).collect();
+            assert_eq!(names, ["build", "publish"]);
+            assert_eq!(steps[1].command, "cargo publish");
+        }
+        other => panic!("expected a workflow to save, got {:?}", other),
+    }
+    assert!(matches!(session.state, ConversationState::Active));
+}
+
+#[test]
+fn test_incomplete_workflow_is_not_offered() {
+    let mut session = ConversationSession::new();
+
+    let reply = "[WORKFLOW DRAFT]\nName: release\n[WORKFLOW COMPLETE]";
+    assert!(session.track_workflow_creation(reply).is_none());
+    match &session.state {
+        ConversationState::CreatingWorkflow(state) => assert_eq!(
+            state.pending_refinements,
+            [
+                "The workflow needs a description",
+                "The workflow needs steps"
+            ]
+        ),
+        other => panic!("expected a workflow being created, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_sessions_saved_concurrently_all_persist() {
+    let temp_dir = TempDir::new().unwrap();
+    let barrier = std::sync::Arc::new(std::sync::Barrier::new(8));
+
+    let handles: Vec<_> = (0..8)
+        .map(|index| {
+            let dir = temp_dir.path().to_path_buf();
+            let barrier = std::sync::Arc::clone(&barrier);
+            std::thread::spawn(move || {
+                let storage = ConversationStorage::new_with_dir(dir).unwrap();
+                let mut session = ConversationSession::new();
+                session.add_message(MessageRole::User, format!("Message {}", index));
+                barrier.wait();
+                storage.save_session(&session).unwrap();
+                session
+            })
+        })
+        .collect();
+    let sessions: Vec<ConversationSession> = handles
+        .into_iter()
+        .map(|handle| handle.join().unwrap())
+        .collect();
+
+    let storage = ConversationStorage::new_with_dir(temp_dir.path().to_path_buf()).unwrap();
+    let store = storage.load().unwrap();
+    assert_eq!(store.sessions.len(), sessions.len());
+    for session in &sessions {
+        let saved = storage.get_session(&session.id).unwrap().unwrap();
+        assert_eq!(saved.messages[0].content, session.messages[0].content);
+    }
+}
@@ -0,0 +1,39 @@
+mod common;
+
+use common::clix;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn test_preview_prints_steps_and_saves_nothing() {
+    let home = TempDir::new().unwrap();
+    let script = home.path().join("scripts.sh");
+    fs::write(
+        &script,
+        "deploy() {\n    echo \"building\"\n    cargo build --release\n}\n",
+    )
+    .unwrap();
+
+    let output = clix(
+        home.path(),
+        &[
+            "convert-function",
+            "deploy",
+            "--file",
+            script.to_str().unwrap(),
+            "--function",
+            "deploy",
+            "--description",
+            "Deploy the app",
+            "--preview",
+        ],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("cargo build --release"), "{}", stdout);
+    assert!(stdout.contains("Command"), "{}", stdout);
+    assert!(stdout.contains("'deploy' was not saved"), "{}", stdout);
+
+    let output = clix(home.path(), &["list", "--format", "table"]);
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("deploy"));
+}
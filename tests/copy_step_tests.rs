@@ -0,0 +1,93 @@
+mod common;
+
+use clix::storage::Storage;
+use common::clix;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn add_workflow(home: &Path, name: &str, step_names: &[&str]) {
+    let steps: Vec<serde_json::Value> = step_names
+        .iter()
+        .map(|step| {
+            serde_json::json!({
+                "name": step,
+                "command": format!("echo {}", step),
+                "description": format!("Step {}", step),
+                "continue_on_error": false,
+                "step_type": "Command",
+                "require_approval": false
+            })
+        })
+        .collect();
+    let steps_file = home.join(format!("{}.json", name));
+    std::fs::write(&steps_file, serde_json::Value::from(steps).to_string()).unwrap();
+
+    let added = clix(
+        home,
+        &[
+            "add",
+            name,
+            "--description",
+            "Test workflow",
+            "--steps-file",
+            steps_file.to_str().unwrap(),
+        ],
+    );
+    assert!(added.status.success());
+}
+
+fn step_names(home: &Path, workflow: &str) -> Vec<String> {
+    let storage = Storage::new_with_dir(home.join(".clix")).unwrap();
+    let command = storage.get_command(workflow).unwrap();
+    command
+        .steps
+        .unwrap_or_default()
+        .into_iter()
+        .map(|step| step.name)
+        .collect()
+}
+
+#[test]
+fn test_copy_step_inserts_a_clone_into_the_target() {
+    let home = TempDir::new().unwrap();
+    add_workflow(home.path(), "shared", &["checkout", "notify"]);
+    add_workflow(home.path(), "deploy", &["build", "release"]);
+
+    let copied = clix(
+        home.path(),
+        &[
+            "copy-step",
+            "deploy",
+            "--from",
+            "shared",
+            "--step",
+            "notify",
+            "--position",
+            "2",
+        ],
+    );
+    assert!(copied.status.success(), "{:?}", copied);
+    assert_eq!(
+        step_names(home.path(), "deploy"),
+        vec!["build", "notify", "release"]
+    );
+
+    // The source keeps its step, and copying it again would duplicate the name
+    assert_eq!(
+        step_names(home.path(), "shared"),
+        vec!["checkout", "notify"]
+    );
+    let again = clix(
+        home.path(),
+        &[
+            "copy-step",
+            "deploy",
+            "--from",
+            "shared",
+            "--step",
+            "notify",
+        ],
+    );
+    assert!(!again.status.success());
+    assert!(String::from_utf8_lossy(&again.stderr).contains("already has a step named 'notify'"));
+}
@@ -0,0 +1,26 @@
+mod common;
+
+use common::clix;
+use tempfile::TempDir;
+
+#[test]
+fn test_doctor_fix_creates_a_missing_config_dir() {
+    let home = TempDir::new().unwrap();
+    let config_dir = home.path().join(".clix");
+
+    let output = clix(home.path(), &["doctor"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("does not exist"));
+    assert!(!config_dir.exists());
+
+    let output = clix(home.path(), &["doctor", "--fix", "--yes"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("Fixed: Create"), "{}", stdout);
+    assert!(stdout.contains("Fixed 1 of 1 problem(s)"), "{}", stdout);
+    assert!(config_dir.is_dir());
+
+    let output = clix(home.path(), &["doctor"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No problems found."));
+}
@@ -0,0 +1,102 @@
+use clix::commands::{
+    CommandExecutor, Condition, ExecutionOptions, ScriptEmitter, Workflow, WorkflowStep,
+};
+use std::collections::HashMap;
+
+fn echo(text: &str) -> WorkflowStep {
+    WorkflowStep::new_command(
+        format!("Echo {}", text),
+        format!("echo {}", text),
+        format!("Print {}", text),
+        false,
+    )
+}
+
+fn condition(expression: &str) -> Condition {
+    Condition {
+        expression: expression.to_string(),
+        variable: None,
+    }
+}
+
+fn three_way_workflow() -> Workflow {
+    let step = WorkflowStep::new_conditional(
+        "Pick environment".to_string(),
+        "Choose a branch by environment".to_string(),
+        condition("[ \"$ENV\" = \"dev\" ]"),
+        vec![echo("first")],
+        Some(vec![echo("last")]),
+        None,
+    )
+    .with_elif(
+        condition("[ \"$ENV\" = \"staging\" ]"),
+        vec![echo("middle")],
+    )
+    .with_elif(
+        condition("[ \"$ENV\" = \"staging\" ]"),
+        vec![echo("shadowed")],
+    );
+
+    Workflow::new(
+        "pick-env".to_string(),
+        "Three-way conditional".to_string(),
+        vec![step],
+        vec![],
+    )
+}
+
+#[test]
+fn test_elif_chain_selects_middle_branch() {
+    let options = ExecutionOptions {
+        require_approval: false,
+        non_interactive: true,
+        ..ExecutionOptions::default()
+    };
+    let vars = HashMap::from([("ENV".to_string(), "staging".to_string())]);
+
+    let results = CommandExecutor::execute_workflow_with_options(
+        &three_way_workflow(),
+        None,
+        Some(vars),
+        &options,
+    )
+    .unwrap();
+
+    assert_eq!(results.len(), 1);
+    let output = results[0].1.as_ref().unwrap();
+    // Only the first matching elif runs, and neither then nor else does
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "middle");
+}
+
+#[test]
+fn test_elif_blocks_round_trip_and_emit() {
+    let workflow = three_way_workflow();
+
+    let json = serde_json::to_string(&workflow).unwrap();
+    let parsed: Workflow = serde_json::from_str(&json).unwrap();
+    let conditional = parsed.steps[0].conditional.as_ref().unwrap();
+    assert_eq!(conditional.elif_blocks.len(), 2);
+    assert_eq!(
+        conditional.elif_blocks[0].then_block.steps[0].command,
+        "echo middle"
+    );
+
+    // Conditionals without elif branches keep their old serialized form
+    let plain = WorkflowStep::new_conditional(
+        "Plain".to_string(),
+        "No elif branches".to_string(),
+        condition("$? -eq 0"),
+        vec![echo("ok")],
+        None,
+        None,
+    );
+    assert!(
+        !serde_json::to_string(&plain)
+            .unwrap()
+            .contains("elif_blocks")
+    );
+
+    let script = ScriptEmitter::emit(&workflow);
+    assert!(script.contains("elif [ \"$ENV\" = \"staging\" ]; then"));
+    assert!(script.find("echo middle") < script.find("else"));
+}
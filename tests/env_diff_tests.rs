@@ -0,0 +1,65 @@
+mod common;
+
+use clix::commands::capture::DEFAULT_MAX_OUTPUT_BYTES;
+use clix::commands::env_diff::run_with_env_diff;
+use common::clix;
+use std::collections::HashMap;
+use tempfile::TempDir;
+
+#[test]
+fn test_exported_variable_is_reported_as_added() {
+    let (output, diff) = run_with_env_diff(
+        "export CLIX_DIFF_ADDED=hello; echo exported",
+        DEFAULT_MAX_OUTPUT_BYTES,
+        &HashMap::new(),
+    )
+    .unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "exported\n");
+    assert_eq!(
+        diff.added,
+        vec![("CLIX_DIFF_ADDED".to_string(), "hello".to_string())]
+    );
+    assert!(diff.changed.is_empty());
+    assert!(diff.removed.is_empty());
+}
+
+#[test]
+fn test_failing_command_keeps_its_exit_code() {
+    let (output, diff) = run_with_env_diff(
+        "unset HOME; false",
+        DEFAULT_MAX_OUTPUT_BYTES,
+        &HashMap::new(),
+    )
+    .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(diff.removed, vec!["HOME".to_string()]);
+}
+
+#[test]
+fn test_run_diff_env_prints_changes() {
+    let home = TempDir::new().unwrap();
+    let added = clix(
+        home.path(),
+        &[
+            "add",
+            "setup-env",
+            "--description",
+            "Export settings",
+            "--command",
+            "export CLIX_REGION=eu-west-1 GITHUB_TOKEN=abc123",
+        ],
+    );
+    assert!(added.status.success());
+
+    let output = clix(home.path(), &["run", "setup-env", "--diff-env"]);
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (_, changes) = stdout.split_once("Environment changes:").unwrap();
+    assert!(changes.contains("+ CLIX_REGION=eu-west-1"));
+    assert!(changes.contains("+ GITHUB_TOKEN=********"));
+    assert!(!changes.contains("abc123"));
+}
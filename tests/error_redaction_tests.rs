@@ -0,0 +1,16 @@
+mod common;
+
+use common::clix;
+use tempfile::TempDir;
+
+#[test]
+fn test_api_key_in_error_output_is_redacted() {
+    let home = TempDir::new().unwrap();
+    let key = "sk-ant-REDACTED";
+    let output = clix(home.path(), &["run", key]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Command '****' not found"), "{}", stderr);
+    assert!(!stderr.contains(key));
+}
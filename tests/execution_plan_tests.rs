@@ -0,0 +1,109 @@
+use clix::commands::{
+    BranchCase, CommandExecutor, Condition, ExecutionOptions, Workflow, WorkflowStep,
+};
+use std::collections::HashMap;
+
+fn echo(name: &str) -> WorkflowStep {
+    WorkflowStep::new_command(
+        name.to_string(),
+        format!("echo {}", name),
+        format!("Run {}", name),
+        false,
+    )
+}
+
+fn condition(expression: &str) -> Condition {
+    Condition {
+        expression: expression.to_string(),
+        variable: None,
+    }
+}
+
+fn deploy_workflow() -> Workflow {
+    Workflow::new(
+        "deploy".to_string(),
+        "Deploy to an environment".to_string(),
+        vec![
+            echo("build"),
+            WorkflowStep::new_branch(
+                "pick-env".to_string(),
+                "Deploy to the chosen environment".to_string(),
+                "ENV".to_string(),
+                vec![
+                    BranchCase {
+                        value: "dev".to_string(),
+                        steps: vec![echo("deploy-dev")],
+                    },
+                    BranchCase {
+                        value: "prod".to_string(),
+                        steps: vec![echo("deploy-prod")],
+                    },
+                ],
+                None,
+            ),
+            WorkflowStep::new_conditional(
+                "notify".to_string(),
+                "Only page people for production".to_string(),
+                condition("[ \"{{ ENV }}\" = \"prod\" ]"),
+                vec![echo("page-oncall")],
+                Some(vec![echo("post-to-channel")]),
+                None,
+            ),
+            WorkflowStep::new_conditional(
+                "check".to_string(),
+                "Depends on the previous exit code".to_string(),
+                condition("$? -eq 0"),
+                vec![echo("celebrate")],
+                None,
+                None,
+            ),
+        ],
+        vec![],
+    )
+}
+
+fn plan(vars: &[(&str, &str)]) -> String {
+    let vars: HashMap<String, String> = vars
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    CommandExecutor::plan_workflow(
+        &deploy_workflow(),
+        None,
+        Some(vars),
+        &ExecutionOptions::default(),
+    )
+    .unwrap()
+    .render()
+}
+
+#[test]
+fn test_plan_follows_known_variables() {
+    let plan = plan(&[("ENV", "dev")]);
+
+    assert!(plan.contains("- build: echo build"));
+    assert!(plan.contains("pick-env: ENV = 'dev' selects case 'dev'"));
+    assert!(plan.contains("  - deploy-dev: echo deploy-dev"));
+    assert!(!plan.contains("deploy-prod"));
+
+    // Static tests are resolved once their variables are filled in
+    assert!(plan.contains("notify: run the 'else' block"));
+    assert!(plan.contains("post-to-channel"));
+    assert!(!plan.contains("page-oncall"));
+
+    // Conditions on runtime state list their alternatives instead
+    assert!(plan.contains("check: decided at run time, one of:"));
+    assert!(plan.contains("celebrate"));
+    assert!(plan.contains("otherwise nothing runs"));
+}
+
+#[test]
+fn test_plan_lists_every_case_for_unknown_variables() {
+    let plan = plan(&[]);
+
+    assert!(plan.contains("pick-env: branch on ENV at run time, one of:"));
+    assert!(plan.contains("deploy-dev"));
+    assert!(plan.contains("deploy-prod"));
+    assert!(plan.contains("page-oncall"));
+    assert!(plan.contains("post-to-channel"));
+}
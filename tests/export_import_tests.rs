@@ -159,3 +159,346 @@ async fn test_export_import(ctx: &mut ExportImportContext) {
     assert_eq!(filtered_commands.len(), 1);
     assert_eq!(filtered_commands[0].name, command1.name);
 }
+
+#[test]
+fn test_export_only_changed() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let storage = Storage::new_with_dir(temp_dir.path().join(".clix")).unwrap();
+
+    // Give both commands creation times well before the first export
+    for name in ["unchanged", "modified"] {
+        let mut command = Command::new(
+            name.to_string(),
+            format!("The {} command", name),
+            "echo hi".to_string(),
+            vec![],
+        );
+        command.created_at -= 100;
+        storage.add_command(command).unwrap();
+    }
+
+    let export_manager = ExportManager::new(storage.clone());
+    let first_export = temp_dir.path().join("first.json");
+    export_manager
+        .export_only_changed(first_export.to_str().unwrap(), None, false, false)
+        .unwrap();
+
+    let first: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&first_export).unwrap()).unwrap();
+    assert_eq!(first["commands"].as_object().unwrap().len(), 2);
+
+    let mut modified = storage.get_command("modified").unwrap();
+    modified.description = "Changed after the first export".to_string();
+    storage.update_command(&modified).unwrap();
+
+    let second_export = temp_dir.path().join("second.json");
+    export_manager
+        .export_only_changed(second_export.to_str().unwrap(), None, false, false)
+        .unwrap();
+
+    let second: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&second_export).unwrap()).unwrap();
+    let commands = second["commands"].as_object().unwrap();
+    assert_eq!(commands.len(), 1);
+    assert!(commands.contains_key("modified"));
+}
+
+#[test]
+fn test_export_only_changed_includes_updated_legacy_workflows() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let storage = Storage::new_with_dir(temp_dir.path().join(".clix")).unwrap();
+
+    for name in ["unchanged", "modified"] {
+        let mut workflow = Workflow::new(
+            name.to_string(),
+            format!("The {} workflow", name),
+            vec![],
+            vec![],
+        );
+        workflow.created_at -= 100;
+        storage.add_workflow(workflow).unwrap();
+    }
+
+    let export_manager = ExportManager::new(storage.clone());
+    let first_export = temp_dir.path().join("first.json");
+    export_manager
+        .export_only_changed(first_export.to_str().unwrap(), None, false, false)
+        .unwrap();
+
+    let mut modified = storage.get_workflow("modified").unwrap();
+    modified.description = "Changed after the first export".to_string();
+    storage.update_workflow(&modified).unwrap();
+
+    let second_export = temp_dir.path().join("second.json");
+    export_manager
+        .export_only_changed(second_export.to_str().unwrap(), None, false, false)
+        .unwrap();
+
+    let second: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&second_export).unwrap()).unwrap();
+    let workflows = second["workflows"].as_object().unwrap();
+    assert_eq!(workflows.len(), 1);
+    assert!(workflows.contains_key("modified"));
+}
+
+#[test]
+fn test_clipboard_text_imports_into_fresh_store() {
+    let source_dir = tempfile::TempDir::new().unwrap();
+    let source = Storage::new_with_dir(source_dir.path().to_path_buf()).unwrap();
+    source
+        .add_command(Command::new(
+            "greet".to_string(),
+            "Say hello".to_string(),
+            "echo hello".to_string(),
+            vec!["share".to_string()],
+        ))
+        .unwrap();
+    source
+        .add_command(Command::new(
+            "private".to_string(),
+            "Not shared".to_string(),
+            "echo secret".to_string(),
+            vec![],
+        ))
+        .unwrap();
+
+    // This is the text --to-clipboard puts on the clipboard
+    let text = ExportManager::new(source)
+        .export_to_string(Some("share".to_string()), false, false)
+        .unwrap();
+
+    let target_dir = tempfile::TempDir::new().unwrap();
+    let target = Storage::new_with_dir(target_dir.path().to_path_buf()).unwrap();
+    let summary = ImportManager::new(target.clone())
+        .import_from_str(&text, false)
+        .unwrap();
+
+    assert_eq!(summary.commands_added, 1);
+    let imported = target.get_command("greet").unwrap();
+    assert_eq!(imported.command.as_deref(), Some("echo hello"));
+    assert!(target.get_command("private").is_err());
+}
+
+#[test]
+fn test_bundle_includes_called_workflows() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let storage = Storage::new_with_dir(temp_dir.path().to_path_buf()).unwrap();
+
+    let workflow = |name: &str, command: &str| {
+        Command::new_workflow(
+            name.to_string(),
+            format!("The {} workflow", name),
+            vec![WorkflowStep::new_command(
+                "Run".to_string(),
+                command.to_string(),
+                "Run it".to_string(),
+                false,
+            )],
+            vec![],
+        )
+    };
+    storage
+        .add_command(workflow("release", "clix flow run build && ./publish.sh"))
+        .unwrap();
+    storage
+        .add_command(workflow("build", "clix flow run lint; clix flow run ghost"))
+        .unwrap();
+    storage
+        .add_command(workflow("lint", "cargo clippy"))
+        .unwrap();
+    storage
+        .add_command(workflow("unrelated", "echo hi"))
+        .unwrap();
+
+    let bundle = ExportManager::new(storage)
+        .bundle(&["release".to_string()], false)
+        .unwrap();
+
+    let mut names: Vec<&String> = bundle.data.commands.as_ref().unwrap().keys().collect();
+    names.sort();
+    assert_eq!(names, ["build", "lint", "release"]);
+    assert_eq!(
+        bundle.unresolved,
+        [("ghost".to_string(), "build".to_string())]
+    );
+}
+
+#[test]
+fn test_bundle_of_unknown_name_is_an_error() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let storage = Storage::new_with_dir(temp_dir.path().to_path_buf()).unwrap();
+    assert!(
+        ExportManager::new(storage)
+            .bundle(&["missing".to_string()], false)
+            .is_err()
+    );
+}
+
+#[test]
+fn test_bundle_with_dependencies_includes_called_commands() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let storage = Storage::new_with_dir(temp_dir.path().to_path_buf()).unwrap();
+
+    storage
+        .add_command(Command::new_workflow(
+            "deploy".to_string(),
+            "Deploy the app".to_string(),
+            vec![WorkflowStep::new_command(
+                "Prepare".to_string(),
+                "clix run setup-env && clix run missing-cmd".to_string(),
+                "Set up the environment".to_string(),
+                false,
+            )],
+            vec![],
+        ))
+        .unwrap();
+    let command = |name: &str, text: &str| {
+        Command::new(name.to_string(), name.to_string(), text.to_string(), vec![])
+    };
+    storage
+        .add_command(command("setup-env", "clix run load-secrets"))
+        .unwrap();
+    storage
+        .add_command(command("load-secrets", "echo loaded"))
+        .unwrap();
+
+    let export_manager = ExportManager::new(storage);
+    let without = export_manager
+        .bundle(&["deploy".to_string()], false)
+        .unwrap();
+    assert_eq!(without.data.commands.as_ref().unwrap().len(), 1);
+    assert!(without.unresolved.is_empty());
+
+    let with = export_manager
+        .bundle(&["deploy".to_string()], true)
+        .unwrap();
+    let mut names: Vec<&String> = with.data.commands.as_ref().unwrap().keys().collect();
+    names.sort();
+    assert_eq!(names, ["deploy", "load-secrets", "setup-env"]);
+    assert_eq!(
+        with.unresolved,
+        [("missing-cmd".to_string(), "deploy".to_string())]
+    );
+}
+
+#[test]
+fn test_metadata_round_trips_through_export_and_import() {
+    let source_dir = tempfile::TempDir::new().unwrap();
+    let source = Storage::new_with_dir(source_dir.path().to_path_buf()).unwrap();
+    let mut command = Command::new_workflow(
+        "release".to_string(),
+        "Cut a release".to_string(),
+        vec![WorkflowStep::new_command(
+            "Tag".to_string(),
+            "git tag v1".to_string(),
+            "Tag the release".to_string(),
+            false,
+        )],
+        vec![],
+    );
+    command.author = Some("platform-team".to_string());
+    command.version = Some("1.4.2".to_string());
+    command.changelog = vec![
+        "First version".to_string(),
+        "Push tags separately".to_string(),
+    ];
+    command.notes = Some("Needs push access to origin.\nSee RELEASING.md".to_string());
+    source.add_command(command).unwrap();
+
+    let export_file = source_dir.path().join("export.json");
+    ExportManager::new(source)
+        .export_with_filter(export_file.to_str().unwrap(), None, false, false)
+        .unwrap();
+
+    let target_dir = tempfile::TempDir::new().unwrap();
+    let target = Storage::new_with_dir(target_dir.path().to_path_buf()).unwrap();
+    ImportManager::new(target.clone())
+        .import_from_file(export_file.to_str().unwrap(), false)
+        .unwrap();
+
+    let imported = target.get_command("release").unwrap();
+    assert_eq!(imported.author.as_deref(), Some("platform-team"));
+    assert_eq!(imported.version.as_deref(), Some("1.4.2"));
+    assert_eq!(
+        imported.changelog,
+        ["First version", "Push tags separately"]
+    );
+    assert_eq!(
+        imported.notes.as_deref(),
+        Some("Needs push access to origin.\nSee RELEASING.md")
+    );
+}
+
+/// Split CSV text into records, honouring quoted fields
+fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', _) => quoted = !quoted,
+            (',', false) => record.push(std::mem::take(&mut field)),
+            ('\n', false) => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            (c, _) => field.push(c),
+        }
+    }
+    records
+}
+
+#[test]
+fn test_csv_export_parses_back_with_a_row_per_item() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let storage = Storage::new_with_dir(temp_dir.path().to_path_buf()).unwrap();
+    storage
+        .add_command(Command::new(
+            "build".to_string(),
+            "Build, then \"package\"\nfor release".to_string(),
+            "make".to_string(),
+            vec!["ci".to_string(), "release".to_string()],
+        ))
+        .unwrap();
+    storage
+        .add_command(Command::new_workflow(
+            "deploy".to_string(),
+            "Deploy".to_string(),
+            vec![],
+            vec![],
+        ))
+        .unwrap();
+
+    let csv = ExportManager::new(storage)
+        .export_csv_to_string(None, false, false)
+        .unwrap();
+    let records = parse_csv(&csv);
+
+    assert_eq!(records.len(), 3);
+    assert_eq!(
+        records[0],
+        [
+            "name",
+            "type",
+            "description",
+            "tags",
+            "use_count",
+            "last_used",
+            "created_at"
+        ]
+    );
+    assert_eq!(records[1][0], "build");
+    assert_eq!(records[1][1], "command");
+    assert_eq!(records[1][2], "Build, then \"package\"\nfor release");
+    assert_eq!(records[1][3], "ci;release");
+    assert_eq!(records[1][5], "");
+    assert_eq!(records[2][0], "deploy");
+    assert_eq!(records[2][1], "workflow");
+    assert!(records.iter().all(|record| record.len() == 7));
+}
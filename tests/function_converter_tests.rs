@@ -141,3 +141,57 @@ async fn test_convert_deploy_env_function(ctx: &mut FunctionConverterContext) {
     }
     */
 }
+
+#[test]
+fn test_inline_calls_expands_helpers_from_the_same_file() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let script = temp_dir.path().join("release.sh");
+    fs::write(
+        &script,
+        r#"build() {
+    cargo build --release
+    strip target/release/app
+}
+
+loop_forever() {
+    loop_forever
+}
+
+release() {
+    build
+    loop_forever
+    cargo publish
+}
+"#,
+    )
+    .unwrap();
+    let script = script.to_str().unwrap();
+
+    let commands = |inline_calls: bool| -> Vec<String> {
+        FunctionConverter::convert_function_with_options(
+            script,
+            "release",
+            "release",
+            "Build and publish",
+            vec![],
+            inline_calls,
+        )
+        .unwrap()
+        .steps
+        .into_iter()
+        .map(|step| step.command)
+        .collect()
+    };
+
+    assert_eq!(
+        commands(true),
+        [
+            "cargo build --release",
+            "strip target/release/app",
+            // A recursive helper is left as a call
+            "loop_forever",
+            "cargo publish",
+        ]
+    );
+    assert_eq!(commands(false), ["build", "loop_forever", "cargo publish"]);
+}
@@ -165,3 +165,124 @@ fn test_command_merge_behavior() {
     assert_eq!(merged.get("cmd1").unwrap().description, "Local version");
     assert_eq!(merged.get("cmd2").unwrap().description, "Only in repo");
 }
+
+/// Initialise a local checkout under the repos dir holding a single command
+fn local_repository(repos_dir: &std::path::Path, name: &str, command: &str) {
+    let repo_path = repos_dir.join(name);
+    git2::Repository::init(&repo_path).expect("Should init repository");
+
+    let mut store = CommandStore::new();
+    store.commands.insert(
+        command.to_string(),
+        Command::new(
+            command.to_string(),
+            format!("From {}", name),
+            format!("echo {}", command),
+            vec![],
+        ),
+    );
+    fs::write(
+        repo_path.join("commands.json"),
+        serde_json::to_string_pretty(&store).unwrap(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_sync_single_repository_only_loads_its_items() {
+    let temp_dir = TempDir::new().unwrap();
+    let repos_dir = temp_dir.path().join("repos");
+    fs::create_dir_all(&repos_dir).unwrap();
+    local_repository(&repos_dir, "alpha", "alpha-cmd");
+    local_repository(&repos_dir, "beta", "beta-cmd");
+    fs::write(
+        repos_dir.join("config.json"),
+        r#"[
+            {"name": "alpha", "url": "https://example.invalid/alpha.git", "enabled": true},
+            {"name": "beta", "url": "https://example.invalid/beta.git", "enabled": true}
+        ]"#,
+    )
+    .unwrap();
+
+    let storage = GitIntegratedStorage::new_with_dir(temp_dir.path().to_path_buf()).unwrap();
+    let results = storage.sync_repositories(Some("alpha")).unwrap();
+
+    // The checkouts have no remote, so the pull fails but the items still load
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].0, "alpha");
+    assert!(results[0].1.is_err());
+
+    let names: Vec<String> = storage
+        .list_commands()
+        .unwrap()
+        .into_iter()
+        .map(|command| command.name)
+        .collect();
+    assert_eq!(names, vec!["alpha-cmd".to_string()]);
+
+    // Unknown repositories are an error rather than a silent no-op
+    assert!(storage.sync_repositories(Some("gamma")).is_err());
+
+    let results = storage.sync_repositories(None).unwrap();
+    assert_eq!(results.len(), 2);
+    assert!(storage.get_command("beta-cmd").is_ok());
+}
+
+fn single_command_store(name: &str) -> String {
+    let mut store = CommandStore::new();
+    store.commands.insert(
+        name.to_string(),
+        Command::new(
+            name.to_string(),
+            "From a command file".to_string(),
+            format!("echo {}", name),
+            vec![],
+        ),
+    );
+    serde_json::to_string_pretty(&store).unwrap()
+}
+
+#[test]
+fn test_clixignore_skips_matching_command_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let repos_dir = temp_dir.path().join("repos");
+    fs::create_dir_all(&repos_dir).unwrap();
+    local_repository(&repos_dir, "team", "root-cmd");
+    let repo_path = repos_dir.join("team");
+    fs::create_dir_all(repo_path.join("commands")).unwrap();
+    fs::write(
+        repo_path.join("commands").join("deploy.json"),
+        single_command_store("deploy-cmd"),
+    )
+    .unwrap();
+    fs::write(
+        repo_path.join("commands").join("draft-release.json"),
+        single_command_store("draft-cmd"),
+    )
+    .unwrap();
+    fs::write(
+        repo_path.join(".clixignore"),
+        "# Work in progress\ndraft-*.json\n",
+    )
+    .unwrap();
+    fs::write(
+        repos_dir.join("config.json"),
+        r#"[{"name": "team", "url": "https://example.invalid/team.git", "enabled": true}]"#,
+    )
+    .unwrap();
+
+    let storage = GitIntegratedStorage::new_with_dir(temp_dir.path().to_path_buf()).unwrap();
+    storage.sync_repositories(Some("team")).unwrap();
+
+    let mut names: Vec<String> = storage
+        .list_commands()
+        .unwrap()
+        .into_iter()
+        .map(|command| command.name)
+        .collect();
+    names.sort();
+    assert_eq!(
+        names,
+        vec!["deploy-cmd".to_string(), "root-cmd".to_string()]
+    );
+}
@@ -0,0 +1,229 @@
+mod common;
+
+use clix::storage::{ExecutionKind, ExecutionRecord, HistoryStorage};
+use common::clix;
+use tempfile::TempDir;
+
+fn record(name: &str, kind: ExecutionKind, started_at: u64, success: bool) -> ExecutionRecord {
+    ExecutionRecord {
+        name: name.to_string(),
+        kind,
+        started_at,
+        duration_ms: 10,
+        success,
+        allowed_dangerous: false,
+        environment: None,
+    }
+}
+
+#[test]
+fn test_only_workflows_whose_last_run_failed_are_selected() {
+    let temp_dir = TempDir::new().unwrap();
+    let history = HistoryStorage::new_with_dir(temp_dir.path().to_path_buf()).unwrap();
+
+    history
+        .record(&record("deploy", ExecutionKind::Workflow, 1, true))
+        .unwrap();
+    history
+        .record(&record("deploy", ExecutionKind::Workflow, 2, false))
+        .unwrap();
+    history
+        .record(&record("build", ExecutionKind::Workflow, 3, false))
+        .unwrap();
+    history
+        .record(&record("build", ExecutionKind::Workflow, 4, true))
+        .unwrap();
+    // Failed simple commands are not retried as workflows
+    history
+        .record(&record("lint", ExecutionKind::Command, 5, false))
+        .unwrap();
+
+    let statuses = history.last_status_per_workflow().unwrap();
+    assert_eq!(statuses.get("deploy"), Some(&false));
+    assert_eq!(statuses.get("build"), Some(&true));
+    assert!(!statuses.contains_key("lint"));
+
+    assert_eq!(history.failed_workflows().unwrap(), vec!["deploy"]);
+    assert_eq!(history.load().unwrap().len(), 5);
+}
+
+#[test]
+fn test_empty_history_has_nothing_to_retry() {
+    let temp_dir = TempDir::new().unwrap();
+    let history = HistoryStorage::new_with_dir(temp_dir.path().to_path_buf()).unwrap();
+
+    assert!(history.load().unwrap().is_empty());
+    assert!(history.failed_workflows().unwrap().is_empty());
+}
+
+#[test]
+fn test_history_past_the_limit_keeps_the_newest_runs() {
+    let temp_dir = TempDir::new().unwrap();
+    let history = HistoryStorage::new_with_dir(temp_dir.path().to_path_buf())
+        .unwrap()
+        .with_max_entries(3);
+
+    for started_at in 1..=5 {
+        history
+            .record(&record("deploy", ExecutionKind::Workflow, started_at, true))
+            .unwrap();
+    }
+
+    let kept: Vec<u64> = history
+        .load()
+        .unwrap()
+        .iter()
+        .map(|record| record.started_at)
+        .collect();
+    assert_eq!(kept, [3, 4, 5]);
+
+    assert_eq!(history.clear().unwrap(), 3);
+    assert!(history.load().unwrap().is_empty());
+}
+
+#[test]
+fn test_retry_failed_reruns_only_failed_workflows() {
+    let home = TempDir::new().unwrap();
+    let config_dir = home.path().join(".clix");
+    let marker = home.path().join("flaky-ran");
+
+    let steps = serde_json::json!([
+        {
+            "name": "Flaky",
+            "command": format!("touch {}; test -e {}.ok", marker.display(), marker.display()),
+            "description": "Fails until the .ok file exists",
+            "continue_on_error": false,
+            "step_type": "Command",
+            "require_approval": false
+        }
+    ]);
+    let steps_file = home.path().join("steps.json");
+    std::fs::write(&steps_file, steps.to_string()).unwrap();
+
+    let added = clix(
+        home.path(),
+        &[
+            "add",
+            "flaky",
+            "--description",
+            "Fails the first time",
+            "--steps-file",
+            steps_file.to_str().unwrap(),
+        ],
+    );
+    assert!(added.status.success());
+
+    clix(home.path(), &["run", "flaky"]);
+    let history = HistoryStorage::new_with_dir(config_dir).unwrap();
+    assert_eq!(history.failed_workflows().unwrap(), vec!["flaky"]);
+
+    // Make the step pass and retry
+    std::fs::remove_file(&marker).unwrap();
+    std::fs::write(format!("{}.ok", marker.display()), "").unwrap();
+    let retried = clix(home.path(), &["retry-failed"]);
+    assert!(retried.status.success());
+    assert!(marker.exists());
+    assert!(history.failed_workflows().unwrap().is_empty());
+
+    // Nothing left to retry
+    let output = clix(home.path(), &["retry-failed"]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No failed workflows to retry."));
+}
+
+#[test]
+fn test_time_estimate_averages_recorded_runs() {
+    let home = TempDir::new().unwrap();
+    let history = HistoryStorage::new_with_dir(home.path().join(".clix")).unwrap();
+
+    let output = clix(home.path(), &["time-estimate", "deploy"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No successful runs of 'deploy'"));
+
+    for (started_at, duration_ms) in [(1, 1000), (2, 3000)] {
+        history
+            .record(&ExecutionRecord {
+                duration_ms,
+                ..record("deploy", ExecutionKind::Workflow, started_at, true)
+            })
+            .unwrap();
+    }
+    // Failed runs and other workflows don't count
+    history
+        .record(&record("deploy", ExecutionKind::Workflow, 3, false))
+        .unwrap();
+    history
+        .record(&record("build", ExecutionKind::Workflow, 4, true))
+        .unwrap();
+
+    let estimate = history.duration_estimate("deploy").unwrap().unwrap();
+    assert_eq!(estimate.runs, 2);
+    assert_eq!(estimate.average_ms, 2000);
+    assert_eq!(estimate.last_ms, 3000);
+    assert_eq!(estimate.p90_ms, 3000);
+
+    let output = clix(home.path(), &["time-estimate", "deploy"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("from 2 successful run(s)"), "{}", stdout);
+    assert!(stdout.contains("Average: 2.0s"), "{}", stdout);
+}
+
+#[test]
+fn test_capture_env_records_os_and_profile() {
+    let home = TempDir::new().unwrap();
+    let steps = serde_json::json!([
+        {
+            "name": "Greet",
+            "command": "echo hello {{ TARGET }}",
+            "description": "Say hello",
+            "continue_on_error": false,
+            "step_type": "Command",
+            "require_approval": false
+        }
+    ]);
+    let steps_file = home.path().join("steps.json");
+    std::fs::write(&steps_file, steps.to_string()).unwrap();
+
+    for args in [
+        &[
+            "add",
+            "greet",
+            "--description",
+            "Say hello",
+            "--steps-file",
+            steps_file.to_str().unwrap(),
+        ][..],
+        &[
+            "add-profile",
+            "greet",
+            "--name",
+            "staging",
+            "--description",
+            "Staging",
+            "--var",
+            "TARGET=staging",
+        ],
+    ] {
+        assert!(clix(home.path(), args).status.success());
+    }
+
+    let run = clix(
+        home.path(),
+        &[
+            "run",
+            "greet",
+            "--profile",
+            "staging",
+            "--var",
+            "API_TOKEN=hunter2",
+            "--capture-env",
+        ],
+    );
+    assert!(run.status.success());
+
+    let history = HistoryStorage::new_with_dir(home.path().join(".clix")).unwrap();
+    let records = history.load().unwrap();
+    let environment = records[0].environment.as_ref().expect("no snapshot");
+    assert_eq!(environment.os, std::env::consts::OS);
+    assert_eq!(environment.profile.as_deref(), Some("staging"));
+    assert_eq!(environment.variables["API_TOKEN"], "********");
+}
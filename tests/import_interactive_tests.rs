@@ -0,0 +1,79 @@
+mod common;
+
+use common::clix_with_stdin;
+use serde_json::Value;
+use std::fs;
+use tempfile::TempDir;
+
+fn add(home: &TempDir, name: &str, command: &str) {
+    let output = clix_with_stdin(
+        home.path(),
+        &["add", name, "--description", name, "--command", command],
+        "",
+    );
+    assert!(output.status.success());
+}
+
+fn stored_command(home: &TempDir, name: &str) -> String {
+    let store = fs::read_to_string(home.path().join(".clix/commands.json")).unwrap();
+    let store: Value = serde_json::from_str(&store).unwrap();
+    store["commands"][name]["command"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+#[test]
+fn test_interactive_import_resolves_each_conflict() {
+    let source = TempDir::new().unwrap();
+    add(&source, "build", "make release");
+    add(&source, "deploy", "./deploy.sh --prod");
+    let export = source.path().join("export.json");
+    let output = clix_with_stdin(
+        source.path(),
+        &["export", "--output", export.to_str().unwrap()],
+        "",
+    );
+    assert!(output.status.success());
+
+    let target = TempDir::new().unwrap();
+    add(&target, "build", "make");
+    add(&target, "deploy", "./deploy.sh");
+
+    // Conflicts are offered in name order: overwrite build, skip deploy
+    let output = clix_with_stdin(
+        target.path(),
+        &[
+            "import",
+            "--input",
+            export.to_str().unwrap(),
+            "--interactive",
+        ],
+        "o\ns\n",
+    );
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("command 'build' already exists"));
+    assert!(stdout.contains("command 'build': overwritten"));
+    assert!(stdout.contains("command 'deploy': skipped"));
+
+    assert_eq!(stored_command(&target, "build"), "make release");
+    assert_eq!(stored_command(&target, "deploy"), "./deploy.sh");
+
+    // --yes falls back to --overwrite without prompting
+    let output = clix_with_stdin(
+        target.path(),
+        &[
+            "import",
+            "--input",
+            export.to_str().unwrap(),
+            "--interactive",
+            "--yes",
+            "--overwrite",
+        ],
+        "",
+    );
+    assert!(output.status.success());
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("already exists"));
+    assert_eq!(stored_command(&target, "deploy"), "./deploy.sh --prod");
+}
@@ -0,0 +1,87 @@
+mod common;
+
+use common::clix_with_stdin;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn add_deploy(home: &Path) {
+    let steps = serde_json::json!([
+        {
+            "name": "deploy",
+            "command": "echo \"deploying {{ TAG }} to {{ ENV }} x{{ REPLICAS }}\"",
+            "description": "Deploy",
+            "continue_on_error": false,
+            "step_type": "Command",
+            "require_approval": false
+        }
+    ]);
+    let steps_file = home.join("steps.json");
+    fs::write(&steps_file, steps.to_string()).unwrap();
+
+    let added = clix_with_stdin(
+        home,
+        &[
+            "add",
+            "deploy",
+            "--description",
+            "Deploy the app",
+            "--steps-file",
+            steps_file.to_str().unwrap(),
+        ],
+        "",
+    );
+    assert!(added.status.success());
+}
+
+#[test]
+fn test_json_variables_are_substituted_into_steps() {
+    let home = TempDir::new().unwrap();
+    add_deploy(home.path());
+
+    let output = clix_with_stdin(
+        home.path(),
+        &[
+            "run",
+            "deploy",
+            "--input-json",
+            r#"{"ENV": "prod", "TAG": "1.2.3", "REPLICAS": 3}"#,
+        ],
+        "",
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("deploying 1.2.3 to prod x3"), "{}", stdout);
+}
+
+#[test]
+fn test_json_from_stdin_and_var_overrides_it() {
+    let home = TempDir::new().unwrap();
+    add_deploy(home.path());
+
+    let output = clix_with_stdin(
+        home.path(),
+        &["run", "deploy", "--input-json", "-", "--var", "ENV=staging"],
+        r#"{"ENV": "prod", "TAG": "2.0.0", "REPLICAS": "1"}"#,
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("deploying 2.0.0 to staging x1"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn test_json_that_is_not_an_object_is_rejected() {
+    let home = TempDir::new().unwrap();
+    add_deploy(home.path());
+
+    let output = clix_with_stdin(
+        home.path(),
+        &["run", "deploy", "--input-json", r#"["prod"]"#],
+        "",
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("must be a JSON object"));
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use common::clix;
+use std::fs;
+use tempfile::TempDir;
+
+/// Check that every element is closed in order, so the report is well-formed
+fn assert_balanced(xml: &str) {
+    let body = xml
+        .strip_prefix("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n")
+        .expect("missing XML declaration");
+
+    let mut open = Vec::new();
+    let mut rest = body;
+    while let Some(start) = rest.find('<') {
+        let end = start + rest[start..].find('>').expect("unterminated tag");
+        let tag = &rest[start + 1..end];
+        if let Some(name) = tag.strip_prefix('/') {
+            assert_eq!(open.pop(), Some(name.to_string()), "mismatched </{}>", name);
+        } else if !tag.ends_with('/') {
+            open.push(tag.split_whitespace().next().unwrap().to_string());
+        }
+        rest = &rest[end + 1..];
+    }
+    assert!(open.is_empty(), "unclosed elements: {:?}", open);
+}
+
+#[test]
+fn test_junit_report_has_a_failure_for_the_failing_step() {
+    let home = TempDir::new().unwrap();
+    let steps = serde_json::json!([
+        {
+            "name": "build",
+            "command": "echo built",
+            "description": "Build",
+            "continue_on_error": false,
+            "step_type": "Command",
+            "require_approval": false
+        },
+        {
+            "name": "test",
+            "command": "echo '2 tests <failed>' >&2; exit 3",
+            "description": "Test",
+            "continue_on_error": false,
+            "step_type": "Command",
+            "require_approval": false
+        }
+    ]);
+    let steps_file = home.path().join("steps.json");
+    fs::write(&steps_file, steps.to_string()).unwrap();
+
+    let added = clix(
+        home.path(),
+        &[
+            "add",
+            "ci",
+            "--description",
+            "CI checks",
+            "--steps-file",
+            steps_file.to_str().unwrap(),
+        ],
+    );
+    assert!(added.status.success());
+
+    let report = home.path().join("results.xml");
+    let output = clix(
+        home.path(),
+        &[
+            "run",
+            "ci",
+            "--format",
+            "junit",
+            "--output",
+            report.to_str().unwrap(),
+        ],
+    );
+    assert!(
+        String::from_utf8_lossy(&output.stdout).contains("Wrote JUnit report"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let xml = fs::read_to_string(&report).unwrap();
+    assert_balanced(&xml);
+    assert!(xml.contains("<testsuite name=\"ci\" tests=\"2\" failures=\"1\""));
+    assert!(xml.contains("name=\"build\""));
+    assert_eq!(xml.matches("<failure").count(), 1);
+    assert!(xml.contains("<failure message=\"exit code 3\">2 tests &lt;failed&gt;"));
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use common::clix;
+use tempfile::TempDir;
+
+#[test]
+fn test_list_table_has_header_and_one_row_per_command() {
+    let home = TempDir::new().unwrap();
+    for (name, tags) in [("list-files", "fs"), ("show-disk-usage", "fs,ops")] {
+        let added = clix(
+            home.path(),
+            &[
+                "add",
+                name,
+                "--description",
+                "Table test",
+                "--command",
+                "echo hi",
+                "--tags",
+                tags,
+            ],
+        );
+        assert!(added.status.success());
+    }
+    assert!(clix(home.path(), &["run", "list-files"]).status.success());
+
+    let output = clix(home.path(), &["--no-color", "list", "--format", "table"]);
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 3);
+
+    let header: Vec<&str> = lines[0].split_whitespace().collect();
+    assert_eq!(header, vec!["Name", "Type", "Tags", "Uses", "Last-Used"]);
+
+    let first: Vec<&str> = lines[1].split_whitespace().collect();
+    assert_eq!(first, vec!["list-files", "command", "fs", "1", "today"]);
+
+    let second: Vec<&str> = lines[2].split_whitespace().collect();
+    assert_eq!(
+        second,
+        vec!["show-disk-usage", "command", "fs,ops", "0", "never"]
+    );
+
+    // Columns line up under their headers
+    let type_column = lines[0].find("Type").unwrap();
+    assert_eq!(lines[1].find("command"), Some(type_column));
+    assert_eq!(lines[2].find("command"), Some(type_column));
+    assert!(!stdout.contains('\u{1b}'));
+}
@@ -0,0 +1,210 @@
+mod common;
+
+use clix::commands::{CommandExecutor, Condition, ExecutionOptions, Workflow, WorkflowStep};
+use clix::storage::CheckpointStorage;
+use common::clix;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn line_count(path: &Path) -> usize {
+    std::fs::read_to_string(path)
+        .map(|text| text.lines().count())
+        .unwrap_or(0)
+}
+
+/// A setup step, then a loop that appends a line per iteration until there
+/// are six. The third iteration kills clix the first time it runs.
+fn add_workflow(home: &Path, work: &Path) {
+    let setup_log = work.join("setup.log");
+    let items = work.join("items.log");
+    let crashed = work.join("crashed");
+    let steps = vec![
+        WorkflowStep::new_command(
+            "Setup".to_string(),
+            format!("echo setup >> {}", setup_log.display()),
+            "Prepare the run".to_string(),
+            false,
+        ),
+        WorkflowStep::new_loop(
+            "Process items".to_string(),
+            "Handle one item per pass".to_string(),
+            Condition {
+                expression: format!("[ $(cat {} 2>/dev/null | wc -l) -lt 6 ]", items.display()),
+                variable: None,
+            },
+            vec![WorkflowStep::new_command(
+                "Process".to_string(),
+                format!(
+                    "echo item >> {items}; if [ $(wc -l < {items}) -eq 3 ] && [ ! -f {crashed} ]; then touch {crashed}; kill -KILL $PPID; fi",
+                    items = items.display(),
+                    crashed = crashed.display()
+                ),
+                "Process the next item".to_string(),
+                false,
+            )],
+        ),
+    ];
+    let steps_file = work.join("steps.json");
+    std::fs::write(&steps_file, serde_json::to_string(&steps).unwrap()).unwrap();
+
+    let added = clix(
+        home,
+        &[
+            "add",
+            "batch",
+            "--description",
+            "Process a batch",
+            "--steps-file",
+            steps_file.to_str().unwrap(),
+        ],
+    );
+    assert!(added.status.success());
+}
+
+#[test]
+fn test_interrupted_loop_resumes_from_the_checkpoint() {
+    let home = TempDir::new().unwrap();
+    let work = TempDir::new().unwrap();
+    add_workflow(home.path(), work.path());
+    let checkpoint = home
+        .path()
+        .join(".clix")
+        .join("checkpoints")
+        .join("batch.json");
+
+    let crashed = clix(
+        home.path(),
+        &[
+            "run",
+            "batch",
+            "--checkpoint-every",
+            "2",
+            "--var",
+            "API_TOKEN=hunter2",
+            "--allow-dangerous",
+            "--non-interactive",
+        ],
+    );
+    assert!(!crashed.status.success());
+    assert_eq!(line_count(&work.path().join("items.log")), 3);
+    let saved: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&checkpoint).unwrap()).unwrap();
+    assert_eq!(saved["iteration"], 2);
+    assert_eq!(saved["loop_step"], "Process items");
+    assert!(saved["variables"].get("API_TOKEN").is_none(), "{}", saved);
+
+    let resumed = clix(
+        home.path(),
+        &[
+            "run",
+            "batch",
+            "--continue",
+            "--checkpoint-every",
+            "2",
+            "--allow-dangerous",
+            "--non-interactive",
+        ],
+    );
+    let stdout = String::from_utf8_lossy(&resumed.stdout);
+    assert!(resumed.status.success(), "{}", stdout);
+    assert!(
+        stdout.contains("Resuming loop at iteration 3"),
+        "{}",
+        stdout
+    );
+    assert!(!stdout.contains("Loop iteration: 1\n"), "{}", stdout);
+    assert!(stdout.contains("Loop iteration: 5"), "{}", stdout);
+
+    // The setup step ran once, and the loop finished the batch
+    assert_eq!(line_count(&work.path().join("setup.log")), 1);
+    assert_eq!(line_count(&work.path().join("items.log")), 6);
+    assert!(!checkpoint.exists());
+}
+
+#[test]
+fn test_continue_without_a_checkpoint_is_an_error() {
+    let home = TempDir::new().unwrap();
+    let work = TempDir::new().unwrap();
+    add_workflow(home.path(), work.path());
+
+    let output = clix(home.path(), &["run", "batch", "--continue"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No checkpoint saved for 'batch'"));
+    assert_eq!(line_count(&work.path().join("setup.log")), 0);
+}
+
+#[test]
+fn test_loop_inside_a_conditional_checkpoints_under_its_workflow() {
+    let home = TempDir::new().unwrap();
+    let work = TempDir::new().unwrap();
+    let items = work.path().join("items.log");
+    let ready = work.path().join("ready");
+    let workflow = Workflow::new(
+        "nested batch".to_string(),
+        "A loop inside a conditional".to_string(),
+        vec![
+            WorkflowStep::new_conditional(
+                "When enabled".to_string(),
+                "Only process items when enabled".to_string(),
+                Condition {
+                    expression: "true".to_string(),
+                    variable: None,
+                },
+                vec![WorkflowStep::new_loop(
+                    "Process items".to_string(),
+                    "Handle one item per pass".to_string(),
+                    Condition {
+                        expression: format!(
+                            "[ $(cat {} 2>/dev/null | wc -l) -lt 2 ]",
+                            items.display()
+                        ),
+                        variable: None,
+                    },
+                    vec![WorkflowStep::new_command(
+                        "Process".to_string(),
+                        format!("echo item >> {}", items.display()),
+                        "Process the next item".to_string(),
+                        false,
+                    )],
+                )],
+                None,
+                None,
+            ),
+            WorkflowStep::new_command(
+                "Check ready".to_string(),
+                format!("test -f {}", ready.display()),
+                "Fails until the ready file exists".to_string(),
+                false,
+            ),
+        ],
+        vec![],
+    );
+    let options = |resume_from| ExecutionOptions {
+        require_approval: false,
+        checkpoint_every: Some(1),
+        resume_from,
+        config_dir: Some(home.path().to_path_buf()),
+        ..ExecutionOptions::default()
+    };
+
+    CommandExecutor::execute_workflow_with_options(&workflow, None, None, &options(None)).unwrap();
+    let storage = CheckpointStorage::new_with_dir(home.path().to_path_buf()).unwrap();
+    let checkpoint = storage.load("nested batch").unwrap().unwrap();
+    assert_eq!(checkpoint.workflow, "nested batch");
+    assert_eq!(checkpoint.step_index, 0);
+    assert_eq!(checkpoint.loop_step, "Process items");
+    assert_eq!(checkpoint.iteration, 2);
+
+    // Resumed, the loop has nothing left to do and the run completes
+    std::fs::write(&ready, "").unwrap();
+    let results = CommandExecutor::execute_workflow_with_options(
+        &workflow,
+        None,
+        None,
+        &options(Some(checkpoint)),
+    )
+    .unwrap();
+    assert!(results.iter().all(|(_, result)| result.is_ok()));
+    assert_eq!(line_count(&items), 2);
+    assert!(storage.load("nested batch").unwrap().is_none());
+}
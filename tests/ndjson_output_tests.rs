@@ -0,0 +1,119 @@
+mod common;
+
+use common::{clix, clix_with_stdin};
+use tempfile::TempDir;
+
+#[test]
+fn test_ndjson_writes_one_json_line_per_step() {
+    let home = TempDir::new().unwrap();
+    let steps = serde_json::json!([
+        {
+            "name": "greet",
+            "command": "echo hello",
+            "description": "Prints a greeting",
+            "continue_on_error": true,
+            "step_type": "Command",
+            "require_approval": false
+        },
+        {
+            "name": "fail",
+            "command": "exit 4",
+            "description": "Fails on purpose",
+            "continue_on_error": true,
+            "step_type": "Command",
+            "require_approval": false
+        }
+    ]);
+    let steps_file = home.path().join("steps.json");
+    std::fs::write(&steps_file, steps.to_string()).unwrap();
+
+    let added = clix(
+        home.path(),
+        &[
+            "add",
+            "pipeline",
+            "--description",
+            "Two steps",
+            "--steps-file",
+            steps_file.to_str().unwrap(),
+        ],
+    );
+    assert!(added.status.success());
+
+    let output = clix(home.path(), &["run", "pipeline", "--format", "ndjson"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("every stdout line is JSON"))
+        .collect();
+
+    assert_eq!(lines.len(), 2);
+    for line in &lines {
+        for key in ["step", "exit_code", "duration_ms", "stdout_len"] {
+            assert!(line.get(key).is_some(), "missing '{}' in {}", key, line);
+        }
+    }
+    assert_eq!(lines[0]["step"], "greet");
+    assert_eq!(lines[0]["exit_code"], 0);
+    assert_eq!(lines[0]["stdout_len"], "hello\n".len());
+    assert_eq!(lines[1]["step"], "fail");
+    assert_eq!(lines[1]["exit_code"], 4);
+
+    // Progress still goes somewhere a person can read it
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Executing workflow:"));
+}
+
+/// Every stdout line of an ndjson run, parsed
+fn json_lines(stdout: &[u8]) -> Vec<serde_json::Value> {
+    String::from_utf8_lossy(stdout)
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("every stdout line is JSON"))
+        .collect()
+}
+
+#[test]
+fn test_ndjson_keeps_variable_prompts_off_stdout() {
+    let home = TempDir::new().unwrap();
+    let steps = serde_json::json!([
+        {
+            "name": "greet",
+            "command": "echo hello {{ who }}",
+            "description": "Greets someone",
+            "continue_on_error": false,
+            "step_type": "Command",
+            "require_approval": false
+        }
+    ]);
+    let steps_file = home.path().join("steps.json");
+    std::fs::write(&steps_file, steps.to_string()).unwrap();
+    let added = clix(
+        home.path(),
+        &[
+            "add",
+            "greeting",
+            "--description",
+            "Greet someone",
+            "--steps-file",
+            steps_file.to_str().unwrap(),
+        ],
+    );
+    assert!(added.status.success());
+
+    // Prompted for the first time
+    let args = ["--remember-vars", "run", "greeting", "--format", "ndjson"];
+    let prompted = clix_with_stdin(home.path(), &args, "world\n");
+    let lines = json_lines(&prompted.stdout);
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0]["stdout_len"], "hello world\n".len());
+    let stderr = String::from_utf8_lossy(&prompted.stderr);
+    assert!(stderr.contains("Variable: who"), "{}", stderr);
+    assert!(stderr.contains("Enter value"), "{}", stderr);
+
+    // Then taken from the remembered values
+    let remembered = clix(home.path(), &args);
+    let lines = json_lines(&remembered.stdout);
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0]["stdout_len"], "hello world\n".len());
+    let stderr = String::from_utf8_lossy(&remembered.stderr);
+    assert!(stderr.contains("Variable: who (remembered)"), "{}", stderr);
+}
@@ -0,0 +1,74 @@
+use clix::commands::{CommandExecutor, ExecutionOptions, Workflow, WorkflowStep};
+use std::fs;
+use tempfile::TempDir;
+
+fn workflow(steps: Vec<WorkflowStep>) -> Workflow {
+    Workflow::new(
+        "deploy".to_string(),
+        "Deploy the app".to_string(),
+        steps,
+        vec![],
+    )
+}
+
+fn step(name: &str, command: &str, continue_on_error: bool) -> WorkflowStep {
+    WorkflowStep::new_command(
+        name.to_string(),
+        command.to_string(),
+        name.to_string(),
+        continue_on_error,
+    )
+}
+
+#[test]
+fn test_failing_step_runs_the_handler() {
+    let temp_dir = TempDir::new().unwrap();
+    let marker = temp_dir.path().join("failed.txt");
+
+    let workflow = workflow(vec![
+        step("build", "echo built", false),
+        step("migrate", "exit 3", true),
+        step("notify", "echo done", false),
+    ]);
+    let options = ExecutionOptions {
+        require_approval: false,
+        on_step_fail: Some(format!(
+            "echo \"$CLIX_FAILED_STEP $CLIX_FAILED_EXIT_CODE\" >> '{}'",
+            marker.display()
+        )),
+        ..ExecutionOptions::default()
+    };
+
+    let results =
+        CommandExecutor::execute_workflow_with_options(&workflow, None, None, &options).unwrap();
+
+    // The handler ran once, for the failed step only, and the workflow went on
+    assert_eq!(fs::read_to_string(&marker).unwrap(), "migrate 3\n");
+    assert_eq!(results.len(), 3);
+}
+
+#[test]
+fn test_handler_runs_before_the_workflow_stops() {
+    let temp_dir = TempDir::new().unwrap();
+    let marker = temp_dir.path().join("failed.txt");
+
+    let workflow = workflow(vec![
+        step("migrate", "exit 1", false),
+        step("notify", "echo done", false),
+    ]);
+    let options = ExecutionOptions {
+        require_approval: false,
+        on_step_fail: Some(format!(
+            "echo \"$CLIX_FAILED_STEP\" > '{}'; exit 1",
+            marker.display()
+        )),
+        ..ExecutionOptions::default()
+    };
+
+    let results =
+        CommandExecutor::execute_workflow_with_options(&workflow, None, None, &options).unwrap();
+
+    // A failing handler doesn't change how the workflow goes on
+    assert_eq!(fs::read_to_string(&marker).unwrap(), "migrate\n");
+    assert_eq!(results.len(), 1);
+}
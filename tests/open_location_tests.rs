@@ -0,0 +1,39 @@
+mod common;
+
+use common::clix_command;
+use tempfile::TempDir;
+
+fn clix_open(config_dir: &std::path::Path, args: &[&str]) -> String {
+    let output = clix_command(config_dir, &["open"])
+        .args(args)
+        .env("CLIX_CONFIG_DIR", config_dir)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout)
+        .trim_end()
+        .to_string()
+}
+
+#[test]
+fn test_open_prints_resolved_paths() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_dir = temp_dir.path().join("clix-data");
+
+    assert_eq!(
+        clix_open(&config_dir, &["--what", "store"]),
+        config_dir.join("commands.json").display().to_string()
+    );
+    assert_eq!(
+        clix_open(&config_dir, &["--what", "repos"]),
+        config_dir.join("repos").display().to_string()
+    );
+    assert_eq!(
+        clix_open(&config_dir, &[]),
+        config_dir.display().to_string()
+    );
+}
@@ -0,0 +1,33 @@
+use clix::commands::capture::truncation_marker;
+use clix::commands::{CommandExecutor, ExecutionOptions, Workflow, WorkflowStep};
+
+#[test]
+fn test_step_output_past_the_limit_is_truncated_with_a_marker() {
+    let workflow = Workflow::new(
+        "noisy".to_string(),
+        "Prints more than the limit".to_string(),
+        vec![WorkflowStep::new_command(
+            "flood".to_string(),
+            "yes | head -n 50000; exit 3".to_string(),
+            "Print 100000 bytes".to_string(),
+            true,
+        )],
+        vec![],
+    );
+    let options = ExecutionOptions {
+        require_approval: false,
+        max_output_bytes: 1000,
+        ..ExecutionOptions::default()
+    };
+
+    let results =
+        CommandExecutor::execute_workflow_with_options(&workflow, None, None, &options).unwrap();
+    let output = results[0].1.as_ref().unwrap();
+
+    let marker = truncation_marker(1000);
+    assert_eq!(output.stdout.len(), 1000 + marker.len());
+    assert!(output.stdout.starts_with(b"y\ny\n"));
+    assert!(String::from_utf8_lossy(&output.stdout).ends_with(&marker));
+    assert!(marker.contains("output truncated at 1000 bytes"));
+    assert_eq!(output.status.code(), Some(3));
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use clix::cli::Pager;
+use common::clix_command;
+use std::collections::HashMap;
+use tempfile::TempDir;
+
+fn select(vars: &[(&str, &str)]) -> Option<Pager> {
+    let env: HashMap<String, String> = vars
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+    Pager::select(|name| env.get(name).cloned())
+}
+
+fn pager(program: &str, args: &[&str]) -> Option<Pager> {
+    Some(Pager {
+        program: program.to_string(),
+        args: args.iter().map(|arg| arg.to_string()).collect(),
+    })
+}
+
+#[test]
+fn test_pager_selection_order() {
+    assert_eq!(select(&[]), pager("less", &["-R"]));
+    assert_eq!(select(&[("PAGER", "more")]), pager("more", &[]));
+    assert_eq!(
+        select(&[("PAGER", "more"), ("CLIX_PAGER", "bat --paging always")]),
+        pager("bat", &["--paging", "always"])
+    );
+
+    // An empty pager or cat means no paging
+    assert_eq!(select(&[("CLIX_PAGER", ""), ("PAGER", "more")]), None);
+    assert_eq!(select(&[("PAGER", "cat")]), None);
+}
+
+#[test]
+fn test_paging_needs_the_flag_and_a_terminal() {
+    assert!(Pager::should_page(true, false, true));
+    assert!(!Pager::should_page(false, false, true));
+    assert!(!Pager::should_page(true, true, true));
+    assert!(!Pager::should_page(true, false, false));
+}
+
+#[test]
+fn test_pager_is_skipped_when_stdout_is_not_a_terminal() {
+    let home = TempDir::new().unwrap();
+    // A pager that would swallow all output if it were used
+    let output = clix_command(home.path(), &["--pager", "list"])
+        .env("CLIX_PAGER", "true")
+        .output()
+        .expect("failed to run clix");
+
+    assert!(output.status.success());
+    assert!(!output.stdout.is_empty());
+}
@@ -0,0 +1,48 @@
+mod common;
+
+use common::{clix_command, output_with_stdin};
+use std::fs;
+use std::process::Output;
+use tempfile::TempDir;
+
+fn clix(home: &TempDir, args: &[&str], input: &str) -> Output {
+    // Commands refer to files relative to the home directory
+    output_with_stdin(
+        clix_command(home.path(), args).current_dir(home.path()),
+        input,
+    )
+}
+
+#[test]
+fn test_preview_lists_files_before_approval() {
+    let home = TempDir::new().unwrap();
+    fs::create_dir(home.path().join("build")).unwrap();
+    fs::write(home.path().join("build").join("app.bin"), "binary").unwrap();
+
+    let added = clix(
+        &home,
+        &[
+            "add",
+            "wipe",
+            "--description",
+            "Remove the build directory",
+            "--command",
+            "rm -rf build",
+        ],
+        "",
+    );
+    assert!(added.status.success());
+
+    // Decline the approval prompt after seeing the preview
+    let output = clix(&home, &["run", "wipe", "--preview-destructive"], "n\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let preview = stdout
+        .find("Destructive preview: ls -la build")
+        .expect("no preview shown");
+    let approval = stdout
+        .find("Security Approval Required")
+        .expect("no approval prompt");
+    assert!(preview < approval, "{}", stdout);
+    assert!(stdout[preview..approval].contains("app.bin"));
+    assert!(home.path().join("build").join("app.bin").exists());
+}
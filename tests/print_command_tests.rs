@@ -0,0 +1,62 @@
+mod common;
+
+use common::clix;
+use tempfile::TempDir;
+
+#[test]
+fn test_print_command_shows_resolved_command_without_running_it() {
+    let home = TempDir::new().unwrap();
+    let marker = home.path().join("ran");
+    let command = format!(
+        "touch {} && echo {{{{ greeting }}}} {{{{ name }}}}",
+        marker.display()
+    );
+
+    let added = clix(
+        home.path(),
+        &[
+            "add",
+            "greet",
+            "--description",
+            "Greet someone",
+            "--command",
+            &command,
+        ],
+    );
+    assert!(added.status.success());
+
+    let output = clix(
+        home.path(),
+        &[
+            "run",
+            "greet",
+            "--var",
+            "greeting=hello",
+            "--var",
+            "name='Ada Lovelace'",
+            "--print-command",
+        ],
+    );
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        format!("touch {} && echo hello 'Ada Lovelace'\n", marker.display())
+    );
+    assert!(!marker.exists(), "--print-command must not run the command");
+
+    // The resolved form is exactly what a real run executes
+    let ran = clix(
+        home.path(),
+        &[
+            "run",
+            "greet",
+            "--var",
+            "greeting=hello",
+            "--var",
+            "name='Ada Lovelace'",
+        ],
+    );
+    assert!(ran.status.success());
+    assert!(marker.exists());
+    assert!(String::from_utf8_lossy(&ran.stdout).contains("hello Ada Lovelace"));
+}
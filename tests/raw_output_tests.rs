@@ -0,0 +1,73 @@
+mod common;
+
+use common::clix;
+use tempfile::TempDir;
+
+#[test]
+fn test_raw_prints_only_command_stdout() {
+    let home = TempDir::new().unwrap();
+    let added = clix(
+        home.path(),
+        &[
+            "add",
+            "get-token",
+            "--description",
+            "Print a token",
+            "--command",
+            "printf 'abc123\\n'",
+        ],
+    );
+    assert!(added.status.success());
+
+    let output = clix(home.path(), &["run", "get-token", "--raw"]);
+
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "abc123\n");
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn test_raw_exits_with_command_exit_code() {
+    let home = TempDir::new().unwrap();
+    let added = clix(
+        home.path(),
+        &[
+            "add",
+            "fail-three",
+            "--description",
+            "Exit with status 3",
+            "--command",
+            "echo partial; exit 3",
+        ],
+    );
+    assert!(added.status.success());
+
+    let output = clix(home.path(), &["run", "fail-three", "--raw"]);
+
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "partial\n");
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn test_raw_passes_command_stderr_through() {
+    let home = TempDir::new().unwrap();
+    let added = clix(
+        home.path(),
+        &[
+            "add",
+            "warn",
+            "--description",
+            "Print a warning",
+            "--command",
+            "echo result; echo 'token expires soon' >&2",
+        ],
+    );
+    assert!(added.status.success());
+
+    let output = clix(home.path(), &["run", "warn", "--raw"]);
+
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "result\n");
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "token expires soon\n"
+    );
+}
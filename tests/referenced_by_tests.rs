@@ -0,0 +1,99 @@
+mod common;
+
+use common::clix;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn setup(home: &Path) {
+    let added = clix(
+        home,
+        &[
+            "add",
+            "build",
+            "--description",
+            "Build",
+            "--command",
+            "make",
+        ],
+    );
+    assert!(added.status.success());
+
+    let steps = serde_json::json!([
+        {
+            "name": "build",
+            "command": "clix run build",
+            "description": "Build first",
+            "continue_on_error": false,
+            "step_type": "Command",
+            "require_approval": false
+        }
+    ]);
+    let steps_file = home.join("steps.json");
+    fs::write(&steps_file, steps.to_string()).unwrap();
+    let added = clix(
+        home,
+        &[
+            "add",
+            "release",
+            "--description",
+            "Build and ship",
+            "--steps-file",
+            steps_file.to_str().unwrap(),
+        ],
+    );
+    assert!(added.status.success());
+
+    let added = clix(
+        home,
+        &[
+            "add",
+            "lint",
+            "--description",
+            "Lint",
+            "--command",
+            "cargo clippy",
+        ],
+    );
+    assert!(added.status.success());
+}
+
+#[test]
+fn test_list_referenced_by_reports_the_caller() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+
+    let output = clix(
+        home.path(),
+        &["list", "--referenced-by", "build", "--format", "table"],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success());
+    assert!(
+        stdout.lines().any(|line| line.starts_with("release ")),
+        "{}",
+        stdout
+    );
+    assert!(!stdout.contains("lint"), "{}", stdout);
+
+    let output = clix(home.path(), &["list", "--referenced-by", "lint"]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Nothing references 'lint'."));
+}
+
+#[test]
+fn test_removing_a_referenced_command_warns() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+
+    let output = clix(home.path(), &["remove", "build"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success());
+    assert!(
+        stdout.contains("Warning: 'build' is still called by: release"),
+        "{}",
+        stdout
+    );
+
+    let output = clix(home.path(), &["remove", "lint"]);
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("Warning:"));
+}
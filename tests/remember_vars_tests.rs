@@ -0,0 +1,87 @@
+use clix::commands::{
+    CommandExecutor, ExecutionOptions, VariableCache, VariableProcessor, Workflow, WorkflowContext,
+    WorkflowStep, WorkflowVariable,
+};
+use std::rc::Rc;
+use tempfile::TempDir;
+
+fn deploy_workflow(name: &str) -> Workflow {
+    Workflow::with_variables(
+        name.to_string(),
+        "Uses the shared environment variable".to_string(),
+        vec![WorkflowStep::new_command(
+            "Show".to_string(),
+            "echo target={{ ENV }}".to_string(),
+            "Print the environment".to_string(),
+            false,
+        )],
+        vec![],
+        vec![WorkflowVariable::new(
+            "ENV".to_string(),
+            "Target environment".to_string(),
+            None,
+            true,
+        )],
+    )
+}
+
+#[test]
+fn test_second_workflow_reuses_remembered_variable() {
+    let cache = VariableCache::new();
+
+    let mut first = WorkflowContext::new();
+    VariableProcessor::prompt_for_variables_with(
+        &deploy_workflow("build"),
+        &mut first,
+        Some(&cache),
+        || Ok("prod".to_string()),
+    )
+    .unwrap();
+    assert_eq!(first.variables["ENV"], "prod");
+
+    let mut second = WorkflowContext::new();
+    VariableProcessor::prompt_for_variables_with(
+        &deploy_workflow("deploy"),
+        &mut second,
+        Some(&cache),
+        || panic!("ENV should not be prompted for again"),
+    )
+    .unwrap();
+    assert_eq!(second.variables["ENV"], "prod");
+}
+
+#[test]
+fn test_executor_uses_the_shared_cache() {
+    let cache = Rc::new(VariableCache::new());
+    cache.remember("ENV", "staging").unwrap();
+    let options = ExecutionOptions {
+        require_approval: false,
+        variable_cache: Some(Rc::clone(&cache)),
+        ..ExecutionOptions::default()
+    };
+
+    let results = CommandExecutor::execute_workflow_with_options(
+        &deploy_workflow("deploy"),
+        None,
+        None,
+        &options,
+    )
+    .unwrap();
+    let output = results[0].1.as_ref().unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "target=staging\n");
+}
+
+#[test]
+fn test_file_backed_cache_carries_over_without_secrets() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("remembered_vars.json");
+
+    let cache = VariableCache::with_file(path.clone(), VariableCache::FILE_TTL).unwrap();
+    cache.remember("ENV", "prod").unwrap();
+    cache.remember("API_TOKEN", "s3cret").unwrap();
+    assert_eq!(cache.get("API_TOKEN").as_deref(), Some("s3cret"));
+
+    let later = VariableCache::with_file(path, VariableCache::FILE_TTL).unwrap();
+    assert_eq!(later.get("ENV").as_deref(), Some("prod"));
+    assert_eq!(later.get("API_TOKEN"), None);
+}
@@ -0,0 +1,48 @@
+mod common;
+
+use common::clix_command;
+use std::process::Output;
+use tempfile::TempDir;
+
+fn clix(home: &TempDir, args: &[&str]) -> Output {
+    clix_command(home.path(), args)
+        .env_remove("FOO")
+        .output()
+        .expect("failed to run clix")
+}
+
+#[test]
+fn test_env_flag_sets_variables_for_simple_commands() {
+    let home = TempDir::new().unwrap();
+    let added = clix(
+        &home,
+        &[
+            "add",
+            "show-foo",
+            "--description",
+            "Print FOO",
+            "--command",
+            "echo \"$FOO\"",
+        ],
+    );
+    assert!(added.status.success());
+
+    let output = clix(&home, &["run", "show-foo", "--raw", "--env", "FOO=bar"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "bar\n");
+
+    // Values may contain '=' and are passed through unchanged
+    let output = clix(
+        &home,
+        &["run", "show-foo", "--raw", "--env", "FOO=a=b {{ x }}"],
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "a=b {{ x }}\n");
+
+    let output = clix(&home, &["run", "show-foo", "--env", "FOO"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("expected KEY=VALUE"));
+
+    let output = clix(&home, &["run", "show-foo", "--env", "1FOO=bar"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Invalid environment variable name"));
+}
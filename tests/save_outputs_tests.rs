@@ -0,0 +1,76 @@
+use clix::commands::{CommandExecutor, ExecutionOptions, Workflow, WorkflowStep};
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn test_each_step_output_is_saved_to_its_own_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let dir = temp_dir.path().join("outputs").join("run-1");
+
+    let workflow = Workflow::new(
+        "audit".to_string(),
+        "Two steps to audit".to_string(),
+        vec![
+            WorkflowStep::new_command(
+                "Build app".to_string(),
+                "echo built".to_string(),
+                "Build".to_string(),
+                false,
+            ),
+            WorkflowStep::new_command(
+                "check/status".to_string(),
+                "echo warning >&2; echo checked".to_string(),
+                "Check".to_string(),
+                false,
+            ),
+        ],
+        vec![],
+    );
+    let options = ExecutionOptions {
+        require_approval: false,
+        save_outputs: Some(dir.clone()),
+        ..ExecutionOptions::default()
+    };
+
+    CommandExecutor::execute_workflow_with_options(&workflow, None, None, &options).unwrap();
+
+    let read = |file: &str| fs::read_to_string(dir.join(file)).unwrap();
+    assert_eq!(read("1-Build%20app.stdout"), "built\n");
+    assert_eq!(read("1-Build%20app.stderr"), "");
+    assert_eq!(read("2-check%2Fstatus.stdout"), "checked\n");
+    assert_eq!(read("2-check%2Fstatus.stderr"), "warning\n");
+    assert_eq!(read("2-check%2Fstatus.exit_code"), "0\n");
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 6);
+}
+
+#[test]
+fn test_failed_step_output_is_saved() {
+    let temp_dir = TempDir::new().unwrap();
+    let dir = temp_dir.path().join("outputs");
+
+    let workflow = Workflow::new(
+        "deploy".to_string(),
+        "A step that fails".to_string(),
+        vec![WorkflowStep::new_command(
+            "Deploy".to_string(),
+            "echo uploading; echo 'no credentials' >&2; exit 3".to_string(),
+            "Deploy".to_string(),
+            false,
+        )],
+        vec![],
+    );
+    let options = ExecutionOptions {
+        require_approval: false,
+        save_outputs: Some(dir.clone()),
+        ..ExecutionOptions::default()
+    };
+
+    let results =
+        CommandExecutor::execute_workflow_with_options(&workflow, None, None, &options).unwrap();
+    assert!(results[0].1.is_err());
+
+    let read = |file: &str| fs::read_to_string(dir.join(file)).unwrap();
+    assert_eq!(read("1-Deploy.stdout"), "uploading\n");
+    assert_eq!(read("1-Deploy.stderr"), "no credentials\n");
+    assert_eq!(read("1-Deploy.exit_code"), "3\n");
+}
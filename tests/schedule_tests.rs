@@ -0,0 +1,57 @@
+mod common;
+
+use clix::storage::{ExecutionKind, HistoryStorage};
+use common::clix;
+use tempfile::TempDir;
+
+#[test]
+fn test_schedule_runs_once_per_interval() {
+    let home = TempDir::new().unwrap();
+    let added = clix(
+        home.path(),
+        &[
+            "add",
+            "tick",
+            "--description",
+            "Scheduled test command",
+            "--command",
+            "echo tick",
+        ],
+    );
+    assert!(added.status.success());
+
+    // Slots start at 0, 100, 200 and 300 ms; the one at 400 ms is past the end
+    let output = clix(
+        home.path(),
+        &[
+            "schedule", "run", "tick", "--every", "100ms", "--for", "350ms",
+        ],
+    );
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Run 4 of 'tick' succeeded"));
+    assert!(stdout.contains("4 run(s), 0 failed"));
+
+    let records = HistoryStorage::new_with_dir(home.path().join(".clix"))
+        .unwrap()
+        .load()
+        .unwrap();
+    assert_eq!(records.len(), 4);
+    assert!(
+        records
+            .iter()
+            .all(|record| record.name == "tick" && record.kind == ExecutionKind::Command)
+    );
+}
+
+#[test]
+fn test_schedule_rejects_bad_interval() {
+    let home = TempDir::new().unwrap();
+    let output = clix(
+        home.path(),
+        &["schedule", "run", "tick", "--every", "soon", "--for", "1m"],
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Invalid duration 'soon'"));
+}
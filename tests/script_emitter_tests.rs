@@ -0,0 +1,115 @@
+use clix::commands::{
+    BranchCase, Condition, ScriptEmitter, Workflow, WorkflowStep, WorkflowVariable,
+};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+fn command(name: &str, cmd: &str) -> WorkflowStep {
+    WorkflowStep::new_command(
+        name.to_string(),
+        cmd.to_string(),
+        format!("{} step", name),
+        false,
+    )
+}
+
+fn conditional_workflow() -> Workflow {
+    Workflow::with_variables(
+        "deploy".to_string(),
+        "Deploy the app".to_string(),
+        vec![
+            command("Check", "test -f config.yml"),
+            WorkflowStep::new_conditional(
+                "Config present".to_string(),
+                "Deploy only when configured".to_string(),
+                Condition {
+                    expression: "[ -f config.yml ]".to_string(),
+                    variable: None,
+                },
+                vec![command("Deploy", "echo deploying to {{ env }}")],
+                Some(vec![command("Skip", "echo missing config")]),
+                None,
+            ),
+        ],
+        vec![],
+        vec![WorkflowVariable::new(
+            "env".to_string(),
+            "Target environment".to_string(),
+            Some("staging".to_string()),
+            false,
+        )],
+    )
+}
+
+/// Ask bash to parse the script without running it
+fn assert_valid_bash(script: &str) {
+    let mut child = Command::new("bash")
+        .arg("-n")
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(script.as_bytes())
+        .unwrap();
+    assert!(child.wait().unwrap().success(), "invalid bash:\n{}", script);
+}
+
+#[test]
+fn test_conditional_emits_if_else_fi() {
+    let script = ScriptEmitter::emit(&conditional_workflow());
+
+    assert!(script.starts_with("#!/usr/bin/env bash\n"));
+    assert!(script.contains("if [ -f config.yml ]; then"));
+    assert!(script.contains("    echo deploying to ${env}"));
+    assert!(script.contains("\nelse\n"));
+    assert!(script.contains("    echo missing config"));
+    assert!(script.contains("\nfi\n"));
+    assert!(script.find("if [").unwrap() < script.find("\nelse\n").unwrap());
+    assert!(script.find("\nelse\n").unwrap() < script.find("\nfi\n").unwrap());
+    assert!(script.contains("env=\"${env:-staging}\""));
+    assert!(script.contains("# Deploy only when configured"));
+    assert_valid_bash(&script);
+}
+
+#[test]
+fn test_branch_and_loop_emit_case_and_while() {
+    let workflow = Workflow::new(
+        "mixed".to_string(),
+        "Branch and loop".to_string(),
+        vec![
+            WorkflowStep::new_branch(
+                "By env".to_string(),
+                "Pick per environment".to_string(),
+                "env".to_string(),
+                vec![BranchCase {
+                    value: "prod".to_string(),
+                    steps: vec![command("Prod", "echo prod")],
+                }],
+                Some(vec![command("Other", "echo other")]),
+            ),
+            WorkflowStep::new_loop(
+                "Retry".to_string(),
+                "Wait for the marker".to_string(),
+                Condition {
+                    expression: "[ ! -f done.marker ]".to_string(),
+                    variable: None,
+                },
+                vec![command("Sleep", "sleep 1")],
+            ),
+        ],
+        vec![],
+    );
+
+    let script = ScriptEmitter::emit(&workflow);
+
+    assert!(script.contains("case \"${env}\" in"));
+    assert!(script.contains("    prod)"));
+    assert!(script.contains("    *)"));
+    assert!(script.contains("esac"));
+    assert!(script.contains("while [ ! -f done.marker ]; do"));
+    assert!(script.contains("done"));
+    assert_valid_bash(&script);
+}
@@ -1,4 +1,4 @@
-use clix::settings::SettingsManager;
+use clix::settings::{SettingsManager, SettingsSection};
 use std::env;
 use std::fs;
 use std::path::PathBuf;
@@ -127,3 +127,90 @@ async fn test_persistence(ctx: &mut SettingsContext) {
     assert_eq!(settings.ai_settings.temperature, new_temperature);
     assert_eq!(settings.ai_settings.max_tokens, new_max_tokens);
 }
+
+#[test_context(SettingsContext)]
+#[tokio::test]
+async fn test_set_workflow_var_default(ctx: &mut SettingsContext) {
+    ctx.settings_manager
+        .set_workflow_var_default("REGION", "eu")
+        .unwrap();
+
+    let settings = ctx.settings_manager.load().unwrap();
+    assert_eq!(
+        settings
+            .workflow_var_defaults
+            .get("REGION")
+            .map(String::as_str),
+        Some("eu")
+    );
+    assert!(
+        ctx.settings_manager
+            .set_workflow_var_default("", "x")
+            .is_err()
+    );
+}
+
+#[test_context(SettingsContext)]
+#[tokio::test]
+async fn test_generic_get_and_set_by_path(ctx: &mut SettingsContext) {
+    let temperature = ctx
+        .settings_manager
+        .get_value("ai_settings.temperature")
+        .unwrap();
+    assert_eq!(temperature, serde_json::json!(0.7));
+
+    ctx.settings_manager
+        .set_value("ai_settings.temperature", "0.3")
+        .unwrap();
+    let settings = ctx.settings_manager.load().unwrap();
+    assert_eq!(settings.ai_settings.temperature, 0.3);
+
+    // Out of range and mistyped values are rejected and leave the setting alone
+    assert!(
+        ctx.settings_manager
+            .set_value("ai_settings.temperature", "1.5")
+            .is_err()
+    );
+    assert!(
+        ctx.settings_manager
+            .set_value("ai_settings.temperature", "warm")
+            .is_err()
+    );
+    assert_eq!(
+        ctx.settings_manager
+            .get_value("ai_settings.temperature")
+            .unwrap(),
+        serde_json::json!(0.3)
+    );
+
+    // Unknown keys are reported rather than silently dropped
+    assert!(
+        ctx.settings_manager
+            .get_value("ai_settings.colour")
+            .is_err()
+    );
+    assert!(
+        ctx.settings_manager
+            .set_value("ai_settings.colour", "blue")
+            .is_err()
+    );
+}
+
+#[test_context(SettingsContext)]
+#[tokio::test]
+async fn test_reset_restores_defaults(ctx: &mut SettingsContext) {
+    ctx.settings_manager.update_ai_temperature(0.2).unwrap();
+    ctx.settings_manager
+        .set_value("git_settings.auto_sync", "false")
+        .unwrap();
+
+    // Resetting one section leaves the others alone
+    ctx.settings_manager.reset(SettingsSection::Ai).unwrap();
+    let settings = ctx.settings_manager.load().unwrap();
+    assert_eq!(settings.ai_settings.temperature, 0.7);
+    assert!(!settings.git_settings.auto_sync);
+
+    ctx.settings_manager.reset(SettingsSection::All).unwrap();
+    let settings = ctx.settings_manager.load().unwrap();
+    assert!(settings.git_settings.auto_sync);
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use common::{clix, clix_command};
+use std::fs;
+use std::process::Stdio;
+use std::thread;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+#[test]
+fn test_second_run_of_single_instance_workflow_is_refused() {
+    let home = TempDir::new().unwrap();
+    let steps = serde_json::json!([
+        {
+            "name": "migrate",
+            "command": "sleep 2",
+            "description": "Takes a while",
+            "continue_on_error": false,
+            "step_type": "Command",
+            "require_approval": false
+        }
+    ]);
+    let steps_file = home.path().join("steps.json");
+    fs::write(&steps_file, steps.to_string()).unwrap();
+
+    let added = clix(
+        home.path(),
+        &[
+            "add",
+            "migrate",
+            "--description",
+            "Run migrations",
+            "--steps-file",
+            steps_file.to_str().unwrap(),
+            "--lock",
+        ],
+    );
+    assert!(added.status.success());
+
+    let mut first = clix_command(home.path(), &["run", "migrate"])
+        .stdout(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    // Wait for the first run to take the lock
+    let lock_path = home.path().join(".clix").join("locks").join("migrate.lock");
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !lock_path.exists() {
+        assert!(Instant::now() < deadline, "first run never took the lock");
+        thread::sleep(Duration::from_millis(20));
+    }
+
+    let second = clix(home.path(), &["run", "migrate"]);
+    assert_eq!(second.status.code(), Some(5));
+    assert!(
+        String::from_utf8_lossy(&second.stderr).contains("'migrate' is already running"),
+        "{}",
+        String::from_utf8_lossy(&second.stderr)
+    );
+
+    assert!(first.wait().unwrap().success());
+    assert!(!lock_path.exists());
+    assert!(clix(home.path(), &["run", "migrate"]).status.success());
+}
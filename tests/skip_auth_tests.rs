@@ -0,0 +1,48 @@
+use clix::commands::{CommandExecutor, ExecutionOptions, Workflow, WorkflowStep};
+use clix::error::ClixError;
+use tempfile::TempDir;
+
+#[test]
+fn test_skip_auth_skips_auth_steps_and_runs_the_rest() {
+    let work = TempDir::new().unwrap();
+    let login_log = work.path().join("login.log");
+    let workflow = Workflow::new(
+        "deploy".to_string(),
+        "Log in and deploy".to_string(),
+        vec![
+            WorkflowStep::new_auth(
+                "Login".to_string(),
+                format!("echo login >> {}", login_log.display()),
+                "Log in to the cloud provider".to_string(),
+            ),
+            WorkflowStep::new_command(
+                "Deploy".to_string(),
+                "echo deployed".to_string(),
+                "Deploy the app".to_string(),
+                false,
+            ),
+        ],
+        vec![],
+    );
+    let options = ExecutionOptions {
+        require_approval: false,
+        skip_auth: true,
+        ..ExecutionOptions::default()
+    };
+
+    let results =
+        CommandExecutor::execute_workflow_with_options(&workflow, None, None, &options).unwrap();
+
+    assert_eq!(results.len(), 2);
+    let (name, login) = &results[0];
+    assert_eq!(name, "Login");
+    assert!(
+        matches!(login, Err(ClixError::StepSkipped(_))),
+        "{:?}",
+        login
+    );
+    assert!(!login_log.exists());
+
+    let deploy = results[1].1.as_ref().unwrap();
+    assert_eq!(String::from_utf8_lossy(&deploy.stdout), "deployed\n");
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use common::clix;
+use std::fs;
+use tempfile::TempDir;
+
+fn step(name: &str, command: String) -> serde_json::Value {
+    serde_json::json!({
+        "name": name,
+        "command": command,
+        "description": name,
+        "continue_on_error": false,
+        "step_type": "Command",
+        "require_approval": false
+    })
+}
+
+#[test]
+fn test_flagged_step_is_skipped_and_safe_steps_run() {
+    let home = TempDir::new().unwrap();
+    let before = home.path().join("before");
+    let after = home.path().join("after");
+    let target = home.path().join("target");
+    fs::create_dir(&target).unwrap();
+
+    let steps = serde_json::json!([
+        step("Before", format!("touch {}", before.display())),
+        step("Wipe", format!("rm -rf {}/*", target.display())),
+        step("After", format!("touch {}", after.display())),
+    ]);
+    let steps_file = home.path().join("steps.json");
+    fs::write(&steps_file, steps.to_string()).unwrap();
+    let added = clix(
+        home.path(),
+        &[
+            "add",
+            "tidy",
+            "--description",
+            "Tidy up",
+            "--steps-file",
+            steps_file.to_str().unwrap(),
+        ],
+    );
+    assert!(added.status.success());
+    fs::write(target.join("keep.txt"), "data").unwrap();
+
+    let output = clix(
+        home.path(),
+        &["run", "tidy", "--skip-unsafe", "--format", "ndjson"],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(before.exists());
+    assert!(after.exists());
+    assert!(target.join("keep.txt").exists());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Skipped: flagged by the security checks"));
+    let reports: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let steps: Vec<&str> = reports
+        .iter()
+        .map(|report| report["step"].as_str().unwrap())
+        .collect();
+    assert_eq!(steps, ["Before", "Wipe", "After"]);
+    // The skipped step is reported as skipped, not as a clean exit
+    assert_eq!(reports[1]["skipped"], true);
+    assert!(reports[1]["exit_code"].is_null());
+    assert!(reports[0].get("skipped").is_none());
+}
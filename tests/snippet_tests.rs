@@ -0,0 +1,102 @@
+use clix::commands::{CommandExecutor, ExecutionOptions, Workflow, WorkflowStep};
+use clix::error::ClixError;
+use clix::storage::SnippetStorage;
+use std::collections::HashMap;
+use tempfile::TempDir;
+
+fn step(name: &str, command: &str) -> WorkflowStep {
+    WorkflowStep::new_command(
+        name.to_string(),
+        command.to_string(),
+        format!("Step {}", name),
+        false,
+    )
+}
+
+fn include(name: &str, snippet: &str) -> WorkflowStep {
+    WorkflowStep::new_include(name.to_string(), snippet.to_string())
+}
+
+fn workflow(steps: Vec<WorkflowStep>) -> Workflow {
+    Workflow::new(
+        "release".to_string(),
+        "Release with shared steps".to_string(),
+        steps,
+        vec![],
+    )
+}
+
+#[test]
+fn test_include_expands_to_the_stored_snippet() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage = SnippetStorage::new_with_dir(temp_dir.path().to_path_buf()).unwrap();
+    assert!(
+        !storage
+            .add("notify", vec![step("announce", "echo announced")])
+            .unwrap()
+    );
+    // Snippets may include other snippets
+    storage
+        .add(
+            "finish",
+            vec![step("cleanup", "echo cleaned"), include("tell", "notify")],
+        )
+        .unwrap();
+
+    let workflow = workflow(vec![
+        step("build", "echo built"),
+        include("wrap-up", "finish"),
+    ]);
+    let options = ExecutionOptions {
+        require_approval: false,
+        snippets: storage.load().unwrap(),
+        ..ExecutionOptions::default()
+    };
+
+    let results =
+        CommandExecutor::execute_workflow_with_options(&workflow, None, None, &options).unwrap();
+    let ran: Vec<(&str, String)> = results
+        .iter()
+        .map(|(name, result)| {
+            let stdout = String::from_utf8_lossy(&result.as_ref().unwrap().stdout).to_string();
+            (name.as_str(), stdout)
+        })
+        .collect();
+    assert_eq!(
+        ran,
+        vec![
+            ("build", "built\n".to_string()),
+            ("cleanup", "cleaned\n".to_string()),
+            ("announce", "announced\n".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_recursive_include_is_rejected() {
+    let snippets = HashMap::from([
+        ("ping".to_string(), vec![include("to-pong", "pong")]),
+        ("pong".to_string(), vec![include("to-ping", "ping")]),
+    ]);
+    let options = ExecutionOptions {
+        require_approval: false,
+        snippets,
+        ..ExecutionOptions::default()
+    };
+
+    let recursive = workflow(vec![include("start", "ping")]);
+    match CommandExecutor::execute_workflow_with_options(&recursive, None, None, &options) {
+        Err(ClixError::ValidationError(message)) => {
+            assert!(message.contains("ping -> pong -> ping"), "{}", message)
+        }
+        other => panic!("expected a recursion error, got {:?}", other.map(|_| ())),
+    }
+
+    let missing = workflow(vec![include("start", "absent")]);
+    match missing.with_includes_expanded(&options.snippets) {
+        Err(ClixError::ValidationError(message)) => {
+            assert!(message.contains("unknown snippet 'absent'"))
+        }
+        other => panic!("expected a missing snippet error, got {:?}", other),
+    }
+}
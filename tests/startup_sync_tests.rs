@@ -0,0 +1,65 @@
+mod common;
+
+use common::clix_command_with_sync;
+use std::fs;
+use std::path::Path;
+use std::process::Output;
+use tempfile::TempDir;
+
+/// A home whose only configured repository was never cloned, so any startup
+/// pull attempt shows up as a sync failure on stderr
+fn home_with_repository() -> TempDir {
+    let home = TempDir::new().unwrap();
+    let repos_dir = home.path().join(".clix").join("repos");
+    fs::create_dir_all(&repos_dir).unwrap();
+    fs::write(
+        repos_dir.join("config.json"),
+        r#"[{"name": "team", "url": "https://example.invalid/team.git", "enabled": true}]"#,
+    )
+    .unwrap();
+    home
+}
+
+fn clix(home: &Path, args: &[&str], envs: &[(&str, &str)]) -> Output {
+    let mut command = clix_command_with_sync(home, args);
+    for (key, value) in envs {
+        command.env(key, value);
+    }
+    command.output().expect("failed to run clix")
+}
+
+fn attempted_pull(output: &Output) -> bool {
+    String::from_utf8_lossy(&output.stderr).contains("Failed to sync repository team")
+}
+
+#[test]
+fn test_startup_sync_runs_by_default() {
+    let home = home_with_repository();
+    let output = clix(home.path(), &["list"], &[]);
+    assert!(attempted_pull(&output));
+}
+
+#[test]
+fn test_no_sync_flag_and_env_skip_startup_sync() {
+    let home = home_with_repository();
+
+    let output = clix(home.path(), &["list", "--no-sync"], &[]);
+    assert!(output.status.success());
+    assert!(!attempted_pull(&output));
+
+    let output = clix(home.path(), &["list"], &[("CLIX_NO_SYNC", "1")]);
+    assert!(!attempted_pull(&output));
+}
+
+#[test]
+fn test_auto_sync_setting_skips_startup_sync() {
+    let home = home_with_repository();
+    fs::write(
+        home.path().join(".clix").join("settings.json"),
+        r#"{"git_settings": {"auto_sync": false}}"#,
+    )
+    .unwrap();
+
+    let output = clix(home.path(), &["list"], &[]);
+    assert!(!attempted_pull(&output));
+}
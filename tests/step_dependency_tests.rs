@@ -0,0 +1,94 @@
+use clix::commands::{CommandExecutor, ExecutionOptions, Workflow, WorkflowStep};
+use clix::error::ClixError;
+
+fn step(name: &str, command: &str, depends_on: &[&str]) -> WorkflowStep {
+    WorkflowStep::new_command(
+        name.to_string(),
+        command.to_string(),
+        format!("Step {}", name),
+        true,
+    )
+    .with_depends_on(depends_on.iter().map(|name| name.to_string()).collect())
+}
+
+fn workflow(steps: Vec<WorkflowStep>) -> Workflow {
+    Workflow::new(
+        "dag".to_string(),
+        "Workflow with step dependencies".to_string(),
+        steps,
+        vec![],
+    )
+}
+
+fn names(workflow: &Workflow) -> Vec<String> {
+    workflow
+        .dependency_order()
+        .unwrap()
+        .into_iter()
+        .map(|index| workflow.steps[index].name.clone())
+        .collect()
+}
+
+#[test]
+fn test_dependencies_run_first_and_listed_order_is_kept_otherwise() {
+    let workflow = workflow(vec![
+        step("deploy", "echo deploy", &["build", "test"]),
+        step("lint", "echo lint", &[]),
+        step("test", "echo test", &["build"]),
+        step("build", "echo build", &[]),
+    ]);
+
+    assert_eq!(names(&workflow), vec!["lint", "build", "test", "deploy"]);
+}
+
+#[test]
+fn test_cycles_and_unknown_steps_are_rejected() {
+    let cyclic = workflow(vec![
+        step("a", "echo a", &["c"]),
+        step("b", "echo b", &["a"]),
+        step("c", "echo c", &["b"]),
+        step("d", "echo d", &[]),
+    ]);
+    match cyclic.dependency_order() {
+        // The independent step is not part of the reported cycle
+        Err(ClixError::ValidationError(message)) => {
+            assert_eq!(message, "Step dependencies form a cycle between: a, b, c")
+        }
+        other => panic!("expected a cycle error, got {:?}", other),
+    }
+
+    let unknown = workflow(vec![step("a", "echo a", &["missing"])]);
+    match unknown.dependency_order() {
+        Err(ClixError::ValidationError(message)) => assert!(message.contains("'missing'")),
+        other => panic!("expected an unknown step error, got {:?}", other),
+    }
+
+    // The executor refuses to start rather than running in listed order
+    let options = ExecutionOptions {
+        require_approval: false,
+        ..ExecutionOptions::default()
+    };
+    assert!(CommandExecutor::execute_workflow_with_options(&cyclic, None, None, &options).is_err());
+}
+
+#[test]
+fn test_executor_follows_dependencies_and_skips_dependents_of_failures() {
+    let workflow = workflow(vec![
+        step("report", "echo report", &["fetch"]),
+        step("fetch", "exit 3", &[]),
+        step("cleanup", "echo cleanup", &[]),
+    ]);
+    let options = ExecutionOptions {
+        require_approval: false,
+        ..ExecutionOptions::default()
+    };
+
+    let results =
+        CommandExecutor::execute_workflow_with_options(&workflow, None, None, &options).unwrap();
+
+    let order: Vec<&str> = results.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(order, vec!["fetch", "report", "cleanup"]);
+    assert!(!results[0].1.as_ref().unwrap().status.success());
+    assert!(results[1].1.is_err());
+    assert!(results[2].1.as_ref().unwrap().status.success());
+}
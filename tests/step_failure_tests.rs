@@ -0,0 +1,169 @@
+use clix::commands::{CommandExecutor, ExecutionOptions, Workflow, WorkflowStep};
+use clix::error::ClixError;
+
+fn run(steps: Vec<WorkflowStep>) -> Vec<(String, clix::error::Result<std::process::Output>)> {
+    let workflow = Workflow::new(
+        "failing".to_string(),
+        "Workflow with a failing step".to_string(),
+        steps,
+        vec![],
+    );
+    let options = ExecutionOptions {
+        require_approval: false,
+        non_interactive: true,
+        ..ExecutionOptions::default()
+    };
+
+    CommandExecutor::execute_workflow_with_options(&workflow, None, None, &options).unwrap()
+}
+
+fn step(name: &str, command: &str, continue_on_error: bool) -> WorkflowStep {
+    WorkflowStep::new_command(
+        name.to_string(),
+        command.to_string(),
+        format!("{} step", name),
+        continue_on_error,
+    )
+}
+
+#[test]
+fn test_failing_step_reports_structured_error() {
+    let results = run(vec![
+        step("Break", "echo 'disk full' >&2; exit 7", false),
+        step("Never", "echo unreachable", false),
+    ]);
+
+    // The failing step stops the workflow and is recorded with its details
+    assert_eq!(results.len(), 1);
+    match &results[0].1 {
+        Err(ClixError::StepFailed {
+            step,
+            command,
+            exit_code,
+            stderr,
+            ..
+        }) => {
+            assert_eq!(step, "Break");
+            assert_eq!(command, "echo 'disk full' >&2; exit 7");
+            assert_eq!(*exit_code, Some(7));
+            assert_eq!(stderr.trim(), "disk full");
+        }
+        other => panic!("expected StepFailed, got {:?}", other),
+    }
+
+    let error = results[0].1.as_ref().unwrap_err();
+    assert_eq!(error.to_string(), "Step 'Break' failed with exit code 7");
+    let message = error.to_user_friendly_message();
+    assert!(message.contains("Command: echo 'disk full' >&2; exit 7"));
+    assert!(message.contains("Stderr: disk full"));
+}
+
+#[test]
+fn test_step_allowed_to_fail_keeps_its_output() {
+    let results = run(vec![
+        step("Probe", "exit 3", true),
+        step("After", "echo still running", false),
+    ]);
+
+    assert_eq!(results.len(), 2);
+    let probe = results[0].1.as_ref().unwrap();
+    assert_eq!(probe.status.code(), Some(3));
+    assert!(results[1].1.is_ok());
+}
+
+fn unset_step(name: &str, command: &str) -> WorkflowStep {
+    WorkflowStep {
+        continue_on_error: None,
+        ..step(name, command, false)
+    }
+}
+
+fn run_with_default(
+    steps: Vec<WorkflowStep>,
+    default_continue_on_error: bool,
+) -> Vec<(String, clix::error::Result<std::process::Output>)> {
+    let mut workflow = Workflow::new(
+        "defaults".to_string(),
+        "Workflow with a continue_on_error default".to_string(),
+        steps,
+        vec![],
+    );
+    workflow.default_continue_on_error = default_continue_on_error;
+    let options = ExecutionOptions {
+        require_approval: false,
+        non_interactive: true,
+        ..ExecutionOptions::default()
+    };
+
+    CommandExecutor::execute_workflow_with_options(&workflow, None, None, &options).unwrap()
+}
+
+#[test]
+fn test_workflow_default_applies_to_unset_steps() {
+    let results = run_with_default(
+        vec![
+            unset_step("Flaky", "exit 1"),
+            unset_step("After", "echo after"),
+        ],
+        true,
+    );
+
+    assert_eq!(results.len(), 2);
+    assert!(results[1].1.is_ok());
+
+    // Without the default an unset step stops the workflow
+    let results = run_with_default(
+        vec![
+            unset_step("Flaky", "exit 1"),
+            unset_step("After", "echo after"),
+        ],
+        false,
+    );
+    assert_eq!(results.len(), 1);
+}
+
+#[test]
+fn test_step_setting_wins_over_workflow_default() {
+    let results = run_with_default(
+        vec![
+            step("Strict", "exit 1", false),
+            unset_step("After", "echo after"),
+        ],
+        true,
+    );
+
+    assert_eq!(results.len(), 1);
+    assert!(matches!(results[0].1, Err(ClixError::StepFailed { .. })));
+}
+
+#[test]
+fn test_max_step_failures_aborts_after_the_budget_is_spent() {
+    let workflow = Workflow::new(
+        "batch".to_string(),
+        "Batch with a failure budget".to_string(),
+        vec![
+            step("First", "exit 1", false),
+            step("Second", "exit 2", false),
+            step("Between", "echo ok", false),
+            step("Third", "exit 3", true),
+            step("Never", "echo unreachable", true),
+        ],
+        vec![],
+    );
+    let options = ExecutionOptions {
+        require_approval: false,
+        non_interactive: true,
+        max_step_failures: Some(2),
+        ..ExecutionOptions::default()
+    };
+
+    let results =
+        CommandExecutor::execute_workflow_with_options(&workflow, None, None, &options).unwrap();
+
+    // Two failures are tolerated despite continue_on_error being off; the third stops the run
+    let names: Vec<&str> = results.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["First", "Second", "Between", "Third"]);
+    assert!(results[2].1.as_ref().unwrap().status.success());
+    // A continue-on-error step reports its failure as a non-zero status
+    assert!(!results[3].1.as_ref().unwrap().status.success());
+}
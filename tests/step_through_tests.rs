@@ -0,0 +1,86 @@
+use clix::commands::{CommandExecutor, ExecutionOptions, Workflow, WorkflowStep};
+use std::io::Cursor;
+
+fn echo_step(name: &str, text: &str) -> WorkflowStep {
+    WorkflowStep::new_command(
+        name.to_string(),
+        format!("echo {}", text),
+        format!("Print {}", text),
+        false,
+    )
+}
+
+fn three_steps() -> Workflow {
+    Workflow::new(
+        "debug-me".to_string(),
+        "Workflow run in step-through mode".to_string(),
+        vec![
+            echo_step("First", "one"),
+            echo_step("Middle", "two"),
+            echo_step("Last", "{{ greeting }}"),
+        ],
+        vec![],
+    )
+}
+
+fn stdout(result: &clix::error::Result<std::process::Output>) -> String {
+    String::from_utf8_lossy(&result.as_ref().unwrap().stdout)
+        .trim()
+        .to_string()
+}
+
+#[test]
+fn test_step_through_skips_middle_step_and_edits_vars() {
+    // Run the first step, skip the second, set a variable, then run the last
+    let options = ExecutionOptions {
+        require_approval: false,
+        step_through: true,
+        ..ExecutionOptions::default()
+    }
+    .with_input(Cursor::new("r\ns\ne\ngreeting=hi\n\nr\n"));
+
+    let results =
+        CommandExecutor::execute_workflow_with_options(&three_steps(), None, None, &options)
+            .unwrap();
+
+    let names: Vec<&str> = results.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, vec!["First", "Middle", "Last"]);
+    assert_eq!(stdout(&results[0].1), "one");
+    assert!(CommandExecutor::was_skipped(&results[1].1));
+    assert_eq!(stdout(&results[2].1), "hi");
+}
+
+#[test]
+fn test_step_through_abort_stops_workflow() {
+    let options = ExecutionOptions {
+        require_approval: false,
+        step_through: true,
+        ..ExecutionOptions::default()
+    }
+    .with_input(Cursor::new("run\nabort\n"));
+
+    let results =
+        CommandExecutor::execute_workflow_with_options(&three_steps(), None, None, &options)
+            .unwrap();
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(stdout(&results[0].1), "one");
+}
+
+#[test]
+fn test_step_through_is_disabled_when_non_interactive() {
+    let options = ExecutionOptions {
+        require_approval: false,
+        non_interactive: true,
+        step_through: true,
+        ..ExecutionOptions::default()
+    }
+    .with_input(Cursor::new("a\n"));
+
+    let results =
+        CommandExecutor::execute_workflow_with_options(&three_steps(), None, None, &options)
+            .unwrap();
+
+    assert_eq!(results.len(), 3);
+    assert_eq!(stdout(&results[1].1), "two");
+}
@@ -0,0 +1,206 @@
+mod common;
+
+use clix::commands::{CommandExecutor, ExecutionOptions, Workflow, WorkflowStep};
+use clix::error::{ClixError, Result};
+use common::{clix, clix_command};
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+type Results = Vec<(String, Result<Output>)>;
+
+fn step(name: &str, command: &str, timeout_secs: Option<u64>) -> WorkflowStep {
+    let mut step = WorkflowStep::new_command(
+        name.to_string(),
+        command.to_string(),
+        format!("Run {}", name),
+        true,
+    );
+    step.timeout_secs = timeout_secs;
+    step
+}
+
+fn run(steps: Vec<WorkflowStep>, step_timeout_secs: Option<u64>) -> Results {
+    let workflow = Workflow::new(
+        "timeouts".to_string(),
+        "Steps with time limits".to_string(),
+        steps,
+        vec![],
+    );
+    let options = ExecutionOptions {
+        require_approval: false,
+        step_timeout_secs,
+        ..ExecutionOptions::default()
+    };
+    CommandExecutor::execute_workflow_with_options(&workflow, None, None, &options).unwrap()
+}
+
+fn add_workflow(home: &Path, steps: &[WorkflowStep]) -> Output {
+    let steps_file = home.join("steps.json");
+    std::fs::write(&steps_file, serde_json::to_string(steps).unwrap()).unwrap();
+    clix(
+        home,
+        &[
+            "add",
+            "slow",
+            "--description",
+            "A slow workflow",
+            "--steps-file",
+            steps_file.to_str().unwrap(),
+        ],
+    )
+}
+
+fn is_running(pid: &str) -> bool {
+    Command::new("kill")
+        .args(["-0", pid])
+        .stderr(Stdio::null())
+        .status()
+        .unwrap()
+        .success()
+}
+
+#[test]
+fn test_hung_step_is_killed_with_its_output_kept() {
+    let started = Instant::now();
+    let results = run(
+        vec![
+            step(
+                "Fetch",
+                "echo connecting; echo retrying >&2; sleep 30",
+                Some(1),
+            ),
+            step("Next", "echo next", None),
+        ],
+        None,
+    );
+    assert!(started.elapsed() < Duration::from_secs(15));
+
+    match &results[0].1 {
+        Err(ClixError::CommandExecutionFailed(message)) => {
+            assert!(
+                message.contains("Step 'Fetch' timed out after 1s"),
+                "{}",
+                message
+            );
+            assert!(
+                message.contains("stdout before the timeout:\nconnecting"),
+                "{}",
+                message
+            );
+            assert!(
+                message.contains("stderr before the timeout:\nretrying"),
+                "{}",
+                message
+            );
+        }
+        other => panic!("expected a timeout, got {:?}", other),
+    }
+    // The step continues on error, so the workflow goes on
+    assert!(
+        results[1]
+            .1
+            .as_ref()
+            .is_ok_and(|output| output.status.success())
+    );
+}
+
+#[test]
+fn test_default_timeout_applies_only_to_steps_without_their_own() {
+    let results = run(
+        vec![
+            step("Slow", "sleep 30", None),
+            step("Patient", "sleep 2; echo finished", Some(20)),
+        ],
+        Some(1),
+    );
+
+    assert!(matches!(
+        &results[0].1,
+        Err(ClixError::CommandExecutionFailed(message)) if message.contains("timed out after 1s")
+    ));
+    let patient = results[1].1.as_ref().unwrap();
+    assert_eq!(String::from_utf8_lossy(&patient.stdout), "finished\n");
+}
+
+#[test]
+fn test_zero_timeout_is_rejected() {
+    let home = TempDir::new().unwrap();
+
+    let mut looped = WorkflowStep::new_loop(
+        "Poll".to_string(),
+        "Poll until ready".to_string(),
+        clix::commands::Condition {
+            expression: "false".to_string(),
+            variable: None,
+        },
+        vec![step("Check", "echo checking", Some(0))],
+    );
+    looped.continue_on_error = Some(true);
+    let added = add_workflow(home.path(), &[looped]);
+    assert!(!added.status.success());
+    assert!(
+        String::from_utf8_lossy(&added.stderr).contains("Step 'Check' has timeout_secs 0"),
+        "{}",
+        String::from_utf8_lossy(&added.stderr)
+    );
+
+    let added = add_workflow(home.path(), &[step("Wait", "sleep 1", None)]);
+    assert!(added.status.success());
+    let ran = clix(home.path(), &["run", "slow", "--step-timeout", "0"]);
+    assert!(!ran.status.success());
+    assert!(String::from_utf8_lossy(&ran.stderr).contains("--step-timeout"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_interrupting_clix_stops_a_step_with_a_timeout() {
+    let home = TempDir::new().unwrap();
+    let pid_file = home.path().join("step.pid");
+    let added = add_workflow(
+        home.path(),
+        &[step(
+            "Wait",
+            &format!("echo $$ > {}; exec sleep 30", pid_file.display()),
+            Some(60),
+        )],
+    );
+    assert!(added.status.success());
+
+    let mut running = clix_command(home.path(), &["run", "slow", "--allow-dangerous"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let started = Instant::now();
+    let pid = loop {
+        if let Ok(pid) = std::fs::read_to_string(&pid_file)
+            && !pid.trim().is_empty()
+        {
+            break pid.trim().to_string();
+        }
+        assert!(
+            started.elapsed() < Duration::from_secs(15),
+            "step never started"
+        );
+        std::thread::sleep(Duration::from_millis(20));
+    };
+
+    Command::new("kill")
+        .args(["-TERM", &running.id().to_string()])
+        .status()
+        .unwrap();
+    assert!(!running.wait().unwrap().success());
+
+    // The step's own process group got the signal too
+    let stopped = Instant::now();
+    while is_running(&pid) {
+        assert!(
+            stopped.elapsed() < Duration::from_secs(5),
+            "step {} outlived clix",
+            pid
+        );
+        std::thread::sleep(Duration::from_millis(20));
+    }
+}
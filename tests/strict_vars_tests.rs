@@ -0,0 +1,91 @@
+use clix::commands::{CommandExecutor, ExecutionOptions, Workflow, WorkflowStep};
+use clix::error::{ClixError, Result};
+use std::collections::HashMap;
+use std::process::Output;
+
+type Results = Vec<(String, Result<Output>)>;
+
+fn workflow(command: &str) -> Workflow {
+    Workflow::new(
+        "report".to_string(),
+        "Print a report".to_string(),
+        vec![WorkflowStep::new_command(
+            "Print".to_string(),
+            command.to_string(),
+            "Print the report".to_string(),
+            false,
+        )],
+        vec![],
+    )
+}
+
+fn run(workflow: &Workflow, strict: bool) -> Result<Results> {
+    let options = ExecutionOptions {
+        require_approval: false,
+        strict_variables: strict,
+        ..ExecutionOptions::default()
+    };
+    let vars = HashMap::from([("target".to_string(), "prod".to_string())]);
+    CommandExecutor::execute_workflow_with_options(workflow, None, Some(vars), &options)
+}
+
+fn stdout(results: &Results) -> String {
+    String::from_utf8_lossy(&results[0].1.as_ref().unwrap().stdout).to_string()
+}
+
+/// The message of the strict-variables error the first step failed with
+fn strict_error(results: &Results) -> &str {
+    match &results[0].1 {
+        Err(ClixError::ValidationError(message)) => message,
+        other => panic!("expected a validation error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_undefined_variable_fails_only_in_strict_mode() {
+    let workflow = workflow("echo \"[$CLIX_STRICT_TEST_UNSET]\"");
+
+    let message = strict_error(&run(&workflow, true).unwrap()).to_string();
+    assert!(message.contains("CLIX_STRICT_TEST_UNSET"), "{}", message);
+
+    // By default the shell expands it to nothing
+    assert_eq!(stdout(&run(&workflow, false).unwrap()), "[]\n");
+}
+
+#[test]
+fn test_workflow_setting_turns_on_strict_mode() {
+    let mut workflow = workflow("echo $CLIX_STRICT_TEST_REGION");
+    assert!(run(&workflow, false).is_ok());
+
+    workflow.strict_vars = true;
+    let results = run(&workflow, false).unwrap();
+    assert!(strict_error(&results).contains("CLIX_STRICT_TEST_REGION"));
+}
+
+#[test]
+fn test_strict_mode_accepts_defined_and_local_variables() {
+    let workflow = workflow(
+        "name=web; for i in 1; do echo {{ target }} $name $i $HOME ${MISSING:-none} '$literal'; done",
+    );
+    let results = run(&workflow, true).unwrap();
+    assert!(stdout(&results).starts_with("prod web 1 "));
+    assert!(stdout(&results).ends_with(" none $literal\n"));
+}
+
+#[test]
+fn test_undefined_variable_fails_the_step_and_later_steps_still_run() {
+    let mut workflow = workflow("echo $CLIX_STRICT_TEST_MISSING");
+    workflow.steps[0].continue_on_error = Some(true);
+    workflow.steps.push(WorkflowStep::new_command(
+        "Done".to_string(),
+        "echo done".to_string(),
+        "Report completion".to_string(),
+        false,
+    ));
+
+    let results = run(&workflow, true).unwrap();
+    assert_eq!(results.len(), 2);
+    assert!(strict_error(&results).contains("CLIX_STRICT_TEST_MISSING"));
+    let done = results[1].1.as_ref().unwrap();
+    assert_eq!(String::from_utf8_lossy(&done.stdout), "done\n");
+}
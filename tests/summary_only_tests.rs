@@ -0,0 +1,86 @@
+mod common;
+
+use common::clix;
+use tempfile::TempDir;
+
+#[test]
+fn test_summary_only_prints_one_status_line_per_step() {
+    let home = TempDir::new().unwrap();
+    let steps = serde_json::json!([
+        {
+            "name": "greet",
+            "command": "echo greeting-$((40 + 2))",
+            "description": "Prints a greeting",
+            "continue_on_error": true,
+            "step_type": "Command",
+            "require_approval": false
+        },
+        {
+            "name": "fail",
+            "command": "echo complaint-$((6 * 7)) >&2; exit 4",
+            "description": "Fails on purpose",
+            "continue_on_error": true,
+            "step_type": "Command",
+            "require_approval": false
+        }
+    ]);
+    let steps_file = home.path().join("steps.json");
+    std::fs::write(&steps_file, steps.to_string()).unwrap();
+
+    let added = clix(
+        home.path(),
+        &[
+            "add",
+            "pipeline",
+            "--description",
+            "Two steps",
+            "--steps-file",
+            steps_file.to_str().unwrap(),
+        ],
+    );
+    assert!(added.status.success());
+
+    let output = clix(home.path(), &["run", "pipeline", "--summary-only"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    // The steps' own output is left out
+    assert!(!stdout.contains("greeting-42"), "{}", stdout);
+    assert!(!stdout.contains("complaint-42"), "{}", stdout);
+    assert!(!stdout.contains("STDOUT:"), "{}", stdout);
+
+    let summary = stdout.split("Workflow Summary:").nth(1).unwrap();
+    let greet: Vec<&str> = summary
+        .lines()
+        .filter(|line| line.starts_with("greet "))
+        .collect();
+    let fail: Vec<&str> = summary
+        .lines()
+        .filter(|line| line.starts_with("fail "))
+        .collect();
+    assert_eq!(greet.len(), 1);
+    assert!(greet[0].contains(" ok "), "{}", greet[0]);
+    assert_eq!(fail.len(), 1);
+    assert!(fail[0].contains("failed (exit 4)"), "{}", fail[0]);
+    assert!(summary.contains("1 of 2 step(s) failed"));
+}
+
+#[test]
+fn test_summary_only_is_for_workflows() {
+    let home = TempDir::new().unwrap();
+    let added = clix(
+        home.path(),
+        &[
+            "add",
+            "hello",
+            "--description",
+            "Hi",
+            "--command",
+            "echo hi",
+        ],
+    );
+    assert!(added.status.success());
+
+    let output = clix(home.path(), &["run", "hello", "--summary-only"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("only applies to workflows"));
+}
@@ -0,0 +1,87 @@
+mod common;
+
+use common::clix;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn setup(home: &Path) {
+    let added = clix(
+        home,
+        &[
+            "add", "build", "-d", "Build", "-c", "make", "-t", "ci", "-t", "rust", "-t", "ci",
+        ],
+    );
+    assert!(added.status.success());
+
+    let steps = serde_json::json!([
+        {
+            "name": "ship",
+            "command": "echo shipping",
+            "description": "Ship it",
+            "continue_on_error": false,
+            "step_type": "Command",
+            "require_approval": false
+        }
+    ]);
+    let steps_file = home.join("steps.json");
+    fs::write(&steps_file, steps.to_string()).unwrap();
+    let added = clix(
+        home,
+        &[
+            "add",
+            "release",
+            "-d",
+            "Release",
+            "--steps-file",
+            steps_file.to_str().unwrap(),
+            "-t",
+            "ci",
+            "-t",
+            "deploy",
+        ],
+    );
+    assert!(added.status.success());
+}
+
+#[test]
+fn test_tags_json_counts_each_item_once_per_tag() {
+    let home = TempDir::new().unwrap();
+    setup(home.path());
+
+    let output = clix(home.path(), &["tags", "--json"]);
+    assert!(output.status.success());
+    let tags: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        tags,
+        serde_json::json!([
+            {"tag": "ci", "count": 2},
+            {"tag": "deploy", "count": 1},
+            {"tag": "rust", "count": 1}
+        ])
+    );
+
+    let output = clix(home.path(), &["tags", "--names"]);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "ci\ndeploy\nrust\n"
+    );
+}
+
+#[test]
+fn test_completion_scripts_complete_tags_from_the_store() {
+    let home = TempDir::new().unwrap();
+
+    let bash = clix(home.path(), &["completions", "bash"]);
+    let bash = String::from_utf8_lossy(&bash.stdout);
+    assert!(bash.contains("clix tags --names"), "{}", bash);
+    assert!(bash.contains("complete -F _clix_tags "));
+
+    let zsh = clix(home.path(), &["completions", "zsh"]);
+    let zsh = String::from_utf8_lossy(&zsh.stdout);
+    assert!(zsh.contains(":TAG:_clix_tags"));
+    assert!(!zsh.contains(":TAG:_default"));
+
+    let fish = clix(home.path(), &["completions", "fish"]);
+    assert!(String::from_utf8_lossy(&fish.stdout).contains("-l tag -f -a '(clix tags --names"));
+}
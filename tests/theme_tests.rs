@@ -0,0 +1,46 @@
+mod common;
+
+use common::clix_command;
+use tempfile::TempDir;
+
+/// Add a command and return what clix printed, with colors forced on
+fn add_output(theme: &str) -> String {
+    let home = TempDir::new().unwrap();
+    let output = clix_command(
+        home.path(),
+        &[
+            "--theme",
+            theme,
+            "add",
+            "hello",
+            "--description",
+            "Say hello",
+            "--command",
+            "echo hello",
+        ],
+    )
+    .env("CLICOLOR_FORCE", "1")
+    .env_remove("NO_COLOR")
+    .output()
+    .unwrap();
+    assert!(output.status.success());
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+/// Whether text contains an ANSI foreground color escape
+fn has_color(text: &str) -> bool {
+    text.split("\x1b[").skip(1).any(|code| {
+        code.split(['m', ';'])
+            .any(|part| part.len() == 2 && part.starts_with(['3', '9']))
+    })
+}
+
+#[test]
+fn test_monochrome_theme_prints_no_colors() {
+    let default = add_output("default");
+    assert!(has_color(&default), "{:?}", default);
+
+    let monochrome = add_output("monochrome");
+    assert!(monochrome.contains("Command added successfully"));
+    assert!(!has_color(&monochrome), "{:?}", monochrome);
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use common::clix;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn test_timing_csv_has_a_row_per_step() {
+    let home = TempDir::new().unwrap();
+    let steps = serde_json::json!([
+        {
+            "name": "Compile, fast",
+            "command": "echo compiled",
+            "description": "Compile",
+            "continue_on_error": true,
+            "step_type": "Command",
+            "require_approval": false
+        },
+        {
+            "name": "Test",
+            "command": "exit 2",
+            "description": "Test",
+            "continue_on_error": true,
+            "step_type": "Command",
+            "require_approval": false
+        },
+        {
+            "name": "Package",
+            "command": "sleep 0.1",
+            "description": "Package",
+            "continue_on_error": false,
+            "step_type": "Command",
+            "require_approval": false
+        }
+    ]);
+    let steps_file = home.path().join("steps.json");
+    fs::write(&steps_file, steps.to_string()).unwrap();
+    let added = clix(
+        home.path(),
+        &[
+            "add",
+            "build",
+            "--description",
+            "Build",
+            "--steps-file",
+            steps_file.to_str().unwrap(),
+        ],
+    );
+    assert!(added.status.success());
+
+    let csv_path = home.path().join("timing.csv");
+    clix(
+        home.path(),
+        &[
+            "run",
+            "build",
+            "--capture-timing-csv",
+            csv_path.to_str().unwrap(),
+        ],
+    );
+
+    let csv = fs::read_to_string(&csv_path).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "step,duration_ms,exit_code");
+    assert_eq!(lines.len(), 4, "{}", csv);
+
+    let rows: Vec<(&str, &str)> = lines[1..]
+        .iter()
+        .map(|line| {
+            // Split from the right, since the step name may hold a quoted comma
+            let (without_exit_code, _) = line.rsplit_once(',').unwrap();
+            without_exit_code.rsplit_once(',').unwrap()
+        })
+        .collect();
+    let steps: Vec<&str> = rows.iter().map(|(step, _)| *step).collect();
+    assert_eq!(steps, ["\"Compile, fast\"", "Test", "Package"]);
+    for (_, duration) in &rows {
+        assert!(duration.parse::<u64>().is_ok(), "{}", csv);
+    }
+    assert!(lines[2].ends_with(",2"));
+    assert!(lines[3].ends_with(",0"));
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use clix::storage::{ExecutionKind, ExecutionRecord, HistoryStorage};
+use common::clix;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tempfile::TempDir;
+
+fn record(name: &str, kind: ExecutionKind, days_ago: u64, success: bool) -> ExecutionRecord {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    ExecutionRecord {
+        name: name.to_string(),
+        kind,
+        started_at: now - days_ago * 86400,
+        duration_ms: 100,
+        success,
+        allowed_dangerous: false,
+        environment: None,
+    }
+}
+
+#[test]
+fn test_report_counts_runs_from_history() {
+    let home = TempDir::new().unwrap();
+    let added = clix(
+        home.path(),
+        &[
+            "add", "build", "-d", "Build it", "-c", "true", "--tags", "ci", "--tags", "daily",
+        ],
+    );
+    assert!(added.status.success());
+
+    let history = HistoryStorage::new_with_dir(home.path().join(".clix")).unwrap();
+    for entry in [
+        record("build", ExecutionKind::Command, 1, true),
+        record("build", ExecutionKind::Command, 2, false),
+        record("build", ExecutionKind::Command, 3, true),
+        record("deploy", ExecutionKind::Workflow, 5, false),
+        // Outside a 30 day window
+        record("build", ExecutionKind::Command, 60, false),
+    ] {
+        history.record(&entry).unwrap();
+    }
+
+    let output = clix(home.path(), &["report", "--since", "30d"]);
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    assert_eq!(report["total_runs"], 4);
+    assert_eq!(report["total_failures"], 2);
+    assert_eq!(report["commands"][0]["name"], "build");
+    assert_eq!(report["commands"][0]["runs"], 3);
+    assert_eq!(report["commands"][0]["failures"], 1);
+    assert_eq!(report["commands"][1]["name"], "deploy");
+    assert_eq!(report["commands"][1]["kind"], "workflow");
+    assert_eq!(report["commands"][1]["failure_rate"], 1.0);
+    assert_eq!(
+        report["tags"],
+        serde_json::json!([{"tag": "ci", "runs": 3}, {"tag": "daily", "runs": 3}])
+    );
+
+    // Without --since the whole history counts
+    let report_file = home.path().join("report.csv");
+    let written = clix(
+        home.path(),
+        &[
+            "report",
+            "--format",
+            "csv",
+            "--output",
+            report_file.to_str().unwrap(),
+        ],
+    );
+    assert!(written.status.success());
+    let csv = std::fs::read_to_string(&report_file).unwrap();
+    let rows: Vec<Vec<&str>> = csv.lines().map(|line| line.split(',').collect()).collect();
+    assert_eq!(
+        rows[0],
+        vec![
+            "name",
+            "kind",
+            "runs",
+            "failures",
+            "failure_rate",
+            "avg_duration_ms",
+            "last_run_at",
+            "tags"
+        ]
+    );
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[1][..6], ["build", "command", "4", "2", "0.500", "100"]);
+    assert_eq!(rows[1][7], "ci;daily");
+    assert_eq!(
+        rows[2][..6],
+        ["deploy", "workflow", "1", "1", "1.000", "100"]
+    );
+}
@@ -0,0 +1,193 @@
+use clix::commands::{
+    CommandExecutor, Condition, ExecutionOptions, ExpressionEvaluator, VarType, Workflow,
+    WorkflowStep, WorkflowVariable,
+};
+use std::collections::HashMap;
+
+fn workflow_with(variable: WorkflowVariable, command: &str) -> Workflow {
+    let mut workflow = Workflow::new(
+        "scale".to_string(),
+        "Scale the app".to_string(),
+        vec![WorkflowStep::new_command(
+            "scale".to_string(),
+            command.to_string(),
+            "Scale".to_string(),
+            false,
+        )],
+        vec![],
+    );
+    workflow.variables.push(variable);
+    workflow
+}
+
+fn vars(name: &str, value: &str) -> Option<HashMap<String, String>> {
+    Some(HashMap::from([(name.to_string(), value.to_string())]))
+}
+
+fn options() -> ExecutionOptions {
+    ExecutionOptions {
+        require_approval: false,
+        ..ExecutionOptions::default()
+    }
+}
+
+#[test]
+fn test_int_variable_rejects_a_non_integer() {
+    assert_eq!(VarType::Int.coerce(" 42 ").unwrap(), "42");
+    assert_eq!(VarType::Int.coerce("+7").unwrap(), "7");
+    assert!(VarType::Int.coerce("4.5").is_err());
+
+    let workflow = workflow_with(
+        WorkflowVariable::new("replicas".to_string(), "Replicas".to_string(), None, true)
+            .with_type(VarType::Int),
+        "echo {{ replicas }}",
+    );
+
+    let err = CommandExecutor::execute_workflow_with_options(
+        &workflow,
+        None,
+        vars("replicas", "three"),
+        &options(),
+    )
+    .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Invalid value for variable 'replicas': expected an integer, got 'three'"),
+        "{}",
+        err
+    );
+
+    let results = CommandExecutor::execute_workflow_with_options(
+        &workflow,
+        None,
+        vars("replicas", "03"),
+        &options(),
+    )
+    .unwrap();
+    let output = results[0].1.as_ref().unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "3\n");
+}
+
+#[test]
+fn test_bool_variable_is_parsed() {
+    for value in ["true", "Yes", "on", "1"] {
+        assert_eq!(VarType::Bool.coerce(value).unwrap(), "true");
+    }
+    for value in ["false", "NO", "off", "0"] {
+        assert_eq!(VarType::Bool.coerce(value).unwrap(), "false");
+    }
+    assert!(VarType::Bool.coerce("maybe").is_err());
+
+    let workflow = workflow_with(
+        WorkflowVariable::new("dry_run".to_string(), "Dry run".to_string(), None, true)
+            .with_type(VarType::Bool),
+        "echo {{ dry_run }}",
+    );
+    let results = CommandExecutor::execute_workflow_with_options(
+        &workflow,
+        None,
+        vars("dry_run", "yes"),
+        &options(),
+    )
+    .unwrap();
+    let output = results[0].1.as_ref().unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "true\n");
+}
+
+#[test]
+fn test_enum_variable_must_be_a_member() {
+    let env = VarType::Enum(vec!["dev".to_string(), "prod".to_string()]);
+    assert_eq!(env.coerce("prod").unwrap(), "prod");
+    assert_eq!(
+        env.coerce("staging").unwrap_err(),
+        "expected one of dev, prod, got 'staging'"
+    );
+
+    let workflow = workflow_with(
+        WorkflowVariable::new("env".to_string(), "Environment".to_string(), None, true)
+            .with_type(env),
+        "echo {{ env }}",
+    );
+    assert!(
+        CommandExecutor::execute_workflow_with_options(
+            &workflow,
+            None,
+            vars("env", "staging"),
+            &options(),
+        )
+        .is_err()
+    );
+}
+
+#[test]
+fn test_typed_variables_compare_by_value() {
+    let context = HashMap::from([
+        ("replicas".to_string(), "10".to_string()),
+        ("deploy".to_string(), "true".to_string()),
+    ]);
+    let types = HashMap::from([
+        ("replicas".to_string(), VarType::Int),
+        ("deploy".to_string(), VarType::Bool),
+    ]);
+    let evaluate =
+        |expr: &str| ExpressionEvaluator::evaluate_typed(expr, &context, &types, None).unwrap();
+
+    // 10 > 9 as numbers, although "10" < "9" as text
+    assert!(evaluate("$replicas > 9"));
+    assert!(evaluate("[ $replicas -ge 10 ]"));
+    assert!(!evaluate("${replicas} < 2"));
+    assert!(evaluate("[ \"$deploy\" == yes ]"));
+    assert!(!evaluate("$deploy != on"));
+    assert!(ExpressionEvaluator::evaluate_typed("$deploy > no", &context, &types, None).is_err());
+}
+
+#[test]
+fn test_typed_comparison_inside_a_conditional_block() {
+    let echo = |text: &str| {
+        WorkflowStep::new_command(
+            format!("Echo {}", text),
+            format!("echo {}", text),
+            format!("Print {}", text),
+            false,
+        )
+    };
+    let condition = |expression: &str| Condition {
+        expression: expression.to_string(),
+        variable: None,
+    };
+    let inner = WorkflowStep::new_conditional(
+        "Size".to_string(),
+        "Compare the replica count".to_string(),
+        condition("$replicas > 9"),
+        vec![echo("many")],
+        Some(vec![echo("few")]),
+        None,
+    );
+    let mut workflow = Workflow::new(
+        "scale".to_string(),
+        "Scale the app".to_string(),
+        vec![WorkflowStep::new_conditional(
+            "Enabled".to_string(),
+            "Only when scaling is enabled".to_string(),
+            condition("true"),
+            vec![inner],
+            None,
+            None,
+        )],
+        vec![],
+    );
+    workflow.variables.push(
+        WorkflowVariable::new("replicas".to_string(), "Replicas".to_string(), None, true)
+            .with_type(VarType::Int),
+    );
+
+    let results = CommandExecutor::execute_workflow_with_options(
+        &workflow,
+        None,
+        vars("replicas", "10"),
+        &options(),
+    )
+    .unwrap();
+    let output = results[0].1.as_ref().unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "many\n");
+}
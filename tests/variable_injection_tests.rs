@@ -0,0 +1,142 @@
+use clix::commands::{
+    BranchCase, CommandExecutor, ExecutionOptions, Workflow, WorkflowStep, WorkflowVariable,
+};
+use clix::error::ClixError;
+use std::collections::HashMap;
+
+const MALICIOUS: &str = "hello; echo pwned";
+
+fn greet_workflow(quote: bool) -> Workflow {
+    let mut workflow = Workflow::new(
+        "greet".to_string(),
+        "Echo a message".to_string(),
+        vec![WorkflowStep::new_command(
+            "Greet".to_string(),
+            "echo {{ message }}".to_string(),
+            "Print the message".to_string(),
+            false,
+        )],
+        vec![],
+    );
+    workflow.add_variable(
+        WorkflowVariable::new(
+            "message".to_string(),
+            "Message to print".to_string(),
+            None,
+            true,
+        )
+        .with_quote(quote),
+    );
+    workflow
+}
+
+fn run(workflow: &Workflow, reject_unsafe: bool) -> clix::error::Result<String> {
+    let options = ExecutionOptions {
+        require_approval: false,
+        reject_unsafe_vars: reject_unsafe,
+        ..ExecutionOptions::default()
+    };
+    let vars = HashMap::from([("message".to_string(), MALICIOUS.to_string())]);
+
+    let results =
+        CommandExecutor::execute_workflow_with_options(workflow, None, Some(vars), &options)?;
+    let output = results[0].1.as_ref().unwrap();
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[test]
+fn test_reject_unsafe_vars_rejects_malicious_value() {
+    match run(&greet_workflow(false), true) {
+        Err(ClixError::SecurityError(issue)) => assert!(issue.contains("'message'")),
+        other => panic!("expected a security error, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_strict_vars_alone_does_not_reject_unsafe_values() {
+    let options = ExecutionOptions {
+        require_approval: false,
+        strict_variables: true,
+        ..ExecutionOptions::default()
+    };
+    let vars = HashMap::from([("message".to_string(), MALICIOUS.to_string())]);
+    let results = CommandExecutor::execute_workflow_with_options(
+        &greet_workflow(false),
+        None,
+        Some(vars),
+        &options,
+    )
+    .unwrap();
+    assert!(results[0].1.is_ok());
+}
+
+#[test]
+fn test_workflow_setting_turns_on_unsafe_value_rejection() {
+    let mut workflow = greet_workflow(false);
+    workflow.reject_unsafe_vars = true;
+    assert!(matches!(
+        run(&workflow, false),
+        Err(ClixError::SecurityError(_))
+    ));
+}
+
+#[test]
+fn test_unquoted_value_is_only_flagged_without_rejection() {
+    // Without quoting the injected command really runs, which is the risk being flagged
+    let stdout = run(&greet_workflow(false), false).unwrap();
+    assert_eq!(stdout, "hello\npwned\n");
+}
+
+#[test]
+fn test_quoted_variable_is_passed_literally() {
+    // Quoted variables are safe, so --reject-unsafe-vars lets them through
+    let stdout = run(&greet_workflow(true), true).unwrap();
+    assert_eq!(stdout, format!("{}\n", MALICIOUS));
+}
+
+#[test]
+fn test_branch_matches_the_raw_value_of_a_quoted_variable() {
+    let deploy = |name: &str, command: &str| {
+        WorkflowStep::new_command(
+            name.to_string(),
+            command.to_string(),
+            format!("Deploy to {}", name),
+            false,
+        )
+    };
+    let mut workflow = Workflow::new(
+        "deploy".to_string(),
+        "Deploy to the chosen environment".to_string(),
+        vec![WorkflowStep::new_branch(
+            "Pick environment".to_string(),
+            "Run the steps for ENV".to_string(),
+            "ENV".to_string(),
+            vec![BranchCase {
+                value: "prod".to_string(),
+                steps: vec![deploy("prod", "echo deploying to {{ ENV }}")],
+            }],
+            Some(vec![deploy("other", "echo fell through")]),
+        )],
+        vec![],
+    );
+    workflow.add_variable(
+        WorkflowVariable::new("ENV".to_string(), "Environment".to_string(), None, true)
+            .with_quote(true),
+    );
+
+    let options = ExecutionOptions {
+        require_approval: false,
+        ..ExecutionOptions::default()
+    };
+    let vars = HashMap::from([("ENV".to_string(), "prod".to_string())]);
+    let results =
+        CommandExecutor::execute_workflow_with_options(&workflow, None, Some(vars), &options)
+            .unwrap();
+
+    let (name, output) = &results[0];
+    assert!(name.ends_with("prod"), "{}", name);
+    assert_eq!(
+        String::from_utf8_lossy(&output.as_ref().unwrap().stdout),
+        "deploying to prod\n"
+    );
+}
@@ -1,5 +1,8 @@
 use clix::commands::variables::{VariableProcessor, WorkflowContext};
-use clix::commands::{Workflow, WorkflowStep, WorkflowVariable, WorkflowVariableProfile};
+use clix::commands::{
+    CommandExecutor, ExecutionOptions, Workflow, WorkflowStep, WorkflowVariable,
+    WorkflowVariableProfile,
+};
 use std::collections::HashMap;
 
 #[test]
@@ -136,3 +139,74 @@ fn test_workflow_with_profiles() {
         "prod-cluster"
     );
 }
+
+#[test]
+fn test_global_var_defaults_have_lowest_precedence() {
+    let workflow = Workflow::new(
+        "defaults_test".to_string(),
+        "Workflow using a global default".to_string(),
+        vec![WorkflowStep::new_command(
+            "Echo".to_string(),
+            "echo {{ REGION }}".to_string(),
+            "Echo the region".to_string(),
+            false,
+        )],
+        vec![],
+    );
+
+    let options = ExecutionOptions {
+        require_approval: false,
+        var_defaults: HashMap::from([("REGION".to_string(), "eu".to_string())]),
+        ..ExecutionOptions::default()
+    };
+    let stdout = |results: &[(String, clix::error::Result<std::process::Output>)]| {
+        let output = results[0].1.as_ref().unwrap();
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    };
+
+    // The global default fills the unprovided variable
+    let results =
+        CommandExecutor::execute_workflow_with_options(&workflow, None, None, &options).unwrap();
+    assert_eq!(stdout(&results), "eu");
+
+    // An explicit --var overrides it
+    let provided = HashMap::from([("REGION".to_string(), "us".to_string())]);
+    let results =
+        CommandExecutor::execute_workflow_with_options(&workflow, None, Some(provided), &options)
+            .unwrap();
+    assert_eq!(stdout(&results), "us");
+}
+
+#[test]
+fn test_capture_var_feeds_later_steps() {
+    let workflow = Workflow::new(
+        "capture_test".to_string(),
+        "Workflow capturing a step's output".to_string(),
+        vec![
+            WorkflowStep::new_command(
+                "Get id".to_string(),
+                "echo abc".to_string(),
+                "Produce an id".to_string(),
+                false,
+            )
+            .with_capture_var("id".to_string()),
+            WorkflowStep::new_command(
+                "Use id".to_string(),
+                "echo id={{ id }}".to_string(),
+                "Use the captured id".to_string(),
+                false,
+            ),
+        ],
+        vec![],
+    );
+
+    let options = ExecutionOptions {
+        require_approval: false,
+        ..ExecutionOptions::default()
+    };
+    let results =
+        CommandExecutor::execute_workflow_with_options(&workflow, None, None, &options).unwrap();
+
+    let output = results[1].1.as_ref().unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "id=abc");
+}
@@ -0,0 +1,86 @@
+use clix::commands::models::StepType;
+use clix::commands::{CommandExecutor, ExecutionOptions, Workflow, WorkflowStep};
+use std::fs;
+use std::time::Duration;
+use tempfile::TempDir;
+
+fn run(step: WorkflowStep) -> Vec<(String, clix::error::Result<std::process::Output>)> {
+    let workflow = Workflow::new(
+        "wait_for_test".to_string(),
+        "Workflow with a readiness check".to_string(),
+        vec![step],
+        vec![],
+    );
+    let options = ExecutionOptions {
+        require_approval: false,
+        ..ExecutionOptions::default()
+    };
+    CommandExecutor::execute_workflow_with_options(&workflow, None, None, &options).unwrap()
+}
+
+#[test]
+fn test_wait_for_succeeds_after_a_few_polls() {
+    let temp_dir = TempDir::new().unwrap();
+    let counter = temp_dir.path().join("counter");
+
+    // Becomes ready on the third poll
+    let step = WorkflowStep::new_wait_for(
+        "Wait for ready".to_string(),
+        "Poll until the counter reaches 3".to_string(),
+        format!(
+            "n=$(cat {0} 2>/dev/null || echo 0); n=$((n + 1)); echo $n > {0}; [ $n -ge 3 ]",
+            counter.display()
+        ),
+        Duration::from_millis(20),
+        Duration::from_secs(5),
+    );
+    assert_eq!(step.step_type, StepType::WaitFor);
+
+    let results = run(step);
+
+    assert_eq!(results.len(), 1);
+    assert!(results[0].1.is_ok());
+    assert_eq!(fs::read_to_string(&counter).unwrap().trim(), "3");
+}
+
+#[test]
+fn test_wait_for_fails_on_timeout() {
+    let step = WorkflowStep::new_wait_for(
+        "Never ready".to_string(),
+        "A readiness check that never passes".to_string(),
+        "false".to_string(),
+        Duration::from_millis(20),
+        Duration::from_millis(200),
+    );
+
+    // A failed step stops the workflow but its error is still recorded
+    let results = run(step.clone());
+    assert_eq!(results.len(), 1);
+    assert!(results[0].1.is_err());
+
+    let tolerant = WorkflowStep {
+        continue_on_error: Some(true),
+        ..step
+    };
+    let results = run(tolerant);
+    let error = results[0].1.as_ref().unwrap_err().to_string();
+    assert!(error.contains("was not ready"));
+}
+
+#[test]
+fn test_wait_for_rejects_a_zero_interval() {
+    let temp_dir = TempDir::new().unwrap();
+    let polls = temp_dir.path().join("polls");
+    let step = WorkflowStep::new_wait_for(
+        "Spin".to_string(),
+        "Would poll without pause".to_string(),
+        format!("echo poll >> {}; false", polls.display()),
+        Duration::ZERO,
+        Duration::from_millis(200),
+    );
+
+    let results = run(step);
+    let error = results[0].1.as_ref().unwrap_err().to_string();
+    assert!(error.contains("interval_ms of 0"), "{}", error);
+    assert!(!polls.exists());
+}
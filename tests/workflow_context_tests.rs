@@ -0,0 +1,95 @@
+use clix::commands::variables::WorkflowContext;
+use clix::commands::{CommandExecutor, ExecutionOptions, Workflow, WorkflowStep};
+use std::collections::HashMap;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn echo_workflow(command: &str) -> Workflow {
+    Workflow::new(
+        "context_test".to_string(),
+        "Workflow used to round-trip its context".to_string(),
+        vec![WorkflowStep::new_command(
+            "Echo".to_string(),
+            command.to_string(),
+            "Echo a variable".to_string(),
+            false,
+        )],
+        vec![],
+    )
+}
+
+fn dump_options(path: &Path, include_secrets: bool) -> ExecutionOptions {
+    ExecutionOptions {
+        require_approval: false,
+        non_interactive: true,
+        dump_context: Some(path.to_path_buf()),
+        include_secrets,
+        ..ExecutionOptions::default()
+    }
+}
+
+fn first_stdout(results: &[(String, clix::error::Result<std::process::Output>)]) -> String {
+    let output = results[0].1.as_ref().unwrap();
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+#[test]
+fn test_context_round_trips_between_runs() {
+    let temp_dir = TempDir::new().unwrap();
+    let context_file = temp_dir.path().join("context.json");
+
+    let mut vars = HashMap::new();
+    vars.insert("region".to_string(), "eu-west-1".to_string());
+    vars.insert("API_TOKEN".to_string(), "s3cr3t".to_string());
+
+    // First run dumps its final variables
+    CommandExecutor::execute_workflow_with_options(
+        &echo_workflow("echo {{ region }}"),
+        None,
+        Some(vars),
+        &dump_options(&context_file, false),
+    )
+    .unwrap();
+
+    let dumped = std::fs::read_to_string(&context_file).unwrap();
+    assert!(!dumped.contains("s3cr3t"));
+
+    // Second run is seeded from the dump; masked secrets are dropped
+    let loaded = WorkflowContext::load_from_file(&context_file).unwrap();
+    assert_eq!(loaded.get("region").map(String::as_str), Some("eu-west-1"));
+    assert!(!loaded.contains_key("API_TOKEN"));
+
+    let options = ExecutionOptions {
+        require_approval: false,
+        non_interactive: true,
+        ..ExecutionOptions::default()
+    };
+    let results = CommandExecutor::execute_workflow_with_options(
+        &echo_workflow("echo {{ region }}"),
+        None,
+        Some(loaded),
+        &options,
+    )
+    .unwrap();
+    assert_eq!(first_stdout(&results), "eu-west-1");
+}
+
+#[test]
+fn test_context_dump_keeps_secrets_when_requested() {
+    let temp_dir = TempDir::new().unwrap();
+    let context_file = temp_dir.path().join("context.json");
+
+    let mut vars = HashMap::new();
+    vars.insert("API_TOKEN".to_string(), "s3cr3t".to_string());
+
+    CommandExecutor::execute_workflow_with_options(
+        &echo_workflow("echo done"),
+        None,
+        Some(vars),
+        &dump_options(&context_file, true),
+    )
+    .unwrap();
+
+    let loaded = WorkflowContext::load_from_file(&context_file).unwrap();
+    assert_eq!(loaded.get("API_TOKEN").map(String::as_str), Some("s3cr3t"));
+}
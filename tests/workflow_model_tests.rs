@@ -1,3 +1,4 @@
+use clix::commands::models::Condition;
 use clix::commands::{StepType, Workflow, WorkflowStep};
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -92,7 +93,7 @@ fn test_auth_step_creation() {
     assert_eq!(auth_step.command, "gcloud auth login");
     assert_eq!(auth_step.description, "Login to Google Cloud");
     assert_eq!(auth_step.step_type, StepType::Auth);
-    assert!(!auth_step.continue_on_error);
+    assert!(!auth_step.continues_on_error());
 
     let command_step = WorkflowStep::new_command(
         "Command Step".to_string(),
@@ -102,5 +103,130 @@ fn test_auth_step_creation() {
     );
 
     assert_eq!(command_step.step_type, StepType::Command);
-    assert!(command_step.continue_on_error);
+    assert!(command_step.continues_on_error());
+}
+
+#[test]
+fn test_continue_on_error_can_be_left_unset() {
+    let explicit: WorkflowStep = serde_json::from_str(
+        r#"{"name": "a", "command": "true", "description": "", "continue_on_error": true, "step_type": "Command"}"#,
+    )
+    .unwrap();
+    assert_eq!(explicit.continue_on_error, Some(true));
+
+    let unset: WorkflowStep = serde_json::from_str(
+        r#"{"name": "b", "command": "true", "description": "", "step_type": "Command"}"#,
+    )
+    .unwrap();
+    assert_eq!(unset.continue_on_error, None);
+    assert!(!unset.continues_on_error());
+    assert!(
+        !serde_json::to_string(&unset)
+            .unwrap()
+            .contains("continue_on_error")
+    );
+
+    let mut workflow = Workflow::new(
+        "defaults".to_string(),
+        "Workflow with a step default".to_string(),
+        vec![explicit, unset],
+        vec![],
+    );
+    workflow.default_continue_on_error = true;
+
+    let resolved = workflow.with_step_defaults_applied();
+    assert_eq!(resolved.steps[0].continue_on_error, Some(true));
+    assert_eq!(resolved.steps[1].continue_on_error, Some(true));
+}
+
+#[test]
+fn test_find_step_looks_inside_nested_blocks() {
+    let notify = WorkflowStep::new_command(
+        "notify".to_string(),
+        "echo done".to_string(),
+        "Announce the result".to_string(),
+        false,
+    );
+    let retry = WorkflowStep::new_loop(
+        "retry".to_string(),
+        "Retry until it works".to_string(),
+        Condition {
+            expression: "[ -e /tmp/never ]".to_string(),
+            variable: None,
+        },
+        vec![notify],
+    );
+    let workflow = Workflow::new(
+        "release".to_string(),
+        "Release workflow".to_string(),
+        vec![retry],
+        vec![],
+    );
+
+    assert_eq!(
+        workflow
+            .find_step("notify")
+            .map(|step| step.command.as_str()),
+        Some("echo done")
+    );
+    assert_eq!(
+        workflow.find_step("retry").map(|step| &step.step_type),
+        Some(&StepType::Loop)
+    );
+    assert!(workflow.find_step("missing").is_none());
+}
+
+#[test]
+fn test_flatten_steps_expands_conditional_blocks() {
+    let step = |name: &str| {
+        WorkflowStep::new_command(
+            name.to_string(),
+            format!("echo {}", name),
+            String::new(),
+            false,
+        )
+    };
+    let workflow = Workflow::new(
+        "deploy".to_string(),
+        "Deploy".to_string(),
+        vec![
+            WorkflowStep::new_conditional(
+                "check".to_string(),
+                "Check the environment".to_string(),
+                Condition {
+                    expression: "[ -f .env ]".to_string(),
+                    variable: None,
+                },
+                vec![step("load"), step("migrate")],
+                Some(vec![step("warn")]),
+                None,
+            ),
+            step("finish"),
+        ],
+        vec![],
+    );
+
+    let flat: Vec<_> = workflow
+        .flatten_steps()
+        .into_iter()
+        .map(|step| (step.name, step.depth, step.parent, step.block))
+        .collect();
+    let child = |name: &str, block: &str| {
+        (
+            name.to_string(),
+            1,
+            Some("check".to_string()),
+            Some(block.to_string()),
+        )
+    };
+    assert_eq!(
+        flat,
+        vec![
+            ("check".to_string(), 0, None, None),
+            child("load", "then"),
+            child("migrate", "then"),
+            child("warn", "else"),
+            ("finish".to_string(), 0, None, None),
+        ]
+    );
 }
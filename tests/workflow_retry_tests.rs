@@ -0,0 +1,82 @@
+mod common;
+
+use common::clix;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// Add a workflow whose single step counts its runs in `counter` and fails
+/// until it has run `succeed_on` times
+fn add_flaky_workflow(home: &Path, counter: &Path, succeed_on: u32) {
+    let command = format!(
+        "n=0; [ -f '{0}' ] && read n < '{0}'; n=$((n + 1)); echo $n > '{0}'; [ $n -ge {1} ]",
+        counter.display(),
+        succeed_on
+    );
+    let steps = serde_json::json!([
+        {
+            "name": "flaky",
+            "command": command,
+            "description": "Fails until it has run enough times",
+            "continue_on_error": false,
+            "step_type": "Command",
+            "require_approval": false
+        }
+    ]);
+    let steps_file = home.join("steps.json");
+    fs::write(&steps_file, steps.to_string()).unwrap();
+
+    let added = clix(
+        home,
+        &[
+            "add",
+            "e2e",
+            "--description",
+            "Flaky end-to-end run",
+            "--steps-file",
+            steps_file.to_str().unwrap(),
+        ],
+    );
+    assert!(added.status.success());
+}
+
+#[test]
+fn test_workflow_is_rerun_after_a_failed_run() {
+    let home = TempDir::new().unwrap();
+    let counter = home.path().join("runs");
+    add_flaky_workflow(home.path(), &counter, 2);
+
+    let output = clix(home.path(), &["run", "e2e", "--retry", "2"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    // Failed the first time, passed the second, and was not run a third time
+    assert_eq!(fs::read_to_string(&counter).unwrap().trim(), "2");
+    assert!(
+        stdout.contains("retrying in 0s (attempt 2 of 3)"),
+        "{}",
+        stdout
+    );
+    assert!(!stdout.contains("attempt 3 of 3"), "{}", stdout);
+}
+
+#[test]
+fn test_retries_stop_after_the_limit() {
+    let home = TempDir::new().unwrap();
+    let counter = home.path().join("runs");
+    add_flaky_workflow(home.path(), &counter, 10);
+
+    clix(home.path(), &["run", "e2e", "--retry", "1"]);
+
+    assert_eq!(fs::read_to_string(&counter).unwrap().trim(), "2");
+}
+
+#[test]
+fn test_workflow_is_not_rerun_without_retry() {
+    let home = TempDir::new().unwrap();
+    let counter = home.path().join("runs");
+    add_flaky_workflow(home.path(), &counter, 2);
+
+    clix(home.path(), &["run", "e2e"]);
+
+    assert_eq!(fs::read_to_string(&counter).unwrap().trim(), "1");
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use clix::commands::{Command, WorkflowStep};
+use clix::storage::Storage;
+use common::clix;
+use tempfile::TempDir;
+
+fn step(name: &str, section: &str) -> WorkflowStep {
+    WorkflowStep::new_command(
+        name.to_string(),
+        format!("echo '{}'", name),
+        format!("Run {}", name),
+        false,
+    )
+    .with_section(section.to_string())
+}
+
+#[test]
+fn test_section_banner_printed_once_per_section() {
+    let home = TempDir::new().unwrap();
+    let storage = Storage::new_with_dir(home.path().join(".clix")).unwrap();
+    storage
+        .add_command(Command::new_workflow(
+            "sectioned".to_string(),
+            "Workflow with sections".to_string(),
+            vec![
+                step("compile", "Build"),
+                step("package", "Build"),
+                step("upload", "Deploy"),
+            ],
+            vec![],
+        ))
+        .unwrap();
+
+    let output = clix(home.path(), &["run", "sectioned"]);
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.matches("=== Build ===").count(), 1);
+    assert_eq!(stdout.matches("=== Deploy ===").count(), 1);
+    assert!(stdout.find("=== Build ===") < stdout.find("=== Deploy ==="));
+}
+
+#[test]
+fn test_section_round_trips_through_json() {
+    let json = serde_json::to_string(&step("compile", "Build")).unwrap();
+    assert!(json.contains("\"section\":\"Build\""));
+
+    let parsed: WorkflowStep = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.section.as_deref(), Some("Build"));
+
+    // Steps without a section keep serializing as before
+    let plain = WorkflowStep::new_command(
+        "plain".to_string(),
+        "echo plain".to_string(),
+        "No section".to_string(),
+        false,
+    );
+    assert!(
+        !serde_json::to_string(&plain)
+            .unwrap()
+            .contains("\"section\"")
+    );
+}